@@ -0,0 +1,41 @@
+use std::io::{self, Write};
+use std::rc::Rc;
+
+use crate::value::{NativeFunction, Value};
+
+// Functions every script can call without declaring them
+pub fn builtins() -> Vec<(&'static str, NativeFunction)> {
+    vec![
+        ("print", Rc::new(print) as NativeFunction),
+        ("println", Rc::new(println) as NativeFunction),
+        ("len", Rc::new(len) as NativeFunction),
+    ]
+}
+
+fn write_values(arguments: &[Value]) -> String {
+    arguments.iter()
+        .map(|argument| argument.to_string())
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn print(arguments: &[Value]) -> Result<Value, String> {
+    let mut stdout = io::stdout();
+    write!(stdout, "{}", write_values(arguments)).map_err(|error| error.to_string())?;
+    stdout.flush().map_err(|error| error.to_string())?;
+    Ok(Value::Void)
+}
+
+fn println(arguments: &[Value]) -> Result<Value, String> {
+    writeln!(io::stdout(), "{}", write_values(arguments)).map_err(|error| error.to_string())?;
+    Ok(Value::Void)
+}
+
+fn len(arguments: &[Value]) -> Result<Value, String> {
+    match arguments {
+        [Value::Array(values)] => Ok(Value::UInt(values.len() as u64)),
+        [Value::Str(value)] => Ok(Value::UInt(value.chars().count() as u64)),
+        [other] => Err(format!("len() expects an array or a str, found {}", other.type_name())),
+        _ => Err(format!("len() takes 1 argument but {} were given", arguments.len())),
+    }
+}
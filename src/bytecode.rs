@@ -0,0 +1,296 @@
+use core::fmt;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::builtins::builtins;
+use crate::error::ErrorCode;
+use crate::parser::StatementSpan;
+use crate::tokenizer::TokenKind;
+use crate::value::{EnumType, NativeFunction, StructType, Value};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Instruction {
+    // Pushes a value from the constant pool
+    Constant(u16),
+    Pop,
+    PopN(u16),
+    Dup,
+    // Duplicates the two values on top of the stack, keeping their order
+    Dup2,
+
+    GetGlobal(u16),
+    SetGlobal(u16),
+    GetLocal(u16),
+    SetLocal(u16),
+
+    // Converts the value on top of the stack to the declared type
+    Cast(TokenKind),
+    // Pops the length and pushes an array filled with default values
+    DefaultArray(TokenKind),
+    // Pops a value and the length and pushes an array filled with copies of the value
+    Fill,
+    // Pops that many values and pushes an array of them, in the order they were pushed
+    Array(u16),
+    // Pops an array and its length and pushes the array back, once it's known to have that many elements
+    CheckLength,
+
+    Binary(TokenKind),
+    Unary(TokenKind),
+    Index,
+    // Pops the value, the index and the array and pushes the updated array
+    SetIndex,
+
+    // Pops the values of the fields of a struct, in declaration order, and pushes the struct
+    Struct(u16),
+    // The name of the field is a constant. SetField pops the value and the struct and pushes the updated struct
+    GetField(u16),
+    SetField(u16),
+
+    // Pops the values the variant holds and pushes the variant of the enum in the slot
+    Variant(u16, u16),
+    // Pops a value and pushes whether it's that variant of the enum
+    IsVariant(u16, u16),
+    // Pops a variant and pushes one of the values it holds
+    Payload(u16),
+
+    // Absolute jumps inside the current chunk, JumpIfFalse pops the condition
+    Jump(u32),
+    JumpIfFalse(u32),
+    // Steps a 'for' loop: the local holds the position and the one after it the array or
+    // the end of the range. Pushes the next value and advances the position, or jumps when done
+    Next(u16, u32),
+
+    Call(u16, u8),
+    Return,
+    ReturnVoid,
+
+    // Raises a runtime error the compiler could already predict
+    Abort(ErrorCode),
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Constant(index) => write!(f, "CONSTANT {}", index),
+            Self::Pop => write!(f, "POP"),
+            Self::PopN(count) => write!(f, "POPN {}", count),
+            Self::Dup => write!(f, "DUP"),
+            Self::Dup2 => write!(f, "DUP2"),
+            Self::GetGlobal(slot) => write!(f, "GET_GLOBAL {}", slot),
+            Self::SetGlobal(slot) => write!(f, "SET_GLOBAL {}", slot),
+            Self::GetLocal(slot) => write!(f, "GET_LOCAL {}", slot),
+            Self::SetLocal(slot) => write!(f, "SET_LOCAL {}", slot),
+            Self::Cast(kind) => write!(f, "CAST {}", kind),
+            Self::DefaultArray(kind) => write!(f, "DEFAULT_ARRAY {}", kind),
+            Self::Fill => write!(f, "FILL"),
+            Self::Array(count) => write!(f, "ARRAY {}", count),
+            Self::CheckLength => write!(f, "CHECK_LENGTH"),
+            Self::Binary(operator) => write!(f, "BINARY {}", operator),
+            Self::Unary(operator) => write!(f, "UNARY {}", operator),
+            Self::Index => write!(f, "INDEX"),
+            Self::SetIndex => write!(f, "SET_INDEX"),
+            Self::Struct(slot) => write!(f, "STRUCT {}", slot),
+            Self::GetField(name) => write!(f, "GET_FIELD {}", name),
+            Self::SetField(name) => write!(f, "SET_FIELD {}", name),
+            Self::Variant(slot, index) => write!(f, "VARIANT {} {}", slot, index),
+            Self::IsVariant(slot, index) => write!(f, "IS_VARIANT {} {}", slot, index),
+            Self::Payload(index) => write!(f, "PAYLOAD {}", index),
+            Self::Jump(target) => write!(f, "JUMP {}", target),
+            Self::JumpIfFalse(target) => write!(f, "JUMP_IF_FALSE {}", target),
+            Self::Next(slot, target) => write!(f, "NEXT {} {}", slot, target),
+            Self::Call(function, arguments) => write!(f, "CALL {} {}", function, arguments),
+            Self::Return => write!(f, "RETURN"),
+            Self::ReturnVoid => write!(f, "RETURN_VOID"),
+            Self::Abort(code) => write!(f, "ABORT {:?}", code),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Chunk {
+    pub code: Vec<Instruction>,
+    pub constants: Vec<Value>,
+    // The source span of every instruction, used to report runtime errors
+    pub spans: Vec<StatementSpan>,
+}
+
+impl Chunk {
+    pub fn emit(&mut self, instruction: Instruction, span: StatementSpan) -> usize {
+        self.code.push(instruction);
+        self.spans.push(span);
+        self.code.len() - 1
+    }
+
+    pub fn add_constant(&mut self, value: Value) -> Option<u16> {
+        if let Some(index) = self.constants.iter().position(|constant| *constant == value) {
+            return u16::try_from(index).ok();
+        }
+        self.constants.push(value);
+        u16::try_from(self.constants.len() - 1).ok()
+    }
+}
+
+impl fmt::Display for Chunk {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (offset, instruction) in self.code.iter().enumerate() {
+            match instruction {
+                Instruction::Constant(index) | Instruction::GetField(index) | Instruction::SetField(index) => {
+                    writeln!(f, "{:04} {:<20} ({:?})", offset, instruction.to_string(), self.constants[*index as usize])?
+                },
+                _ => writeln!(f, "{:04} {}", offset, instruction)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Function {
+    pub name: Rc<str>,
+    // The kind of every parameter and whether it's an array
+    pub parameters: Vec<(TokenKind, bool)>,
+    // TokenKind::Null for functions without a return value
+    pub return_kind: TokenKind,
+    pub chunk: Chunk,
+}
+
+#[derive(Clone)]
+pub enum Callable {
+    Script(Rc<Function>),
+    Native(NativeFunction),
+}
+
+#[derive(Debug, Clone)]
+pub struct Global {
+    pub name: Rc<str>,
+    pub kind: TokenKind,
+    pub is_array: bool,
+    // The slot in 'types' of the struct or enum a global of kind Identifier holds
+    pub type_: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Definition {
+    Struct(Rc<StructType>),
+    Enum(Rc<EnumType>),
+}
+
+impl Definition {
+    pub fn name(&self) -> &Rc<str> {
+        match self {
+            Definition::Struct(type_) => &type_.name,
+            Definition::Enum(type_) => &type_.name,
+        }
+    }
+}
+
+// A struct or an enum
+#[derive(Debug, Clone)]
+pub struct TypeDefinition {
+    pub definition: Definition,
+    // What a variable of the type holds when it's declared without an initializer
+    pub default: Value,
+}
+
+// Everything compiled so far: the slots of the globals, the callable functions and the types.
+// It outlives a single compilation, so new code can use what older code declared
+pub struct Program {
+    pub globals: Vec<Global>,
+    pub functions: Vec<Option<Callable>>,
+    pub types: Vec<TypeDefinition>,
+    global_slots: HashMap<Rc<str>, usize>,
+    function_slots: HashMap<Rc<str>, usize>,
+    type_slots: HashMap<Rc<str>, usize>,
+}
+
+impl Default for Program {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Program {
+    pub fn new() -> Program {
+        let mut program: Program = Program {
+            globals: Vec::new(),
+            functions: Vec::new(),
+            types: Vec::new(),
+            global_slots: HashMap::new(),
+            function_slots: HashMap::new(),
+            type_slots: HashMap::new(),
+        };
+
+        for (name, function) in builtins() {
+            program.register_native(name, function);
+        }
+
+        program
+    }
+
+    pub fn register_native(&mut self, name: &str, function: NativeFunction) -> usize {
+        let slot: usize = self.declare_function(name);
+        self.functions[slot] = Some(Callable::Native(function));
+        slot
+    }
+
+    pub fn global(&self, name: &str) -> Option<usize> {
+        self.global_slots.get(name).copied()
+    }
+
+    pub fn function(&self, name: &str) -> Option<usize> {
+        self.function_slots.get(name).copied()
+    }
+
+    pub fn type_(&self, name: &str) -> Option<usize> {
+        self.type_slots.get(name).copied()
+    }
+
+    pub fn function_names(&self) -> impl Iterator<Item = &Rc<str>> {
+        self.function_slots.keys()
+    }
+
+    pub fn function_name(&self, slot: usize) -> &str {
+        self.function_slots.iter()
+            .find_map(|(name, index)| (*index == slot).then_some(&**name))
+            .unwrap_or("<unknown>")
+    }
+
+    pub fn declare_global(&mut self, name: &str, kind: TokenKind, is_array: bool, type_: Option<usize>) -> usize {
+        if let Some(slot) = self.global(name) {
+            self.globals[slot].kind = kind;
+            self.globals[slot].is_array = is_array;
+            self.globals[slot].type_ = type_;
+            return slot;
+        }
+
+        let name: Rc<str> = Rc::from(name);
+        self.globals.push(Global { name: name.clone(), kind, is_array, type_ });
+        self.global_slots.insert(name, self.globals.len() - 1);
+        self.globals.len() - 1
+    }
+
+    // Reserves a slot, the function itself is stored once it's compiled
+    pub fn declare_function(&mut self, name: &str) -> usize {
+        if let Some(slot) = self.function(name) {
+            return slot;
+        }
+
+        self.functions.push(None);
+        self.function_slots.insert(Rc::from(name), self.functions.len() - 1);
+        self.functions.len() - 1
+    }
+
+    // A type declared again replaces the old one, values built before keep their type
+    pub fn define_type(&mut self, definition: Definition, default: Value) -> usize {
+        let name: Rc<str> = definition.name().clone();
+        let definition: TypeDefinition = TypeDefinition { definition, default };
+        if let Some(slot) = self.type_(&name) {
+            self.types[slot] = definition;
+            return slot;
+        }
+
+        self.types.push(definition);
+        self.type_slots.insert(name, self.types.len() - 1);
+        self.types.len() - 1
+    }
+}
@@ -0,0 +1,1031 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::bytecode::{Callable, Chunk, Definition, Function, Instruction, Program};
+use crate::parser::{Accessor, Arm, Body, ElseBranch, Expression, Field, Iterable, Path, Pattern, RawExpression, RawStatement, Statement, StatementSpan, Type, Variant};
+use crate::tokenizer::TokenKind;
+use crate::typechecker::Kinds;
+use crate::value::{EnumType, StructType, Value};
+use crate::error::*;
+use crate::source::SourceMap;
+
+#[derive(Debug)]
+pub struct CompileError {
+    pub code: ErrorCode,
+    pub span: StatementSpan,
+    pub hint: String,
+}
+
+impl CompileError {
+    pub fn to_diagnostic<'a>(&'a self, sources: &'a SourceMap) -> Diagnostic<'a> {
+        Diagnostic {
+            kind: DiagnosticKind::Error(self.code),
+            info: DiagnosticInfo::from_span(sources, self.span.file, self.span.start, self.span.end),
+            hint: Some(&self.hint)
+        }
+    }
+}
+
+struct Local<'a> {
+    name: &'a str,
+    depth: usize,
+    kind: TokenKind,
+    // Position on the stack of the frame, values of an unfinished expression can sit below it
+    slot: usize,
+}
+
+struct Loop {
+    start: usize,
+    // Jumps emitted by 'break', patched once the end of the loop is known
+    breaks: Vec<usize>,
+    // Number of locals alive when the loop started
+    locals: usize,
+}
+
+// A struct or an enum of the code being compiled, waiting to be defined
+#[derive(Clone, Copy)]
+enum Declaration<'a> {
+    Struct(&'a [Field<'a>]),
+    Enum(&'a [Variant<'a>]),
+}
+
+// Where a variable lives, decided at compile time
+enum Slot {
+    Local(u16, TokenKind),
+    Global(u16, TokenKind),
+}
+
+pub struct Compiler<'p, 'a> {
+    program: &'p mut Program,
+
+    // State of the function being compiled, the top level is a function too
+    chunk: Chunk,
+    locals: Vec<Local<'a>>,
+    // Values of the expression being compiled that are on the stack above the locals,
+    // like the left side of a binary expression while the right side is compiled
+    temporaries: usize,
+    depth: usize,
+    loops: Vec<Loop>,
+    inside_function: bool,
+    // Namespaces of the code being compiled, globals and functions are stored by their qualified name
+    namespaces: Vec<&'a str>,
+    // Structs and enums declared by the code being compiled, with the namespaces the types of their fields
+    // and values are written in. They are defined before the first statement, the types they hold first
+    pending: HashMap<String, (Declaration<'a>, Vec<&'a str>)>,
+    // From the type checker, what each arithmetic expression wraps its result to
+    kinds: Kinds,
+}
+
+impl<'p, 'a> Compiler<'p, 'a> {
+    pub fn new(program: &'p mut Program) -> Compiler<'p, 'a> {
+        Compiler {
+            program,
+            chunk: Chunk::default(),
+            locals: Vec::new(),
+            temporaries: 0,
+            depth: 0,
+            loops: Vec::new(),
+            inside_function: false,
+            namespaces: Vec::new(),
+            pending: HashMap::new(),
+            kinds: Kinds::new(),
+        }
+    }
+
+    // Without the kinds the checker found, integer math is done in 64 bits and is
+    // only cut to the width of a variable when it's stored
+    pub fn with_kinds(mut self, kinds: Kinds) -> Compiler<'p, 'a> {
+        self.kinds = kinds;
+        self
+    }
+
+    // Compiles the top level statements into a function that takes no arguments
+    pub fn compile(mut self, statements: &'a [Statement<'a>]) -> Result<Function, CompileError> {
+        self.compile_statements(statements)?;
+
+        let end: StatementSpan = statements.last().map_or(StatementSpan::default(), |statement| statement.span);
+        self.chunk.emit(Instruction::ReturnVoid, end);
+
+        Ok(Function {
+            name: Rc::from("<script>"),
+            parameters: Vec::new(),
+            return_kind: TokenKind::Null,
+            chunk: self.chunk,
+        })
+    }
+
+    // Compiles a single expression into a function that returns its value
+    pub fn compile_standalone(mut self, expression: &'a Expression<'a>) -> Result<Function, CompileError> {
+        self.compile_expression(expression)?;
+        self.chunk.emit(Instruction::Return, expression.span);
+
+        Ok(Function {
+            name: Rc::from("<expression>"),
+            parameters: Vec::new(),
+            return_kind: TokenKind::Null,
+            chunk: self.chunk,
+        })
+    }
+
+    fn compile_statements(&mut self, statements: &'a [Statement<'a>]) -> Result<(), CompileError> {
+        self.hoist(statements);
+        if self.depth == 0 && !self.inside_function {
+            self.define_types()?;
+        }
+
+        for statement in statements {
+            self.compile_statement(statement)?;
+        }
+
+        Ok(())
+    }
+
+    // Same hoisting rule as the SemanticAnalyzer
+    fn hoist(&mut self, statements: &'a [Statement<'a>]) {
+        for statement in statements {
+            match &statement.node {
+                RawStatement::Function { name, .. } => {
+                    self.program.declare_function(&self.qualified(name));
+                },
+
+                RawStatement::Struct { name, fields } => {
+                    self.pending.insert(self.qualified(name), (Declaration::Struct(fields), self.namespaces.clone()));
+                },
+
+                RawStatement::Enum { name, variants } => {
+                    self.pending.insert(self.qualified(name), (Declaration::Enum(variants), self.namespaces.clone()));
+                },
+
+                RawStatement::Namespace { name, body } if self.depth == 0 && !self.inside_function => {
+                    self.namespaces.push(name);
+                    self.hoist(&body.statements);
+                    self.namespaces.pop();
+                },
+
+                _ => {},
+            }
+        }
+    }
+
+    fn compile_body(&mut self, body: &'a Body<'a>, span: StatementSpan) -> Result<(), CompileError> {
+        self.depth += 1;
+        self.compile_statements(&body.statements)?;
+        self.depth -= 1;
+
+        let alive: usize = self.locals.iter().take_while(|local| local.depth <= self.depth).count();
+        let dropped: usize = self.locals.len() - alive;
+        self.locals.truncate(alive);
+        self.pop_locals(dropped, span)
+    }
+
+    fn compile_statement(&mut self, statement: &'a Statement<'a>) -> Result<(), CompileError> {
+        let span: StatementSpan = statement.span;
+
+        match &statement.node {
+            RawStatement::VariableDeclaration { type_, name, value, .. } => {
+                match value {
+                    // The size is evaluated before the value, the array must have that many elements
+                    Some(value) => match &type_.array_length {
+                        Some(length) => {
+                            self.compile_expression(length)?;
+                            self.compile_above(1, value)?;
+                            self.emit(Instruction::Cast(type_.kind), span);
+                            self.emit(Instruction::CheckLength, span);
+                        },
+                        None => {
+                            self.compile_expression(value)?;
+                            self.emit(Instruction::Cast(type_.kind), span);
+                        },
+                    },
+                    None => self.default_value(type_, span)?,
+                }
+
+                if self.depth == 0 && !self.inside_function {
+                    let definition: Option<usize> = match &type_.name {
+                        Some(path) => self.lookup_type(path, &self.namespaces.clone())?,
+                        None => None,
+                    };
+                    let slot: usize = self.program.declare_global(&self.qualified(name), type_.kind, type_.is_array, definition);
+                    let slot: u16 = self.index(slot, span)?;
+                    self.emit(Instruction::SetGlobal(slot), span);
+                }
+                else {
+                    // The value stays on the stack and becomes the local
+                    self.add_local(name, type_.kind, span)?;
+                }
+            },
+
+            RawStatement::VariableAssignment { target, operator, value } => {
+                let slot: Slot = self.resolve(&Path::from(target.name), span)?;
+
+                // The indexes and then the value wait in hidden locals, their names can't be written in a script.
+                // An element or a field is set on a copy of the variable taken after the value is evaluated,
+                // so the changes the value makes to it are kept
+                let locals: usize = self.locals.len();
+                let mut setters: Vec<(Instruction, Option<u16>)> = Vec::new();
+                for accessor in &target.accessors {
+                    setters.push(match accessor {
+                        Accessor::Index(index) => {
+                            self.compile_expression(index)?;
+                            (Instruction::SetIndex, Some(self.add_local("<index>", TokenKind::Null, span)?))
+                        },
+                        Accessor::Field(name) => (Instruction::SetField(self.add_constant(Value::Str(Rc::from(*name)), span)?), None),
+                    });
+                }
+
+                // The current value is read first, like the left side of a binary expression
+                if *operator != TokenKind::Assignment {
+                    self.get(&slot, span);
+                    for (setter, index) in &setters {
+                        if let Some(index) = index {
+                            self.emit(Instruction::GetLocal(*index), span);
+                        }
+                        self.emit(Self::getter(*setter), span);
+                    }
+                    self.compile_above(1, value)?;
+                    self.emit(Instruction::Binary(operator.compound_operator()), span);
+                }
+                else {
+                    self.compile_expression(value)?;
+                }
+
+                // Every array and struct the place goes through is pushed, with its index, and stored back in reverse
+                if !setters.is_empty() {
+                    let value: u16 = self.add_local("<value>", TokenKind::Null, span)?;
+                    self.get(&slot, span);
+                    for (position, (setter, index)) in setters.iter().enumerate() {
+                        if let Some(index) = index {
+                            self.emit(Instruction::GetLocal(*index), span);
+                        }
+                        if position + 1 < setters.len() {
+                            self.read(*setter, span);
+                        }
+                    }
+                    self.emit(Instruction::GetLocal(value), span);
+
+                    for (setter, _) in setters.into_iter().rev() {
+                        self.emit(setter, span);
+                    }
+                }
+
+                match slot {
+                    Slot::Local(slot, kind) => {
+                        self.emit(Instruction::Cast(kind), span);
+                        self.emit(Instruction::SetLocal(slot), span);
+                    },
+                    Slot::Global(slot, kind) => {
+                        self.emit(Instruction::Cast(kind), span);
+                        self.emit(Instruction::SetGlobal(slot), span);
+                    },
+                }
+
+                self.pop_locals(self.locals.len() - locals, span)?;
+                self.locals.truncate(locals);
+            },
+
+            RawStatement::If { condition, body, elses } => {
+                let mut exits: Vec<usize> = Vec::new();
+
+                self.compile_expression(condition)?;
+                let mut next: Option<usize> = Some(self.emit(Instruction::JumpIfFalse(0), span));
+                self.compile_body(body, span)?;
+
+                for branch in elses {
+                    exits.push(self.emit(Instruction::Jump(0), span));
+                    if let Some(next) = next.take() {
+                        self.patch(next)?;
+                    }
+
+                    match branch {
+                        ElseBranch::ElseIf(statement) => {
+                            let RawStatement::If { condition, body, .. } = &statement.node else {
+                                continue;
+                            };
+                            self.compile_expression(condition)?;
+                            next = Some(self.emit(Instruction::JumpIfFalse(0), statement.span));
+                            self.compile_body(body, statement.span)?;
+                        },
+                        ElseBranch::Else(body) => self.compile_body(body, span)?,
+                    }
+                }
+
+                if let Some(next) = next {
+                    self.patch(next)?;
+                }
+                for exit in exits {
+                    self.patch(exit)?;
+                }
+            },
+
+            RawStatement::While { condition, body } => {
+                let start: usize = self.chunk.code.len();
+
+                self.compile_expression(condition)?;
+                let exit: usize = self.emit(Instruction::JumpIfFalse(0), span);
+
+                self.loops.push(Loop { start, breaks: Vec::new(), locals: self.locals.len() });
+                self.compile_body(body, span)?;
+                let lp: Loop = self.loops.pop().unwrap();
+
+                self.emit(Instruction::Jump(self.target(start, span)?), span);
+                self.patch(exit)?;
+                for jump in lp.breaks {
+                    self.patch(jump)?;
+                }
+            },
+
+            RawStatement::For { type_, name, iterable, body } => {
+                // Without a type the values are stored as they are
+                let kind: TokenKind = type_.unwrap_or(TokenKind::Null);
+                let cast = |compiler: &mut Self| if type_.is_some() {
+                    compiler.emit(Instruction::Cast(kind), span);
+                };
+
+                // Two hidden locals drive the loop, the position and what it goes through.
+                // Their names can't be written in a script
+                self.depth += 1;
+                match iterable {
+                    Iterable::Range { start, end } => {
+                        self.compile_expression(start)?;
+                        cast(self);
+                        self.compile_above(1, end)?;
+                        cast(self);
+                    },
+                    Iterable::Array(array) => {
+                        self.constant(Value::Int(0), span)?;
+                        self.compile_above(1, array)?;
+                    },
+                }
+                let slot: u16 = self.add_local("<position>", TokenKind::Null, span)?;
+                self.add_local("<iterable>", TokenKind::Null, span)?;
+
+                // 'Next' advances the position before the body runs, so 'continue' jumps straight to it
+                let start: usize = self.emit(Instruction::Next(slot, 0), span);
+                self.loops.push(Loop { start, breaks: Vec::new(), locals: self.locals.len() });
+
+                // The value pushed by 'Next' becomes the variable, it shares the scope of the body
+                cast(self);
+                self.add_local(name, kind, span)?;
+                self.compile_body(body, span)?;
+                self.locals.pop();
+                self.emit(Instruction::Pop, span);
+
+                let lp: Loop = self.loops.pop().unwrap();
+                self.emit(Instruction::Jump(self.target(start, span)?), span);
+                self.patch(start)?;
+                for jump in lp.breaks {
+                    self.patch(jump)?;
+                }
+
+                self.depth -= 1;
+                self.locals.truncate(self.locals.len() - 2);
+                self.emit(Instruction::PopN(2), span);
+            },
+
+            RawStatement::LoopControl(keyword) => {
+                let Some(lp) = self.loops.last() else {
+                    self.emit(Instruction::Abort(ErrorCode::ER003), span);
+                    return Ok(());
+                };
+
+                // Locals declared inside the loop are dropped before jumping out of the body
+                let (start, dropped) = (lp.start, self.locals.len() - lp.locals);
+                self.pop_locals(dropped, span)?;
+
+                if *keyword == "break" {
+                    let jump: usize = self.emit(Instruction::Jump(0), span);
+                    self.loops.last_mut().unwrap().breaks.push(jump);
+                }
+                else {
+                    self.emit(Instruction::Jump(self.target(start, span)?), span);
+                }
+            },
+
+            RawStatement::Function { name, parameters, type_, body } => {
+                let name: String = self.qualified(name);
+                let slot: usize = self.program.declare_function(&name);
+
+                // Functions get a fresh chunk and only see their parameters and the globals
+                let chunk: Chunk = std::mem::take(&mut self.chunk);
+                let locals: Vec<Local<'a>> = std::mem::take(&mut self.locals);
+                let loops: Vec<Loop> = std::mem::take(&mut self.loops);
+                let (depth, inside_function) = (self.depth, self.inside_function);
+
+                self.depth = 1;
+                self.inside_function = true;
+                for parameter in parameters {
+                    self.add_local(parameter.name, parameter.type_.kind, span)?;
+                }
+
+                let result: Result<(), CompileError> = self.compile_statements(&body.statements);
+                if *type_ == TokenKind::Null {
+                    self.emit(Instruction::ReturnVoid, span);
+                }
+                else {
+                    self.emit(Instruction::Abort(ErrorCode::ER005), span);
+                }
+
+                let function: Function = Function {
+                    name: Rc::from(name),
+                    parameters: parameters.iter().map(|parameter| (parameter.type_.kind, parameter.type_.is_array)).collect(),
+                    return_kind: *type_,
+                    chunk: std::mem::replace(&mut self.chunk, chunk),
+                };
+                self.locals = locals;
+                self.loops = loops;
+                self.depth = depth;
+                self.inside_function = inside_function;
+
+                result?;
+                self.program.functions[slot] = Some(Callable::Script(Rc::new(function)));
+            },
+
+            RawStatement::Return(value) => {
+                match value {
+                    Some(value) => {
+                        self.compile_expression(value)?;
+                        if self.inside_function {
+                            self.emit(Instruction::Return, span);
+                        }
+                    },
+                    None if self.inside_function => {
+                        self.emit(Instruction::ReturnVoid, span);
+                    },
+                    None => {},
+                }
+
+                if !self.inside_function {
+                    self.emit(Instruction::Abort(ErrorCode::ER004), span);
+                }
+            },
+
+            RawStatement::FunctionCall { name, arguments } => {
+                self.call(name, arguments, span)?;
+                self.emit(Instruction::Pop, span);
+            },
+
+            // Its functions were declared by 'hoist', only the constants are left
+            RawStatement::Namespace { name, body } => {
+                self.namespaces.push(name);
+                let result: Result<(), CompileError> = body.statements.iter()
+                    .try_for_each(|statement| self.compile_statement(statement));
+                self.namespaces.pop();
+                result?;
+            },
+
+            // The ModuleLoader put the statements of the module before this one
+            RawStatement::Import(_) => {},
+
+            // Defined before the first statement of the script
+            RawStatement::Struct { .. } | RawStatement::Enum { .. } => {},
+
+            RawStatement::Match { value, arms } => {
+                let matched: u16 = self.start_match(value, span)?;
+                let mut exits: Vec<usize> = Vec::new();
+
+                for arm in arms {
+                    let next: Option<usize> = self.test_pattern(matched, arm)?;
+
+                    // Like the variable of a 'for' loop, the bindings share the scope of the body
+                    let bindings: usize = self.bind(matched, arm)?;
+                    self.compile_body(&arm.value, span)?;
+                    self.locals.truncate(self.locals.len() - bindings);
+                    self.pop_locals(bindings, span)?;
+
+                    exits.push(self.emit(Instruction::Jump(0), span));
+                    if let Some(next) = next {
+                        self.patch(next)?;
+                    }
+                }
+
+                self.end_match(exits, span)?;
+                self.emit(Instruction::Pop, span);
+            },
+        }
+
+        Ok(())
+    }
+
+    // The matched value is kept in a hidden local while the arms compare it, its name can't be written in a script
+    fn start_match(&mut self, value: &'a Expression<'a>, span: StatementSpan) -> Result<u16, CompileError> {
+        self.depth += 1;
+        self.compile_expression(value)?;
+        self.add_local("<match>", TokenKind::Null, span)
+    }
+
+    // Every arm jumps here once it's done, when none of them matches the script stops.
+    // The hidden local is left on the stack
+    fn end_match(&mut self, exits: Vec<usize>, span: StatementSpan) -> Result<(), CompileError> {
+        self.emit(Instruction::Abort(ErrorCode::ER011), span);
+        for exit in exits {
+            self.patch(exit)?;
+        }
+
+        self.locals.pop();
+        self.depth -= 1;
+        Ok(())
+    }
+
+    // The value of the arm that matches takes the place of the matched value
+    fn match_expression(&mut self, value: &'a Expression<'a>, arms: &'a [Arm<'a, Expression<'a>>], span: StatementSpan) -> Result<(), CompileError> {
+        let matched: u16 = self.start_match(value, span)?;
+        let mut exits: Vec<usize> = Vec::new();
+
+        for arm in arms {
+            let next: Option<usize> = self.test_pattern(matched, arm)?;
+
+            let bindings: usize = self.bind(matched, arm)?;
+            self.compile_expression(&arm.value)?;
+            self.emit(Instruction::SetLocal(matched), span);
+            self.locals.truncate(self.locals.len() - bindings);
+            self.pop_locals(bindings, span)?;
+
+            exits.push(self.emit(Instruction::Jump(0), span));
+            if let Some(next) = next {
+                self.patch(next)?;
+            }
+        }
+
+        self.end_match(exits, span)
+    }
+
+    // Emits the comparison of the matched value with the pattern, returns the jump taken when it doesn't match
+    fn test_pattern<T>(&mut self, matched: u16, arm: &'a Arm<'a, T>) -> Result<Option<usize>, CompileError> {
+        let span: StatementSpan = arm.span;
+
+        match &arm.pattern {
+            Pattern::Wildcard => return Ok(None),
+            Pattern::Literal(literal) => {
+                self.emit(Instruction::GetLocal(matched), span);
+                self.compile_above(1, literal)?;
+                self.emit(Instruction::Binary(TokenKind::Equal), span);
+            },
+            Pattern::Variant { path, bindings } => {
+                let (slot, index, count) = self.find_variant(path, span)?.ok_or_else(|| CompileError {
+                    code: ErrorCode::EB001,
+                    span,
+                    hint: format!("'{}' is not defined", path),
+                })?;
+
+                // The TypeChecker doesn't know the variants of enums declared by an earlier script
+                if !bindings.is_empty() && bindings.len() != count {
+                    return Err(CompileError {
+                        code: ErrorCode::EC022,
+                        span,
+                        hint: format!("'{}' holds {} value(s) but {} name(s) were given", path, count, bindings.len()),
+                    });
+                }
+
+                self.emit(Instruction::GetLocal(matched), span);
+                self.emit(Instruction::IsVariant(slot, index), span);
+            },
+        }
+
+        Ok(Some(self.emit(Instruction::JumpIfFalse(0), span)))
+    }
+
+    // Copies the values of the matched variant into locals, returns how many there are
+    fn bind<T>(&mut self, matched: u16, arm: &'a Arm<'a, T>) -> Result<usize, CompileError> {
+        let Pattern::Variant { bindings, .. } = &arm.pattern else {
+            return Ok(0);
+        };
+
+        let mut count: usize = 0;
+        for (index, binding) in bindings.iter().enumerate().filter(|(_, binding)| **binding != "_") {
+            self.emit(Instruction::GetLocal(matched), arm.span);
+            self.emit(Instruction::Payload(self.index(index, arm.span)?), arm.span);
+            self.add_local(binding, TokenKind::Null, arm.span)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    fn compile_expression(&mut self, expression: &'a Expression<'a>) -> Result<(), CompileError> {
+        let span: StatementSpan = expression.span;
+
+        match &expression.node {
+            RawExpression::Variable(name) => {
+                if let Some((slot, index, count)) = self.find_variant(name, span)? {
+                    return self.variant(name, slot, index, count, 0, span);
+                }
+
+                let slot: Slot = self.resolve(name, span)?;
+                self.get(&slot, span);
+            },
+
+            RawExpression::Literal { kind, value } => {
+                let constant: Value = Value::from_literal(*kind, value).ok_or_else(|| CompileError {
+                    code: ErrorCode::ER006,
+                    span,
+                    hint: format!("'{}' can't be represented", value),
+                })?;
+                self.constant(constant, span)?;
+            },
+
+            RawExpression::Binary { left, operator: operator @ (TokenKind::And | TokenKind::Or), right } => {
+                // Short-circuits exactly like the interpreter, the result is always a bool
+                self.compile_expression(left)?;
+                let (short, long): (Value, Value) = if *operator == TokenKind::And {
+                    (Value::Bool(false), Value::Bool(true))
+                }
+                else {
+                    self.emit(Instruction::Unary(TokenKind::Not), span);
+                    (Value::Bool(true), Value::Bool(false))
+                };
+
+                let first: usize = self.emit(Instruction::JumpIfFalse(0), span);
+                self.compile_expression(right)?;
+                if *operator == TokenKind::Or {
+                    self.emit(Instruction::Unary(TokenKind::Not), span);
+                }
+                let second: usize = self.emit(Instruction::JumpIfFalse(0), span);
+
+                self.constant(long, span)?;
+                let end: usize = self.emit(Instruction::Jump(0), span);
+                self.patch(first)?;
+                self.patch(second)?;
+                self.constant(short, span)?;
+                self.patch(end)?;
+            },
+
+            RawExpression::Binary { left, operator, right } => {
+                self.compile_expression(left)?;
+                self.compile_above(1, right)?;
+                self.emit(Instruction::Binary(*operator), span);
+                self.wrap(span);
+            },
+
+            RawExpression::Unary { operator, operand } => {
+                self.compile_expression(operand)?;
+                self.emit(Instruction::Unary(*operator), span);
+                self.wrap(span);
+            },
+
+            RawExpression::FunctionCall { name, arguments } => self.call(name, arguments, span)?,
+
+            RawExpression::ArrayAccess { array, index } => {
+                self.compile_expression(array)?;
+                self.compile_above(1, index)?;
+                self.emit(Instruction::Index, span);
+            },
+
+            RawExpression::StructLiteral { name, fields } => self.struct_literal(name, fields, span)?,
+
+            RawExpression::Field { object, name } => {
+                self.compile_expression(object)?;
+                let name: u16 = self.add_constant(Value::Str(Rc::from(*name)), span)?;
+                self.emit(Instruction::GetField(name), span);
+            },
+
+            RawExpression::Match { value, arms } => self.match_expression(value, arms, span)?,
+
+            RawExpression::ArrayLiteral(elements) => {
+                for (index, element) in elements.iter().enumerate() {
+                    self.compile_above(index, element)?;
+                }
+
+                let count: u16 = u16::try_from(elements.len()).map_err(|_| CompileError {
+                    code: ErrorCode::EB002,
+                    span,
+                    hint: "an array literal can hold at most 65535 elements".to_string(),
+                })?;
+                self.emit(Instruction::Array(count), span);
+            },
+        }
+
+        Ok(())
+    }
+
+    // The instruction that reads what a setter of an assignment writes
+    fn getter(setter: Instruction) -> Instruction {
+        match setter {
+            Instruction::SetField(name) => Instruction::GetField(name),
+            _ => Instruction::Index,
+        }
+    }
+
+    // Reads the element or the field a setter of an assignment writes, keeping what it's read from
+    fn read(&mut self, setter: Instruction, span: StatementSpan) {
+        match setter {
+            Instruction::SetField(name) => {
+                self.emit(Instruction::Dup, span);
+                self.emit(Instruction::GetField(name), span);
+            },
+            _ => {
+                self.emit(Instruction::Dup2, span);
+                self.emit(Instruction::Index, span);
+            },
+        }
+    }
+
+    // Compiles an expression while 'count' values it will be combined with are on the stack
+    fn compile_above(&mut self, count: usize, expression: &'a Expression<'a>) -> Result<(), CompileError> {
+        self.temporaries += count;
+        let result: Result<(), CompileError> = self.compile_expression(expression);
+        self.temporaries -= count;
+        result
+    }
+
+    fn call(&mut self, name: &Path<'a>, arguments: &'a [Expression<'a>], span: StatementSpan) -> Result<(), CompileError> {
+        for (index, argument) in arguments.iter().enumerate() {
+            self.compile_above(index, argument)?;
+        }
+
+        // Variants holding values are built like a call
+        if let Some((slot, index, count)) = self.find_variant(name, span)? {
+            return self.variant(name, slot, index, count, arguments.len(), span);
+        }
+
+        let slot: usize = name.candidates(&self.namespaces).find_map(|name| self.program.function(&name)).ok_or_else(|| CompileError {
+            code: ErrorCode::EB001,
+            span,
+            hint: format!("'{}' is not defined", name),
+        })?;
+        let count: u8 = u8::try_from(arguments.len()).map_err(|_| CompileError {
+            code: ErrorCode::EB002,
+            span,
+            hint: "a call can pass at most 255 arguments".to_string(),
+        })?;
+
+        let slot: u16 = self.index(slot, span)?;
+        self.emit(Instruction::Call(slot, count), span);
+        Ok(())
+    }
+
+    // Emits the variant once its values are on the stack
+    fn variant(&mut self, name: &Path<'a>, slot: u16, index: u16, count: usize, found: usize, span: StatementSpan) -> Result<(), CompileError> {
+        // The TypeChecker doesn't know the variants of enums declared by an earlier script
+        if count != found {
+            return Err(CompileError {
+                code: ErrorCode::EC022,
+                span,
+                hint: format!("'{}' holds {} value(s) but {} were given", name, count, found),
+            });
+        }
+
+        self.emit(Instruction::Variant(slot, index), span);
+        Ok(())
+    }
+
+    // The values are evaluated in the order the struct declares its fields
+    fn struct_literal(&mut self, name: &Path<'a>, values: &'a [(&'a str, Expression<'a>)], span: StatementSpan) -> Result<(), CompileError> {
+        let slot: usize = self.find_type(name, &self.namespaces.clone(), span)?;
+        let Definition::Struct(type_) = self.program.types[slot].definition.clone() else {
+            return Err(CompileError { code: ErrorCode::ES023, span, hint: format!("'{}' is an enum, pick one of its variants", name) });
+        };
+
+        // The TypeChecker doesn't know the fields of structs declared by an earlier script
+        for (index, (field, value)) in values.iter().enumerate() {
+            if type_.field(field).is_none() {
+                return Err(CompileError { code: ErrorCode::EC015, span: value.span, hint: format!("'{}' has no field '{}'", type_.name, field) });
+            }
+            if values[..index].iter().any(|(other, _)| other == field) {
+                return Err(CompileError { code: ErrorCode::EC018, span: value.span, hint: format!("'{}' is already set", field) });
+            }
+        }
+
+        for (index, (field, _)) in type_.fields.iter().enumerate() {
+            let (_, value) = values.iter().find(|(name, _)| *name == &**field).ok_or_else(|| CompileError {
+                code: ErrorCode::EC017,
+                span,
+                hint: format!("set '{}' too, every field needs a value", field),
+            })?;
+            self.compile_above(index, value)?;
+        }
+
+        let slot: u16 = self.index(slot, span)?;
+        self.emit(Instruction::Struct(slot), span);
+        Ok(())
+    }
+
+    fn default_value(&mut self, type_: &'a Type<'a>, span: StatementSpan) -> Result<(), CompileError> {
+        if let Some(name) = &type_.name {
+            let slot: usize = self.find_type(name, &self.namespaces.clone(), span)?;
+            let default: Value = self.program.types[slot].default.clone();
+
+            if let Some(length) = &type_.array_length {
+                self.compile_expression(length)?;
+                self.constant(default, span)?;
+                self.emit(Instruction::Fill, span);
+                return Ok(());
+            }
+            return self.constant(default, span);
+        }
+
+        match &type_.array_length {
+            Some(length) => {
+                self.compile_expression(length)?;
+                self.emit(Instruction::DefaultArray(type_.kind), span);
+                Ok(())
+            },
+            None => self.constant(Value::default_for(type_.kind, None), span),
+        }
+    }
+
+    fn define_types(&mut self) -> Result<(), CompileError> {
+        while let Some(name) = self.pending.keys().next().cloned() {
+            self.define_type(&name)?;
+        }
+        Ok(())
+    }
+
+    // The default value of a struct holds the default value of every field,
+    // the one of an enum is its first variant holding default values
+    fn define_type(&mut self, name: &str) -> Result<usize, CompileError> {
+        let (declaration, namespaces) = self.pending.remove(name).expect("only pending types are defined");
+
+        match declaration {
+            Declaration::Struct(fields) => {
+                let mut kinds: Vec<(Rc<str>, TokenKind)> = Vec::with_capacity(fields.len());
+                let mut defaults: Vec<Value> = Vec::with_capacity(fields.len());
+                for field in fields {
+                    kinds.push((Rc::from(field.name), field.type_.kind));
+                    defaults.push(self.member_default(&field.type_, &namespaces, field.span)?);
+                }
+
+                let type_: Rc<StructType> = Rc::new(StructType { name: Rc::from(name), fields: kinds });
+                Ok(self.program.define_type(Definition::Struct(type_.clone()), Value::Struct(type_, defaults)))
+            },
+
+            Declaration::Enum(variants) => {
+                let kinds: Vec<(Rc<str>, Vec<TokenKind>)> = variants.iter()
+                    .map(|variant| (Rc::from(variant.name), variant.payload.iter().map(|type_| type_.kind).collect()))
+                    .collect();
+                let type_: Rc<EnumType> = Rc::new(EnumType { name: Rc::from(name), variants: kinds });
+
+                // An enum without variants has no value to start from
+                let default: Value = match variants.first() {
+                    Some(variant) => {
+                        let values: Vec<Value> = variant.payload.iter()
+                            .map(|type_| self.member_default(type_, &namespaces, variant.span))
+                            .collect::<Result<_, _>>()?;
+                        Value::Enum(type_.clone(), 0, values)
+                    },
+                    None => Value::Void,
+                };
+                Ok(self.program.define_type(Definition::Enum(type_), default))
+            },
+        }
+    }
+
+    // The default value of a field or of a value of a variant, their arrays have a literal size
+    fn member_default(&mut self, type_: &'a Type<'a>, namespaces: &[&'a str], span: StatementSpan) -> Result<Value, CompileError> {
+        let Some(path) = &type_.name else {
+            return Ok(Value::default_for(type_.kind, type_.literal_length()));
+        };
+
+        let slot: usize = self.find_type(path, namespaces, span)?;
+        let default: Value = self.program.types[slot].default.clone();
+        Ok(match type_.is_array {
+            true => Value::Array(vec![default; type_.literal_length().unwrap_or(0)]),
+            false => default,
+        })
+    }
+
+    // Types of the code being compiled come before the ones of earlier scripts with the same name
+    fn lookup_type(&mut self, path: &Path<'a>, namespaces: &[&'a str]) -> Result<Option<usize>, CompileError> {
+        let candidates: Vec<String> = path.candidates(namespaces).map(Cow::into_owned).collect();
+
+        for candidate in candidates {
+            if self.pending.contains_key(&candidate) {
+                return self.define_type(&candidate).map(Some);
+            }
+            if let Some(slot) = self.program.type_(&candidate) {
+                return Ok(Some(slot));
+            }
+        }
+        Ok(None)
+    }
+
+    fn find_type(&mut self, path: &Path<'a>, namespaces: &[&'a str], span: StatementSpan) -> Result<usize, CompileError> {
+        self.lookup_type(path, namespaces)?.ok_or_else(|| CompileError {
+            code: ErrorCode::EB001,
+            span,
+            hint: format!("'{}' is not defined", path),
+        })
+    }
+
+    // The slot of the enum, the index of the variant a path refers to and how many values it holds
+    fn find_variant(&mut self, path: &Path<'a>, span: StatementSpan) -> Result<Option<(u16, u16, usize)>, CompileError> {
+        let Some(parent) = path.parent() else {
+            return Ok(None);
+        };
+        let Some(slot) = self.lookup_type(&parent, &self.namespaces.clone())? else {
+            return Ok(None);
+        };
+        let Definition::Enum(type_) = &self.program.types[slot].definition else {
+            return Ok(None);
+        };
+        let Some(index) = type_.variant(path.name) else {
+            return Ok(None);
+        };
+
+        let count: usize = type_.variants[index].1.len();
+        Ok(Some((self.index(slot, span)?, self.index(index, span)?, count)))
+    }
+
+    // Same lookup rule as the SemanticAnalyzer's Scope
+    fn resolve(&mut self, path: &Path<'a>, span: StatementSpan) -> Result<Slot, CompileError> {
+        if !path.is_qualified() &&
+            let Some(local) = self.locals.iter().rev().find(|local| local.name == path.name) {
+            let (slot, kind): (usize, TokenKind) = (local.slot, local.kind);
+            return Ok(Slot::Local(self.index(slot, span)?, kind));
+        }
+
+        if let Some(slot) = path.candidates(&self.namespaces).find_map(|name| self.program.global(&name)) {
+            let kind: TokenKind = self.program.globals[slot].kind;
+            return Ok(Slot::Global(self.index(slot, span)?, kind));
+        }
+
+        Err(CompileError {
+            code: ErrorCode::EB001,
+            span,
+            hint: format!("'{}' is not defined", path),
+        })
+    }
+
+    // The name a global or a function declared here is stored with
+    fn qualified(&self, name: &'a str) -> String {
+        Path::from(name).qualified(&self.namespaces).into_owned()
+    }
+
+    fn get(&mut self, slot: &Slot, span: StatementSpan) {
+        match slot {
+            Slot::Local(slot, _) => self.emit(Instruction::GetLocal(*slot), span),
+            Slot::Global(slot, _) => self.emit(Instruction::GetGlobal(*slot), span),
+        };
+    }
+
+    // The value on top of the stack becomes the local, returns its slot
+    fn add_local(&mut self, name: &'a str, kind: TokenKind, span: StatementSpan) -> Result<u16, CompileError> {
+        let slot: usize = self.locals.len() + self.temporaries;
+        self.locals.push(Local { name, depth: self.depth, kind, slot });
+        self.index(slot, span)
+    }
+
+    fn pop_locals(&mut self, count: usize, span: StatementSpan) -> Result<(), CompileError> {
+        match count {
+            0 => {},
+            1 => { self.emit(Instruction::Pop, span); },
+            count => {
+                let count: u16 = self.index(count, span)?;
+                self.emit(Instruction::PopN(count), span);
+            },
+        }
+        Ok(())
+    }
+
+    fn constant(&mut self, value: Value, span: StatementSpan) -> Result<(), CompileError> {
+        let index: u16 = self.add_constant(value, span)?;
+        self.emit(Instruction::Constant(index), span);
+        Ok(())
+    }
+
+    fn add_constant(&mut self, value: Value, span: StatementSpan) -> Result<u16, CompileError> {
+        self.chunk.add_constant(value).ok_or_else(|| CompileError {
+            code: ErrorCode::EB002,
+            span,
+            hint: "a single function can use at most 65536 constants".to_string(),
+        })
+    }
+
+    // Points a previously emitted jump to the next instruction
+    fn patch(&mut self, jump: usize) -> Result<(), CompileError> {
+        let span: StatementSpan = self.chunk.spans[jump];
+        let target: u32 = self.target(self.chunk.code.len(), span)?;
+
+        match &mut self.chunk.code[jump] {
+            Instruction::Jump(destination) | Instruction::JumpIfFalse(destination) | Instruction::Next(_, destination) => *destination = target,
+            _ => unreachable!("only jumps are patched"),
+        }
+        Ok(())
+    }
+
+    fn target(&self, offset: usize, span: StatementSpan) -> Result<u32, CompileError> {
+        u32::try_from(offset).map_err(|_| CompileError {
+            code: ErrorCode::EB002,
+            span,
+            hint: "the function is too large to be compiled".to_string(),
+        })
+    }
+
+    fn index(&self, index: usize, span: StatementSpan) -> Result<u16, CompileError> {
+        u16::try_from(index).map_err(|_| CompileError {
+            code: ErrorCode::EB002,
+            span,
+            hint: "at most 65536 variables or functions can be declared".to_string(),
+        })
+    }
+
+    // Cuts the result of an arithmetic expression to the width of its operands
+    fn wrap(&mut self, span: StatementSpan) {
+        if let Some(kind) = self.kinds.get(&span).copied() {
+            self.emit(Instruction::Cast(kind), span);
+        }
+    }
+
+    fn emit(&mut self, instruction: Instruction, span: StatementSpan) -> usize {
+        self.chunk.emit(instruction, span)
+    }
+}
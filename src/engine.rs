@@ -0,0 +1,784 @@
+use core::fmt;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::bytecode::{Callable, Definition, Function, Program};
+use crate::compiler::Compiler;
+use crate::error::*;
+use crate::interpreter::RuntimeError;
+use crate::loader::{Module, ModuleLoader};
+use crate::owned::borrow;
+use crate::parser::{Expression, Parser, Statement};
+use crate::semantics::SemanticAnalyzer;
+use crate::source::{FileId, SourceMap};
+use crate::tokenizer::{Token, TokenKind, Tokenizer};
+use crate::typechecker::{Kinds, Signature, TypeChecker, ValueType};
+use crate::value::{NativeFunction, Value};
+use crate::vm::VM;
+
+// An error reported by one of the phases, with the position already resolved
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptError {
+    pub code: ErrorCode,
+    pub hint: String,
+    // The script the error is in, registered in the Engine's SourceMap
+    pub file: FileId,
+    pub line: usize,
+    pub column: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl ScriptError {
+    pub fn to_diagnostic<'a>(&'a self, sources: &'a SourceMap) -> Diagnostic<'a> {
+        Diagnostic {
+            kind: DiagnosticKind::Error(self.code),
+            info: DiagnosticInfo::new(sources, self.file, self.start, self.end, self.line, self.column),
+            hint: Some(&self.hint)
+        }
+    }
+
+    pub(crate) fn from_diagnostic(diagnostic: Diagnostic, file: FileId) -> ScriptError {
+        let DiagnosticKind::Error(code) = diagnostic.kind else {
+            unreachable!("the engine doesn't run the lints");
+        };
+        ScriptError {
+            code,
+            hint: diagnostic.hint.unwrap_or_default().to_string(),
+            file,
+            line: diagnostic.info.line,
+            column: diagnostic.info.column,
+            start: diagnostic.info.start,
+            end: diagnostic.info.end,
+        }
+    }
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "error[{:?}] at {}:{}: {}", self.code, self.line, self.column, self.hint)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EngineError {
+    // The tokenizer and the parser stop at the first error
+    Syntax(ScriptError),
+    // Every error found by the later phases before the script could run
+    Compile(Vec<ScriptError>),
+    Runtime(ScriptError),
+    Conversion { expected: &'static str, found: &'static str },
+    UndefinedGlobal(String),
+    UndefinedFunction(String),
+    ArgumentCount { name: String, expected: usize, found: usize },
+    // The script was compiled by another Engine
+    ForeignScript,
+}
+
+impl fmt::Display for EngineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Compile(errors) => {
+                let errors: Vec<String> = errors.iter().map(|error| error.to_string()).collect();
+                write!(f, "{}", errors.join("\n"))
+            },
+            Self::Syntax(error) | Self::Runtime(error) => write!(f, "{}", error),
+            Self::Conversion { expected, found } => write!(f, "expected a value of type {}, found {}", expected, found),
+            Self::UndefinedGlobal(name) => write!(f, "no global named '{}'", name),
+            Self::UndefinedFunction(name) => write!(f, "no function named '{}'", name),
+            Self::ArgumentCount { name, expected, found } => {
+                write!(f, "'{}' takes {} argument(s) but {} were given", name, expected, found)
+            },
+            Self::ForeignScript => write!(f, "the script was compiled by another engine"),
+        }
+    }
+}
+
+impl std::error::Error for EngineError {}
+
+// Conversion from the language's values to Rust types
+pub trait FromValue: Sized {
+    fn from_value(value: Value) -> Result<Self, EngineError>;
+
+    // The type scripts have to pass for it, None when any value can be converted
+    fn expected_kind() -> Option<(TokenKind, bool)> {
+        None
+    }
+}
+
+// Conversion from Rust types to the language's values
+pub trait IntoValue {
+    // The declared type a new global gets when the host creates it: element type and array flag
+    fn kind(&self) -> (TokenKind, bool);
+    fn into_value(self) -> Value;
+
+    // The type scripts get from every value of the Rust type, None when it depends on the value
+    fn static_kind() -> Option<(TokenKind, bool)> where Self: Sized {
+        None
+    }
+}
+
+fn mismatch<T>(expected: &'static str, found: &Value) -> Result<T, EngineError> {
+    Err(EngineError::Conversion { expected, found: found.type_name() })
+}
+
+// A value from the host stored with a declared type must already have it, integers only have to fit in it.
+// Structs and enums must be of the definition given, any one is accepted without it
+fn check_value(value: &Value, kind: TokenKind, is_array: bool, definition: Option<&Definition>) -> Result<(), EngineError> {
+    use TokenKind::*;
+
+    let fits: bool = match (value, is_array) {
+        (Value::Array(values), true) => return values.iter().try_for_each(|value| check_value(value, kind, false, definition)),
+        (value, true) => return mismatch("array", value),
+        (value, false) => match kind {
+            SignedInt8 => i8::from_value(value.clone()).is_ok(),
+            SignedInt16 => i16::from_value(value.clone()).is_ok(),
+            SignedInt32 => i32::from_value(value.clone()).is_ok(),
+            SignedInt64 => i64::from_value(value.clone()).is_ok(),
+            UnsignedInt8 => u8::from_value(value.clone()).is_ok(),
+            UnsignedInt16 => u16::from_value(value.clone()).is_ok(),
+            UnsignedInt32 => u32::from_value(value.clone()).is_ok(),
+            UnsignedInt64 => u64::from_value(value.clone()).is_ok(),
+            Float32 | Float64 => matches!(value, Value::Float(_) | Value::Float32(_)),
+            Boolean => matches!(value, Value::Bool(_)),
+            Character => matches!(value, Value::Char(_)),
+            String => matches!(value, Value::Str(_)),
+            Identifier => match (value, definition) {
+                (Value::Struct(type_, _), Some(Definition::Struct(expected))) => Rc::ptr_eq(type_, expected),
+                (Value::Enum(type_, ..), Some(Definition::Enum(expected))) => Rc::ptr_eq(type_, expected),
+                (Value::Struct(..) | Value::Enum(..), None) => true,
+                _ => false,
+            },
+            _ => matches!(value, Value::Void),
+        },
+    };
+
+    if fits { Ok(()) } else { mismatch(kind_name(kind), value) }
+}
+
+fn kind_name(kind: TokenKind) -> &'static str {
+    use TokenKind::*;
+    match kind {
+        SignedInt8 => "i8",
+        SignedInt16 => "i16",
+        SignedInt32 => "i32",
+        SignedInt64 => "i64",
+        UnsignedInt8 => "u8",
+        UnsignedInt16 => "u16",
+        UnsignedInt32 => "u32",
+        UnsignedInt64 => "u64",
+        Float32 => "f32",
+        Float64 => "f64",
+        Boolean => "bool",
+        Character => "char",
+        String => "str",
+        Identifier => "struct or enum",
+        _ => "void",
+    }
+}
+
+macro_rules! impl_integer {
+    ($($type_:ty => $kind:ident, $variant:ident);* $(;)?) => {
+        $(
+            impl FromValue for $type_ {
+                fn from_value(value: Value) -> Result<Self, EngineError> {
+                    let converted: Option<$type_> = match value {
+                        Value::Int(value) => <$type_>::try_from(value).ok(),
+                        Value::UInt(value) => <$type_>::try_from(value).ok(),
+                        _ => None,
+                    };
+                    converted.map_or_else(|| mismatch(stringify!($type_), &value), Ok)
+                }
+
+                fn expected_kind() -> Option<(TokenKind, bool)> {
+                    Some((TokenKind::$kind, false))
+                }
+            }
+
+            impl IntoValue for $type_ {
+                fn kind(&self) -> (TokenKind, bool) {
+                    (TokenKind::$kind, false)
+                }
+
+                fn into_value(self) -> Value {
+                    Value::$variant(self.into())
+                }
+
+                fn static_kind() -> Option<(TokenKind, bool)> {
+                    Some((TokenKind::$kind, false))
+                }
+            }
+        )*
+    };
+}
+
+impl_integer! {
+    i8 => SignedInt8, Int;
+    i16 => SignedInt16, Int;
+    i32 => SignedInt32, Int;
+    i64 => SignedInt64, Int;
+    u8 => UnsignedInt8, UInt;
+    u16 => UnsignedInt16, UInt;
+    u32 => UnsignedInt32, UInt;
+    u64 => UnsignedInt64, UInt;
+}
+
+impl FromValue for f64 {
+    fn from_value(value: Value) -> Result<Self, EngineError> {
+        match value {
+            Value::Float(value) => Ok(value),
+            Value::Float32(value) => Ok(value.into()),
+            other => mismatch("f64", &other),
+        }
+    }
+    fn expected_kind() -> Option<(TokenKind, bool)> {
+        Some((TokenKind::Float64, false))
+    }
+}
+
+impl IntoValue for f64 {
+    fn kind(&self) -> (TokenKind, bool) {
+        (TokenKind::Float64, false)
+    }
+
+    fn into_value(self) -> Value {
+        Value::Float(self)
+    }
+    fn static_kind() -> Option<(TokenKind, bool)> {
+        Some((TokenKind::Float64, false))
+    }
+}
+
+impl FromValue for f32 {
+    fn from_value(value: Value) -> Result<Self, EngineError> {
+        match value {
+            Value::Float(value) => Ok(value as f32),
+            Value::Float32(value) => Ok(value),
+            other => mismatch("f32", &other),
+        }
+    }
+    fn expected_kind() -> Option<(TokenKind, bool)> {
+        Some((TokenKind::Float32, false))
+    }
+}
+
+impl IntoValue for f32 {
+    fn kind(&self) -> (TokenKind, bool) {
+        (TokenKind::Float32, false)
+    }
+
+    fn into_value(self) -> Value {
+        Value::Float32(self)
+    }
+    fn static_kind() -> Option<(TokenKind, bool)> {
+        Some((TokenKind::Float32, false))
+    }
+}
+
+impl FromValue for bool {
+    fn from_value(value: Value) -> Result<Self, EngineError> {
+        match value {
+            Value::Bool(value) => Ok(value),
+            other => mismatch("bool", &other),
+        }
+    }
+    fn expected_kind() -> Option<(TokenKind, bool)> {
+        Some((TokenKind::Boolean, false))
+    }
+}
+
+impl IntoValue for bool {
+    fn kind(&self) -> (TokenKind, bool) {
+        (TokenKind::Boolean, false)
+    }
+
+    fn into_value(self) -> Value {
+        Value::Bool(self)
+    }
+    fn static_kind() -> Option<(TokenKind, bool)> {
+        Some((TokenKind::Boolean, false))
+    }
+}
+
+impl FromValue for char {
+    fn from_value(value: Value) -> Result<Self, EngineError> {
+        match value {
+            Value::Char(value) => Ok(value),
+            other => mismatch("char", &other),
+        }
+    }
+    fn expected_kind() -> Option<(TokenKind, bool)> {
+        Some((TokenKind::Character, false))
+    }
+}
+
+impl IntoValue for char {
+    fn kind(&self) -> (TokenKind, bool) {
+        (TokenKind::Character, false)
+    }
+
+    fn into_value(self) -> Value {
+        Value::Char(self)
+    }
+    fn static_kind() -> Option<(TokenKind, bool)> {
+        Some((TokenKind::Character, false))
+    }
+}
+
+impl FromValue for String {
+    fn from_value(value: Value) -> Result<Self, EngineError> {
+        match value {
+            Value::Str(value) => Ok(value.to_string()),
+            other => mismatch("str", &other),
+        }
+    }
+    fn expected_kind() -> Option<(TokenKind, bool)> {
+        Some((TokenKind::String, false))
+    }
+}
+
+impl IntoValue for String {
+    fn kind(&self) -> (TokenKind, bool) {
+        (TokenKind::String, false)
+    }
+
+    fn into_value(self) -> Value {
+        Value::Str(Rc::from(self))
+    }
+    fn static_kind() -> Option<(TokenKind, bool)> {
+        Some((TokenKind::String, false))
+    }
+}
+
+impl IntoValue for &str {
+    fn kind(&self) -> (TokenKind, bool) {
+        (TokenKind::String, false)
+    }
+
+    fn into_value(self) -> Value {
+        Value::Str(Rc::from(self))
+    }
+    fn static_kind() -> Option<(TokenKind, bool)> {
+        Some((TokenKind::String, false))
+    }
+}
+
+impl<T: FromValue> FromValue for Vec<T> {
+    fn from_value(value: Value) -> Result<Self, EngineError> {
+        match value {
+            Value::Array(values) => values.into_iter().map(T::from_value).collect(),
+            other => mismatch("array", &other),
+        }
+    }
+
+    fn expected_kind() -> Option<(TokenKind, bool)> {
+        T::expected_kind().filter(|(_, is_array)| !is_array).map(|(kind, _)| (kind, true))
+    }
+}
+
+impl<T: IntoValue + Default> IntoValue for Vec<T> {
+    fn kind(&self) -> (TokenKind, bool) {
+        // An empty array still needs an element type, the default value provides it
+        let (kind, _) = self.first().map_or_else(|| T::default().kind(), |value| value.kind());
+        (kind, true)
+    }
+
+    fn into_value(self) -> Value {
+        Value::Array(self.into_iter().map(IntoValue::into_value).collect())
+    }
+
+    fn static_kind() -> Option<(TokenKind, bool)> {
+        T::static_kind().filter(|(_, is_array)| !is_array).map(|(kind, _)| (kind, true))
+    }
+}
+
+impl FromValue for () {
+    fn from_value(value: Value) -> Result<Self, EngineError> {
+        match value {
+            Value::Void => Ok(()),
+            other => mismatch("void", &other),
+        }
+    }
+}
+
+impl IntoValue for () {
+    fn kind(&self) -> (TokenKind, bool) {
+        (TokenKind::Null, false)
+    }
+
+    fn into_value(self) -> Value {
+        Value::Void
+    }
+    fn static_kind() -> Option<(TokenKind, bool)> {
+        Some((TokenKind::Null, false))
+    }
+}
+
+impl FromValue for Value {
+    fn from_value(value: Value) -> Result<Self, EngineError> {
+        Ok(value)
+    }
+}
+
+impl IntoValue for Value {
+    fn kind(&self) -> (TokenKind, bool) {
+        match self {
+            Value::Int(_) => (TokenKind::SignedInt64, false),
+            Value::UInt(_) => (TokenKind::UnsignedInt64, false),
+            Value::Float(_) => (TokenKind::Float64, false),
+            Value::Float32(_) => (TokenKind::Float32, false),
+            Value::Bool(_) => (TokenKind::Boolean, false),
+            Value::Char(_) => (TokenKind::Character, false),
+            Value::Str(_) => (TokenKind::String, false),
+            Value::Array(values) => (values.first().map_or(TokenKind::SignedInt64, |value| value.kind().0), true),
+            // Scripts see a struct or enum global as a name without a type, like a field holding a struct
+            Value::Struct(..) | Value::Enum(..) => (TokenKind::Identifier, false),
+            Value::Void => (TokenKind::Null, false),
+        }
+    }
+
+    fn into_value(self) -> Value {
+        self
+    }
+}
+
+// The arguments of a call from the host, implemented for tuples of convertible values
+pub trait IntoArguments {
+    fn into_arguments(self) -> Vec<Value>;
+}
+
+impl IntoArguments for Vec<Value> {
+    fn into_arguments(self) -> Vec<Value> {
+        self
+    }
+}
+
+// Rust closures that can be registered as natives, their arguments and result are converted
+pub trait IntoNative<Arguments> {
+    fn into_native(self) -> NativeFunction;
+    // The types calls are checked against, from the Rust types of the arguments and the result
+    fn signature(&self) -> Signature;
+}
+
+fn signature_type(kind: Option<(TokenKind, bool)>) -> ValueType {
+    kind.map_or(ValueType::Unknown, |(kind, is_array)| ValueType::from_kind(kind, is_array))
+}
+
+macro_rules! impl_arguments {
+    ($($argument:ident),*) => {
+        impl<$($argument: IntoValue),*> IntoArguments for ($($argument,)*) {
+            #[allow(non_snake_case, clippy::unused_unit)]
+            fn into_arguments(self) -> Vec<Value> {
+                let ($($argument,)*) = self;
+                vec![$($argument.into_value()),*]
+            }
+        }
+
+        impl<F, R, $($argument),*> IntoNative<($($argument,)*)> for F
+        where
+            F: Fn($($argument),*) -> R + 'static,
+            R: IntoValue,
+            $($argument: FromValue),*
+        {
+            #[allow(non_snake_case, unused_mut, unused_variables)]
+            fn into_native(self) -> NativeFunction {
+                Rc::new(move |arguments: &[Value]| {
+                    let expected: usize = <[&str]>::len(&[$(stringify!($argument)),*]);
+                    if arguments.len() != expected {
+                        return Err(format!("expected {} argument(s) but {} were given", expected, arguments.len()));
+                    }
+
+                    let mut arguments = arguments.iter().cloned();
+                    $(
+                        let $argument: $argument = $argument::from_value(arguments.next().unwrap())
+                            .map_err(|error| error.to_string())?;
+                    )*
+                    Ok(self($($argument),*).into_value())
+                })
+            }
+
+            fn signature(&self) -> Signature {
+                (vec![$(signature_type($argument::expected_kind())),*], signature_type(R::static_kind()))
+            }
+        }
+    };
+}
+
+impl_arguments!();
+impl_arguments!(A);
+impl_arguments!(A, B);
+impl_arguments!(A, B, C);
+impl_arguments!(A, B, C, D);
+impl_arguments!(A, B, C, D, E);
+
+// A compiled script, it can be run any number of times by the Engine that compiled it
+#[derive(Clone)]
+pub struct Script {
+    function: Rc<Function>,
+    // The id of the Engine that compiled it, its slots only mean something in that Engine's program
+    engine: usize,
+}
+
+// Gives every Engine its own id, so a Script can't be run by another one
+static NEXT_ENGINE: AtomicUsize = AtomicUsize::new(0);
+
+// Hosts scripts in-process: natives, globals and functions survive between scripts
+pub struct Engine {
+    id: usize,
+    program: Program,
+    vm: VM,
+    // Every compiled source, errors of any script point inside the right one
+    sources: SourceMap,
+    // Modules imported by earlier scripts are already compiled, so they aren't loaded again
+    loader: ModuleLoader,
+    // Calls to natives registered with register_fn are type checked
+    signatures: HashMap<String, Signature>,
+}
+
+impl Default for Engine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine {
+    pub fn new() -> Engine {
+        Engine {
+            id: NEXT_ENGINE.fetch_add(1, Ordering::Relaxed),
+            program: Program::new(),
+            vm: VM::new(),
+            sources: SourceMap::new(),
+            loader: ModuleLoader::new(),
+            signatures: HashMap::new(),
+        }
+    }
+
+    pub fn sources(&self) -> &SourceMap {
+        &self.sources
+    }
+
+    // Registers a Rust closure, arguments and the result are converted automatically
+    pub fn register_fn<Arguments>(&mut self, name: &str, function: impl IntoNative<Arguments>) {
+        self.signatures.insert(name.to_string(), function.signature());
+        self.program.register_native(name, function.into_native());
+    }
+
+    // Registers a native that works on raw values, for variadic or fallible functions
+    pub fn register_native(&mut self, name: &str, function: impl Fn(&[Value]) -> Result<Value, String> + 'static) {
+        self.signatures.remove(name);
+        self.program.register_native(name, Rc::new(function));
+    }
+
+    // Directory searched for the modules scripts import, after the one of the script
+    pub fn add_search_path(&mut self, path: impl Into<std::path::PathBuf>) {
+        self.loader.add_search_path(path);
+    }
+
+    pub fn compile(&mut self, source: &str) -> Result<Script, EngineError> {
+        self.compile_named("<script>", source)
+    }
+
+    // Same as 'compile', diagnostics show 'name' as the file the script comes from
+    // and imports are looked up next to it
+    pub fn compile_named(&mut self, name: &str, source: &str) -> Result<Script, EngineError> {
+        let modules: Vec<Module> = self.loader.load(name, source, &mut self.sources).map_err(load_error)?;
+
+        let script: Result<Script, EngineError> = self.compile_modules(&modules);
+        if script.is_err() {
+            self.loader.forget(&modules);
+        }
+        script
+    }
+
+    fn compile_modules(&mut self, modules: &[Module]) -> Result<Script, EngineError> {
+        let statements: Vec<Statement> = modules.iter().flat_map(|module| borrow(&module.statements)).collect();
+
+        let mut analyzer: SemanticAnalyzer = SemanticAnalyzer::new(&statements);
+        let mut checker: TypeChecker = TypeChecker::new(&statements);
+        self.declare_known_names(&mut analyzer, &mut checker);
+        analyzer.analyze().map_err(|errors| compile_errors(&errors, &self.sources, |error| error.span.file, |error, sources| error.to_diagnostic(sources)))?;
+        checker.check().map_err(|errors| compile_errors(&errors, &self.sources, |error| error.span.file, |error, sources| error.to_diagnostic(sources)))?;
+        let kinds: Kinds = checker.into_kinds();
+
+        let function: Function = Compiler::new(&mut self.program).with_kinds(kinds).compile(&statements)
+            .map_err(|error| EngineError::Compile(vec![ScriptError::from_diagnostic(error.to_diagnostic(&self.sources), error.span.file)]))?;
+
+        Ok(Script { function: Rc::new(function), engine: self.id })
+    }
+
+    pub fn run(&mut self, script: &Script) -> Result<(), EngineError> {
+        if script.engine != self.id {
+            return Err(EngineError::ForeignScript);
+        }
+        self.vm.run(&self.program, script.function.clone()).map_err(|error| self.runtime_error(error))?;
+        Ok(())
+    }
+
+    // Compiles and runs a script in one step
+    pub fn eval(&mut self, source: &str) -> Result<(), EngineError> {
+        let script: Script = self.compile(source)?;
+        self.run(&script)
+    }
+
+    // Evaluates a single expression, like '1 + 2' or 'add(x, 1)', and returns its value
+    pub fn eval_expression<T: FromValue>(&mut self, source: &str) -> Result<T, EngineError> {
+        self.eval_expression_named("<expression>", source)
+    }
+
+    // Same as 'eval_expression', diagnostics show 'name' as the file the expression comes from
+    pub fn eval_expression_named<T: FromValue>(&mut self, name: &str, source: &str) -> Result<T, EngineError> {
+        let file: FileId = self.sources.add(name, source);
+
+        let tokens: Vec<Token> = Tokenizer::with_file(source, file).tokenize()
+            .map_err(|error| EngineError::Syntax(ScriptError::from_diagnostic(error.to_diagnostic(&self.sources), file)))?;
+
+        let expression: Expression = Parser::new(tokens).parse_standalone_expression()
+            .map_err(|error| EngineError::Syntax(ScriptError::from_diagnostic(error.to_diagnostic(&self.sources), file)))?;
+
+        let mut analyzer: SemanticAnalyzer = SemanticAnalyzer::new(&[]);
+        let mut checker: TypeChecker = TypeChecker::new(&[]);
+        self.declare_known_names(&mut analyzer, &mut checker);
+        analyzer.analyze_standalone(&expression)
+            .map_err(|errors| compile_errors(&errors, &self.sources, |_| file, |error, sources| error.to_diagnostic(sources)))?;
+        checker.check_standalone(&expression)
+            .map_err(|errors| compile_errors(&errors, &self.sources, |_| file, |error, sources| error.to_diagnostic(sources)))?;
+        let kinds: Kinds = checker.into_kinds();
+
+        let function: Function = Compiler::new(&mut self.program).with_kinds(kinds).compile_standalone(&expression)
+            .map_err(|error| EngineError::Compile(vec![ScriptError::from_diagnostic(error.to_diagnostic(&self.sources), file)]))?;
+
+        let value: Value = self.vm.run(&self.program, Rc::new(function)).map_err(|error| self.runtime_error(error))?;
+        T::from_value(value)
+    }
+
+    pub fn get_global<T: FromValue>(&self, name: &str) -> Result<T, EngineError> {
+        let value: Value = self.program.global(name)
+            .and_then(|slot| self.vm.global(slot))
+            .cloned()
+            .ok_or_else(|| EngineError::UndefinedGlobal(name.to_string()))?;
+        T::from_value(value)
+    }
+
+    // Assigns a global, declaring it with the type of the value if scripts don't know it yet.
+    // A global scripts already know keeps its type, the value must have it
+    pub fn set_global(&mut self, name: &str, value: impl IntoValue) -> Result<(), EngineError> {
+        let slot: Option<usize> = self.program.global(name);
+        let (kind, is_array) = match slot {
+            Some(slot) => (self.program.globals[slot].kind, self.program.globals[slot].is_array),
+            None => value.kind(),
+        };
+
+        let value: Value = value.into_value();
+        // A new global gets the struct or enum of its value
+        let type_: Option<usize> = match slot {
+            Some(slot) => self.program.globals[slot].type_,
+            None => self.type_of(&value),
+        };
+        let definition: Option<&Definition> = type_.map(|type_| &self.program.types[type_].definition);
+        check_value(&value, kind, is_array, definition)?;
+
+        let slot: usize = slot.unwrap_or_else(|| self.program.declare_global(name, kind, is_array, type_));
+        self.vm.set_global(slot, value.cast(kind));
+        Ok(())
+    }
+
+    pub fn call_fn<T: FromValue>(&mut self, name: &str, arguments: impl IntoArguments) -> Result<T, EngineError> {
+        let arguments: Vec<Value> = arguments.into_arguments();
+        let slot: usize = self.program.function(name)
+            .ok_or_else(|| EngineError::UndefinedFunction(name.to_string()))?;
+
+        // Natives check their own arguments
+        if let Some(Some(Callable::Script(function))) = self.program.functions.get(slot) {
+            if function.parameters.len() != arguments.len() {
+                return Err(EngineError::ArgumentCount {
+                    name: name.to_string(),
+                    expected: function.parameters.len(),
+                    found: arguments.len(),
+                });
+            }
+
+            for (argument, (kind, is_array)) in arguments.iter().zip(&function.parameters) {
+                check_value(argument, *kind, *is_array, None)?;
+            }
+        }
+
+        let value: Value = self.vm.call(&self.program, slot, arguments).map_err(|error| self.runtime_error(error))?;
+        T::from_value(value)
+    }
+
+    // The span of a runtime error knows which script the failing code comes from
+    fn runtime_error(&self, error: RuntimeError) -> EngineError {
+        EngineError::Runtime(ScriptError::from_diagnostic(error.to_diagnostic(&self.sources), error.span.file))
+    }
+
+    // The slot of the struct or enum a value holds, the first element's one for arrays
+    fn type_of(&self, value: &Value) -> Option<usize> {
+        let value: &Value = match value {
+            Value::Array(values) => values.first()?,
+            value => value,
+        };
+        self.program.types.iter().position(|type_| match (value, &type_.definition) {
+            (Value::Struct(value, _), Definition::Struct(definition)) => Rc::ptr_eq(value, definition),
+            (Value::Enum(value, ..), Definition::Enum(definition)) => Rc::ptr_eq(value, definition),
+            _ => false,
+        })
+    }
+
+    // Functions of earlier scripts have their declared types, natives have the ones of their Rust closure
+    fn signature(&self, name: &str) -> Option<Signature> {
+        let slot: usize = self.program.function(name)?;
+        match self.program.functions[slot].as_ref()? {
+            Callable::Script(function) => Some((
+                function.parameters.iter().map(|(kind, is_array)| ValueType::from_kind(*kind, *is_array)).collect(),
+                ValueType::from_kind(function.return_kind, false),
+            )),
+            Callable::Native(_) => self.signatures.get(name).cloned(),
+        }
+    }
+
+    // Names declared by the host or earlier scripts are known to the semantic passes
+    fn declare_known_names<'a>(&'a self, analyzer: &mut SemanticAnalyzer<'a>, checker: &mut TypeChecker<'a>) {
+        for name in self.program.function_names() {
+            analyzer.declare_native(name);
+            match self.signature(name) {
+                Some(signature) => checker.declare_signature(name, signature),
+                None => checker.declare_native(name),
+            }
+        }
+        for global in &self.program.globals {
+            analyzer.declare_global(&global.name, global.kind, global.is_array);
+            checker.declare_global(&global.name, global.kind, global.is_array);
+        }
+        for type_ in &self.program.types {
+            match &type_.definition {
+                Definition::Struct(struct_) => {
+                    analyzer.declare_type(&struct_.name);
+                    checker.declare_type(&struct_.name);
+                },
+                Definition::Enum(enum_) => {
+                    let variants = || enum_.variants.iter().map(|(name, _)| &**name);
+                    analyzer.declare_enum(&enum_.name, variants());
+                    checker.declare_enum(&enum_.name, variants());
+                },
+            }
+        }
+    }
+}
+
+fn compile_errors<E>(errors: &[E], sources: &SourceMap, file: impl Fn(&E) -> FileId, to_diagnostic: impl for<'e> Fn(&'e E, &'e SourceMap) -> Diagnostic<'e>) -> EngineError {
+    EngineError::Compile(errors.iter()
+        .map(|error| ScriptError::from_diagnostic(to_diagnostic(error, sources), file(error)))
+        .collect())
+}
+
+// Like in every script only the first syntax error is reported, the imports that
+// failed are all reported when there is none
+fn load_error(errors: Vec<ScriptError>) -> EngineError {
+    let is_import = |error: &ScriptError| matches!(error.code, ErrorCode::EM001 | ErrorCode::EM002 | ErrorCode::EM003);
+
+    match errors.iter().position(|error| !is_import(error)) {
+        Some(index) => EngineError::Syntax(errors[index].clone()),
+        None => EngineError::Compile(errors),
+    }
+}
@@ -1,5 +1,6 @@
-use std::fs::File;
-use std::io::{self, BufRead, BufReader};
+use std::io::{self, Write};
+
+use crate::source::{FileId, SourceMap};
 
 trait DiagnosticCode where Self: std::fmt::Debug {
     fn message(&self) -> &'static str;
@@ -8,61 +9,208 @@ trait DiagnosticCode where Self: std::fmt::Debug {
     }
 }
 
-#[derive(Debug, PartialEq, Clone, Copy)]
-pub enum ErrorCode {
-    ET001,
-    ET002,
-    ET003,
-    ET004,
-    ET005,
-    ET006,
-    ET007,
-    ET008,
-    ET009,
-    ET010,
-    ET011,
-    ET012,
-    EP000,
-    EP001,
-    EP002,
-    EP003,
-    EP004,
-    EP005,
-    EP006,
-    EP007,
-    EP008,
-    EP009,
-    EP010,
-    EP011,
-    EP012,
-    EP013,
-    EP014,
-    EP015,
-    EP016,
-    EP017,
-    EP018,
-    EP019,
-    EP020,
-    EP021,
-    EP022,
-    EP023,
-    EP024,
-    EP025,
-    EP026,
-    EP027,
-    EP028,
-    EP029,
-    EP030,
-    EP031,
-    EP032,
-    EP033,
-    EP034,
+// Declares an enum of codes with ALL, every code in declaration order, so a new code can't be left out of it
+macro_rules! codes {
+    (#[$attribute:meta] $name:ident { $($code:ident,)* }) => {
+        #[$attribute]
+        pub enum $name {
+            $($code,)*
+        }
+
+        impl $name {
+            pub const ALL: [$name; [$(stringify!($code)),*].len()] = [$(Self::$code),*];
+        }
+    };
 }
 
-// #[derive(Debug, PartialEq, Clone, Copy)]
-// pub enum WarningCode {
-//     WT000
-// }
+codes! {
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    ErrorCode {
+        ET001,
+        ET002,
+        ET003,
+        ET004,
+        ET005,
+        ET006,
+        ET007,
+        ET008,
+        ET009,
+        ET010,
+        ET011,
+        ET012,
+        EP000,
+        EP001,
+        EP002,
+        EP003,
+        EP004,
+        EP005,
+        EP006,
+        EP007,
+        EP008,
+        EP009,
+        EP010,
+        EP011,
+        EP012,
+        EP013,
+        EP014,
+        EP015,
+        EP016,
+        EP017,
+        EP018,
+        EP019,
+        EP020,
+        EP021,
+        EP022,
+        EP023,
+        EP024,
+        EP025,
+        EP026,
+        EP027,
+        EP028,
+        EP029,
+        EP030,
+        EP031,
+        EP032,
+        EP033,
+        EP034,
+        EP035,
+        EP036,
+        EP037,
+        EP038,
+        EP039,
+        EP040,
+        EP041,
+        EP042,
+        EP043,
+        EP044,
+        EP045,
+        EP046,
+        EP047,
+        EP048,
+        EP049,
+        EP050,
+        EP051,
+        EP052,
+        EP053,
+        EP054,
+        EP055,
+        EP056,
+        EP057,
+        EP058,
+        EP059,
+        EP060,
+        EP061,
+        EP062,
+        EP063,
+        EP064,
+        EP065,
+        EP066,
+        EP067,
+        EP068,
+        EM001,
+        EM002,
+        EM003,
+        ES001,
+        ES002,
+        ES003,
+        ES004,
+        ES005,
+        ES006,
+        ES007,
+        ES008,
+        ES009,
+        ES010,
+        ES011,
+        ES012,
+        ES013,
+        ES014,
+        ES015,
+        ES016,
+        ES017,
+        ES018,
+        ES019,
+        ES020,
+        ES021,
+        ES022,
+        ES023,
+        ES024,
+        ES025,
+        EC001,
+        EC002,
+        EC003,
+        EC004,
+        EC005,
+        EC006,
+        EC007,
+        EC008,
+        EC009,
+        EC010,
+        EC011,
+        EC012,
+        EC013,
+        EC014,
+        EC015,
+        EC016,
+        EC017,
+        EC018,
+        EC019,
+        EC020,
+        EC021,
+        EC022,
+        EC023,
+        EC024,
+        EC025,
+        EC026,
+        EC027,
+        EC028,
+        EC029,
+        ER001,
+        ER002,
+        ER003,
+        ER004,
+        ER005,
+        ER006,
+        ER007,
+        ER008,
+        ER009,
+        ER010,
+        ER011,
+        ER012,
+        EB001,
+        EB002,
+    }
+}
+
+codes! {
+    #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+    WarningCode {
+        WL001,
+        WL002,
+        WL003,
+        WL004,
+        WL005,
+        WL006,
+    }
+}
+
+impl WarningCode {
+    // Name used by the -W/-A/-D flags
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::WL001 => "unused-variables",
+            Self::WL002 => "unused-parameters",
+            Self::WL003 => "unused-functions",
+            Self::WL004 => "shadowing",
+            Self::WL005 => "unreachable-code",
+            Self::WL006 => "constant-condition",
+        }
+    }
+
+    // Accepts both the name and the code, like 'shadowing' or 'WL004'
+    pub fn from_name(name: &str) -> Option<WarningCode> {
+        Self::ALL.into_iter().find(|code| code.name() == name || code.code_str() == name)
+    }
+}
 
 impl DiagnosticCode for ErrorCode {
     fn message(&self) -> &'static str {
@@ -115,40 +263,162 @@ impl DiagnosticCode for ErrorCode {
             Self::EP029 => "Expected name after parameter type",
             Self::EP030 => "Expected another parameter or ')'",
             Self::EP031 => "Expected ')' after expression",            
-            Self::EP032 => "Expected identifier, literal, '(', '-', or '!'",
+            Self::EP032 => "Expected identifier, literal, '(', '[', '-', or '!'",
             Self::EP033 => "Expected ']' for array access",
             Self::EP034 => "Expected a valid function name before '('",
+            Self::EP035 => "Unexpected token after expression",
+            Self::EP036 => "Expected a statement",
+            Self::EP037 => "Expected a variable name after 'for'",
+            Self::EP038 => "Expected 'in' after the loop variable",
+            Self::EP039 => "Expected a range or an array after 'in'",
+            Self::EP040 => "Expected the end of the range after '..'",
+            Self::EP041 => "Expected '{' after the range or array of 'for'",
+            Self::EP042 => "Expected a name after 'namespace'",
+            Self::EP043 => "Expected '{' after the namespace name",
+            Self::EP044 => "Expected a name after '::'",
+            Self::EP045 => "Expected '(' after a qualified name",
+            Self::EP046 => "Expected a file path or a module name after 'import'",
+            Self::EP047 => "Expected ';' after an import",
+            Self::EP048 => "Expected a name after 'struct'",
+            Self::EP049 => "Expected '{' after the struct name",
+            Self::EP050 => "Expected a field declaration like 'i32 x;' in a struct",
+            Self::EP051 => "Expected a field name after '.'",
+            Self::EP052 => "Expected 'field = value' in a struct literal",
+            Self::EP053 => "Expected ',' or '}' after a field value",
+            Self::EP054 => "Expected a name after 'enum'",
+            Self::EP055 => "Expected '{' after the enum name",
+            Self::EP056 => "Expected a variant name in an enum",
+            Self::EP057 => "Expected ',' or '}' after a variant",
+            Self::EP058 => "Expected the type of a value the variant holds",
+            Self::EP059 => "Expected ',' or ')' after the type of a variant value",
+            Self::EP060 => "Expected a value after 'match'",
+            Self::EP061 => "Expected '{' after the matched value",
+            Self::EP062 => "Expected a pattern in a match arm",
+            Self::EP063 => "Expected '=>' after a pattern",
+            Self::EP064 => "Expected a name or '_' to bind a variant value",
+            Self::EP065 => "Expected ',' or ')' after a bound name",
+            Self::EP066 => "Expected the body or the value of a match arm after '=>'",
+            Self::EP067 => "Expected ',' or '}' after the value of a match arm",
+            Self::EP068 => "Expected ',' or ']' after an array element",
+
+            // === Module Errors === //
+            Self::EM001 => "Imported module not found",
+            Self::EM002 => "Modules import each other in a cycle",
+            Self::EM003 => "Imported module couldn't be read",
+
+            // === Semantic Errors === //
+            Self::ES001 => "Use of undeclared variable",
+            Self::ES002 => "Call to undeclared function",
+            Self::ES003 => "Name is already declared in this scope",
+            Self::ES004 => "Called name is not a function",
+            Self::ES005 => "Function used as a variable",
+            Self::ES006 => "Functions can only be declared at the top level or in a namespace",
+            Self::ES007 => "Loop control keyword outside of a loop",
+            Self::ES008 => "Return statement outside of a function",
+            Self::ES009 => "Namespaces can only be declared at the top level or in another namespace",
+            Self::ES010 => "Namespaces can only contain functions, constants, structs, enums and namespaces",
+            Self::ES011 => "Use of undeclared namespace",
+            Self::ES012 => "Namespace used as a value",
+            Self::ES013 => "Imports can only be at the top level of a file",
+            Self::ES014 => "Structs can only be declared at the top level or in a namespace",
+            Self::ES015 => "Use of undeclared type",
+            Self::ES016 => "Name used as a type is not a struct or an enum",
+            Self::ES017 => "Field is already declared in this struct",
+            Self::ES018 => "Struct or enum used as a value",
+            Self::ES019 => "Enums can only be declared at the top level or in a namespace",
+            Self::ES020 => "Variant is already declared in this enum",
+            Self::ES021 => "Enum has no variant with this name",
+            Self::ES022 => "Pattern is not a variant of an enum",
+            Self::ES023 => "Only structs can be built with a literal",
+            Self::ES024 => "Assignment to a constant",
+            Self::ES025 => "Constant without a value",
+
+            // === Type Errors === //
+            Self::EC001 => "Mismatched types in variable declaration",
+            Self::EC002 => "Mismatched types in assignment",
+            Self::EC003 => "Condition must be of type 'bool'",
+            Self::EC004 => "Mismatched argument type in function call",
+            Self::EC005 => "Wrong number of arguments in function call",
+            Self::EC006 => "Invalid operand types for binary operator",
+            Self::EC007 => "Invalid operand type for unary operator",
+            Self::EC008 => "Mismatched return type",
+            Self::EC009 => "Only arrays can be indexed",
+            Self::EC010 => "Array index must be an integer",
+            Self::EC011 => "Array size must be an integer",
+            Self::EC012 => "Function without a return value used in an expression",
+            Self::EC013 => "For loops can only iterate over an integer range or an array",
+            Self::EC014 => "Mismatched type of the loop variable",
+            Self::EC015 => "Struct has no field with this name",
+            Self::EC016 => "Only structs have fields",
+            Self::EC017 => "Struct literal is missing fields",
+            Self::EC018 => "Field is set more than once in a struct literal",
+            Self::EC019 => "Mismatched type of a field value",
+            Self::EC020 => "Struct or enum contains itself",
+            Self::EC021 => "Array fields, variant values and parameters must have a literal size",
+            Self::EC022 => "Wrong number of values for a variant",
+            Self::EC023 => "Mismatched type of a variant value",
+            Self::EC024 => "Pattern doesn't match the type of the matched value",
+            Self::EC025 => "Match doesn't cover every value",
+            Self::EC026 => "Match arm can never be reached",
+            Self::EC027 => "Match arms have different types",
+            Self::EC028 => "Array elements have different types",
+            Self::EC029 => "Integer literal out of range",
+
+            // === Runtime Errors === //
+            Self::ER001 => "Division by zero",
+            Self::ER002 => "Array index out of bounds",
+            Self::ER003 => "Loop control keyword outside of a loop",
+            Self::ER004 => "Return statement outside of a function",
+            Self::ER005 => "Function ended without returning a value",
+            Self::ER006 => "Invalid literal value",
+            Self::ER007 => "Native function failed",
+            Self::ER008 => "Undefined name",
+            Self::ER009 => "Operation not supported by the operand types",
+            Self::ER010 => "Maximum call depth exceeded",
+            Self::ER011 => "No match arm matches the value",
+            Self::ER012 => "Invalid array size",
+
+            // === Bytecode Errors === //
+            Self::EB001 => "Name not defined at compile time",
+            Self::EB002 => "Bytecode limit exceeded",
 
         }
     }
 }
 
-// impl DiagnosticCode for WarningCode {
-//     fn message(&self) -> &'static str {
-//         match self {
-//             WarningCode::WT000 => "Currently there are no warning"
-//         }
-//     }
-// }
+impl DiagnosticCode for WarningCode {
+    fn message(&self) -> &'static str {
+        match self {
+            Self::WL001 => "Variable is never used",
+            Self::WL002 => "Parameter is never used",
+            Self::WL003 => "Function is never called",
+            Self::WL004 => "Variable shadows a variable of an outer scope",
+            Self::WL005 => "Unreachable statement",
+            Self::WL006 => "Condition is always the same",
+        }
+    }
+}
 
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum DiagnosticKind {
     Error(ErrorCode),
-    // Warning(WarningCode),
+    Warning(WarningCode),
+    // A warning promoted to an error with -D, it stops the compilation
+    Denied(WarningCode),
 }
 
 impl DiagnosticKind {
     pub fn kind_to_str(&self) -> &'static str {
         match self {
-            Self::Error(_) => "error",
-            // Self::Warning(_) => "warning"
+            Self::Error(_) | Self::Denied(_) => "error",
+            Self::Warning(_) => "warning"
         }
     }
 
     pub fn code_to_string(&self) -> String {
         match self {
             Self::Error(code) => code.code_str(),
-            // Self::Warning(code) => code.code_str(),
+            Self::Warning(code) | Self::Denied(code) => code.code_str(),
         }
     }
 
@@ -156,7 +426,7 @@ impl DiagnosticKind {
     pub fn message(&self) -> &'static str {
         match self {
             Self::Error(code) => code.message(),
-            //Self::Warning(code) => code.message(),
+            Self::Warning(code) | Self::Denied(code) => code.message(),
         }
     }
 
@@ -166,7 +436,34 @@ impl DiagnosticKind {
 pub struct DiagnosticInfo<'a> {
     pub filename: &'a str,
     pub line: usize,
-    pub column: usize
+    pub column: usize,
+    // Byte offsets of the offending code in the source
+    pub start: usize,
+    pub end: usize,
+    // The line the error is on, taken from the SourceMap
+    pub snippet: Option<&'a str>,
+}
+
+impl<'a> DiagnosticInfo<'a> {
+    pub fn new(sources: &'a SourceMap, file: FileId, start: usize, end: usize, line: usize, column: usize) -> Self {
+        let source = sources.get(file);
+
+        Self {
+            filename: source.map_or("<unknown>", |source| source.name.as_str()),
+            line,
+            column,
+            start,
+            end,
+            snippet: source.and_then(|source| source.line(line)),
+        }
+    }
+
+    // Later passes only keep byte offsets, so the line and column
+    // are recovered from the registered source
+    pub fn from_span(sources: &'a SourceMap, file: FileId, start: usize, end: usize) -> Self {
+        let (line, column) = sources.get(file).map_or((1, 1), |source| source.location(start));
+        Self::new(sources, file, start, end, line, column)
+    }
 }
 
 pub struct Diagnostic<'a> {
@@ -176,7 +473,13 @@ pub struct Diagnostic<'a> {
 }
 
 impl<'a> Diagnostic<'a> {
+    // A closed stdout, like 'rcompiler check file.rc | head', only stops the output
     pub fn print(&self) {
+        let _ = self.write(&mut io::stdout().lock());
+    }
+
+    // The human readable form, with the line of source and a caret under the column
+    pub fn write(&self, out: &mut impl Write) -> io::Result<()> {
         let red: &str = "\x1b[31;1m";
         let cyan: &str = "\x1b[36m";
         let yellow: &str = "\x1b[33m";
@@ -186,42 +489,74 @@ impl<'a> Diagnostic<'a> {
         let kind: &str = self.kind.kind_to_str();
         let code: String = self.kind.code_to_string();
         let message: &str = self.kind.message();
+        let color: &str = if matches!(self.kind, DiagnosticKind::Warning(_)) { yellow } else { red };
 
-        println!("{red}{kind}[{code}]{reset}: {bold}{message}{reset}");
+        writeln!(out, "{color}{kind}[{code}]{reset}: {bold}{message}{reset}")?;
         
-        println!("{cyan}  -->{reset} {}:{}:{}", self.info.filename, self.info.line, self.info.column);
+        writeln!(out, "{cyan}  -->{reset} {}:{}:{}", self.info.filename, self.info.line, self.info.column)?;
 
-        if let Ok(line_content) = self.read_line(self.info.line) {
+        if let Some(line_content) = self.info.snippet {
             let line_num_str: String = self.info.line.to_string();
             let gutter_width: usize = line_num_str.len();
             let gutter_padding: String = " ".repeat(gutter_width);
 
-            println!("{cyan} {} |{reset}", gutter_padding);
+            writeln!(out, "{cyan} {} |{reset}", gutter_padding)?;
 
-            println!("{cyan} {} |{reset} {}", line_num_str, line_content);
+            writeln!(out, "{cyan} {} |{reset} {}", line_num_str, line_content)?;
 
-            print!("{cyan} {} |{reset} ", gutter_padding);
+            write!(out, "{cyan} {} |{reset} ", gutter_padding)?;
             
-            for _ in 0..(self.info.column - gutter_width) {
-                print!(" ");
+            // The gutter is already printed, the caret is under the column itself
+            for _ in 0..self.info.column.saturating_sub(1) {
+                write!(out, " ")?;
             }
 
             if let Some(h) = &self.hint {
-                println!("{yellow}^__ {}{reset}", h);
+                writeln!(out, "{yellow}^__ {}{reset}", h)?;
             } 
             else {
-                println!("{yellow}^{reset}");
+                writeln!(out, "{yellow}^{reset}")?;
             }
 
-            println!("{cyan} {} |{reset}", gutter_padding);
+            writeln!(out, "{cyan} {} |{reset}", gutter_padding)?;
         }
+
+        Ok(())
     }
 
-    fn read_line(&self, line: usize) -> io::Result<String> {
-        let file: File = File::open(&self.info.filename)?;
-        let reader: BufReader<File> = BufReader::new(file);
+    // One line JSON object, the schema is documented in the README and only grows new fields
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"code\":{},\"severity\":{},\"message\":{},\"hint\":{},\"file\":{},\"line\":{},\"column\":{},\"span\":{{\"start\":{},\"end\":{}}}}}",
+            json_string(&self.kind.code_to_string()),
+            json_string(self.kind.kind_to_str()),
+            json_string(self.kind.message()),
+            self.hint.filter(|hint| !hint.is_empty()).map_or("null".to_string(), json_string),
+            json_string(self.info.filename),
+            self.info.line,
+            self.info.column,
+            self.info.start,
+            self.info.end,
+        )
+    }
+}
+
+fn json_string(text: &str) -> String {
+    let mut escaped: String = String::with_capacity(text.len() + 2);
+    escaped.push('"');
 
-        reader.lines().nth(line - 1)
-            .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "Line not found"))?
+    for character in text.chars() {
+        match character {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            character if (character as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", character as u32)),
+            character => escaped.push(character),
+        }
     }
+
+    escaped.push('"');
+    escaped
 }
\ No newline at end of file
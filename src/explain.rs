@@ -0,0 +1,1153 @@
+use crate::error::{DiagnosticKind, ErrorCode, WarningCode};
+
+// Long form documentation of a diagnostic code, shown by 'rcompiler explain'
+#[derive(Debug, Clone, Copy)]
+pub struct Explanation {
+    // Paragraphs separated by an empty line
+    pub text: &'static str,
+    // Code that reports the diagnostic
+    pub wrong: &'static str,
+    // The same code, fixed
+    pub right: &'static str,
+}
+
+// Looks up a code like 'EP013', lowercase is accepted too
+pub fn explain(code: &str) -> Option<(DiagnosticKind, Explanation)> {
+    let kind: DiagnosticKind = ErrorCode::ALL.into_iter().map(DiagnosticKind::Error)
+        .chain(WarningCode::ALL.into_iter().map(DiagnosticKind::Warning))
+        .find(|kind| kind.code_to_string().eq_ignore_ascii_case(code))?;
+    Some((kind, explanation(kind)))
+}
+
+// The entry of every code, a code without one doesn't compile
+pub fn explanation(kind: DiagnosticKind) -> Explanation {
+    use ErrorCode::*;
+    use WarningCode::*;
+
+    match kind {
+        DiagnosticKind::Error(code) => match code {
+            // === Tokenizer Errors === //
+            ET001 => Explanation {
+                text: "A number was written with more than one decimal point.
+
+Float literals have a single '.' between the integer and the fractional part. Versions, dates and similar values with several dots can't be numbers, write them as a string instead.",
+                wrong: "f32 x = 1.2.3;",
+                right: "f32 x = 1.23;",
+            },
+            ET002 => Explanation {
+                text: "A number is immediately followed by letters or underscores.
+
+Identifiers can't start with a digit and numbers have no suffixes, so the tokenizer doesn't know what '2x' is meant to be. Put an operator between the number and the name, or rename the identifier so it starts with a letter.",
+                wrong: "i32 x = 1;\ni32 y = 2x;",
+                right: "i32 x = 1;\ni32 y = 2 * x;",
+            },
+            ET003 => Explanation {
+                text: "A float literal ends with a '.' and has no fractional part.
+
+The digits after the decimal point are required, even when the fractional part is zero.",
+                wrong: "f32 x = 3.;",
+                right: "f32 x = 3.0;",
+            },
+            ET004 => Explanation {
+                text: "The source contains a character that isn't part of the language.
+
+Outside of string and character literals only identifiers, numbers, operators and punctuation are allowed. This usually comes from a typo or from text pasted from another language.",
+                wrong: "i32 x = 1 @ 2;",
+                right: "i32 x = 1 + 2;",
+            },
+            ET005 => Explanation {
+                text: "A character literal contains more than one character.
+
+Single quotes make a 'char', which holds exactly one character or one escape sequence. Text with more characters is a 'str' and uses double quotes.",
+                wrong: "char c = 'ab';",
+                right: "str s = \"ab\";",
+            },
+            ET006 => Explanation {
+                text: "A character literal has nothing between its quotes.
+
+A 'char' always holds one character. Use '\\0' for the null character, or a 'str' if an empty text is needed.",
+                wrong: "char c = '';",
+                right: "char c = '\\0';",
+            },
+            ET007 => Explanation {
+                text: "A character literal is never closed.
+
+Character literals can't span lines, so the literal ends with the line when the closing single quote is missing.",
+                wrong: "char c = 'a;",
+                right: "char c = 'a';",
+            },
+            ET008 => Explanation {
+                text: "The only quote that could close a character literal is escaped.
+
+'\\'' is an escaped quote, so in '\\' the backslash and the last quote form a single escape sequence and the literal is never closed. To write a backslash, escape it too.",
+                wrong: "char c = '\\'",
+                right: "char c = '\\\\';",
+            },
+            ET009 => Explanation {
+                text: "A backslash is followed by a character that doesn't form an escape sequence.
+
+The supported escapes are \\a, \\b, \\f, \\n, \\r, \\t, \\v, \\\\, \\', \\\", \\?, \\0, octal escapes like \\101 and hexadecimal escapes like \\x41. To write a backslash, escape it.",
+                wrong: "str path = \"C:\\qdir\";",
+                right: "str path = \"C:\\\\qdir\";",
+            },
+            ET010 => Explanation {
+                text: "An octal escape has more than three digits or contains a digit that isn't octal.
+
+Octal escapes start with a digit from 1 to 7 and have at most three digits from 0 to 7, like '\\101' for 'A'.",
+                wrong: "char c = '\\1019';",
+                right: "char c = '\\101';",
+            },
+            ET011 => Explanation {
+                text: "A hexadecimal escape is empty, too long or contains a character that isn't a hex digit.
+
+Hexadecimal escapes are '\\x' followed by one or two hex digits, like '\\x41' for 'A'.",
+                wrong: "char c = '\\xZZ';",
+                right: "char c = '\\x41';",
+            },
+            ET012 => Explanation {
+                text: "A string literal is never closed.
+
+String literals can't span lines, so the literal ends with the line when the closing double quote is missing. Use '\\n' to put a line break inside a string.",
+                wrong: "str s = \"hello;",
+                right: "str s = \"hello\";",
+            },
+
+            // === Parser Errors === //
+            EP000 => Explanation {
+                text: "An 'else' or 'elif' doesn't follow the body of an 'if'.
+
+Both keywords continue an 'if' statement, so they must come right after the '}' of an 'if' or of another 'elif'. Any statement in between ends the 'if'.",
+                wrong: "i32 x = 1;\nelse {\n    x = 2;\n}",
+                right: "i32 x = 1;\nif x > 0 {\n    x = 2;\n}\nelse {\n    x = 3;\n}",
+            },
+            EP001 => Explanation {
+                text: "The brackets of an array type are empty.
+
+Arrays have a fixed size, written as an expression between the brackets of the type.",
+                wrong: "i32[] values;",
+                right: "i32[4] values;",
+            },
+            EP002 => Explanation {
+                text: "The size of an array type isn't followed by ']'.
+
+The size is a single expression, the bracket must be closed right after it.",
+                wrong: "i32[4 values;",
+                right: "i32[4] values;",
+            },
+            EP003 => Explanation {
+                text: "A type isn't followed by the name of the variable being declared.
+
+Declarations are written as the type followed by the name, and optionally by '=' and a value.",
+                wrong: "i32 = 5;",
+                right: "i32 x = 5;",
+            },
+            EP004 => Explanation {
+                text: "The '=' of a declaration isn't followed by a value.
+
+Either write the initial value after '=', or drop the '=' to declare the variable without one.",
+                wrong: "i32 x = ;",
+                right: "i32 x = 0;",
+            },
+            EP005 => Explanation {
+                text: "A declaration with a value doesn't end with ';'.
+
+Every statement that isn't a block ends with a semicolon, the value of a declaration ends at the first token that can't continue the expression.",
+                wrong: "i32 x = 5\ni32 y = x;",
+                right: "i32 x = 5;\ni32 y = x;",
+            },
+            EP006 => Explanation {
+                text: "The name in a declaration is followed by something other than ';' or '='.
+
+A declaration declares a single variable, so after the name only the initial value or the end of the statement can follow.",
+                wrong: "i32 x 5;",
+                right: "i32 x = 5;",
+            },
+            EP007 => Explanation {
+                text: "An 'if' or 'elif' has no condition.
+
+The condition is an expression of type 'bool' between the keyword and the '{' of the body, no parentheses are needed.",
+                wrong: "if {\n}",
+                right: "bool ready = true;\nif ready {\n}",
+            },
+            EP008 => Explanation {
+                text: "The condition of an 'if' isn't followed by the '{' of its body.
+
+Bodies are always blocks, even when they contain a single statement.",
+                wrong: "bool ready = true;\nif ready\n    println(1);",
+                right: "bool ready = true;\nif ready {\n    println(1);\n}",
+            },
+            EP009 => Explanation {
+                text: "An 'else' isn't followed by the '{' of its body.
+
+An 'else' never has a condition, use 'elif' for a branch that needs one.",
+                wrong: "i32 x = 1;\nif x > 0 {\n}\nelse x < 0 {\n}",
+                right: "i32 x = 1;\nif x > 0 {\n}\nelif x < 0 {\n}",
+            },
+            EP010 => Explanation {
+                text: "A body opened with '{' is never closed.
+
+The file ended while the body was still open. Check that every '{' has a matching '}', the missing one is often the body of a function or a loop above the reported line.",
+                wrong: "while false {\n    println(1);",
+                right: "while false {\n    println(1);\n}",
+            },
+            EP011 => Explanation {
+                text: "A 'while' has no condition.
+
+The condition is an expression of type 'bool' between 'while' and the '{' of the body. Write 'while true' for a loop that only ends with 'break'.",
+                wrong: "while {\n    break;\n}",
+                right: "while true {\n    break;\n}",
+            },
+            EP012 => Explanation {
+                text: "The condition of a 'while' isn't followed by the '{' of its body.
+
+Bodies are always blocks, even when they contain a single statement.",
+                wrong: "i32 i = 0;\nwhile i < 3\n    i += 1;",
+                right: "i32 i = 0;\nwhile i < 3 {\n    i += 1;\n}",
+            },
+            EP013 => Explanation {
+                text: "A statement starts with a name that isn't followed by an assignment or a call.
+
+Statements that start with a name either assign it, or one of its elements or fields like 'a[0]' and 'p.x', with '=' or a compound operator like '+=', or call it with '()'. An expression on its own, like a variable or an addition, isn't a statement because its value would be lost.",
+                wrong: "i32 x = 1;\nx + 1;",
+                right: "i32 x = 1;\nx = x + 1;",
+            },
+            EP014 => Explanation {
+                text: "The arguments of a call are never closed with ')'.
+
+The file ended while the argument list was still open.",
+                wrong: "println(1",
+                right: "println(1);",
+            },
+            EP015 => Explanation {
+                text: "A call has something that isn't an expression where an argument should be.
+
+Arguments are expressions separated by commas. This usually means the ')' closing the call is missing, so the next token is read as an argument.",
+                wrong: "println(;",
+                right: "println(1);",
+            },
+            EP016 => Explanation {
+                text: "A ',' in the arguments of a call isn't followed by another argument.
+
+Trailing commas aren't allowed in argument lists, the ')' must come right after the last argument.",
+                wrong: "println(1, 2,);",
+                right: "println(1, 2);",
+            },
+            EP017 => Explanation {
+                text: "A call used as a statement doesn't end with ';'.
+
+Every statement that isn't a block ends with a semicolon.",
+                wrong: "println(1)\nprintln(2);",
+                right: "println(1);\nprintln(2);",
+            },
+            EP018 => Explanation {
+                text: "An assignment operator isn't followed by a value.
+
+'=' and the compound operators like '+=' need the expression to assign on their right.",
+                wrong: "i32 x = 1;\nx = ;",
+                right: "i32 x = 1;\nx = 2;",
+            },
+            EP019 => Explanation {
+                text: "An assignment doesn't end with ';'.
+
+Every statement that isn't a block ends with a semicolon, the value ends at the first token that can't continue the expression.",
+                wrong: "i32 x = 1;\nx = 2\nx = 3;",
+                right: "i32 x = 1;\nx = 2;\nx = 3;",
+            },
+            EP020 => Explanation {
+                text: "A 'break' or 'continue' doesn't end with ';'.
+
+Loop control keywords are statements on their own and end with a semicolon, they take no value.",
+                wrong: "while true {\n    break\n}",
+                right: "while true {\n    break;\n}",
+            },
+            EP021 => Explanation {
+                text: "A 'return' is followed by something that is neither a value nor ';'.
+
+'return;' leaves a function without a value, 'return' followed by an expression returns that value.",
+                wrong: "fn f() {\n    return }\n}\nf();",
+                right: "fn f() {\n    return;\n}\nf();",
+            },
+            EP022 => Explanation {
+                text: "A 'return' with a value doesn't end with ';'.
+
+The returned value ends at the first token that can't continue the expression, the statement must end there.",
+                wrong: "fn f() i32 {\n    return 1\n}\nprintln(f());",
+                right: "fn f() i32 {\n    return 1;\n}\nprintln(f());",
+            },
+            EP023 => Explanation {
+                text: "'fn' isn't followed by the name of the function.
+
+Functions are always named, there are no anonymous functions.",
+                wrong: "fn (i32 x) {\n}",
+                right: "fn f(i32 x) {\n}",
+            },
+            EP024 => Explanation {
+                text: "The name of a function isn't followed by its parameter list.
+
+The parentheses are required even when the function takes no parameters.",
+                wrong: "fn f {\n}",
+                right: "fn f() {\n}",
+            },
+            EP025 => Explanation {
+                text: "The parameters of a function are followed by something that isn't a type or the body.
+
+The return type comes right after ')', without an arrow or a colon. Functions that return nothing omit it.",
+                wrong: "fn f() -> i32 {\n    return 1;\n}",
+                right: "fn f() i32 {\n    return 1;\n}",
+            },
+            EP026 => Explanation {
+                text: "The signature of a function isn't followed by the '{' of its body.
+
+Every function has a body, there are no forward declarations: functions can be called before the line that defines them.",
+                wrong: "fn f() i32;",
+                right: "fn f() i32 {\n    return 1;\n}",
+            },
+            EP027 => Explanation {
+                text: "The parameter list of a function is never closed with ')'.
+
+The file ended while the parameter list was still open.",
+                wrong: "fn f(i32 x",
+                right: "fn f(i32 x) {\n}",
+            },
+            EP028 => Explanation {
+                text: "A parameter doesn't start with a type.
+
+Parameters are written like declarations, the type comes before the name.",
+                wrong: "fn f(1) {\n}",
+                right: "fn f(i32 x) {\n}",
+            },
+            EP029 => Explanation {
+                text: "The type of a parameter isn't followed by its name.
+
+Every parameter needs a name, even when the function doesn't use it. Start the name with '_' to silence the unused parameter warning.",
+                wrong: "fn f(i32) {\n}",
+                right: "fn f(i32 _x) {\n}",
+            },
+            EP030 => Explanation {
+                text: "A ',' in a parameter list isn't followed by another parameter.
+
+Trailing commas aren't allowed in parameter lists.",
+                wrong: "fn f(i32 x,) {\n}",
+                right: "fn f(i32 x) {\n}",
+            },
+            EP031 => Explanation {
+                text: "An expression inside parentheses isn't followed by ')'.
+
+Every '(' that groups an expression needs a matching ')'.",
+                wrong: "i32 x = (1 + 2;",
+                right: "i32 x = (1 + 2);",
+            },
+            EP032 => Explanation {
+                text: "An expression was expected, but the token can't start one.
+
+Expressions start with a name, a literal, '(', '[' or one of the prefix operators '-' and '!'. This often means an operator is missing its right operand.",
+                wrong: "i32 x = 1 + * 2;",
+                right: "i32 x = 1 + 2;",
+            },
+            EP033 => Explanation {
+                text: "The index of an array access isn't followed by ']'.
+
+The index is a single expression, the bracket must be closed right after it.",
+                wrong: "i32[2] a;\ni32 x = a[0;",
+                right: "i32[2] a;\ni32 x = a[0];",
+            },
+            EP034 => Explanation {
+                text: "Something that isn't a name is being called.
+
+Only functions can be called, and they are always called by name. The result of an expression, like an array element or a parenthesized value, can't be called.",
+                wrong: "i32 x = (1)(2);",
+                right: "fn double(i32 x) i32 {\n    return x * 2;\n}\ni32 x = double(2);",
+            },
+            EP035 => Explanation {
+                text: "An expression is followed by tokens that can't continue it.
+
+This is reported when a standalone expression, like the ones typed in the REPL, has leftover tokens after its end. Usually an operator is missing between two values.",
+                wrong: "1 2",
+                right: "1 + 2",
+            },
+            EP036 => Explanation {
+                text: "A statement was expected, but the token can't start one.
+
+Statements are declarations, assignments, calls, 'if', 'while', 'for', 'break', 'continue', 'fn' and 'return'. A bare value or a stray token isn't a statement on its own.",
+                wrong: "42;",
+                right: "println(42);",
+            },
+            EP037 => Explanation {
+                text: "A 'for' isn't followed by the name of its loop variable.
+
+The variable can have a type before its name, without one it takes the type of the range or of the array elements.",
+                wrong: "for in 0..3 {\n}",
+                right: "for i in 0..3 {\n    println(i);\n}",
+            },
+            EP038 => Explanation {
+                text: "The loop variable of a 'for' isn't followed by 'in'.
+
+The 'in' keyword separates the variable from the range or the array it goes through.",
+                wrong: "for i 0..3 {\n}",
+                right: "for i in 0..3 {\n    println(i);\n}",
+            },
+            EP039 => Explanation {
+                text: "The 'in' of a 'for' isn't followed by a range or an array.
+
+A range is written 'start..end', an array is any expression that evaluates to one.",
+                wrong: "for i in {\n}",
+                right: "for i in 0..3 {\n    println(i);\n}",
+            },
+            EP040 => Explanation {
+                text: "A range is missing its end after '..'.
+
+Ranges always have both ends, the start is included and the end is not.",
+                wrong: "for i in 0.. {\n}",
+                right: "for i in 0..3 {\n    println(i);\n}",
+            },
+            EP041 => Explanation {
+                text: "The range or array of a 'for' isn't followed by its body.
+
+The body is a block between '{' and '}', and it's required even when empty.",
+                wrong: "for i in 0..3 println(i);",
+                right: "for i in 0..3 {\n    println(i);\n}",
+            },
+            EP042 => Explanation {
+                text: "The 'namespace' keyword isn't followed by the name of the namespace.
+
+Namespaces are written as 'namespace <name> { ... }', the name is how its members are reached from outside, as in '<name>::member'.",
+                wrong: "namespace {\n}",
+                right: "namespace math {\n}",
+            },
+            EP043 => Explanation {
+                text: "The name of a namespace isn't followed by its body.
+
+The members of a namespace are declared in a block between '{' and '}', right after its name.",
+                wrong: "namespace math fn one() i32 {\n    return 1;\n}",
+                right: "namespace math {\n    fn one() i32 {\n        return 1;\n    }\n}",
+            },
+            EP044 => Explanation {
+                text: "A '::' isn't followed by a name.
+
+'::' separates the namespaces of a qualified name from each other and from the member, so every one of them must be followed by another name.",
+                wrong: "namespace math {\n    const i32 two = 2;\n}\ni32 x = math::;\nprintln(x);",
+                right: "namespace math {\n    const i32 two = 2;\n}\ni32 x = math::two;\nprintln(x);",
+            },
+            EP045 => Explanation {
+                text: "A qualified name is used as a statement without calling it.
+
+Namespaces only contain functions and constants, so the only statement that can start with 'ns::name' is a call. Add the arguments between parentheses.",
+                wrong: "namespace log {\n    fn flush() {\n    }\n}\nlog::flush;",
+                right: "namespace log {\n    fn flush() {\n    }\n}\nlog::flush();",
+            },
+            EP046 => Explanation {
+                text: "The 'import' keyword isn't followed by the module to load.
+
+A module is either the path of a file between double quotes, or a name like 'lib::math' that stands for the file 'lib/math.rc'. Both are looked up next to the importing file first, then in the search path.",
+                wrong: "import;\nprintln(math::square(2));",
+                right: "import \"math.rc\";\nprintln(math::square(2));",
+            },
+            EP047 => Explanation {
+                text: "An import isn't terminated by a semicolon.
+
+Like every statement that doesn't end with a body, an import ends with ';'.",
+                wrong: "import math\nprintln(math::square(2));",
+                right: "import math;\nprintln(math::square(2));",
+            },
+            EP048 => Explanation {
+                text: "The 'struct' keyword isn't followed by the name of the struct.
+
+A struct is declared like 'struct Point { i32 x; i32 y; }', the name is how declarations and literals refer to it.",
+                wrong: "struct {\n    i32 x;\n}",
+                right: "struct Point {\n    i32 x;\n}",
+            },
+            EP049 => Explanation {
+                text: "The name of a struct isn't followed by '{'.
+
+The fields of a struct are listed between braces, even when there is only one.",
+                wrong: "struct Point i32 x;",
+                right: "struct Point {\n    i32 x;\n}",
+            },
+            EP050 => Explanation {
+                text: "Something other than a field declaration is inside a struct.
+
+A struct only holds fields, each one written like a declaration without a value: a type, a name and ';'. Fields can't be constant and get no initial value, a literal gives them one.",
+                wrong: "struct Point {\n    i32 x = 0;\n}",
+                right: "struct Point {\n    i32 x;\n}",
+            },
+            EP051 => Explanation {
+                text: "A '.' isn't followed by the name of a field.
+
+Fields are read and written with their name after the value, like 'p.x'.",
+                wrong: "struct Point {\n    i32 x;\n}\nPoint p;\nprintln(p.);",
+                right: "struct Point {\n    i32 x;\n}\nPoint p;\nprintln(p.x);",
+            },
+            EP052 => Explanation {
+                text: "A struct literal contains something other than 'field = value'.
+
+Every field of a literal is set with '=', like 'Point { x = 1, y = 2 }'. Unlike declarations, the name comes without a type.",
+                wrong: "struct Point {\n    i32 x;\n}\nPoint p = Point { x 1 };",
+                right: "struct Point {\n    i32 x;\n}\nPoint p = Point { x = 1 };",
+            },
+            EP053 => Explanation {
+                text: "A field value in a struct literal isn't followed by ',' or '}'.
+
+The fields of a literal are separated by commas, a trailing comma is allowed.",
+                wrong: "struct Point {\n    i32 x;\n    i32 y;\n}\nPoint p = Point { x = 1 y = 2 };",
+                right: "struct Point {\n    i32 x;\n    i32 y;\n}\nPoint p = Point { x = 1, y = 2 };",
+            },
+            EP054 => Explanation {
+                text: "The 'enum' keyword isn't followed by the name of the enum.
+
+An enum is declared as 'enum Name { Variant, ... }'.",
+                wrong: "enum {\n    Idle,\n}",
+                right: "enum State {\n    Idle,\n}",
+            },
+            EP055 => Explanation {
+                text: "The name of an enum isn't followed by '{'.
+
+The variants of an enum are written between braces.",
+                wrong: "enum State Idle, Busy",
+                right: "enum State {\n    Idle,\n    Busy,\n}",
+            },
+            EP056 => Explanation {
+                text: "Something other than a variant name was found between the braces of an enum.
+
+Every variant is a name, optionally followed by the types of the values it holds between parentheses.",
+                wrong: "enum State {\n    1,\n}",
+                right: "enum State {\n    Idle,\n}",
+            },
+            EP057 => Explanation {
+                text: "A variant isn't followed by ',' or '}'.
+
+The variants of an enum are separated by commas, a trailing comma is allowed.",
+                wrong: "enum State {\n    Idle\n    Busy\n}",
+                right: "enum State {\n    Idle,\n    Busy,\n}",
+            },
+            EP058 => Explanation {
+                text: "The parentheses after a variant contain something other than a type.
+
+They list the types of the values the variant holds, like 'Failed(str, i32)'. A variant that holds nothing is written without parentheses.",
+                wrong: "enum State {\n    Failed(1),\n}",
+                right: "enum State {\n    Failed(i32),\n}",
+            },
+            EP059 => Explanation {
+                text: "The type of a value a variant holds isn't followed by ',' or ')'.
+
+The types are separated by commas.",
+                wrong: "enum State {\n    Failed(str i32),\n}",
+                right: "enum State {\n    Failed(str, i32),\n}",
+            },
+            EP060 => Explanation {
+                text: "The 'match' keyword isn't followed by the value to match.
+
+A match is written as 'match value { pattern => ... }'.",
+                wrong: "match {\n    _ => {}\n}",
+                right: "i32 x = 1;\nmatch x {\n    _ => {}\n}",
+            },
+            EP061 => Explanation {
+                text: "The matched value isn't followed by '{'.
+
+The arms of a match are written between braces.",
+                wrong: "i32 x = 1;\nmatch x _ => {}",
+                right: "i32 x = 1;\nmatch x {\n    _ => {}\n}",
+            },
+            EP062 => Explanation {
+                text: "A match arm doesn't start with a pattern.
+
+A pattern is a variant like 'State::Failed(message, _)', a literal like '1' or '-1', or '_' for every value.",
+                wrong: "i32 x = 1;\nmatch x {\n    + => {}\n}",
+                right: "i32 x = 1;\nmatch x {\n    1 => {}\n    _ => {}\n}",
+            },
+            EP063 => Explanation {
+                text: "A pattern isn't followed by '=>'.
+
+The '=>' separates the pattern of an arm from what runs when it matches.",
+                wrong: "i32 x = 1;\nmatch x {\n    _ {}\n}",
+                right: "i32 x = 1;\nmatch x {\n    _ => {}\n}",
+            },
+            EP064 => Explanation {
+                text: "The parentheses of a variant pattern contain something other than a name.
+
+They give a name to every value of the variant, '_' skips a value. Values can't be compared there, match the variant and compare them in the body instead.",
+                wrong: "enum State {\n    Failed(i32),\n}\nState s = State::Failed(1);\nmatch s {\n    State::Failed(1) => {}\n}",
+                right: "enum State {\n    Failed(i32),\n}\nState s = State::Failed(1);\nmatch s {\n    State::Failed(code) => {\n        println(code == 1);\n    }\n}",
+            },
+            EP065 => Explanation {
+                text: "A name bound by a variant pattern isn't followed by ',' or ')'.
+
+The names are separated by commas.",
+                wrong: "enum State {\n    Failed(str, i32),\n}\nState s = State::Failed(\"disk\", 2);\nmatch s {\n    State::Failed(message code) => {}\n}",
+                right: "enum State {\n    Failed(str, i32),\n}\nState s = State::Failed(\"disk\", 2);\nmatch s {\n    State::Failed(message, code) => {\n        println(message, code);\n    }\n}",
+            },
+            EP066 => Explanation {
+                text: "The '=>' of a match arm isn't followed by what the arm runs or gives.
+
+In a match statement every arm has a body between braces, in a match expression every arm has a value.",
+                wrong: "i32 x = 1;\nmatch x {\n    _ => println(x);\n}",
+                right: "i32 x = 1;\nmatch x {\n    _ => {\n        println(x);\n    }\n}",
+            },
+            EP067 => Explanation {
+                text: "The value of an arm in a match expression isn't followed by ',' or '}'.
+
+The arms of a match expression are separated by commas, a trailing comma is allowed.",
+                wrong: "i32 x = 1;\ni32 y = match x { 1 => 2 _ => 3 };",
+                right: "i32 x = 1;\ni32 y = match x { 1 => 2, _ => 3 };",
+            },
+            EP068 => Explanation {
+                text: "An element of an array literal isn't followed by ',' or ']'.
+
+The elements of an array literal are separated by commas, a trailing comma is allowed.",
+                wrong: "i32[3] a = [1, 2 3];",
+                right: "i32[3] a = [1, 2, 3];",
+            },
+
+            // === Module Errors === //
+            EM001 => Explanation {
+                text: "The file an import names doesn't exist.
+
+The file is looked up in the directory of the importing file first, then in every directory of the search path, which 'rcompiler' takes with '-I'. A module name like 'lib::math' is the file 'lib/math.rc'.",
+                wrong: "import maths;\nprintln(math::square(2));",
+                right: "import math;\nprintln(math::square(2));",
+            },
+            EM002 => Explanation {
+                text: "A module imports itself, directly or through other modules.
+
+The modules a file imports run before it, so two modules importing each other would both have to run first. Move what they share into a third module that both import.",
+                wrong: "# a.rc\nimport b;\n\n# b.rc\nimport a;",
+                right: "# a.rc\nimport shared;\n\n# b.rc\nimport shared;",
+            },
+            EM003 => Explanation {
+                text: "The file an import names exists, but it can't be read.
+
+The file may not be readable by the current user, or its content isn't valid UTF-8. Modules are text files, like the scripts that import them.",
+                wrong: "# data.rc was saved as UTF-16\nimport data;",
+                right: "# data.rc was saved as UTF-8\nimport data;",
+            },
+
+            // === Semantic Errors === //
+            ES001 => Explanation {
+                text: "A variable is used, but no variable with that name is visible.
+
+Variables must be declared before they are used, and only live until the end of the body that declares them. Functions only see their own parameters and locals plus the globals.",
+                wrong: "i32 y = x + 1;",
+                right: "i32 x = 1;\ni32 y = x + 1;",
+            },
+            ES002 => Explanation {
+                text: "A function is called, but no function with that name exists.
+
+Functions can be called before their definition, so this is usually a typo in the name or a function that was never written.",
+                wrong: "prnt(1);",
+                right: "println(1);",
+            },
+            ES003 => Explanation {
+                text: "A name is declared twice in the same scope.
+
+Each variable, parameter and function needs a unique name within its body. A name can be declared again in an inner body, which shadows the outer one.",
+                wrong: "i32 x = 1;\ni32 x = 2;",
+                right: "i32 x = 1;\ni32 y = 2;",
+            },
+            ES004 => Explanation {
+                text: "A variable is called like a function.
+
+Only functions declared with 'fn' and the builtins can be called.",
+                wrong: "i32 x = 1;\nx();",
+                right: "i32 x = 1;\nprintln(x);",
+            },
+            ES005 => Explanation {
+                text: "A function is used as a value without being called.
+
+Functions aren't values, they can't be stored in variables or passed around. Add '()' with the arguments to use the value the function returns.",
+                wrong: "fn one() i32 {\n    return 1;\n}\ni32 x = one;",
+                right: "fn one() i32 {\n    return 1;\n}\ni32 x = one();",
+            },
+            ES006 => Explanation {
+                text: "A function is declared inside another body.
+
+Functions only see the globals and their own locals, so nesting them inside a function, an 'if' or a loop isn't allowed. Declare them at the top level of the file or in a namespace.",
+                wrong: "fn outer() {\n    fn inner() {\n    }\n}",
+                right: "fn inner() {\n}\nfn outer() {\n    inner();\n}",
+            },
+            ES007 => Explanation {
+                text: "A 'break' or 'continue' is outside of a loop.
+
+Both keywords only make sense inside the body of a 'while' or a 'for'. Use 'return' to leave a function early.",
+                wrong: "break;",
+                right: "while true {\n    break;\n}",
+            },
+            ES008 => Explanation {
+                text: "A 'return' is outside of a function.
+
+The top level of a script isn't a function, it ends when its last statement runs.",
+                wrong: "return;",
+                right: "fn f() {\n    return;\n}\nf();",
+            },
+            ES009 => Explanation {
+                text: "A namespace is declared inside a function or another body.
+
+Namespaces group declarations that are reachable from the whole program, so they can only be declared at the top level of the file or inside another namespace.",
+                wrong: "fn setup() {\n    namespace config {\n    }\n}\nsetup();",
+                right: "namespace config {\n}\nfn setup() {\n}\nsetup();",
+            },
+            ES010 => Explanation {
+                text: "A namespace contains something other than a function, a constant, a struct, an enum or a namespace.
+
+The members of a namespace are shared by the whole program, so they can't be variables or statements that run. Use 'const' for values, or move the statements into a function.",
+                wrong: "namespace config {\n    i32 retries = 3;\n}",
+                right: "namespace config {\n    const i32 retries = 3;\n}",
+            },
+            ES011 => Explanation {
+                text: "A qualified name starts with a namespace that doesn't exist.
+
+Namespaces are looked up from the one the code is in out to the top level, so a namespace declared inside another one is only visible by its short name from inside that namespace. Check the spelling, or write the full path.",
+                wrong: "namespace math {\n    const i32 two = 2;\n}\ni32 x = maths::two;\nprintln(x);",
+                right: "namespace math {\n    const i32 two = 2;\n}\ni32 x = math::two;\nprintln(x);",
+            },
+            ES012 => Explanation {
+                text: "The name of a namespace is used where a value is expected.
+
+A namespace only groups other declarations and has no value of its own. Name one of its members with '::'.",
+                wrong: "namespace math {\n    const i32 two = 2;\n}\ni32 x = math;\nprintln(x);",
+                right: "namespace math {\n    const i32 two = 2;\n}\ni32 x = math::two;\nprintln(x);",
+            },
+            ES013 => Explanation {
+                text: "An import is inside a function or another body.
+
+A module is loaded once for the whole program, before the file that imports it runs, so imports are only allowed at the top level of a file.",
+                wrong: "fn setup() {\n    import math;\n    println(math::square(2));\n}\nsetup();",
+                right: "import math;\nfn setup() {\n    println(math::square(2));\n}\nsetup();",
+            },
+            ES014 => Explanation {
+                text: "A struct is declared inside a function or another body.
+
+Structs are types shared by the whole program, so they are declared at the top level or in a namespace, where every function can use them.",
+                wrong: "fn origin() {\n    struct Point {\n        i32 x;\n    }\n}\norigin();",
+                right: "struct Point {\n    i32 x;\n}\nfn origin() {\n    Point p;\n    println(p.x);\n}\norigin();",
+            },
+            ES015 => Explanation {
+                text: "A type name doesn't refer to any declared struct.
+
+Besides the built-in types, only structs can be used as types. Check the spelling, or declare the struct.",
+                wrong: "Pointt p;",
+                right: "struct Point {\n    i32 x;\n}\nPoint p;",
+            },
+            ES016 => Explanation {
+                text: "A name used as a type refers to something that isn't a struct or an enum.
+
+Variables, functions and namespaces can't be used as types, even when their name looks like one.",
+                wrong: "i32 size = 3;\nsize s;",
+                right: "i32 size = 3;\ni32 s = size;",
+            },
+            ES017 => Explanation {
+                text: "A struct declares two fields with the same name.
+
+A field is read by its name, so every field of a struct needs a different one. Rename one of them.",
+                wrong: "struct Point {\n    i32 x;\n    i32 x;\n}",
+                right: "struct Point {\n    i32 x;\n    i32 y;\n}",
+            },
+            ES018 => Explanation {
+                text: "The name of a struct or an enum is used as a value.
+
+Structs and enums are types. The values of a struct are built with a literal like 'Point { x = 1 }', the ones of an enum are its variants like 'State::Idle'.",
+                wrong: "struct Point {\n    i32 x;\n}\nprintln(Point);",
+                right: "struct Point {\n    i32 x;\n}\nprintln(Point { x = 1 });",
+            },
+            ES019 => Explanation {
+                text: "An enum is declared inside a function or another body.
+
+Like structs, enums are types shared by the whole program. Declare them at the top level or in a namespace.",
+                wrong: "fn f() {\n    enum State {\n        Idle,\n    }\n}",
+                right: "enum State {\n    Idle,\n}\nfn f() {}",
+            },
+            ES020 => Explanation {
+                text: "An enum declares two variants with the same name.
+
+A variant is picked by its name, so every variant of an enum needs a different one. Rename one of them.",
+                wrong: "enum State {\n    Idle,\n    Idle,\n}",
+                right: "enum State {\n    Idle,\n    Busy,\n}",
+            },
+            ES021 => Explanation {
+                text: "A variant is picked from an enum that doesn't declare it.
+
+The variants of an enum are written after its name, like 'State::Idle'. Check the spelling or add the variant to the enum.",
+                wrong: "enum State {\n    Idle,\n}\nState s = State::Busy;",
+                right: "enum State {\n    Idle,\n    Busy,\n}\nState s = State::Busy;",
+            },
+            ES022 => Explanation {
+                text: "A name used as a pattern isn't a variant of an enum.
+
+A match arm compares the value with its pattern: a variant like 'State::Idle', a literal like '1', or '_' for every value. Variables and functions can't be used as patterns.",
+                wrong: "i32 one = 1;\ni32 x = 1;\nmatch x {\n    one => {}\n    _ => {}\n}",
+                right: "i32 x = 1;\nmatch x {\n    1 => {}\n    _ => {}\n}",
+            },
+            ES023 => Explanation {
+                text: "An enum is built with a struct literal.
+
+Only structs have fields to set. The values of an enum are its variants, like 'State::Idle' or 'State::Failed(\"disk\")'.",
+                wrong: "enum State {\n    Idle,\n}\nState s = State {};",
+                right: "enum State {\n    Idle,\n}\nState s = State::Idle;",
+            },
+            ES024 => Explanation {
+                text: "A constant, or an element or a field of one, is assigned.
+
+Constants keep the value they are declared with. Declare the variable without 'const' if it has to change.",
+                wrong: "const i32[2] limits = [1, 2];\nlimits[0] = 5;",
+                right: "i32[2] limits = [1, 2];\nlimits[0] = 5;",
+            },
+            ES025 => Explanation {
+                text: "A constant is declared without a value.
+
+A constant can't be assigned later, so it needs its value where it's declared.",
+                wrong: "const i32 limit;",
+                right: "const i32 limit = 10;",
+            },
+            // === Type Errors === //
+            EC001 => Explanation {
+                text: "The value of a declaration doesn't have the type of the variable.
+
+There are no implicit conversions, even between integer types of different size. Change the type of the variable or the value.",
+                wrong: "i32 x = true;",
+                right: "bool x = true;",
+            },
+            EC002 => Explanation {
+                text: "The value assigned to a variable doesn't have its type.
+
+A variable keeps the type it was declared with, values of other types can't be stored in it. The same goes for the elements and the fields of a variable.",
+                wrong: "i32 x = 1;\nx = \"one\";",
+                right: "i32 x = 1;\nx = 2;",
+            },
+            EC003 => Explanation {
+                text: "The condition of an 'if' or 'while' isn't a 'bool'.
+
+Numbers aren't true or false on their own, compare them explicitly.",
+                wrong: "i32 x = 1;\nif x {\n}",
+                right: "i32 x = 1;\nif x != 0 {\n}",
+            },
+            EC004 => Explanation {
+                text: "An argument doesn't have the type of its parameter.
+
+Arguments are checked against the parameters in order, there are no implicit conversions. An array parameter with a size only takes arrays with that size written as a literal, the size of an array like 'i32[n]' is only known when the program runs.",
+                wrong: "fn square(i32 x) i32 {\n    return x * x;\n}\ni32 y = square(true);",
+                right: "fn square(i32 x) i32 {\n    return x * x;\n}\ni32 y = square(3);",
+            },
+            EC005 => Explanation {
+                text: "A function is called with the wrong number of arguments.
+
+Every parameter needs an argument, there are no default values.",
+                wrong: "fn add(i32 a, i32 b) i32 {\n    return a + b;\n}\ni32 x = add(1);",
+                right: "fn add(i32 a, i32 b) i32 {\n    return a + b;\n}\ni32 x = add(1, 2);",
+            },
+            EC006 => Explanation {
+                text: "A binary operator is used with operands it doesn't support.
+
+Arithmetic and bitwise operators need numbers of the same type, '&&' and '||' need booleans, and comparisons need two values of the same type.",
+                wrong: "i32 x = 1 + true;",
+                right: "i32 x = 1 + 1;",
+            },
+            EC007 => Explanation {
+                text: "A unary operator is used with an operand it doesn't support.
+
+'-' negates numbers and '!' negates booleans.",
+                wrong: "bool x = -true;",
+                right: "bool x = !true;",
+            },
+            EC008 => Explanation {
+                text: "A 'return' doesn't match the return type of its function.
+
+Functions with a return type must return a value of that type, functions without one must use 'return;' without a value.",
+                wrong: "fn f() i32 {\n    return true;\n}\ni32 x = f();",
+                right: "fn f() i32 {\n    return 1;\n}\ni32 x = f();",
+            },
+            EC009 => Explanation {
+                text: "A value that isn't an array is indexed.
+
+Only arrays can be read or assigned with '[]'.",
+                wrong: "i32 x = 1;\ni32 y = x[0];",
+                right: "i32[2] x;\ni32 y = x[0];",
+            },
+            EC010 => Explanation {
+                text: "An array is indexed with something that isn't an integer.
+
+Indices are integers starting from 0.",
+                wrong: "i32[2] a;\ni32 x = a[true];",
+                right: "i32[2] a;\ni32 x = a[1];",
+            },
+            EC011 => Explanation {
+                text: "The size of an array type isn't an integer.
+
+Arrays hold a whole number of elements.",
+                wrong: "i32[1.5] a;",
+                right: "i32[2] a;",
+            },
+            EC012 => Explanation {
+                text: "A call to a function without a return type is used as a value.
+
+Functions that don't declare a return type produce no value, so they can only be called as statements.",
+                wrong: "fn greet() {\n}\ni32 x = greet();",
+                right: "fn greet() {\n}\ngreet();",
+            },
+            EC013 => Explanation {
+                text: "A 'for' goes through something that isn't an integer range or an array.
+
+Both ends of a range must be integers of the same type, and strings can't be iterated. Put the values in an array to go through them.",
+                wrong: "for c in \"abc\" {\n    println(c);\n}",
+                right: "char[3] letters;\nfor c in letters {\n    println(c);\n}",
+            },
+            EC014 => Explanation {
+                text: "The type of a loop variable doesn't match the values it receives.
+
+Integer literals in a range take the declared type, but variables keep theirs. Leave the type out to use the one of the range or array.",
+                wrong: "i64 n = 3;\nfor i32 i in 0..n {\n    println(i);\n}",
+                right: "i64 n = 3;\nfor i in 0..n {\n    println(i);\n}",
+            },
+            EC015 => Explanation {
+                text: "A struct doesn't have a field with this name.
+
+Only the fields listed in the declaration of the struct can be read, written or set by a literal. Check the spelling, or add the field to the struct.",
+                wrong: "struct Point {\n    i32 x;\n}\nPoint p;\nprintln(p.z);",
+                right: "struct Point {\n    i32 x;\n}\nPoint p;\nprintln(p.x);",
+            },
+            EC016 => Explanation {
+                text: "A field is read from a value that isn't a struct.
+
+Only structs have fields, arrays and the built-in types don't.",
+                wrong: "i32 x = 1;\nprintln(x.y);",
+                right: "struct Point {\n    i32 y;\n}\nPoint x;\nprintln(x.y);",
+            },
+            EC017 => Explanation {
+                text: "A struct literal doesn't set every field of the struct.
+
+A literal builds the whole value, so every field needs one. To start from zero values, declare a variable of the struct without an initializer.",
+                wrong: "struct Point {\n    i32 x;\n    i32 y;\n}\nPoint p = Point { x = 1 };",
+                right: "struct Point {\n    i32 x;\n    i32 y;\n}\nPoint p = Point { x = 1, y = 0 };",
+            },
+            EC018 => Explanation {
+                text: "A struct literal sets the same field twice.
+
+Only one of the values could be kept, so every field is set once.",
+                wrong: "struct Point {\n    i32 x;\n}\nPoint p = Point { x = 1, x = 2 };",
+                right: "struct Point {\n    i32 x;\n}\nPoint p = Point { x = 2 };",
+            },
+            EC019 => Explanation {
+                text: "The value of a field in a struct literal doesn't have the type of the field.
+
+Fields follow the same rules as variables, there are no implicit conversions.",
+                wrong: "struct Point {\n    i32 x;\n}\nPoint p = Point { x = true };",
+                right: "struct Point {\n    i32 x;\n}\nPoint p = Point { x = 1 };",
+            },
+            EC020 => Explanation {
+                text: "A struct or an enum holds a value of its own type, directly or through the fields and variants of other types.
+
+A value of the type would have to contain another one, which contains another one, forever. Fields and variants can hold other structs and enums as long as they don't lead back.",
+                wrong: "struct Node {\n    i32 value;\n    Node next;\n}",
+                right: "struct Node {\n    i32 value;\n    i32 next;\n}",
+            },
+            EC021 => Explanation {
+                text: "The size of an array field, of an array a variant holds or of an array parameter isn't an integer literal.
+
+Every value of a struct has the same fields, so the size of its arrays is fixed when the struct is declared. The same goes for the values of a variant and for the arrays a function takes. Constants and expressions can't be used.",
+                wrong: "const i32 size = 4;\nstruct Buffer {\n    i32[size] data;\n}",
+                right: "struct Buffer {\n    i32[4] data;\n}",
+            },
+            EC022 => Explanation {
+                text: "A variant is given a different number of values than it holds, or a pattern binds a different number of names.
+
+A variant declared as 'Failed(str, i32)' is built with two values and matched with two names. A pattern without names matches the variant whatever values it holds.",
+                wrong: "enum State {\n    Failed(str, i32),\n}\nState s = State::Failed(\"disk\");",
+                right: "enum State {\n    Failed(str, i32),\n}\nState s = State::Failed(\"disk\", 2);",
+            },
+            EC023 => Explanation {
+                text: "A value given to a variant doesn't have the type the enum declares for it.
+
+Like the arguments of a call, every value must match the type at the same position.",
+                wrong: "enum State {\n    Failed(str, i32),\n}\nState s = State::Failed(2, \"disk\");",
+                right: "enum State {\n    Failed(str, i32),\n}\nState s = State::Failed(\"disk\", 2);",
+            },
+            EC024 => Explanation {
+                text: "A pattern can never match the value, because it has another type.
+
+The variants of an enum only match values of that enum, and literals only match values of their own type.",
+                wrong: "enum State {\n    Idle,\n}\ni32 x = 1;\nmatch x {\n    State::Idle => {}\n    _ => {}\n}",
+                right: "enum State {\n    Idle,\n}\nState s;\nmatch s {\n    State::Idle => {}\n}",
+            },
+            EC025 => Explanation {
+                text: "Some values of the matched value aren't matched by any arm.
+
+A match on an enum needs an arm for every variant, a match on 'bool' one for 'true' and one for 'false'. Other values need a '_' arm, which matches everything the arms above don't.",
+                wrong: "enum State {\n    Idle,\n    Busy,\n}\nState s;\nmatch s {\n    State::Idle => {}\n}",
+                right: "enum State {\n    Idle,\n    Busy,\n}\nState s;\nmatch s {\n    State::Idle => {}\n    State::Busy => {}\n}",
+            },
+            EC026 => Explanation {
+                text: "A match arm comes after arms that already match every value it could match.
+
+The arms are tried in order and only the first one that matches runs, so the arm would never run. Remove it, or move the more general arm below it.",
+                wrong: "i32 x = 1;\nmatch x {\n    _ => {}\n    1 => {}\n}",
+                right: "i32 x = 1;\nmatch x {\n    1 => {}\n    _ => {}\n}",
+            },
+            EC027 => Explanation {
+                text: "The arms of a match expression give values of different types.
+
+The match has the value of the arm that matches, so every arm must give the same type.",
+                wrong: "bool b = true;\ni32 x = match b { true => 1, false => \"zero\" };",
+                right: "bool b = true;\ni32 x = match b { true => 1, false => 0 };",
+            },
+            EC028 => Explanation {
+                text: "The elements of an array literal have different types.
+
+Every element of an array has the same type, so the literal can't mix them. Literals without a suffix take the type of the other elements.",
+                wrong: "i32[2] a = [1, \"two\"];",
+                right: "i32[2] a = [1, 2];",
+            },
+            EC029 => Explanation {
+                text: "An integer literal doesn't fit in the type it's used as.
+
+Literals without a suffix take the type of the variable, the parameter or the other operand they meet, and must be inside its range. A 'u8' holds 0 to 255, an 'i8' -128 to 127. Use a wider type or a smaller value.",
+                wrong: "u8 x = 300;",
+                right: "u16 x = 300;",
+            },
+
+            // === Runtime Errors === //
+            ER001 => Explanation {
+                text: "An integer was divided by zero, with '/' or '%'.
+
+The result of the division doesn't exist, so the program stops. Check the divisor before dividing when it can be zero.",
+                wrong: "i32 zero = 0;\ni32 x = 1 / zero;",
+                right: "i32 zero = 0;\ni32 x = 0;\nif zero != 0 {\n    x = 1 / zero;\n}",
+            },
+            ER002 => Explanation {
+                text: "An array was indexed outside of its bounds.
+
+Indices go from 0 to the length of the array minus one, 'len' returns the length.",
+                wrong: "i32[2] a;\ni32 x = a[2];",
+                right: "i32[2] a;\ni32 x = a[len(a) - 1];",
+            },
+            ER003 => Explanation {
+                text: "A 'break' or 'continue' ran outside of a loop.
+
+Checked programs never report this, the same mistake is caught before running as ES007. It can only happen when a syntax tree that skipped the checks is executed.",
+                wrong: "break;",
+                right: "while true {\n    break;\n}",
+            },
+            ER004 => Explanation {
+                text: "A 'return' ran outside of a function.
+
+Checked programs never report this, the same mistake is caught before running as ES008. It can only happen when a syntax tree that skipped the checks is executed.",
+                wrong: "return;",
+                right: "fn f() {\n    return;\n}\nf();",
+            },
+            ER005 => Explanation {
+                text: "A function with a return type reached the end of its body without returning.
+
+Every path through the body must end with a 'return' that has a value, including the ones where no 'if' branch was taken.",
+                wrong: "fn sign(i32 x) i32 {\n    if x > 0 {\n        return 1;\n    }\n}\ni32 s = sign(0);",
+                right: "fn sign(i32 x) i32 {\n    if x > 0 {\n        return 1;\n    }\n    return 0;\n}\ni32 s = sign(0);",
+            },
+            ER006 => Explanation {
+                text: "A literal can't be represented by its type.
+
+Integer literals must fit in 64 bits, even where a float is expected. A literal too large for the integer type it's used as is reported before running as EC029. Use a smaller value or write it as a float.",
+                wrong: "f64 x = 99999999999999999999;",
+                right: "f64 x = 99999999999999999999.0;",
+            },
+            ER007 => Explanation {
+                text: "A function implemented by the host failed.
+
+Builtins and the functions registered by an embedding application can refuse their arguments at runtime, the hint carries their own message.",
+                wrong: "i32 x = len(1);",
+                right: "i32 x = len(\"one\");",
+            },
+            ER008 => Explanation {
+                text: "A name was used that isn't defined while the program runs.
+
+Checked programs never report this for their own names. It happens when an embedding application calls a function or reads a global that no script defined.",
+                wrong: "missing();",
+                right: "fn missing() {\n}\nmissing();",
+            },
+            ER009 => Explanation {
+                text: "An operation was applied to values of types it doesn't support.
+
+Checked programs never report this, the type checker catches it as EC006 or EC007. It can happen with values passed in by an embedding application.",
+                wrong: "i32 x = 1 + true;",
+                right: "i32 x = 1 + 1;",
+            },
+            ER010 => Explanation {
+                text: "Calls were nested too deeply.
+
+This almost always means a recursive function never reaches the case that stops the recursion.",
+                wrong: "fn down(i32 n) i32 {\n    return down(n - 1);\n}\ni32 x = down(3);",
+                right: "fn down(i32 n) i32 {\n    if n == 0 {\n        return 0;\n    }\n    return down(n - 1);\n}\ni32 x = down(3);",
+            },
+            ER011 => Explanation {
+                text: "No arm of a match matched the value.
+
+The TypeChecker makes sure a match covers every value when it knows the type of the matched value. The values of natives and of enums declared by an earlier script are only known when the program runs. Add a '_' arm for the values the other arms don't match.",
+                wrong: "match len(\"ab\") {\n    1 => {}\n}",
+                right: "match len(\"ab\") {\n    1 => {}\n    _ => {}\n}",
+            },
+
+            ER012 => Explanation {
+                text: "The size of an array evaluated to a value no array can have.
+
+A size that isn't a literal is only known when the program runs. It can't be negative, it can't be larger than 16777216 elements, and an array given as the initial value must have exactly that many elements.",
+                wrong: "i32 n = 2;\ni32[n] values = [1, 2, 3];",
+                right: "i32 n = 3;\ni32[n] values = [1, 2, 3];",
+            },
+
+            // === Bytecode Errors === //
+            EB001 => Explanation {
+                text: "The bytecode compiler found a name that wasn't declared.
+
+Checked programs never report this, undeclared names are caught before compiling as ES001 or ES002.",
+                wrong: "i32 y = x;",
+                right: "i32 x = 1;\ni32 y = x;",
+            },
+            EB002 => Explanation {
+                text: "A function doesn't fit in the limits of the bytecode.
+
+Each function can have at most 65536 constants and locals, and jumps can't cross more than 65535 instructions. Split the code into smaller functions.",
+                wrong: "// a function with more than 65536 distinct constants",
+                right: "// the same code split into several functions",
+            },
+
+        },
+        DiagnosticKind::Warning(code) | DiagnosticKind::Denied(code) => match code {
+            WL001 => Explanation {
+                text: "A variable is declared but its value is never read.
+
+Assigning a variable doesn't count as using it. Remove the variable, or start its name with '_' if it's kept on purpose. Silence the lint with '-A unused-variables'.",
+                wrong: "i32 x = 1;",
+                right: "i32 x = 1;\nprintln(x);",
+            },
+            WL002 => Explanation {
+                text: "A parameter is never read by its function.
+
+Remove the parameter, or start its name with '_' when the signature must keep it. Silence the lint with '-A unused-parameters'.",
+                wrong: "fn f(i32 x) {\n}\nf(1);",
+                right: "fn f(i32 _x) {\n}\nf(1);",
+            },
+            WL003 => Explanation {
+                text: "A function is never called.
+
+Calls a function makes to itself don't count. Remove the function, or start its name with '_' if it's kept on purpose. Silence the lint with '-A unused-functions'.",
+                wrong: "fn helper() {\n}",
+                right: "fn helper() {\n}\nhelper();",
+            },
+            WL004 => Explanation {
+                text: "A variable has the same name as one in an outer scope.
+
+Inside the inner body the outer variable can't be reached anymore, which is easy to miss when reading the code. Pick another name, or silence the lint with '-A shadowing'.",
+                wrong: "i32 x = 1;\nif x > 0 {\n    i32 x = 2;\n    println(x);\n}",
+                right: "i32 x = 1;\nif x > 0 {\n    i32 y = 2;\n    println(y);\n}",
+            },
+            WL005 => Explanation {
+                text: "A statement comes after a 'return', 'break' or 'continue' in the same body, so it never runs.
+
+Only the first unreachable statement of each body is reported. Remove the dead code or move it before the jump. Silence the lint with '-A unreachable-code'.",
+                wrong: "i32 x = 0;\nwhile x < 3 {\n    break;\n    x += 1;\n}",
+                right: "i32 x = 0;\nwhile x < 3 {\n    x += 1;\n    break;\n}",
+            },
+            WL006 => Explanation {
+                text: "The condition of an 'if' or 'while' only uses literals, so it's always true or always false.
+
+'while true' is allowed, it's how infinite loops are written. Silence the lint with '-A constant-condition'.",
+                wrong: "if 1 > 2 {\n    println(1);\n}",
+                right: "i32 x = 1;\nif x > 2 {\n    println(1);\n}",
+            },
+        },
+    }
+}
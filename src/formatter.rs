@@ -0,0 +1,415 @@
+use std::collections::HashSet;
+
+use crate::parser::{Accessor, Body, ElseBranch, Expression, Import, Iterable, Parameter, Path, Pattern, RawExpression, RawStatement, Statement, Type};
+use crate::tokenizer::{Token, TokenKind};
+
+const INDENT: &str = "    ";
+
+// A '#' comment found in the trivia of a token
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Comment<'a> {
+    pub text: &'a str,
+    pub start: usize,
+    // False when code comes before it on the same line
+    pub own_line: bool,
+    // An empty line separates it from what comes before
+    pub blank_before: bool,
+}
+
+// Collects the comments of a token stream in source order
+pub fn comments<'a>(tokens: &[Token<'a>]) -> Vec<Comment<'a>> {
+    let mut comments: Vec<Comment<'a>> = Vec::new();
+
+    for token in tokens {
+        let trivia_start: usize = token.span.start - token.trivia.len();
+        let mut rest: &str = token.trivia;
+        let mut offset: usize = 0;
+
+        while let Some(hash) = rest.find('#') {
+            let before: &str = &rest[..hash];
+            let length: usize = rest[hash..].find('\n').unwrap_or(rest.len() - hash);
+
+            comments.push(Comment {
+                text: rest[hash..hash + length].trim_end(),
+                start: trivia_start + offset + hash,
+                own_line: before.contains('\n') || trivia_start + offset == 0,
+                blank_before: has_blank_line(before),
+            });
+
+            offset += hash + length;
+            rest = &rest[hash + length..];
+        }
+    }
+    comments
+}
+
+// Two line breaks with only whitespace between them
+fn has_blank_line(whitespace: &str) -> bool {
+    whitespace.matches('\n').count() > 1
+}
+
+// Prints a syntax tree in the canonical style: four spaces of indentation, one
+// statement per line, spaces around binary operators and '{' on the line that opens the body.
+// The comments are put back between the statements they were written between
+pub struct Formatter<'a> {
+    comments: Vec<Comment<'a>>,
+    next_comment: usize,
+    // Start of the tokens that follow an empty line, ignoring the comments before them
+    blank_before: HashSet<usize>,
+
+    lines: Vec<String>,
+    depth: usize,
+    // The last line opened a body, so no empty line can follow it
+    block_start: bool,
+}
+
+impl<'a> Formatter<'a> {
+    // The tokens must be the ones the statements were parsed from
+    pub fn new(tokens: &[Token<'a>]) -> Formatter<'a> {
+        let blank_before: HashSet<usize> = tokens.iter()
+            .filter(|token| has_blank_line(&token.trivia[token.trivia.rfind('#').map_or(0, |hash| hash + 1)..]))
+            .map(|token| token.span.start)
+            .collect();
+
+        Formatter {
+            comments: comments(tokens),
+            next_comment: 0,
+            blank_before,
+
+            lines: Vec::new(),
+            depth: 0,
+            block_start: true,
+        }
+    }
+
+    pub fn format(mut self, statements: &[Statement<'a>]) -> String {
+        for statement in statements {
+            self.format_statement(statement);
+        }
+        self.format_comments(usize::MAX);
+
+        let mut output: String = self.lines.join("\n");
+        if !output.is_empty() {
+            output.push('\n');
+        }
+        output
+    }
+
+    fn format_statement(&mut self, statement: &Statement<'a>) {
+        self.format_comments(statement.span.start);
+        if self.blank_before.contains(&statement.span.start) {
+            self.blank_line();
+        }
+
+        match &statement.node {
+            RawStatement::VariableDeclaration { is_const, type_, name, value } => {
+                let constant: &str = if *is_const { "const " } else { "" };
+                let value: String = value.as_ref()
+                    .map_or(String::new(), |value| format!(" = {}", Self::expression(value)));
+
+                self.line(format!("{}{} {}{};", constant, Self::type_(type_), name, value));
+            },
+
+            RawStatement::VariableAssignment { target, operator, value } => {
+                let accessors: String = target.accessors.iter().map(|accessor| match accessor {
+                    Accessor::Field(name) => format!(".{}", name),
+                    Accessor::Index(index) => format!("[{}]", Self::expression(index)),
+                }).collect();
+                self.line(format!("{}{} {} {};", target.name, accessors, operator, Self::expression(value)));
+            },
+
+            RawStatement::If { condition, body, elses } => {
+                self.format_body(format!("if {}", Self::condition(condition)), body);
+
+                for branch in elses {
+                    match branch {
+                        ElseBranch::ElseIf(statement) => {
+                            let RawStatement::If { condition, body, .. } = &statement.node else {
+                                unreachable!("'elif' branches are 'if' statements");
+                            };
+                            self.format_comments(condition.span.start);
+                            self.format_body(format!("elif {}", Self::condition(condition)), body);
+                        },
+                        ElseBranch::Else(body) => {
+                            self.format_comments(body.span.start);
+                            self.format_body("else".to_string(), body);
+                        },
+                    }
+                }
+            },
+
+            RawStatement::While { condition, body } => {
+                self.format_body(format!("while {}", Self::condition(condition)), body);
+            },
+
+            RawStatement::For { type_, name, iterable, body } => {
+                let type_: String = type_.map_or(String::new(), |kind| format!("{} ", kind));
+                let iterable: String = match iterable {
+                    Iterable::Range { start, end } => format!("{}..{}", Self::condition(start), Self::condition(end)),
+                    Iterable::Array(array) => Self::condition(array),
+                };
+
+                self.format_body(format!("for {}{} in {}", type_, name, iterable), body);
+            },
+
+            RawStatement::LoopControl(keyword) => self.line(format!("{};", keyword)),
+
+            RawStatement::Function { name, parameters, type_, body } => {
+                let parameters: Vec<String> = parameters.iter().map(Self::parameter).collect();
+                let return_type: String = match type_ {
+                    TokenKind::Null => String::new(),
+                    kind => format!(" {}", kind),
+                };
+
+                self.format_body(format!("fn {}({}){}", name, parameters.join(", "), return_type), body);
+            },
+
+            RawStatement::Return(value) => match value {
+                Some(value) => self.line(format!("return {};", Self::expression(value))),
+                None => self.line("return;".to_string()),
+            },
+
+            RawStatement::FunctionCall { name, arguments } => {
+                self.line(format!("{};", Self::call(name, arguments)));
+            },
+
+            RawStatement::Namespace { name, body } => {
+                self.format_body(format!("namespace {}", name), body);
+            },
+
+            RawStatement::Import(import) => match import {
+                Import::File(path) => self.line(format!("import {};", path)),
+                Import::Module(path) => self.line(format!("import {};", path)),
+            },
+
+            RawStatement::Struct { name, fields } => {
+                self.format_block(format!("struct {}", name), statement.span.end, fields.is_empty(), |formatter| {
+                    for field in fields {
+                        formatter.format_comments(field.span.start);
+                        if formatter.blank_before.contains(&field.span.start) {
+                            formatter.blank_line();
+                        }
+                        formatter.line(format!("{} {};", Self::type_(&field.type_), field.name));
+                    }
+                });
+            },
+
+            // One variant per line, each followed by a ','
+            RawStatement::Enum { name, variants } => {
+                self.format_block(format!("enum {}", name), statement.span.end, variants.is_empty(), |formatter| {
+                    for variant in variants {
+                        formatter.format_comments(variant.span.start);
+                        if formatter.blank_before.contains(&variant.span.start) {
+                            formatter.blank_line();
+                        }
+
+                        let payload: Vec<String> = variant.payload.iter().map(Self::type_).collect();
+                        match payload.is_empty() {
+                            true => formatter.line(format!("{},", variant.name)),
+                            false => formatter.line(format!("{}({}),", variant.name, payload.join(", "))),
+                        }
+                    }
+                });
+            },
+
+            RawStatement::Match { value, arms } => {
+                self.format_block(format!("match {}", Self::condition(value)), statement.span.end, arms.is_empty(), |formatter| {
+                    for arm in arms {
+                        formatter.format_comments(arm.span.start);
+                        if formatter.blank_before.contains(&arm.span.start) {
+                            formatter.blank_line();
+                        }
+                        formatter.format_body(format!("{} =>", Self::pattern(&arm.pattern)), &arm.value);
+                    }
+                });
+            },
+        }
+    }
+
+    fn format_body(&mut self, header: String, body: &Body<'a>) {
+        self.format_block(header, body.span.end, body.statements.is_empty(), |formatter| {
+            for statement in &body.statements {
+                formatter.format_statement(statement);
+            }
+        });
+    }
+
+    // Prints what is between braces one level deeper, 'end' is the end of the '}'
+    fn format_block(&mut self, header: String, end: usize, is_empty: bool, contents: impl FnOnce(&mut Self)) {
+        // The '}' is the last byte of the block
+        let end: usize = end.saturating_sub(1);
+        let has_comments: bool = self.comments[self.next_comment..].first().is_some_and(|comment| comment.start < end);
+
+        if is_empty && !has_comments {
+            self.line(format!("{} {{}}", header));
+            return;
+        }
+
+        self.line(format!("{} {{", header));
+        self.block_start = true;
+        self.depth += 1;
+
+        contents(self);
+        self.format_comments(end);
+
+        self.depth -= 1;
+        self.line("}".to_string());
+    }
+
+    // Prints the comments that come before 'offset' and weren't printed yet
+    fn format_comments(&mut self, offset: usize) {
+        while let Some(comment) = self.comments.get(self.next_comment).copied() {
+            if comment.start >= offset {
+                break;
+            }
+            self.next_comment += 1;
+
+            match self.lines.last_mut() {
+                Some(last) if !comment.own_line => {
+                    last.push(' ');
+                    last.push_str(comment.text);
+                },
+                _ => {
+                    if comment.blank_before {
+                        self.blank_line();
+                    }
+                    self.line(comment.text.to_string());
+                },
+            }
+        }
+    }
+
+    fn blank_line(&mut self) {
+        if !self.block_start {
+            self.lines.push(String::new());
+        }
+    }
+
+    fn line(&mut self, text: String) {
+        self.lines.push(format!("{}{}", INDENT.repeat(self.depth), text));
+        self.block_start = false;
+    }
+
+    fn type_(type_: &Type<'a>) -> String {
+        let kind: String = match &type_.name {
+            Some(name) => name.to_string(),
+            None => type_.kind.to_string(),
+        };
+
+        match &type_.array_length {
+            Some(length) => format!("{}[{}]", kind, Self::expression(length)),
+            None => kind,
+        }
+    }
+
+    fn parameter(parameter: &Parameter<'a>) -> String {
+        format!("{} {}", Self::type_(&parameter.type_), parameter.name)
+    }
+
+    fn call(name: &Path<'a>, arguments: &[Expression<'a>]) -> String {
+        let arguments: Vec<String> = arguments.iter().map(Self::expression).collect();
+        format!("{}({})", name, arguments.join(", "))
+    }
+
+    fn expression(expression: &Expression<'a>) -> String {
+        match &expression.node {
+            RawExpression::Variable(name) => name.to_string(),
+            RawExpression::Literal { value, .. } => value.to_string(),
+
+            RawExpression::Binary { left, operator, right } => {
+                let (left_power, right_power) = RawExpression::get_binding_power(*operator);
+
+                // Operators are left associative, so an operand on the right
+                // with the same precedence needs parentheses too
+                let left: String = Self::operand(left, left_power);
+                let right: String = Self::operand(right, right_power);
+                format!("{} {} {}", left, operator, right)
+            },
+
+            RawExpression::Unary { operator, operand } => {
+                format!("{}{}", operator, Self::operand(operand, RawExpression::UNARY_BINDING_POWER))
+            },
+
+            RawExpression::FunctionCall { name, arguments } => Self::call(name, arguments),
+
+            RawExpression::ArrayAccess { array, index } => {
+                let (power, _) = RawExpression::get_binding_power(TokenKind::LeftBracket);
+                format!("{}[{}]", Self::operand(array, power), Self::expression(index))
+            },
+
+            RawExpression::StructLiteral { name, fields } if fields.is_empty() => format!("{} {{}}", name),
+            RawExpression::StructLiteral { name, fields } => {
+                let fields: Vec<String> = fields.iter()
+                    .map(|(field, value)| format!("{} = {}", field, Self::expression(value)))
+                    .collect();
+                format!("{} {{ {} }}", name, fields.join(", "))
+            },
+
+            RawExpression::Field { object, name } => {
+                let (power, _) = RawExpression::get_binding_power(TokenKind::Dot);
+                format!("{}.{}", Self::operand(object, power), name)
+            },
+
+            // Kept on one line like a struct literal
+            RawExpression::Match { value, arms } if arms.is_empty() => format!("match {} {{}}", Self::condition(value)),
+            RawExpression::Match { value, arms } => {
+                let arms: Vec<String> = arms.iter()
+                    .map(|arm| format!("{} => {}", Self::pattern(&arm.pattern), Self::expression(&arm.value)))
+                    .collect();
+                format!("match {} {{ {} }}", Self::condition(value), arms.join(", "))
+            },
+
+            RawExpression::ArrayLiteral(elements) => {
+                let elements: Vec<String> = elements.iter().map(Self::expression).collect();
+                format!("[{}]", elements.join(", "))
+            },
+        }
+    }
+
+    fn pattern(pattern: &Pattern<'a>) -> String {
+        match pattern {
+            Pattern::Wildcard => "_".to_string(),
+            Pattern::Literal(literal) => Self::expression(literal),
+            Pattern::Variant { path, bindings } if bindings.is_empty() => path.to_string(),
+            Pattern::Variant { path, bindings } => format!("{}({})", path, bindings.join(", ")),
+        }
+    }
+
+    // A struct literal can't be written right in a condition, its '{' would open the body
+    fn condition(condition: &Expression<'a>) -> String {
+        if Self::has_struct_literal(condition) {
+            format!("({})", Self::expression(condition))
+        }
+        else {
+            Self::expression(condition)
+        }
+    }
+
+    // Arguments and indexes are between brackets already, so they aren't looked into
+    fn has_struct_literal(expression: &Expression<'a>) -> bool {
+        match &expression.node {
+            RawExpression::StructLiteral { .. } => true,
+            RawExpression::Binary { left, right, .. } => Self::has_struct_literal(left) || Self::has_struct_literal(right),
+            RawExpression::Unary { operand, .. } => Self::has_struct_literal(operand),
+            RawExpression::ArrayAccess { array, .. } => Self::has_struct_literal(array),
+            RawExpression::Field { object, .. } => Self::has_struct_literal(object),
+            _ => false,
+        }
+    }
+
+    // Wraps the operand in parentheses when it binds looser than the operator around it
+    fn operand(operand: &Expression<'a>, power: u8) -> String {
+        let operand_power: u8 = match &operand.node {
+            RawExpression::Binary { operator, .. } => RawExpression::get_binding_power(*operator).0,
+            RawExpression::Unary { .. } => RawExpression::UNARY_BINDING_POWER,
+            _ => u8::MAX,
+        };
+
+        if operand_power < power {
+            format!("({})", Self::expression(operand))
+        }
+        else {
+            Self::expression(operand)
+        }
+    }
+}
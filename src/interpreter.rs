@@ -0,0 +1,820 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::builtins::builtins;
+use crate::bytecode::{Definition, TypeDefinition};
+use crate::parser::{Accessor, Arm, Body, ElseBranch, Expression, Iterable, Path, Pattern, Place, RawExpression, RawStatement, Statement, StatementSpan, Type};
+use crate::tokenizer::TokenKind;
+use crate::typechecker::Kinds;
+use crate::value::{ArithmeticError, EnumType, NativeFunction, StructType, Value};
+use crate::error::*;
+use crate::source::SourceMap;
+
+// Deep enough for real recursion, small enough to fail before the Rust stack does
+pub const MAX_CALL_DEPTH: usize = 256;
+
+// Shared with the VM, a match the TypeChecker couldn't check has no arm for the value
+pub(crate) const NO_ARM: &str = "add a '_' arm for the values the other arms don't match";
+
+#[derive(Debug)]
+pub struct RuntimeError {
+    pub code: ErrorCode,
+    pub span: StatementSpan,
+    pub hint: String,
+}
+
+impl RuntimeError {
+    pub fn to_diagnostic<'a>(&'a self, sources: &'a SourceMap) -> Diagnostic<'a> {
+        Diagnostic {
+            kind: DiagnosticKind::Error(self.code),
+            info: DiagnosticInfo::from_span(sources, self.span.file, self.span.start, self.span.end),
+            hint: Some(&self.hint)
+        }
+    }
+}
+
+// What a statement asks the enclosing statements to do next
+enum Flow {
+    Normal,
+    Break,
+    Continue,
+    Return(Value),
+}
+
+struct Variable {
+    value: Value,
+    // Every store is cast to the declared type, so integers keep their width
+    kind: TokenKind,
+}
+
+// Where an assignment writes into an array or a struct, with the index already evaluated
+enum Key<'a> {
+    Field(&'a str),
+    Index(Value),
+}
+
+impl Key<'_> {
+    fn read(&self, object: &Value, span: StatementSpan) -> Result<Value, RuntimeError> {
+        match self {
+            Key::Field(name) => get_field(object, name, span),
+            Key::Index(index) => element(object, index, span),
+        }
+    }
+
+    fn write(self, mut object: Value, value: Value, span: StatementSpan) -> Result<Value, RuntimeError> {
+        match self {
+            Key::Field(name) => {
+                object.set_field(name, value).ok_or_else(|| error(ErrorCode::ER009, span, no_field(&object, name)))?;
+                Ok(object)
+            },
+            Key::Index(index) => set_element(object, &index, value, span),
+        }
+    }
+}
+
+enum Callee<'a> {
+    Native(NativeFunction),
+    // With the namespaces it's declared in
+    Function(&'a Statement<'a>, Vec<&'a str>),
+}
+
+pub struct Interpreter<'a> {
+    // The first scope holds the globals by their qualified name, the others belong to the running function
+    scopes: Vec<HashMap<Cow<'a, str>, Variable>>,
+    // By qualified name, with the namespaces the function is declared in
+    functions: HashMap<String, (&'a Statement<'a>, Vec<&'a str>)>,
+    natives: HashMap<&'a str, NativeFunction>,
+    // Structs and enums by qualified name, with the namespaces the types they hold are written in
+    declarations: HashMap<String, (&'a Statement<'a>, Vec<&'a str>)>,
+    // The type and the default value of the structs and enums used so far
+    types: HashMap<String, TypeDefinition>,
+    // Namespaces of the code that is running, names are looked up from the innermost one
+    namespaces: Vec<&'a str>,
+    depth: usize,
+    // From the type checker, what each arithmetic expression wraps its result to
+    kinds: Kinds,
+}
+
+impl<'a> Default for Interpreter<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> Interpreter<'a> {
+    pub fn new() -> Interpreter<'a> {
+        let mut interpreter: Interpreter = Interpreter {
+            scopes: vec![HashMap::new()],
+            functions: HashMap::new(),
+            natives: HashMap::new(),
+            declarations: HashMap::new(),
+            types: HashMap::new(),
+            namespaces: Vec::new(),
+            depth: 0,
+            kinds: Kinds::new(),
+        };
+
+        for (name, function) in builtins() {
+            interpreter.register_native(name, function);
+        }
+
+        interpreter
+    }
+
+    // Without the kinds the checker found, integer math is done in 64 bits and is
+    // only cut to the width of a variable when it's stored
+    pub fn with_kinds(mut self, kinds: Kinds) -> Interpreter<'a> {
+        self.kinds = kinds;
+        self
+    }
+
+    pub fn register_native(&mut self, name: &'a str, function: NativeFunction) {
+        self.natives.insert(name, function);
+    }
+
+    pub fn global(&self, name: &str) -> Option<&Value> {
+        self.scopes[0].get(name).map(|variable| &variable.value)
+    }
+
+    pub fn run(&mut self, statements: &'a [Statement<'a>]) -> Result<(), RuntimeError> {
+        match self.execute_statements(statements)? {
+            Flow::Normal => Ok(()),
+            Flow::Break | Flow::Continue => Err(RuntimeError {
+                code: ErrorCode::ER003,
+                span: StatementSpan::default(),
+                hint: "'break' and 'continue' can only be used inside a 'while' or 'for' body".to_string(),
+            }),
+            Flow::Return(_) => Err(RuntimeError {
+                code: ErrorCode::ER004,
+                span: StatementSpan::default(),
+                hint: "'return' can only be used inside a function body".to_string(),
+            }),
+        }
+    }
+
+    fn execute_statements(&mut self, statements: &'a [Statement<'a>]) -> Result<Flow, RuntimeError> {
+        self.hoist(statements);
+
+        for statement in statements {
+            match self.execute_statement(statement)? {
+                Flow::Normal => continue,
+                flow => return Ok(flow),
+            }
+        }
+
+        Ok(Flow::Normal)
+    }
+
+    // Same hoisting rule as the SemanticAnalyzer
+    fn hoist(&mut self, statements: &'a [Statement<'a>]) {
+        for statement in statements {
+            match &statement.node {
+                RawStatement::Function { name, .. } => {
+                    let name: String = Path::from(*name).qualified(&self.namespaces).into_owned();
+                    self.functions.insert(name, (statement, self.namespaces.clone()));
+                },
+
+                RawStatement::Struct { name, .. } | RawStatement::Enum { name, .. } => {
+                    let name: String = Path::from(*name).qualified(&self.namespaces).into_owned();
+                    self.declarations.insert(name, (statement, self.namespaces.clone()));
+                },
+
+                RawStatement::Namespace { name, body } if self.scopes.len() == 1 => {
+                    self.namespaces.push(name);
+                    self.hoist(&body.statements);
+                    self.namespaces.pop();
+                },
+
+                _ => {},
+            }
+        }
+    }
+
+    fn execute_body(&mut self, body: &'a Body<'a>) -> Result<Flow, RuntimeError> {
+        self.scopes.push(HashMap::new());
+        let flow: Result<Flow, RuntimeError> = self.execute_statements(&body.statements);
+        self.scopes.pop();
+        flow
+    }
+
+    fn execute_statement(&mut self, statement: &'a Statement<'a>) -> Result<Flow, RuntimeError> {
+        match &statement.node {
+            RawStatement::VariableDeclaration { type_, name, value, .. } => {
+                self.execute_declaration(type_, name, value.as_ref(), statement.span)?;
+            },
+
+            RawStatement::VariableAssignment { target, operator, value } => {
+                self.execute_assignment(target, *operator, value, statement.span)?;
+            },
+
+            RawStatement::If { condition, body, elses } => {
+                if self.evaluate(condition)?.is_truthy() {
+                    return self.execute_body(body);
+                }
+
+                for branch in elses {
+                    match branch {
+                        ElseBranch::ElseIf(statement) => {
+                            let RawStatement::If { condition, body, .. } = &statement.node else {
+                                continue;
+                            };
+                            if self.evaluate(condition)?.is_truthy() {
+                                return self.execute_body(body);
+                            }
+                        },
+                        ElseBranch::Else(body) => return self.execute_body(body),
+                    }
+                }
+            },
+
+            RawStatement::While { condition, body } => {
+                while self.evaluate(condition)?.is_truthy() {
+                    match self.execute_body(body)? {
+                        Flow::Break => break,
+                        Flow::Normal | Flow::Continue => continue,
+                        flow => return Ok(flow),
+                    }
+                }
+            },
+
+            RawStatement::For { type_, name, iterable, body } => {
+                return self.execute_for(*type_, name, iterable, body, statement.span);
+            },
+
+            RawStatement::LoopControl(keyword) => {
+                return Ok(if *keyword == "break" { Flow::Break } else { Flow::Continue });
+            },
+
+            // Functions are registered when the enclosing block starts
+            RawStatement::Function { .. } => {},
+
+            RawStatement::Return(value) => {
+                let value: Value = match value {
+                    Some(value) => self.evaluate(value)?,
+                    None => Value::Void,
+                };
+                return Ok(Flow::Return(value));
+            },
+
+            RawStatement::FunctionCall { name, arguments } => {
+                self.call(name, arguments, statement.span)?;
+            },
+
+            RawStatement::Namespace { name, body } => {
+                self.execute_namespace(name, body)?;
+            },
+
+            // The ModuleLoader put the statements of the module before this one
+            RawStatement::Import(_) => {},
+
+            // Structs and enums are registered when the enclosing block starts
+            RawStatement::Struct { .. } | RawStatement::Enum { .. } => {},
+
+            RawStatement::Match { value, arms } => {
+                return self.execute_match(value, arms, statement.span);
+            },
+        }
+
+        Ok(Flow::Normal)
+    }
+
+    // Kept out of 'execute_statement' so its frame stays small for deep recursion
+    fn execute_declaration(&mut self, type_: &'a Type<'a>, name: &'a str, value: Option<&'a Expression<'a>>, span: StatementSpan) -> Result<(), RuntimeError> {
+        let value: Value = match value {
+            // The size is evaluated before the value, like the VM does
+            Some(value) => {
+                let length: Option<usize> = self.evaluate_length(type_, span)?;
+                let value: Value = self.evaluate(value)?.cast(type_.kind);
+                if let Some(length) = length {
+                    check_length(&value, length, span)?;
+                }
+                value
+            },
+            None => self.default_value(type_, span)?,
+        };
+
+        // Globals are stored with the namespaces they are declared in
+        let name: Cow<'a, str> = match self.scopes.len() {
+            1 => Path::from(name).qualified(&self.namespaces),
+            _ => Cow::Borrowed(name),
+        };
+        self.scopes.last_mut().unwrap().insert(name, Variable { value, kind: type_.kind });
+        Ok(())
+    }
+
+    // Kept out of 'execute_statement' so its frame stays small for deep recursion
+    fn execute_assignment(&mut self, target: &'a Place<'a>, operator: TokenKind, value: &'a Expression<'a>, span: StatementSpan) -> Result<(), RuntimeError> {
+        let path: Path<'a> = Path::from(target.name);
+
+        // The indexes are evaluated first, in order, then the value. Like the VM the arrays and the structs
+        // the place goes through are read after the value, so the changes it makes to them are kept
+        let mut keys: Vec<Key<'a>> = Vec::with_capacity(target.accessors.len());
+        for accessor in &target.accessors {
+            keys.push(match accessor {
+                Accessor::Field(name) => Key::Field(name),
+                Accessor::Index(index) => Key::Index(self.evaluate(index)?),
+            });
+        }
+
+        // The current value is read first, like the left side of a binary expression
+        let mut value: Value = if operator == TokenKind::Assignment {
+            self.evaluate(value)?
+        }
+        else {
+            let objects: Vec<Value> = self.objects(&path, &keys, span)?;
+            let current: Value = match (objects.last(), keys.last()) {
+                (Some(object), Some(key)) => key.read(object, span)?,
+                _ => self.lookup(&path, span)?.value.clone(),
+            };
+            let value: Value = self.evaluate(value)?;
+            self.binary(&current, operator.compound_operator(), &value, span)?
+        };
+
+        let objects: Vec<Value> = self.objects(&path, &keys, span)?;
+        for (object, key) in objects.into_iter().zip(keys).rev() {
+            value = key.write(object, value, span)?;
+        }
+
+        let variable: &mut Variable = self.lookup(&path, span)?;
+        variable.value = value.cast(variable.kind);
+        Ok(())
+    }
+
+    // Copies of every array and struct a place goes through, the last one holds what the place names
+    fn objects(&mut self, path: &Path<'a>, keys: &[Key<'a>], span: StatementSpan) -> Result<Vec<Value>, RuntimeError> {
+        let Some((_, keys)) = keys.split_last() else {
+            return Ok(Vec::new());
+        };
+
+        let mut objects: Vec<Value> = vec![self.lookup(path, span)?.value.clone()];
+        for key in keys {
+            let object: Value = key.read(objects.last().unwrap(), span)?;
+            objects.push(object);
+        }
+        Ok(objects)
+    }
+
+    // Its functions were registered by 'hoist', only the constants are left
+    fn execute_namespace(&mut self, name: &'a str, body: &'a Body<'a>) -> Result<(), RuntimeError> {
+        self.namespaces.push(name);
+        let flow: Result<Flow, RuntimeError> = body.statements.iter()
+            .try_fold(Flow::Normal, |_, statement| self.execute_statement(statement));
+        self.namespaces.pop();
+        flow.map(|_| ())
+    }
+
+    // Kept out of 'execute_statement' so its frame stays small for deep recursion
+    fn execute_for(&mut self, type_: Option<TokenKind>, name: &'a str, iterable: &'a Iterable<'a>, body: &'a Body<'a>, span: StatementSpan) -> Result<Flow, RuntimeError> {
+        // Without a type the values are stored as they are
+        let kind: TokenKind = type_.unwrap_or(TokenKind::Null);
+
+        // The array or the end of the range is evaluated once, before the first iteration
+        let (mut position, iterated): (Value, Value) = match iterable {
+            Iterable::Range { start, end } => (self.evaluate(start)?.cast(kind), self.evaluate(end)?.cast(kind)),
+            Iterable::Array(array) => (Value::Int(0), self.evaluate(array)?),
+        };
+
+        while let Some((value, next)) = iterated.next_element(&position)
+            .map_err(|hint| error(ErrorCode::ER009, span, hint))? {
+            position = next;
+
+            // The variable lives in the same scope as the body
+            self.scopes.push(HashMap::from([(Cow::Borrowed(name), Variable { value: value.cast(kind), kind })]));
+            let flow: Result<Flow, RuntimeError> = self.execute_statements(&body.statements);
+            self.scopes.pop();
+
+            match flow? {
+                Flow::Break => break,
+                Flow::Normal | Flow::Continue => continue,
+                flow => return Ok(flow),
+            }
+        }
+
+        Ok(Flow::Normal)
+    }
+
+    // Kept out of 'execute_statement' so its frame stays small for deep recursion
+    fn execute_match(&mut self, value: &'a Expression<'a>, arms: &'a [Arm<'a, Body<'a>>], span: StatementSpan) -> Result<Flow, RuntimeError> {
+        let value: Value = self.evaluate(value)?;
+
+        for arm in arms {
+            // Like the variable of a 'for' loop, the bindings live in the same scope as the body
+            if let Some(bindings) = self.matches(&value, arm)? {
+                self.scopes.push(bindings);
+                let flow: Result<Flow, RuntimeError> = self.execute_statements(&arm.value.statements);
+                self.scopes.pop();
+                return flow;
+            }
+        }
+
+        Err(error(ErrorCode::ER011, span, NO_ARM.to_string()))
+    }
+
+    fn evaluate_match(&mut self, value: &'a Expression<'a>, arms: &'a [Arm<'a, Expression<'a>>], span: StatementSpan) -> Result<Value, RuntimeError> {
+        let value: Value = self.evaluate(value)?;
+
+        for arm in arms {
+            if let Some(bindings) = self.matches(&value, arm)? {
+                self.scopes.push(bindings);
+                let result: Result<Value, RuntimeError> = self.evaluate(&arm.value);
+                self.scopes.pop();
+                return result;
+            }
+        }
+
+        Err(error(ErrorCode::ER011, span, NO_ARM.to_string()))
+    }
+
+    // The names the arm binds when its pattern matches the value, None when it doesn't
+    fn matches<T>(&mut self, value: &Value, arm: &'a Arm<'a, T>) -> Result<Option<HashMap<Cow<'a, str>, Variable>>, RuntimeError> {
+        let (path, bindings) = match &arm.pattern {
+            Pattern::Wildcard => return Ok(Some(HashMap::new())),
+            Pattern::Literal(literal) => {
+                let literal: Value = self.evaluate(literal)?;
+                let equal: bool = self.binary(value, TokenKind::Equal, &literal, arm.span)?.is_truthy();
+                return Ok(equal.then(HashMap::new));
+            },
+            Pattern::Variant { path, bindings } => (path, bindings),
+        };
+
+        let (type_, index) = self.find_variant(path)?
+            .ok_or_else(|| error(ErrorCode::ER008, arm.span, format!("'{}' is not defined", path)))?;
+        let count: usize = type_.variants[index].1.len();
+        if !bindings.is_empty() && bindings.len() != count {
+            return Err(error(ErrorCode::EC022, arm.span,
+                format!("'{}' holds {} value(s) but {} name(s) were given", path, count, bindings.len())));
+        }
+
+        let Value::Enum(matched, variant, values) = value else {
+            return Ok(None);
+        };
+        if *variant != index || *matched != type_ {
+            return Ok(None);
+        }
+
+        Ok(Some(bindings.iter().zip(values)
+            .filter(|(binding, _)| **binding != "_")
+            .map(|(binding, value)| (Cow::Borrowed(*binding), Variable { value: value.clone(), kind: TokenKind::Null }))
+            .collect()))
+    }
+
+    pub fn evaluate(&mut self, expression: &'a Expression<'a>) -> Result<Value, RuntimeError> {
+        match &expression.node {
+            RawExpression::Variable(name) => match self.find_variant(name)? {
+                Some((type_, index)) => variant(name, type_, index, Vec::new(), expression.span),
+                None => Ok(self.lookup(name, expression.span)?.value.clone()),
+            },
+
+            RawExpression::Literal { kind, value } => Value::from_literal(*kind, value)
+                .ok_or_else(|| error(ErrorCode::ER006, expression.span, format!("'{}' can't be represented", value))),
+
+            RawExpression::Binary { left, operator: TokenKind::And, right } => {
+                let result: bool = self.evaluate(left)?.is_truthy() && self.evaluate(right)?.is_truthy();
+                Ok(Value::Bool(result))
+            },
+
+            RawExpression::Binary { left, operator: TokenKind::Or, right } => {
+                let result: bool = self.evaluate(left)?.is_truthy() || self.evaluate(right)?.is_truthy();
+                Ok(Value::Bool(result))
+            },
+
+            RawExpression::Binary { left, operator, right } => {
+                let left: Value = self.evaluate(left)?;
+                let right: Value = self.evaluate(right)?;
+                let result: Value = self.binary(&left, *operator, &right, expression.span)?;
+                Ok(self.wrap(result, expression.span))
+            },
+
+            RawExpression::Unary { operator, operand } => {
+                let operand: Value = self.evaluate(operand)?;
+                let result: Value = operand.unary(*operator).ok_or_else(|| error(ErrorCode::ER009, expression.span,
+                    format!("'{}' cannot be applied to {}", operator, operand.type_name())))?;
+                Ok(self.wrap(result, expression.span))
+            },
+
+            RawExpression::FunctionCall { name, arguments } => self.call(name, arguments, expression.span),
+
+            RawExpression::ArrayAccess { array, index } => {
+                let array: Value = self.evaluate(array)?;
+                let index: Value = self.evaluate(index)?;
+                element(&array, &index, expression.span)
+            },
+
+            RawExpression::StructLiteral { name, fields } => self.struct_literal(name, fields, expression.span),
+
+            RawExpression::Field { object, name } => {
+                let object: Value = self.evaluate(object)?;
+                get_field(&object, name, expression.span)
+            },
+
+            RawExpression::Match { value, arms } => self.evaluate_match(value, arms, expression.span),
+
+            RawExpression::ArrayLiteral(elements) => {
+                let mut values: Vec<Value> = Vec::with_capacity(elements.len());
+                for element in elements {
+                    values.push(self.evaluate(element)?);
+                }
+                Ok(Value::Array(values))
+            },
+        }
+    }
+
+    // The values are evaluated in the order the struct declares its fields
+    fn struct_literal(&mut self, name: &Path<'a>, values: &'a [(&'a str, Expression<'a>)], span: StatementSpan) -> Result<Value, RuntimeError> {
+        let Definition::Struct(type_) = self.find_type(name, &self.namespaces.clone(), span)?.definition else {
+            return Err(error(ErrorCode::ES023, span, format!("'{}' is an enum, pick one of its variants", name)));
+        };
+
+        let mut fields: Vec<Value> = Vec::with_capacity(type_.fields.len());
+        for (field, kind) in &type_.fields {
+            let (_, value) = values.iter().find(|(name, _)| *name == &**field).ok_or_else(|| error(ErrorCode::EC017, span,
+                format!("set '{}' too, every field needs a value", field)))?;
+            fields.push(self.evaluate(value)?.cast(*kind));
+        }
+
+        Ok(Value::Struct(type_, fields))
+    }
+
+    fn call(&mut self, name: &Path<'a>, arguments: &'a [Expression<'a>], span: StatementSpan) -> Result<Value, RuntimeError> {
+        let mut values: Vec<Value> = Vec::with_capacity(arguments.len());
+        for argument in arguments {
+            values.push(self.evaluate(argument)?);
+        }
+
+        // Variants holding values are built like a call
+        if let Some((type_, index)) = self.find_variant(name)? {
+            return variant(name, type_, index, values, span);
+        }
+
+        let (function, namespaces) = match self.callee(name) {
+            Some(Callee::Native(native)) => {
+                return native(&values).map_err(|message| error(ErrorCode::ER007, span, message));
+            },
+            Some(Callee::Function(function, namespaces)) => (function, namespaces),
+            None => return Err(error(ErrorCode::ER008, span, format!("'{}' is not defined", name))),
+        };
+        let RawStatement::Function { parameters, type_, body, .. } = &function.node else {
+            unreachable!("only functions are stored in 'functions'");
+        };
+
+        if self.depth >= MAX_CALL_DEPTH {
+            return Err(error(ErrorCode::ER010, span, format!("calls nested deeper than {}", MAX_CALL_DEPTH)));
+        }
+
+        // The callee only sees the globals, so the caller's locals are set aside
+        let mut locals: HashMap<Cow<'a, str>, Variable> = HashMap::new();
+        for (parameter, value) in parameters.iter().zip(values) {
+            locals.insert(Cow::Borrowed(parameter.name), Variable {
+                value: value.cast(parameter.type_.kind),
+                kind: parameter.type_.kind,
+            });
+        }
+
+        // and the names in its body are looked up from the namespace it's declared in
+        let caller: Vec<HashMap<Cow<'a, str>, Variable>> = self.scopes.split_off(1);
+        let caller_namespaces: Vec<&'a str> = std::mem::replace(&mut self.namespaces, namespaces);
+        self.scopes.push(locals);
+        self.depth += 1;
+
+        let flow: Result<Flow, RuntimeError> = self.execute_statements(&body.statements);
+
+        self.depth -= 1;
+        self.scopes.truncate(1);
+        self.scopes.extend(caller);
+        self.namespaces = caller_namespaces;
+
+        match flow? {
+            Flow::Return(value) => Ok(value.cast(*type_)),
+            _ if *type_ == TokenKind::Null => Ok(Value::Void),
+            _ => Err(error(ErrorCode::ER005, function.span,
+                format!("'{}' must return a value of type '{}'", name, type_))),
+        }
+    }
+
+    // Natives and functions are looked up together, from the innermost namespace
+    fn callee(&self, name: &Path<'a>) -> Option<Callee<'a>> {
+        name.candidates(&self.namespaces).find_map(|candidate| {
+            if let Some(native) = self.natives.get(candidate.as_ref()) {
+                return Some(Callee::Native(native.clone()));
+            }
+
+            self.functions.get(candidate.as_ref())
+                .map(|(function, namespaces)| Callee::Function(function, namespaces.clone()))
+        })
+    }
+
+    fn binary(&self, left: &Value, operator: TokenKind, right: &Value, span: StatementSpan) -> Result<Value, RuntimeError> {
+        match left.binary(operator, right) {
+            Some(Ok(value)) => Ok(value),
+            Some(Err(ArithmeticError::DivisionByZero)) => Err(error(ErrorCode::ER001, span,
+                "the right side of '/' or '%' evaluated to zero".to_string())),
+            None => Err(error(ErrorCode::ER009, span,
+                format!("'{}' cannot be applied to {} and {}", operator, left.type_name(), right.type_name()))),
+        }
+    }
+
+    // Cuts the result of an arithmetic expression to the width of its operands
+    fn wrap(&self, value: Value, span: StatementSpan) -> Value {
+        match self.kinds.get(&span) {
+            Some(kind) => value.cast(*kind),
+            None => value,
+        }
+    }
+
+    fn default_value(&mut self, type_: &'a Type<'a>, span: StatementSpan) -> Result<Value, RuntimeError> {
+        let length: Option<usize> = self.evaluate_length(type_, span)?;
+        let Some(name) = &type_.name else {
+            return Ok(Value::default_for(type_.kind, length));
+        };
+
+        let default: Value = self.find_type(name, &self.namespaces.clone(), span)?.default;
+        Ok(match length {
+            Some(length) => Value::Array(vec![default; length]),
+            None => default,
+        })
+    }
+
+    fn find_type(&mut self, path: &Path<'a>, namespaces: &[&'a str], span: StatementSpan) -> Result<TypeDefinition, RuntimeError> {
+        self.lookup_type(path, namespaces)?
+            .ok_or_else(|| error(ErrorCode::ER008, span, format!("'{}' is not defined", path)))
+    }
+
+    // The type and the default value of a struct or an enum, worked out the first time it's used
+    fn lookup_type(&mut self, path: &Path<'a>, namespaces: &[&'a str]) -> Result<Option<TypeDefinition>, RuntimeError> {
+        let Some(name) = path.candidates(namespaces).find(|candidate| self.declarations.contains_key(candidate.as_ref())) else {
+            return Ok(None);
+        };
+        let name: String = name.into_owned();
+        if let Some(found) = self.types.get(&name) {
+            return Ok(Some(found.clone()));
+        }
+
+        let (declaration, namespaces) = self.declarations[&name].clone();
+        let found: TypeDefinition = match &declaration.node {
+            RawStatement::Struct { fields, .. } => {
+                let mut kinds: Vec<(Rc<str>, TokenKind)> = Vec::with_capacity(fields.len());
+                let mut defaults: Vec<Value> = Vec::with_capacity(fields.len());
+                for field in fields {
+                    kinds.push((Rc::from(field.name), field.type_.kind));
+                    defaults.push(self.member_default(&field.type_, &namespaces, field.span)?);
+                }
+
+                let type_: Rc<StructType> = Rc::new(StructType { name: Rc::from(name.as_str()), fields: kinds });
+                TypeDefinition { definition: Definition::Struct(type_.clone()), default: Value::Struct(type_, defaults) }
+            },
+
+            RawStatement::Enum { variants, .. } => {
+                let kinds: Vec<(Rc<str>, Vec<TokenKind>)> = variants.iter()
+                    .map(|variant| (Rc::from(variant.name), variant.payload.iter().map(|type_| type_.kind).collect()))
+                    .collect();
+                let type_: Rc<EnumType> = Rc::new(EnumType { name: Rc::from(name.as_str()), variants: kinds });
+
+                // Like the Compiler, an enum without variants has no value to start from
+                let default: Value = match variants.first() {
+                    Some(variant) => {
+                        let mut values: Vec<Value> = Vec::with_capacity(variant.payload.len());
+                        for type_ in &variant.payload {
+                            values.push(self.member_default(type_, &namespaces, variant.span)?);
+                        }
+                        Value::Enum(type_.clone(), 0, values)
+                    },
+                    None => Value::Void,
+                };
+                TypeDefinition { definition: Definition::Enum(type_), default }
+            },
+
+            _ => unreachable!("only structs and enums are stored in 'declarations'"),
+        };
+
+        self.types.insert(name, found.clone());
+        Ok(Some(found))
+    }
+
+    // The default value of a field or of a value of a variant, their arrays have a literal size
+    fn member_default(&mut self, type_: &'a Type<'a>, namespaces: &[&'a str], span: StatementSpan) -> Result<Value, RuntimeError> {
+        let Some(path) = &type_.name else {
+            return Ok(Value::default_for(type_.kind, type_.literal_length()));
+        };
+
+        let default: Value = self.find_type(path, namespaces, span)?.default;
+        Ok(match type_.is_array {
+            true => Value::Array(vec![default; type_.literal_length().unwrap_or(0)]),
+            false => default,
+        })
+    }
+
+    // The enum and the index of the variant a path refers to, None when it isn't a variant
+    fn find_variant(&mut self, path: &Path<'a>) -> Result<Option<(Rc<EnumType>, usize)>, RuntimeError> {
+        let Some(parent) = path.parent() else {
+            return Ok(None);
+        };
+        let Some(TypeDefinition { definition: Definition::Enum(type_), .. }) = self.lookup_type(&parent, &self.namespaces.clone())? else {
+            return Ok(None);
+        };
+
+        Ok(type_.variant(path.name).map(|index| (type_, index)))
+    }
+
+    fn evaluate_length(&mut self, type_: &'a Type<'a>, span: StatementSpan) -> Result<Option<usize>, RuntimeError> {
+        match &type_.array_length {
+            Some(length) => Ok(Some(array_length(&self.evaluate(length)?, span)?)),
+            None => Ok(None),
+        }
+    }
+
+    // Same lookup rule as the SemanticAnalyzer's Scope
+    fn lookup(&mut self, path: &Path<'a>, span: StatementSpan) -> Result<&mut Variable, RuntimeError> {
+        if !path.is_qualified() &&
+            let Some(index) = (1..self.scopes.len()).rev().find(|&index| self.scopes[index].contains_key(path.name)) {
+            return Ok(self.scopes[index].get_mut(path.name).unwrap());
+        }
+
+        let global: Option<Cow<'a, str>> = path.candidates(&self.namespaces)
+            .find(|name| self.scopes[0].contains_key(name.as_ref()));
+        match global {
+            Some(name) => Ok(self.scopes[0].get_mut(name.as_ref()).unwrap()),
+            None => Err(error(ErrorCode::ER008, span, format!("'{}' is not defined", path))),
+        }
+    }
+}
+
+// Like the Compiler, the values are only counted once the enum is known
+fn variant(name: &Path, type_: Rc<EnumType>, index: usize, values: Vec<Value>, span: StatementSpan) -> Result<Value, RuntimeError> {
+    let kinds: &[TokenKind] = &type_.variants[index].1;
+    if kinds.len() != values.len() {
+        return Err(error(ErrorCode::EC022, span, format!("'{}' holds {} value(s) but {} were given", name, kinds.len(), values.len())));
+    }
+
+    let values: Vec<Value> = values.into_iter().zip(kinds).map(|(value, kind)| value.cast(*kind)).collect();
+    Ok(Value::Enum(type_, index, values))
+}
+
+fn get_field(object: &Value, name: &str, span: StatementSpan) -> Result<Value, RuntimeError> {
+    object.get_field(name).cloned().ok_or_else(|| error(ErrorCode::ER009, span, no_field(object, name)))
+}
+
+// Shared with the VM, so both report the same error
+pub(crate) fn no_field(object: &Value, name: &str) -> String {
+    format!("{} has no field '{}'", object.type_name(), name)
+}
+
+// Shared with the VM, reads and writes check the index the same way
+// Arrays can't be longer than this, a larger size would take all the memory
+const MAX_ARRAY_LENGTH: usize = 1 << 24;
+
+// The number of elements of an array whose size evaluated to 'size'
+pub(crate) fn array_length(size: &Value, span: StatementSpan) -> Result<usize, RuntimeError> {
+    let length: i128 = match size {
+        Value::UInt(size) => *size as i128,
+        size => size.as_i64() as i128,
+    };
+
+    usize::try_from(length).ok()
+        .filter(|length| *length <= MAX_ARRAY_LENGTH)
+        .ok_or_else(|| error(ErrorCode::ER012, span,
+            format!("the size is {}, it must be between 0 and {}", length, MAX_ARRAY_LENGTH)))
+}
+
+// An array stored in a variable with a size must have that many elements
+pub(crate) fn check_length(array: &Value, length: usize, span: StatementSpan) -> Result<(), RuntimeError> {
+    match array {
+        Value::Array(values) if values.len() != length => Err(error(ErrorCode::ER012, span,
+            format!("the array has {} elements, its size is {}", values.len(), length))),
+        _ => Ok(()),
+    }
+}
+
+pub(crate) fn element(array: &Value, index: &Value, span: StatementSpan) -> Result<Value, RuntimeError> {
+    match array {
+        Value::Array(values) => Ok(values[position(index, values.len(), span)?].clone()),
+        other => Err(not_indexable(other, span)),
+    }
+}
+
+pub(crate) fn set_element(array: Value, index: &Value, value: Value, span: StatementSpan) -> Result<Value, RuntimeError> {
+    match array {
+        Value::Array(mut values) => {
+            let position: usize = position(index, values.len(), span)?;
+            values[position] = value;
+            Ok(Value::Array(values))
+        },
+        other => Err(not_indexable(&other, span)),
+    }
+}
+
+fn position(index: &Value, length: usize, span: StatementSpan) -> Result<usize, RuntimeError> {
+    let position: i64 = index.as_i64();
+    usize::try_from(position).ok()
+        .filter(|position| *position < length)
+        .ok_or_else(|| error(ErrorCode::ER002, span,
+            format!("index {} is out of bounds for an array of length {}", position, length)))
+}
+
+fn not_indexable(value: &Value, span: StatementSpan) -> RuntimeError {
+    error(ErrorCode::ER009, span, format!("{} cannot be indexed", value.type_name()))
+}
+
+fn error(code: ErrorCode, span: StatementSpan, hint: String) -> RuntimeError {
+    RuntimeError { code, span, hint }
+}
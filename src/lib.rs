@@ -1,4 +1,20 @@
+pub mod builtins;
+pub mod bytecode;
+pub mod compiler;
+pub mod engine;
 pub mod error;
+pub mod explain;
+pub mod formatter;
+pub mod interpreter;
+pub mod lints;
+pub mod loader;
+pub mod owned;
 pub mod parser;
+pub mod repl;
 pub mod semantics;
-pub mod tokenizer;
\ No newline at end of file
+pub mod source;
+pub mod tokenizer;
+pub mod typechecker;
+pub mod value;
+pub mod visitor;
+pub mod vm;
\ No newline at end of file
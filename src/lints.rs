@@ -0,0 +1,342 @@
+use std::collections::HashMap;
+
+use crate::error::*;
+use crate::parser::{Arm, Body, Expression, Parameter, Path, Pattern, RawExpression, RawStatement, Statement, StatementSpan};
+use crate::source::SourceMap;
+use crate::tokenizer::TokenKind;
+use crate::visitor::{walk_expression, walk_parameter, walk_statement, Visitor};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum LintLevel {
+    Allow,
+    Warn,
+    // Reported as an error, the program doesn't run
+    Deny,
+}
+
+// Level of every lint, the ones never set warn
+#[derive(Debug, Clone, Default)]
+pub struct LintConfig {
+    levels: HashMap<WarningCode, LintLevel>,
+}
+
+impl LintConfig {
+    pub fn new() -> LintConfig {
+        LintConfig { levels: HashMap::new() }
+    }
+
+    pub fn set(&mut self, code: WarningCode, level: LintLevel) {
+        self.levels.insert(code, level);
+    }
+
+    pub fn set_all(&mut self, level: LintLevel) {
+        for code in WarningCode::ALL {
+            self.set(code, level);
+        }
+    }
+
+    pub fn level(&self, code: WarningCode) -> LintLevel {
+        self.levels.get(&code).copied().unwrap_or(LintLevel::Warn)
+    }
+}
+
+#[derive(Debug)]
+pub struct Warning {
+    pub code: WarningCode,
+    pub span: StatementSpan,
+    pub hint: String,
+    pub level: LintLevel,
+}
+
+impl Warning {
+    pub fn is_denied(&self) -> bool {
+        self.level == LintLevel::Deny
+    }
+
+    pub fn to_diagnostic<'a>(&'a self, sources: &'a SourceMap) -> Diagnostic<'a> {
+        Diagnostic {
+            kind: if self.is_denied() { DiagnosticKind::Denied(self.code) } else { DiagnosticKind::Warning(self.code) },
+            info: DiagnosticInfo::from_span(sources, self.span.file, self.span.start, self.span.end),
+            hint: Some(&self.hint)
+        }
+    }
+}
+
+struct Binding<'a> {
+    name: &'a str,
+    span: StatementSpan,
+    is_parameter: bool,
+    used: bool,
+}
+
+struct FunctionUse<'a> {
+    name: &'a str,
+    span: StatementSpan,
+    called: bool,
+}
+
+// Looks for code that is valid but likely a mistake, it expects a program
+// that already passed the SemanticAnalyzer and the TypeChecker
+pub struct Linter<'a> {
+    ast: &'a [Statement<'a>],
+    config: &'a LintConfig,
+    warnings: Vec<Warning>,
+
+    scopes: Vec<Vec<Binding<'a>>>,
+    functions: Vec<FunctionUse<'a>>,
+    // Calls a function makes to itself don't count as uses
+    current_function: Option<&'a str>,
+}
+
+impl<'a> Linter<'a> {
+    pub fn new(ast: &'a [Statement<'a>], config: &'a LintConfig) -> Linter<'a> {
+        Linter {
+            ast,
+            config,
+            warnings: Vec::new(),
+
+            scopes: Vec::new(),
+            functions: Vec::new(),
+            current_function: None,
+        }
+    }
+
+    // Returns the warnings that aren't allowed, in source order
+    pub fn lint(&mut self) -> Vec<Warning> {
+        for statement in self.ast {
+            if let RawStatement::Function { name, .. } = &statement.node {
+                self.functions.push(FunctionUse { name, span: statement.span, called: false });
+            }
+        }
+
+        self.scopes.push(Vec::new());
+        self.lint_statements(self.ast);
+        self.exit_scope();
+
+        for index in 0..self.functions.len() {
+            let function: &FunctionUse = &self.functions[index];
+            if !function.called && !function.name.starts_with('_') {
+                let hint: String = format!("'{}' is never called, remove it or rename it to '_{}'", function.name, function.name);
+                self.warn(WarningCode::WL003, function.span, hint);
+            }
+        }
+
+        let mut warnings: Vec<Warning> = std::mem::take(&mut self.warnings);
+        warnings.sort_by_key(|warning| warning.span.start);
+        warnings
+    }
+
+    fn lint_statements(&mut self, statements: &'a [Statement<'a>]) {
+        let mut diverged: Option<&str> = None;
+
+        for statement in statements {
+            // Only the first unreachable statement of a block is reported
+            if let Some(keyword) = diverged.take() {
+                let hint: String = format!("this statement comes after a '{}', so it never runs", keyword);
+                self.warn(WarningCode::WL005, statement.span, hint);
+            }
+
+            self.visit_statement(statement);
+
+            match &statement.node {
+                RawStatement::Return(_) => diverged = Some("return"),
+                RawStatement::LoopControl(keyword) => diverged = Some(keyword),
+                _ => {},
+            }
+        }
+    }
+
+    // Warns when the condition only uses literals
+    fn lint_condition(&mut self, condition: &Expression<'a>, keyword: &str) {
+        if Self::is_constant(condition) {
+            let hint: String = format!("the '{}' condition only uses literals, so it never changes", keyword);
+            self.warn(WarningCode::WL006, condition.span, hint);
+        }
+    }
+
+    fn is_constant(expression: &Expression) -> bool {
+        match &expression.node {
+            RawExpression::Literal { .. } => true,
+            RawExpression::Unary { operand, .. } => Self::is_constant(operand),
+            RawExpression::Binary { left, right, .. } => Self::is_constant(left) && Self::is_constant(right),
+            _ => false,
+        }
+    }
+
+    fn declare(&mut self, name: &'a str, span: StatementSpan, is_parameter: bool) {
+        let (current, outer) = self.scopes.split_last().unwrap();
+        let shadows: bool = !current.iter().any(|binding| binding.name == name)
+            && outer.iter().any(|scope| scope.iter().any(|binding| binding.name == name));
+
+        if shadows {
+            let hint: String = format!("'{}' is already declared in an outer scope, consider another name", name);
+            self.warn(WarningCode::WL004, span, hint);
+        }
+
+        self.scopes.last_mut().unwrap().push(Binding { name, span, is_parameter, used: false });
+    }
+
+    fn use_variable(&mut self, path: &Path) {
+        // Qualified names only reach namespace members, which aren't tracked
+        if path.is_qualified() {
+            return;
+        }
+
+        let name: &str = path.name;
+        let binding = self.scopes.iter_mut().rev()
+            .find_map(|scope| scope.iter_mut().rev().find(|binding| binding.name == name));
+
+        if let Some(binding) = binding {
+            binding.used = true;
+        }
+    }
+
+    fn call(&mut self, path: &Path) {
+        let name: &str = path.name;
+        if path.is_qualified() || self.current_function == Some(name) {
+            return;
+        }
+
+        if let Some(function) = self.functions.iter_mut().find(|function| function.name == name) {
+            function.called = true;
+        }
+    }
+
+    // The names a pattern binds live in the scope of its arm
+    fn enter_arm<T>(&mut self, arm: &'a Arm<'a, T>) {
+        self.visit_pattern(&arm.pattern);
+        self.scopes.push(Vec::new());
+
+        if let Pattern::Variant { bindings, .. } = &arm.pattern {
+            for binding in bindings.iter().filter(|binding| **binding != "_") {
+                self.declare(binding, arm.span, false);
+            }
+        }
+    }
+
+    fn exit_scope(&mut self) {
+        let Some(bindings) = self.scopes.pop() else {
+            return;
+        };
+
+        for binding in bindings {
+            if binding.used || binding.name.starts_with('_') {
+                continue;
+            }
+
+            let code: WarningCode = if binding.is_parameter { WarningCode::WL002 } else { WarningCode::WL001 };
+            let hint: String = format!("'{}' is never read, remove it or rename it to '_{}'", binding.name, binding.name);
+            self.warn(code, binding.span, hint);
+        }
+    }
+
+    fn warn(&mut self, code: WarningCode, span: StatementSpan, hint: String) {
+        let level: LintLevel = self.config.level(code);
+        if level != LintLevel::Allow {
+            self.warnings.push(Warning { code, span, hint, level });
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for Linter<'a> {
+    fn visit_statement(&mut self, statement: &'a Statement<'a>) {
+        match &statement.node {
+            RawStatement::VariableDeclaration { name, .. } => {
+                walk_statement(self, statement);
+                self.declare(name, statement.span, false);
+            },
+
+            RawStatement::If { condition, .. } => {
+                self.lint_condition(condition, "if");
+                walk_statement(self, statement);
+            },
+
+            RawStatement::While { condition, .. } => {
+                // 'while true' is how infinite loops are written
+                if !matches!(condition.node, RawExpression::Literal { kind: TokenKind::True, .. }) {
+                    self.lint_condition(condition, "while");
+                }
+                walk_statement(self, statement);
+            },
+
+            RawStatement::For { name, iterable, body, .. } => {
+                self.visit_iterable(iterable);
+
+                // The variable and the body share the same scope
+                self.scopes.push(Vec::new());
+                self.declare(name, statement.span, false);
+                self.lint_statements(&body.statements);
+                self.exit_scope();
+            },
+
+            RawStatement::Function { name, parameters, body, .. } => {
+                self.current_function = Some(name);
+                self.scopes.push(Vec::new());
+
+                for parameter in parameters {
+                    self.visit_parameter(parameter);
+                }
+
+                // Parameters and the body share the same scope
+                self.lint_statements(&body.statements);
+                self.exit_scope();
+                self.current_function = None;
+            },
+
+            RawStatement::FunctionCall { name, .. } => {
+                self.call(name);
+                walk_statement(self, statement);
+            },
+
+            RawStatement::Match { value, arms } => {
+                self.visit_expression(value);
+
+                // Like a 'for' loop the bindings and the body share the same scope
+                for arm in arms {
+                    self.enter_arm(arm);
+                    self.lint_statements(&arm.value.statements);
+                    self.exit_scope();
+                }
+            },
+
+            // Namespace members can be used from anywhere, so they're never reported as unused
+            RawStatement::Namespace { body, .. } => {
+                self.scopes.push(Vec::new());
+                self.lint_statements(&body.statements);
+                self.scopes.pop();
+            },
+
+            // The name of an assignment isn't visited, writing a variable doesn't count as using it
+            _ => walk_statement(self, statement),
+        }
+    }
+
+    fn visit_expression(&mut self, expression: &'a Expression<'a>) {
+        match &expression.node {
+            RawExpression::Variable(name) => self.use_variable(name),
+            RawExpression::FunctionCall { name, .. } => self.call(name),
+            RawExpression::Match { value, arms } => {
+                self.visit_expression(value);
+                for arm in arms {
+                    self.enter_arm(arm);
+                    self.visit_expression(&arm.value);
+                    self.exit_scope();
+                }
+                return;
+            },
+            _ => {},
+        }
+        walk_expression(self, expression);
+    }
+
+    fn visit_body(&mut self, body: &'a Body<'a>) {
+        self.scopes.push(Vec::new());
+        self.lint_statements(&body.statements);
+        self.exit_scope();
+    }
+
+    fn visit_parameter(&mut self, parameter: &'a Parameter<'a>) {
+        walk_parameter(self, parameter);
+        self.declare(parameter.name, parameter.span, true);
+    }
+}
@@ -0,0 +1,174 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::engine::ScriptError;
+use crate::error::*;
+use crate::owned::{to_owned, OwnedImport, OwnedRawStatement, OwnedStatement};
+use crate::parser::{Parser, StatementSpan};
+use crate::source::{FileId, SourceMap};
+use crate::tokenizer::Tokenizer;
+use crate::value::unescape;
+
+// Extension of the file a module name like 'lib::math' points to
+pub const EXTENSION: &str = "rc";
+
+// A parsed source, its statements borrow nothing so the source can be dropped
+pub struct Module {
+    pub file: FileId,
+    // Canonical path of the file, None for sources that aren't files like the standard input
+    pub path: Option<PathBuf>,
+    pub statements: Vec<OwnedStatement>,
+}
+
+// A module whose imports are being loaded, kept to find cycles
+struct Loading {
+    path: PathBuf,
+    name: String,
+}
+
+// Finds, reads and parses the modules a program imports. A file is parsed only once,
+// even when more modules import it or a later program imports it again
+#[derive(Default)]
+pub struct ModuleLoader {
+    // Directories tried in order when a file isn't next to the module importing it
+    search_paths: Vec<PathBuf>,
+    // Canonical paths of the files already loaded
+    loaded: HashSet<PathBuf>,
+    stack: Vec<Loading>,
+    modules: Vec<Module>,
+    errors: Vec<ScriptError>,
+}
+
+impl ModuleLoader {
+    pub fn new() -> ModuleLoader {
+        ModuleLoader::default()
+    }
+
+    pub fn add_search_path(&mut self, path: impl Into<PathBuf>) {
+        self.search_paths.push(path.into());
+    }
+
+    // Parses a source and every module it imports that wasn't loaded yet. A module comes
+    // after the ones it imports, so their declarations run first and the source is the
+    // last one. The errors are the syntax errors of every module, plus the failed imports
+    pub fn load(&mut self, name: &str, source: &str, sources: &mut SourceMap) -> Result<Vec<Module>, Vec<ScriptError>> {
+        let path: Option<PathBuf> = fs::canonicalize(name).ok();
+        if let Some(path) = &path {
+            self.loaded.insert(path.clone());
+        }
+        self.load_module(name, source, path, sources);
+
+        let modules: Vec<Module> = std::mem::take(&mut self.modules);
+        let errors: Vec<ScriptError> = std::mem::take(&mut self.errors);
+        if errors.is_empty() {
+            return Ok(modules);
+        }
+
+        self.forget(&modules);
+        Err(errors)
+    }
+
+    // Lets the modules be loaded again, for when their statements were thrown away
+    pub fn forget(&mut self, modules: &[Module]) {
+        for path in modules.iter().filter_map(|module| module.path.as_ref()) {
+            self.loaded.remove(path);
+        }
+    }
+
+    fn load_module(&mut self, name: &str, source: &str, path: Option<PathBuf>, sources: &mut SourceMap) {
+        let file: FileId = sources.add(name, source);
+
+        let (tokens, errors) = Tokenizer::with_file(source, file).tokenize_recovering();
+        for error in &errors {
+            self.errors.push(ScriptError::from_diagnostic(error.to_diagnostic(sources), file));
+        }
+
+        let (statements, errors) = Parser::new(tokens).parse_recovering();
+        for error in &errors {
+            self.errors.push(ScriptError::from_diagnostic(error.to_diagnostic(sources), file));
+        }
+        let statements: Vec<OwnedStatement> = to_owned(&statements);
+
+        // Imports inside a body are reported by the SemanticAnalyzer, only the top level ones are loaded
+        let directory: PathBuf = Path::new(name).parent().map(Path::to_path_buf).unwrap_or_default();
+        if let Some(path) = &path {
+            self.stack.push(Loading { path: path.clone(), name: name.to_string() });
+        }
+        for statement in &statements {
+            if let OwnedRawStatement::Import(import) = &statement.node {
+                self.import(import, statement.span, &directory, sources);
+            }
+        }
+        if path.is_some() {
+            self.stack.pop();
+        }
+
+        self.modules.push(Module { file, path, statements });
+    }
+
+    fn import(&mut self, import: &OwnedImport, span: StatementSpan, directory: &Path, sources: &mut SourceMap) {
+        let relative: PathBuf = relative_path(import);
+        let found: Option<PathBuf> = std::iter::once(directory.join(&relative))
+            .chain(self.search_paths.iter().map(|search_path| search_path.join(&relative)))
+            .find(|candidate| candidate.is_file());
+
+        let Some(found) = found else {
+            let hint: String = format!("there is no '{}' next to this file or in the search path", relative.display());
+            return self.error(ErrorCode::EM001, span, hint, sources);
+        };
+        let name: String = found.to_string_lossy().into_owned();
+
+        let path: PathBuf = match fs::canonicalize(&found) {
+            Ok(path) => path,
+            Err(error) => return self.read_error(&name, error, span, sources),
+        };
+
+        // A module still loading its own imports can't be imported again
+        if let Some(start) = self.stack.iter().position(|loading| loading.path == path) {
+            let cycle: Vec<&str> = self.stack[start..].iter().map(|loading| loading.name.as_str())
+                .chain(std::iter::once(name.as_str()))
+                .collect();
+            let hint: String = format!("the modules import each other: {}", cycle.join(" -> "));
+            return self.error(ErrorCode::EM002, span, hint, sources);
+        }
+
+        if !self.loaded.insert(path.clone()) {
+            return;
+        }
+        match fs::read_to_string(&found) {
+            Ok(source) => self.load_module(&name, &source, Some(path), sources),
+            Err(error) => {
+                self.loaded.remove(&path);
+                self.read_error(&name, error, span, sources);
+            },
+        }
+    }
+
+    fn read_error(&mut self, name: &str, error: std::io::Error, span: StatementSpan, sources: &SourceMap) {
+        let hint: String = format!("couldn't read '{}': {}", name, error);
+        self.error(ErrorCode::EM003, span, hint, sources);
+    }
+
+    fn error(&mut self, code: ErrorCode, span: StatementSpan, hint: String, sources: &SourceMap) {
+        let diagnostic: Diagnostic = Diagnostic {
+            kind: DiagnosticKind::Error(code),
+            info: DiagnosticInfo::from_span(sources, span.file, span.start, span.end),
+            hint: Some(&hint),
+        };
+        self.errors.push(ScriptError::from_diagnostic(diagnostic, span.file));
+    }
+}
+
+// 'lib::math' is the file 'lib/math.rc', file paths are used as written
+fn relative_path(import: &OwnedImport) -> PathBuf {
+    match import {
+        OwnedImport::File(literal) => PathBuf::from(unescape(&literal[1..literal.len() - 1])),
+        OwnedImport::Module(path) => {
+            let mut file: PathBuf = path.namespaces.iter().map(|namespace| &**namespace).collect();
+            file.push(&*path.name);
+            file.set_extension(EXTENSION);
+            file
+        },
+    }
+}
@@ -1,72 +1,640 @@
-use clap::Parser;
-use std::{self, fs};
+use clap::{Parser, Subcommand, ValueEnum};
+use std::io::{self, BufRead, IsTerminal, Read, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::{self, env, fs};
 
-mod error;
-use error::Diagnostic;
+use std::rc::Rc;
 
-mod tokenizer;
-use tokenizer::{Token, Tokenizer};
+use rcompiler::builtins::builtins;
+use rcompiler::bytecode::{Callable, Function, Program};
+use rcompiler::compiler::Compiler;
+use rcompiler::engine::{Engine, EngineError};
+use rcompiler::error::{Diagnostic, WarningCode};
+use rcompiler::formatter::Formatter;
+use rcompiler::interpreter::Interpreter;
+use rcompiler::lints::{LintConfig, LintLevel, Linter};
+use rcompiler::loader::{Module, ModuleLoader};
+use rcompiler::owned::borrow;
+use rcompiler::tokenizer::{Token, Tokenizer};
+use rcompiler::parser::{Parser as MyParser, Statement};
+use rcompiler::repl::{Repl, Response};
+use rcompiler::semantics::SemanticAnalyzer;
+use rcompiler::source::{FileId, SourceMap};
+use rcompiler::typechecker::{Kinds, TypeChecker};
+use rcompiler::vm::VM;
 
-mod parser;
-use parser::{Parser as MyParser};
+#[derive(Parser, Debug)]
+#[command(author, about = "gianluur's compiler for his shell (RShell).", long_about = None)]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
 
-mod semantics;
+    /// Only prints errors, warnings are hidden
+    #[arg(short, long, global = true)]
+    quiet: bool,
 
-fn get_source_code() -> (String, String) {
-    #[derive(Parser, Debug)]
-    #[command(author, about = "gianluur's compiler for his shell (RShell).", long_about = None)]
-    struct Args {
-        pub input: String,
+    /// How diagnostics are written
+    #[arg(long, value_enum, default_value_t = ErrorFormat::Human, global = true)]
+    error_format: ErrorFormat,
+
+    /// Reports a lint as a warning, 'warnings' selects every lint
+    #[arg(short = 'W', value_name = "LINT", value_parser = parse_lint, global = true)]
+    warn: Vec<Lint>,
+
+    /// Silences a lint
+    #[arg(short = 'A', value_name = "LINT", value_parser = parse_lint, global = true)]
+    allow: Vec<Lint>,
+
+    /// Reports a lint as an error, the program doesn't run
+    #[arg(short = 'D', value_name = "LINT", value_parser = parse_lint, global = true)]
+    deny: Vec<Lint>,
+
+    /// Directory searched for imported modules, after the one of the importing file
+    #[arg(short = 'I', long = "import-path", value_name = "DIR", global = true)]
+    import_paths: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Lint {
+    // Every lint at once, spelled 'warnings'
+    All,
+    Single(WarningCode),
+}
+
+fn parse_lint(name: &str) -> Result<Lint, String> {
+    if name == "warnings" {
+        return Ok(Lint::All);
     }
 
-    let args: Args = Args::parse();
-    println!("--- Compiler Settings ---");
-    println!("Input File:  {}", args.input);
-    println!("-------------------------");
+    WarningCode::from_name(name).map(Lint::Single).ok_or_else(|| {
+        let names: Vec<&str> = WarningCode::ALL.iter().map(|code| code.name()).collect();
+        format!("unknown lint, expected 'warnings' or one of: {}", names.join(", "))
+    })
+}
 
-    match fs::read_to_string(&args.input) {
-        Ok(contents) => {
-            (args.input, contents)
-        },
-        Err(e) => panic!("Error reading input file: {}", e),
+// 'warnings' is applied before the single lints, so '-D warnings -A shadowing' allows shadowing
+fn lint_config(args: &Args) -> LintConfig {
+    let mut config: LintConfig = LintConfig::new();
+    let flags = [(&args.allow, LintLevel::Allow), (&args.warn, LintLevel::Warn), (&args.deny, LintLevel::Deny)];
+
+    for (lints, level) in flags {
+        if lints.contains(&Lint::All) {
+            config.set_all(level);
+        }
+    }
+    for (lints, level) in flags {
+        for lint in lints {
+            if let Lint::Single(code) = lint {
+                config.set(*code, level);
+            }
+        }
+    }
+    config
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq)]
+enum ErrorFormat {
+    /// Colored text with the offending line, on stdout
+    Human,
+    /// One JSON object per line on stderr, see the README for the schema
+    Json,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq)]
+enum OutputFormat {
+    /// Readable text
+    Text,
+    /// A JSON document with the spans, needs the 'serde' feature
+    Json,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Reports the errors and warnings of a program without running it
+    Check {
+        /// Source file, '-' reads standard input
+        input: String,
+    },
+
+    /// Prints the tokens of a program
+    Tokens {
+        /// Source file, '-' reads standard input
+        input: String,
+
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+
+    /// Prints the syntax tree of a program
+    Ast {
+        /// Source file, '-' reads standard input
+        input: String,
+
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+
+    /// Rewrites source files in the canonical style
+    Fmt {
+        /// Source files, '-' reads standard input and prints the result
+        #[arg(required = true)]
+        inputs: Vec<String>,
+
+        /// Doesn't write anything, fails if a file isn't formatted
+        #[arg(long)]
+        check: bool,
+    },
+
+    /// Compiles and executes a program
+    Run {
+        /// Source file, '-' reads standard input
+        input: String,
+
+        /// Evaluates the syntax tree directly instead of compiling to bytecode
+        #[arg(long)]
+        tree_walk: bool,
+
+        /// Prints the bytecode of every function before running it
+        #[arg(long)]
+        disassemble: bool,
+    },
+
+    /// Reads statements and expressions interactively
+    Repl,
+
+    /// Shows the long form documentation of a diagnostic code, like EP013
+    Explain {
+        code: String,
+    },
+}
+
+fn emit(diagnostic: &Diagnostic, format: ErrorFormat) {
+    match format {
+        ErrorFormat::Human => diagnostic.print(),
+        ErrorFormat::Json => eprintln!("{}", diagnostic.to_json()),
+    }
+}
+
+// The program has errors, or failed while running
+const EXIT_ERRORS: u8 = 1;
+// The arguments are invalid or the input couldn't be read, like clap does for usage errors
+const EXIT_USAGE: u8 = 2;
+
+// Returns the name diagnostics use for the input and its contents
+fn read_input(path: &str) -> Result<(String, String), ExitCode> {
+    let result: io::Result<(String, String)> = if path == "-" {
+        let mut contents: String = String::new();
+        io::stdin().read_to_string(&mut contents).map(|_| ("<stdin>".to_string(), contents))
+    }
+    else {
+        fs::read_to_string(path).map(|contents| (path.to_string(), contents))
+    };
+
+    result.map_err(|error| {
+        eprintln!("error: couldn't read '{}': {}", path, error);
+        ExitCode::from(EXIT_USAGE)
+    })
+}
+
+fn exit_code(success: bool) -> ExitCode {
+    if success { ExitCode::SUCCESS } else { ExitCode::from(EXIT_ERRORS) }
+}
+
+// Prints every lexical error, the tokens are returned anyway so the parser can report its own
+fn tokenize<'a>(contents: &'a str, file: FileId, sources: &SourceMap, format: ErrorFormat) -> (Vec<Token<'a>>, bool) {
+    let (tokens, errors) = Tokenizer::with_file(contents, file).tokenize_recovering();
+
+    for error in &errors {
+        let diagnostic: Diagnostic = error.to_diagnostic(sources);
+        emit(&diagnostic, format);
     }
+    (tokens, errors.is_empty())
+}
+
+fn parse<'a>(tokens: Vec<Token<'a>>, sources: &SourceMap, format: ErrorFormat) -> Option<Vec<Statement<'a>>> {
+    let (statements, errors) = MyParser::new(tokens).parse_recovering();
+    if errors.is_empty() {
+        return Some(statements);
+    }
+
+    for error in &errors {
+        let diagnostic: Diagnostic = error.to_diagnostic(sources);
+        emit(&diagnostic, format);
+    }
+    None
+}
+
+// Tokenizes and parses the whole file, printing every syntax error instead of stopping at the first one
+fn syntax<'a>(contents: &'a str, file: FileId, sources: &SourceMap, format: ErrorFormat) -> Option<Vec<Statement<'a>>> {
+    let (tokens, valid) = tokenize(contents, file, sources, format);
+    let statements: Vec<Statement<'a>> = parse(tokens, sources, format)?;
+    valid.then_some(statements)
 }
 
-fn main() {
-    let (file, contents) = get_source_code();
+// Loads the file and the modules it imports, printing the syntax errors of all of them
+fn load(name: &str, contents: &str, sources: &mut SourceMap, import_paths: &[PathBuf], format: ErrorFormat) -> Option<Vec<Module>> {
+    let mut loader: ModuleLoader = ModuleLoader::new();
+    import_paths.iter().for_each(|path| loader.add_search_path(path));
 
-    println!("=== Tokenizer Start ===");
-    let tokens: Vec<Token<'_>> = match Tokenizer::new(&contents).tokenize() {
-        Ok(tokens) => {
-            for token in &tokens { 
-                println!("{}", token) 
+    match loader.load(name, contents, sources) {
+        Ok(modules) => Some(modules),
+        Err(errors) => {
+            for error in &errors {
+                let diagnostic: Diagnostic = error.to_diagnostic(sources);
+                emit(&diagnostic, format);
             }
-            tokens
+            None
+        },
+    }
+}
+
+// Statements of the imported modules come first. Only the ones of the file itself are
+// linted, the modules are linted when they are checked on their own
+fn program<'a>(modules: &'a [Module]) -> (Vec<Statement<'a>>, usize) {
+    let statements: Vec<Statement<'a>> = modules.iter().flat_map(|module| borrow(&module.statements)).collect();
+    let root: usize = statements.len() - modules.last().map_or(0, |module| module.statements.len());
+    (statements, root)
+}
+
+// Runs the semantic passes, printing every diagnostic they report. Gives the
+// kinds the type checker found for the backends when there were none
+fn analyze(statements: &[Statement<'_>], sources: &SourceMap, format: ErrorFormat) -> Option<Kinds> {
+    let names: Vec<&str> = builtins().into_iter().map(|(name, _)| name).collect();
+
+    let mut analyzer: SemanticAnalyzer = SemanticAnalyzer::new(statements);
+    names.iter().for_each(|name| analyzer.declare_native(name));
+    if let Err(errors) = analyzer.analyze() {
+        for error in &errors {
+            let diagnostic: Diagnostic = error.to_diagnostic(sources);
+            emit(&diagnostic, format);
+        }
+        return None;
+    }
+
+    let mut checker: TypeChecker = TypeChecker::new(statements);
+    names.iter().for_each(|name| checker.declare_native(name));
+    if let Err(errors) = checker.check() {
+        for error in &errors {
+            let diagnostic: Diagnostic = error.to_diagnostic(sources);
+            emit(&diagnostic, format);
+        }
+        return None;
+    }
+
+    Some(checker.into_kinds())
+}
+
+// Prints the warnings, it fails only when one of them was denied
+fn lint(statements: &[Statement<'_>], sources: &SourceMap, config: &LintConfig, quiet: bool, format: ErrorFormat) -> bool {
+    let warnings = Linter::new(statements, config).lint();
+
+    for warning in warnings.iter().filter(|warning| !quiet || warning.is_denied()) {
+        let diagnostic: Diagnostic = warning.to_diagnostic(sources);
+        emit(&diagnostic, format);
+    }
+    !warnings.iter().any(|warning| warning.is_denied())
+}
+
+fn check(path: &str, import_paths: &[PathBuf], lints: &LintConfig, quiet: bool, format: ErrorFormat) -> ExitCode {
+    let (name, contents) = match read_input(path) {
+        Ok(input) => input,
+        Err(code) => return code,
+    };
+    let mut sources: SourceMap = SourceMap::new();
+
+    let Some(modules) = load(&name, &contents, &mut sources, import_paths, format) else {
+        return exit_code(false);
+    };
+    let (statements, root) = program(&modules);
+    exit_code(analyze(&statements, &sources, format).is_some() && lint(&statements[root..], &sources, lints, quiet, format))
+}
+
+#[cfg(feature = "serde")]
+fn to_json<T: serde::Serialize + ?Sized>(value: &T) -> Result<String, ExitCode> {
+    serde_json::to_string_pretty(value).map_err(|error| {
+        eprintln!("error: couldn't serialize to JSON: {}", error);
+        ExitCode::from(EXIT_ERRORS)
+    })
+}
+
+#[cfg(not(feature = "serde"))]
+fn to_json<T: ?Sized>(_value: &T) -> Result<String, ExitCode> {
+    eprintln!("error: '--format json' needs rcompiler to be built with the 'serde' feature");
+    Err(ExitCode::from(EXIT_USAGE))
+}
+
+// Output written to a reader that stops early, like 'rcompiler ast file.rc | head', just ends
+fn finish_output(result: io::Result<()>) -> Result<(), ExitCode> {
+    match result {
+        Err(error) if error.kind() != io::ErrorKind::BrokenPipe => {
+            eprintln!("error: couldn't write the output: {}", error);
+            Err(ExitCode::from(EXIT_ERRORS))
         },
+        _ => Ok(()),
+    }
+}
+
+fn tokens(path: &str, output: OutputFormat, format: ErrorFormat) -> ExitCode {
+    let (name, contents) = match read_input(path) {
+        Ok(input) => input,
+        Err(code) => return code,
+    };
+    let mut sources: SourceMap = SourceMap::new();
+    let file: FileId = sources.add(name, contents.as_str());
+
+    let (tokens, valid) = tokenize(&contents, file, &sources, format);
+    let mut stdout = io::stdout().lock();
+    let written: io::Result<()> = match output {
+        OutputFormat::Text => tokens.iter().try_for_each(|token| writeln!(stdout, "{}", token)),
+        OutputFormat::Json => match to_json(&tokens) {
+            Ok(json) => writeln!(stdout, "{}", json),
+            Err(code) => return code,
+        },
+    };
+    if let Err(code) = finish_output(written) {
+        return code;
+    }
+    exit_code(valid)
+}
+
+fn ast(path: &str, output: OutputFormat, format: ErrorFormat) -> ExitCode {
+    let (name, contents) = match read_input(path) {
+        Ok(input) => input,
+        Err(code) => return code,
+    };
+    let mut sources: SourceMap = SourceMap::new();
+    let file: FileId = sources.add(name, contents.as_str());
+
+    let Some(statements) = syntax(&contents, file, &sources, format) else {
+        return exit_code(false);
+    };
+    let mut stdout = io::stdout().lock();
+    let written: io::Result<()> = match output {
+        OutputFormat::Text => statements.iter().try_for_each(|statement| writeln!(stdout, "{:#?}", statement)),
+        OutputFormat::Json => match to_json(&statements) {
+            Ok(json) => writeln!(stdout, "{}", json),
+            Err(code) => return code,
+        },
+    };
+    match finish_output(written) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(code) => code,
+    }
+}
+
+// Formats one input, returns false when it has syntax errors or, with 'check', isn't formatted
+fn format_file(path: &str, check: bool, format: ErrorFormat) -> Result<bool, ExitCode> {
+    let (name, contents) = read_input(path)?;
+    let mut sources: SourceMap = SourceMap::new();
+    let file: FileId = sources.add(name, contents.as_str());
+
+    let (tokens, valid) = tokenize(&contents, file, &sources, format);
+    let Some(statements) = parse(tokens.clone(), &sources, format) else {
+        return Ok(false);
+    };
+    if !valid {
+        return Ok(false);
+    }
+
+    let formatted: String = Formatter::new(&tokens).format(&statements);
+    if check {
+        if formatted != contents {
+            println!("{} is not formatted", sources.name(file).unwrap_or(path));
+            return Ok(false);
+        }
+    }
+    else if path == "-" {
+        print!("{}", formatted);
+    }
+    else if formatted != contents && let Err(error) = fs::write(path, formatted) {
+        eprintln!("error: couldn't write '{}': {}", path, error);
+        return Err(ExitCode::from(EXIT_USAGE));
+    }
+    Ok(true)
+}
+
+fn fmt(paths: &[String], check: bool, format: ErrorFormat) -> ExitCode {
+    let mut success: bool = true;
+    for path in paths {
+        match format_file(path, check, format) {
+            Ok(formatted) => success &= formatted,
+            Err(code) => return code,
+        }
+    }
+    exit_code(success)
+}
+
+fn run(path: &str, import_paths: &[PathBuf], tree_walk: bool, disassemble: bool, lints: &LintConfig, quiet: bool, format: ErrorFormat) -> ExitCode {
+    let (name, contents) = match read_input(path) {
+        Ok(input) => input,
+        Err(code) => return code,
+    };
+    let mut sources: SourceMap = SourceMap::new();
+
+    let Some(modules) = load(&name, &contents, &mut sources, import_paths, format) else {
+        return exit_code(false);
+    };
+    let (statements, root) = program(&modules);
+    let Some(kinds) = analyze(&statements, &sources, format) else {
+        return exit_code(false);
+    };
+    if !lint(&statements[root..], &sources, lints, quiet, format) {
+        return exit_code(false);
+    }
+
+    if tree_walk {
+        if let Err(error) = Interpreter::new().with_kinds(kinds).run(&statements) {
+            let diagnostic: Diagnostic = error.to_diagnostic(&sources);
+            emit(&diagnostic, format);
+            return exit_code(false);
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    let mut program: Program = Program::new();
+    let script: Function = match Compiler::new(&mut program).with_kinds(kinds).compile(&statements) {
+        Ok(script) => script,
         Err(error) => {
-            let diagnostic: Diagnostic = error.to_diagnostic(&file);
-            diagnostic.print();
-            return;
+            let diagnostic: Diagnostic = error.to_diagnostic(&sources);
+            emit(&diagnostic, format);
+            return exit_code(false);
         }
     };
-    println!("=== Tokenizer End ===");    
 
-    println!();
+    if disassemble {
+        for function in program.functions.iter().flatten() {
+            if let Callable::Script(function) = function {
+                println!("== {} ==\n{}", function.name, function.chunk);
+            }
+        }
+        println!("== {} ==\n{}", script.name, script.chunk);
+    }
+
+    if let Err(error) = VM::new().run(&program, Rc::new(script)) {
+        let diagnostic: Diagnostic = error.to_diagnostic(&sources);
+        emit(&diagnostic, format);
+        return exit_code(false);
+    }
+    ExitCode::SUCCESS
+}
+
+const REPL_HELP: &str = "\
+Statements run as they are entered, expressions print their value.
+An unclosed '{' keeps reading lines until the body is closed.
+
+  :help       shows this message
+  :history    lists the previous entries
+  :cancel     drops the lines of an unfinished entry
+  :quit       exits the REPL
+  !N          runs entry N of the history again";
 
-    println!("=== Parser Start ===");
-    match MyParser::new(tokens).parse() {
-        Ok(statements) => {
-            for statement in &statements { 
-                println!("{:#?}", statement) 
+fn history_path() -> Option<PathBuf> {
+    env::var_os("HOME").map(|home| PathBuf::from(home).join(".rcompiler_history"))
+}
+
+// Entries span multiple lines, the history file keeps one entry per line
+fn escape_entry(entry: &str) -> String {
+    entry.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn unescape_entry(line: &str) -> String {
+    let mut entry: String = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(character) = chars.next() {
+        match (character, chars.clone().next()) {
+            ('\\', Some('n')) => { entry.push('\n'); chars.next(); },
+            ('\\', Some('\\')) => { entry.push('\\'); chars.next(); },
+            (character, _) => entry.push(character),
+        }
+    }
+    entry
+}
+
+fn report(repl: &mut Repl, response: Response, format: ErrorFormat) {
+    let sources: &SourceMap = repl.engine().sources();
+
+    match response {
+        Response::Incomplete | Response::Executed => {},
+        Response::Value(value) => println!("{}", value),
+        Response::Error(EngineError::Syntax(error) | EngineError::Runtime(error)) => {
+            emit(&error.to_diagnostic(sources), format);
+        },
+        Response::Error(EngineError::Compile(errors)) => {
+            for error in &errors {
+                emit(&error.to_diagnostic(sources), format);
             }
-            println!();
-            statements
         },
-        Err(error) => {
-            let diagnostic: Diagnostic = error.to_diagnostic(&file);
-            diagnostic.print();
-            return;
+        Response::Error(error) => println!("error: {}", error),
+    }
+}
+
+fn repl(import_paths: &[PathBuf], format: ErrorFormat) {
+    let interactive: bool = io::stdin().is_terminal();
+
+    let mut engine: Engine = Engine::new();
+    import_paths.iter().for_each(|path| engine.add_search_path(path));
+    let mut repl: Repl = Repl::with_engine(engine);
+
+    let mut history_file: Option<fs::File> = None;
+    if let Some(path) = history_path() {
+        if let Ok(contents) = fs::read_to_string(&path) {
+            repl.load_history(contents.lines().map(unescape_entry));
         }
+        history_file = fs::OpenOptions::new().create(true).append(true).open(path).ok();
+    }
+
+    if interactive {
+        println!("RShell REPL, type :help for the commands");
+    }
+
+    let mut lines = io::stdin().lock().lines();
+    loop {
+        if interactive {
+            print!("{}", if repl.is_continuing() { "... " } else { "> " });
+            let _ = io::stdout().flush();
+        }
+
+        let Some(Ok(mut line)) = lines.next() else {
+            break;
+        };
+
+        match line.trim() {
+            ":quit" | ":q" => break,
+            ":help" => { println!("{}", REPL_HELP); continue; },
+            ":cancel" => { repl.cancel(); continue; },
+            ":history" => {
+                for (index, entry) in repl.history().iter().enumerate() {
+                    println!("{:>4}  {}", index + 1, entry.replace('\n', "\n      "));
+                }
+                continue;
+            },
+            command if command.starts_with(':') => {
+                println!("unknown command '{}', see :help", command);
+                continue;
+            },
+            _ => {},
+        }
+
+        if !repl.is_continuing() && let Some(index) = line.trim().strip_prefix('!') {
+            match index.parse::<usize>().ok().and_then(|index| repl.history().get(index.wrapping_sub(1))) {
+                Some(entry) => {
+                    line = entry.clone();
+                    println!("{}", line);
+                },
+                None => {
+                    println!("no history entry '{}'", index);
+                    continue;
+                },
+            }
+        }
+
+        let response: Response = repl.feed(&line);
+        if !matches!(response, Response::Incomplete)
+            && let (Some(file), Some(entry)) = (&mut history_file, repl.history().last()) {
+            let _ = writeln!(file, "{}", escape_entry(entry));
+        }
+        report(&mut repl, response, format);
+    }
+
+    if let Some(response) = repl.finish() {
+        report(&mut repl, response, format);
+    }
+}
+
+// Code examples are indented, so they stand out from the text
+fn indent(code: &str) -> String {
+    code.lines().map(|line| format!("    {}", line)).collect::<Vec<String>>().join("\n")
+}
+
+fn explain(code: &str) -> ExitCode {
+    let Some((kind, explanation)) = rcompiler::explain::explain(code) else {
+        eprintln!("error: '{}' is not a diagnostic code, they look like 'EP013' or 'WL001'", code);
+        return ExitCode::from(EXIT_USAGE);
     };
-    println!("=== Parser End ===");    
+
+    println!("{}: {}\n", kind.code_to_string(), kind.message());
+    println!("{}\n", explanation.text);
+    println!("Wrong:\n{}\n", indent(explanation.wrong));
+    println!("Right:\n{}", indent(explanation.right));
+    ExitCode::SUCCESS
+}
+
+fn main() -> ExitCode {
+    let args: Args = Args::parse();
+    let lints: LintConfig = lint_config(&args);
+    let (quiet, format) = (args.quiet, args.error_format);
+
+    match args.command {
+        Command::Check { input } => check(&input, &args.import_paths, &lints, quiet, format),
+        Command::Tokens { input, format: output } => tokens(&input, output, format),
+        Command::Ast { input, format: output } => ast(&input, output, format),
+        Command::Fmt { inputs, check } => fmt(&inputs, check, format),
+        Command::Run { input, tree_walk, disassemble } => run(&input, &args.import_paths, tree_walk, disassemble, &lints, quiet, format),
+        Command::Repl => {
+            repl(&args.import_paths, format);
+            ExitCode::SUCCESS
+        },
+        Command::Explain { code } => explain(&code),
+    }
 }
\ No newline at end of file
@@ -0,0 +1,599 @@
+use std::sync::Arc;
+
+use crate::parser::{Accessor, Arm, Body, ElseBranch, Expression, Field, Import, Iterable, Parameter, Path, Pattern, Place, RawExpression, RawStatement, Spanned, Statement, StatementSpan, Type, Variant};
+use crate::tokenizer::TokenKind;
+
+// The same syntax tree as the parser's, but names and literals are shared strings
+// instead of slices of the source, so it can be stored, cached or sent to another
+// thread after the source is gone. 'borrow' gives back a tree every pass accepts
+
+pub type OwnedExpression = Box<Spanned<OwnedRawExpression>>;
+pub type OwnedStatement = Box<Spanned<OwnedRawStatement>>;
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone)]
+pub enum OwnedRawExpression {
+    Variable(OwnedPath),
+    Literal {
+        kind: TokenKind,
+        value: Arc<str>,
+    },
+    Binary {
+        left: OwnedExpression,
+        operator: TokenKind,
+        right: OwnedExpression,
+    },
+    Unary {
+        operator: TokenKind,
+        operand: OwnedExpression,
+    },
+    FunctionCall {
+        name: OwnedPath,
+        arguments: Vec<OwnedExpression>,
+    },
+    ArrayAccess {
+        array: OwnedExpression,
+        index: OwnedExpression,
+    },
+    StructLiteral {
+        name: OwnedPath,
+        fields: Vec<(Arc<str>, OwnedExpression)>,
+    },
+    Field {
+        object: OwnedExpression,
+        name: Arc<str>,
+    },
+    Match {
+        value: OwnedExpression,
+        arms: Vec<OwnedArm<OwnedExpression>>,
+    },
+    ArrayLiteral(Vec<OwnedExpression>),
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone)]
+pub enum OwnedRawStatement {
+    VariableDeclaration {
+        is_const: bool,
+        type_: OwnedType,
+        name: Arc<str>,
+        value: Option<OwnedExpression>,
+    },
+    VariableAssignment {
+        target: OwnedPlace,
+        operator: TokenKind,
+        value: OwnedExpression,
+    },
+    If {
+        condition: OwnedExpression,
+        body: OwnedBody,
+        elses: Vec<OwnedElseBranch>,
+    },
+    While {
+        condition: OwnedExpression,
+        body: OwnedBody,
+    },
+    For {
+        type_: Option<TokenKind>,
+        name: Arc<str>,
+        iterable: OwnedIterable,
+        body: OwnedBody,
+    },
+    LoopControl(Arc<str>),
+    Function {
+        name: Arc<str>,
+        parameters: Vec<OwnedParameter>,
+        type_: TokenKind,
+        body: OwnedBody,
+    },
+    Return(Option<OwnedExpression>),
+    FunctionCall {
+        name: OwnedPath,
+        arguments: Vec<OwnedExpression>,
+    },
+    Namespace {
+        name: Arc<str>,
+        body: OwnedBody,
+    },
+    Import(OwnedImport),
+    Struct {
+        name: Arc<str>,
+        fields: Vec<OwnedField>,
+    },
+    Enum {
+        name: Arc<str>,
+        variants: Vec<OwnedVariant>,
+    },
+    Match {
+        value: OwnedExpression,
+        arms: Vec<OwnedArm<OwnedBody>>,
+    },
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone)]
+pub enum OwnedImport {
+    File(Arc<str>),
+    Module(OwnedPath),
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone)]
+pub struct OwnedPlace {
+    pub name: Arc<str>,
+    pub accessors: Vec<OwnedAccessor>,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone)]
+pub enum OwnedAccessor {
+    Field(Arc<str>),
+    Index(OwnedExpression),
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone)]
+pub enum OwnedElseBranch {
+    ElseIf(OwnedStatement),
+    Else(OwnedBody),
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone)]
+pub enum OwnedIterable {
+    Range {
+        start: OwnedExpression,
+        end: OwnedExpression,
+    },
+    Array(OwnedExpression),
+}
+
+#[derive(Clone, PartialEq)]
+pub struct OwnedPath {
+    pub namespaces: Vec<Arc<str>>,
+    pub name: Arc<str>,
+}
+
+impl std::fmt::Display for OwnedPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for namespace in &self.namespaces {
+            write!(f, "{}::", namespace)?;
+        }
+        write!(f, "{}", self.name)
+    }
+}
+
+impl std::fmt::Debug for OwnedPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.to_string())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for OwnedPath {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone)]
+pub struct OwnedType {
+    pub kind: TokenKind,
+    pub is_array: bool,
+    pub array_length: Option<OwnedExpression>,
+    pub name: Option<OwnedPath>,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Clone)]
+pub struct OwnedParameter {
+    pub name: Arc<str>,
+    pub type_: OwnedType,
+    pub span: StatementSpan,
+}
+
+impl std::fmt::Debug for OwnedParameter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Parameter").field("name", &self.name).field("type_", &self.type_).finish()
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Clone)]
+pub struct OwnedField {
+    pub name: Arc<str>,
+    pub type_: OwnedType,
+    pub span: StatementSpan,
+}
+
+impl std::fmt::Debug for OwnedField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Field").field("name", &self.name).field("type_", &self.type_).finish()
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Clone)]
+pub struct OwnedVariant {
+    pub name: Arc<str>,
+    pub payload: Vec<OwnedType>,
+    pub span: StatementSpan,
+}
+
+impl std::fmt::Debug for OwnedVariant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Variant").field("name", &self.name).field("payload", &self.payload).finish()
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone)]
+pub enum OwnedPattern {
+    Wildcard,
+    Literal(OwnedExpression),
+    Variant {
+        path: OwnedPath,
+        bindings: Vec<Arc<str>>,
+    },
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Clone)]
+pub struct OwnedArm<T> {
+    pub pattern: OwnedPattern,
+    pub value: T,
+    pub span: StatementSpan,
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for OwnedArm<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Arm").field("pattern", &self.pattern).field("value", &self.value).finish()
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Clone)]
+pub struct OwnedBody {
+    pub statements: Vec<OwnedStatement>,
+    pub span: StatementSpan,
+}
+
+impl std::fmt::Debug for OwnedBody {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Body").field("statements", &self.statements).finish()
+    }
+}
+
+// Copies a parsed program into an owned tree
+pub fn to_owned(statements: &[Statement]) -> Vec<OwnedStatement> {
+    statements.iter().map(|statement| owned_statement(statement)).collect()
+}
+
+// A borrowed view of an owned tree, the names point inside it
+pub fn borrow(statements: &[OwnedStatement]) -> Vec<Statement<'_>> {
+    statements.iter().map(|statement| borrowed_statement(statement)).collect()
+}
+
+pub fn owned_expression(expression: &Expression) -> OwnedExpression {
+    let node: OwnedRawExpression = match &expression.node {
+        RawExpression::Variable(path) => OwnedRawExpression::Variable(owned_path(path)),
+        RawExpression::Literal { kind, value } => OwnedRawExpression::Literal { kind: *kind, value: Arc::from(*value) },
+        RawExpression::Binary { left, operator, right } => OwnedRawExpression::Binary {
+            left: owned_expression(left),
+            operator: *operator,
+            right: owned_expression(right),
+        },
+        RawExpression::Unary { operator, operand } => OwnedRawExpression::Unary {
+            operator: *operator,
+            operand: owned_expression(operand),
+        },
+        RawExpression::FunctionCall { name, arguments } => OwnedRawExpression::FunctionCall {
+            name: owned_path(name),
+            arguments: arguments.iter().map(owned_expression).collect(),
+        },
+        RawExpression::ArrayAccess { array, index } => OwnedRawExpression::ArrayAccess {
+            array: owned_expression(array),
+            index: owned_expression(index),
+        },
+        RawExpression::StructLiteral { name, fields } => OwnedRawExpression::StructLiteral {
+            name: owned_path(name),
+            fields: fields.iter().map(|(field, value)| (Arc::from(*field), owned_expression(value))).collect(),
+        },
+        RawExpression::Field { object, name } => OwnedRawExpression::Field {
+            object: owned_expression(object),
+            name: Arc::from(*name),
+        },
+        RawExpression::Match { value, arms } => OwnedRawExpression::Match {
+            value: owned_expression(value),
+            arms: arms.iter().map(|arm| owned_arm(arm, owned_expression(&arm.value))).collect(),
+        },
+        RawExpression::ArrayLiteral(elements) => OwnedRawExpression::ArrayLiteral(elements.iter().map(owned_expression).collect()),
+    };
+    Box::new(Spanned { node, span: expression.span })
+}
+
+pub fn owned_statement(statement: &Statement) -> OwnedStatement {
+    let node: OwnedRawStatement = match &statement.node {
+        RawStatement::VariableDeclaration { is_const, type_, name, value } => OwnedRawStatement::VariableDeclaration {
+            is_const: *is_const,
+            type_: owned_type(type_),
+            name: Arc::from(*name),
+            value: value.as_ref().map(owned_expression),
+        },
+        RawStatement::VariableAssignment { target, operator, value } => OwnedRawStatement::VariableAssignment {
+            target: OwnedPlace {
+                name: Arc::from(target.name),
+                accessors: target.accessors.iter().map(|accessor| match accessor {
+                    Accessor::Field(name) => OwnedAccessor::Field(Arc::from(*name)),
+                    Accessor::Index(index) => OwnedAccessor::Index(owned_expression(index)),
+                }).collect(),
+            },
+            operator: *operator,
+            value: owned_expression(value),
+        },
+        RawStatement::If { condition, body, elses } => OwnedRawStatement::If {
+            condition: owned_expression(condition),
+            body: owned_body(body),
+            elses: elses.iter().map(|branch| match branch {
+                ElseBranch::ElseIf(statement) => OwnedElseBranch::ElseIf(owned_statement(statement)),
+                ElseBranch::Else(body) => OwnedElseBranch::Else(owned_body(body)),
+            }).collect(),
+        },
+        RawStatement::While { condition, body } => OwnedRawStatement::While {
+            condition: owned_expression(condition),
+            body: owned_body(body),
+        },
+        RawStatement::For { type_, name, iterable, body } => OwnedRawStatement::For {
+            type_: *type_,
+            name: Arc::from(*name),
+            iterable: match iterable {
+                Iterable::Range { start, end } => OwnedIterable::Range { start: owned_expression(start), end: owned_expression(end) },
+                Iterable::Array(array) => OwnedIterable::Array(owned_expression(array)),
+            },
+            body: owned_body(body),
+        },
+        RawStatement::LoopControl(keyword) => OwnedRawStatement::LoopControl(Arc::from(*keyword)),
+        RawStatement::Function { name, parameters, type_, body } => OwnedRawStatement::Function {
+            name: Arc::from(*name),
+            parameters: parameters.iter().map(|parameter| OwnedParameter {
+                name: Arc::from(parameter.name),
+                type_: owned_type(&parameter.type_),
+                span: parameter.span,
+            }).collect(),
+            type_: *type_,
+            body: owned_body(body),
+        },
+        RawStatement::Return(value) => OwnedRawStatement::Return(value.as_ref().map(owned_expression)),
+        RawStatement::FunctionCall { name, arguments } => OwnedRawStatement::FunctionCall {
+            name: owned_path(name),
+            arguments: arguments.iter().map(owned_expression).collect(),
+        },
+        RawStatement::Namespace { name, body } => OwnedRawStatement::Namespace {
+            name: Arc::from(*name),
+            body: owned_body(body),
+        },
+        RawStatement::Import(import) => OwnedRawStatement::Import(match import {
+            Import::File(path) => OwnedImport::File(Arc::from(*path)),
+            Import::Module(path) => OwnedImport::Module(owned_path(path)),
+        }),
+        RawStatement::Struct { name, fields } => OwnedRawStatement::Struct {
+            name: Arc::from(*name),
+            fields: fields.iter().map(|field| OwnedField {
+                name: Arc::from(field.name),
+                type_: owned_type(&field.type_),
+                span: field.span,
+            }).collect(),
+        },
+        RawStatement::Enum { name, variants } => OwnedRawStatement::Enum {
+            name: Arc::from(*name),
+            variants: variants.iter().map(|variant| OwnedVariant {
+                name: Arc::from(variant.name),
+                payload: variant.payload.iter().map(owned_type).collect(),
+                span: variant.span,
+            }).collect(),
+        },
+        RawStatement::Match { value, arms } => OwnedRawStatement::Match {
+            value: owned_expression(value),
+            arms: arms.iter().map(|arm| owned_arm(arm, owned_body(&arm.value))).collect(),
+        },
+    };
+    Box::new(Spanned { node, span: statement.span })
+}
+
+fn owned_path(path: &Path) -> OwnedPath {
+    OwnedPath {
+        namespaces: path.namespaces.iter().map(|namespace| Arc::from(*namespace)).collect(),
+        name: Arc::from(path.name),
+    }
+}
+
+fn owned_type(type_: &Type) -> OwnedType {
+    OwnedType {
+        kind: type_.kind,
+        is_array: type_.is_array,
+        array_length: type_.array_length.as_ref().map(owned_expression),
+        name: type_.name.as_ref().map(owned_path),
+    }
+}
+
+fn owned_body(body: &Body) -> OwnedBody {
+    OwnedBody {
+        statements: to_owned(&body.statements),
+        span: body.span,
+    }
+}
+
+// The value of the arm is converted by the caller, it's a body or an expression
+fn owned_arm<T, U>(arm: &Arm<T>, value: U) -> OwnedArm<U> {
+    let pattern: OwnedPattern = match &arm.pattern {
+        Pattern::Wildcard => OwnedPattern::Wildcard,
+        Pattern::Literal(literal) => OwnedPattern::Literal(owned_expression(literal)),
+        Pattern::Variant { path, bindings } => OwnedPattern::Variant {
+            path: owned_path(path),
+            bindings: bindings.iter().map(|binding| Arc::from(*binding)).collect(),
+        },
+    };
+    OwnedArm { pattern, value, span: arm.span }
+}
+
+pub fn borrowed_expression(expression: &OwnedExpression) -> Expression<'_> {
+    let node: RawExpression = match &expression.node {
+        OwnedRawExpression::Variable(path) => RawExpression::Variable(borrowed_path(path)),
+        OwnedRawExpression::Literal { kind, value } => RawExpression::Literal { kind: *kind, value },
+        OwnedRawExpression::Binary { left, operator, right } => RawExpression::Binary {
+            left: borrowed_expression(left),
+            operator: *operator,
+            right: borrowed_expression(right),
+        },
+        OwnedRawExpression::Unary { operator, operand } => RawExpression::Unary {
+            operator: *operator,
+            operand: borrowed_expression(operand),
+        },
+        OwnedRawExpression::FunctionCall { name, arguments } => RawExpression::FunctionCall {
+            name: borrowed_path(name),
+            arguments: arguments.iter().map(borrowed_expression).collect(),
+        },
+        OwnedRawExpression::ArrayAccess { array, index } => RawExpression::ArrayAccess {
+            array: borrowed_expression(array),
+            index: borrowed_expression(index),
+        },
+        OwnedRawExpression::StructLiteral { name, fields } => RawExpression::StructLiteral {
+            name: borrowed_path(name),
+            fields: fields.iter().map(|(field, value)| (&**field, borrowed_expression(value))).collect(),
+        },
+        OwnedRawExpression::Field { object, name } => RawExpression::Field {
+            object: borrowed_expression(object),
+            name,
+        },
+        OwnedRawExpression::Match { value, arms } => RawExpression::Match {
+            value: borrowed_expression(value),
+            arms: arms.iter().map(|arm| borrowed_arm(arm, borrowed_expression(&arm.value))).collect(),
+        },
+        OwnedRawExpression::ArrayLiteral(elements) => RawExpression::ArrayLiteral(elements.iter().map(borrowed_expression).collect()),
+    };
+    Box::new(Spanned { node, span: expression.span })
+}
+
+pub fn borrowed_statement(statement: &OwnedStatement) -> Statement<'_> {
+    let node: RawStatement = match &statement.node {
+        OwnedRawStatement::VariableDeclaration { is_const, type_, name, value } => RawStatement::VariableDeclaration {
+            is_const: *is_const,
+            type_: borrowed_type(type_),
+            name,
+            value: value.as_ref().map(borrowed_expression),
+        },
+        OwnedRawStatement::VariableAssignment { target, operator, value } => RawStatement::VariableAssignment {
+            target: Place {
+                name: &target.name,
+                accessors: target.accessors.iter().map(|accessor| match accessor {
+                    OwnedAccessor::Field(name) => Accessor::Field(name),
+                    OwnedAccessor::Index(index) => Accessor::Index(borrowed_expression(index)),
+                }).collect(),
+            },
+            operator: *operator,
+            value: borrowed_expression(value),
+        },
+        OwnedRawStatement::If { condition, body, elses } => RawStatement::If {
+            condition: borrowed_expression(condition),
+            body: borrowed_body(body),
+            elses: elses.iter().map(|branch| match branch {
+                OwnedElseBranch::ElseIf(statement) => ElseBranch::ElseIf(borrowed_statement(statement)),
+                OwnedElseBranch::Else(body) => ElseBranch::Else(borrowed_body(body)),
+            }).collect(),
+        },
+        OwnedRawStatement::While { condition, body } => RawStatement::While {
+            condition: borrowed_expression(condition),
+            body: borrowed_body(body),
+        },
+        OwnedRawStatement::For { type_, name, iterable, body } => RawStatement::For {
+            type_: *type_,
+            name,
+            iterable: match iterable {
+                OwnedIterable::Range { start, end } => Iterable::Range { start: borrowed_expression(start), end: borrowed_expression(end) },
+                OwnedIterable::Array(array) => Iterable::Array(borrowed_expression(array)),
+            },
+            body: borrowed_body(body),
+        },
+        OwnedRawStatement::LoopControl(keyword) => RawStatement::LoopControl(keyword),
+        OwnedRawStatement::Function { name, parameters, type_, body } => RawStatement::Function {
+            name,
+            parameters: parameters.iter().map(|parameter| Parameter {
+                name: &parameter.name,
+                type_: borrowed_type(&parameter.type_),
+                span: parameter.span,
+            }).collect(),
+            type_: *type_,
+            body: borrowed_body(body),
+        },
+        OwnedRawStatement::Return(value) => RawStatement::Return(value.as_ref().map(borrowed_expression)),
+        OwnedRawStatement::FunctionCall { name, arguments } => RawStatement::FunctionCall {
+            name: borrowed_path(name),
+            arguments: arguments.iter().map(borrowed_expression).collect(),
+        },
+        OwnedRawStatement::Namespace { name, body } => RawStatement::Namespace {
+            name,
+            body: borrowed_body(body),
+        },
+        OwnedRawStatement::Import(import) => RawStatement::Import(match import {
+            OwnedImport::File(path) => Import::File(path),
+            OwnedImport::Module(path) => Import::Module(borrowed_path(path)),
+        }),
+        OwnedRawStatement::Struct { name, fields } => RawStatement::Struct {
+            name,
+            fields: fields.iter().map(|field| Field {
+                name: &field.name,
+                type_: borrowed_type(&field.type_),
+                span: field.span,
+            }).collect(),
+        },
+        OwnedRawStatement::Enum { name, variants } => RawStatement::Enum {
+            name,
+            variants: variants.iter().map(|variant| Variant {
+                name: &variant.name,
+                payload: variant.payload.iter().map(borrowed_type).collect(),
+                span: variant.span,
+            }).collect(),
+        },
+        OwnedRawStatement::Match { value, arms } => RawStatement::Match {
+            value: borrowed_expression(value),
+            arms: arms.iter().map(|arm| borrowed_arm(arm, borrowed_body(&arm.value))).collect(),
+        },
+    };
+    Box::new(Spanned { node, span: statement.span })
+}
+
+fn borrowed_path(path: &OwnedPath) -> Path<'_> {
+    Path {
+        namespaces: path.namespaces.iter().map(|namespace| &**namespace).collect(),
+        name: &path.name,
+    }
+}
+
+fn borrowed_type(type_: &OwnedType) -> Type<'_> {
+    Type {
+        kind: type_.kind,
+        is_array: type_.is_array,
+        array_length: type_.array_length.as_ref().map(borrowed_expression),
+        name: type_.name.as_ref().map(borrowed_path),
+    }
+}
+
+fn borrowed_body(body: &OwnedBody) -> Body<'_> {
+    Body {
+        statements: borrow(&body.statements),
+        span: body.span,
+    }
+}
+
+fn borrowed_arm<'a, T, U>(arm: &'a OwnedArm<T>, value: U) -> Arm<'a, U> {
+    let pattern: Pattern = match &arm.pattern {
+        OwnedPattern::Wildcard => Pattern::Wildcard,
+        OwnedPattern::Literal(literal) => Pattern::Literal(borrowed_expression(literal)),
+        OwnedPattern::Variant { path, bindings } => Pattern::Variant {
+            path: borrowed_path(path),
+            bindings: bindings.iter().map(|binding| &**binding).collect(),
+        },
+    };
+    Arm { pattern, value, span: arm.span }
+}
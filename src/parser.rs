@@ -1,14 +1,19 @@
 use crate::tokenizer::{Token, TokenKind, TokenSpan};
 use crate::error::*;
+use crate::source::{FileId, SourceMap};
 
 use core::panic;
+use std::borrow::Cow;
 
-#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Default, PartialEq, Eq, Hash, Clone, Copy)]
 pub struct StatementSpan {
     pub start: usize,
     pub end: usize,
+    pub file: FileId,
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[derive(Clone)]
 pub struct Spanned<T> {
     pub node: T,
@@ -21,9 +26,75 @@ impl<T: std::fmt::Debug> std::fmt::Debug for Spanned<T> {
     }
 }
 
+// A name, qualified by the namespaces it's declared in when written like 'math::sqrt'
+#[derive(Clone, PartialEq)]
+pub struct Path<'a> {
+    // Outermost first, empty for a plain name
+    pub namespaces: Vec<&'a str>,
+    pub name: &'a str,
+}
+
+impl<'a> Path<'a> {
+    pub fn is_qualified(&self) -> bool {
+        !self.namespaces.is_empty()
+    }
+
+    // The full name of the path when it's written inside 'namespaces'
+    pub fn qualified(&self, namespaces: &[&str]) -> Cow<'a, str> {
+        if namespaces.is_empty() && self.namespaces.is_empty() {
+            return Cow::Borrowed(self.name);
+        }
+
+        let segments: Vec<&str> = namespaces.iter().chain(&self.namespaces).chain([&self.name]).copied().collect();
+        Cow::Owned(segments.join("::"))
+    }
+
+    // The full names the path can refer to when it's written inside 'namespaces',
+    // from the innermost namespace out to the top level
+    pub fn candidates<'n>(&'n self, namespaces: &'n [&'a str]) -> impl Iterator<Item = Cow<'a, str>> + 'n {
+        (0..=namespaces.len()).rev().map(|depth| self.qualified(&namespaces[..depth]))
+    }
+
+    // The namespace the name is looked up in, None for a plain name
+    pub fn parent(&self) -> Option<Path<'a>> {
+        let (name, namespaces) = self.namespaces.split_last()?;
+        Some(Path { namespaces: namespaces.to_vec(), name })
+    }
+}
+
+impl<'a> From<&'a str> for Path<'a> {
+    fn from(name: &'a str) -> Self {
+        Path { namespaces: Vec::new(), name }
+    }
+}
+
+impl std::fmt::Display for Path<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for namespace in &self.namespaces {
+            write!(f, "{}::", namespace)?;
+        }
+        write!(f, "{}", self.name)
+    }
+}
+
+// Printed as written, so plain names look the same as before namespaces existed
+impl std::fmt::Debug for Path<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.to_string())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Path<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[derive(Debug, Clone)]
 pub enum RawExpression<'a> {
-    Variable(&'a str),
+    Variable(Path<'a>),
     Literal {
         kind: TokenKind,
         value: &'a str,
@@ -38,13 +109,30 @@ pub enum RawExpression<'a> {
         operand: Expression<'a>, 
     },
     FunctionCall {
-        name: &'a str,
+        name: Path<'a>,
         arguments: Vec<Expression<'a>>,
     },
     ArrayAccess {
         array: Expression<'a>,
         index: Expression<'a>,
     },
+    // 'Point { x = 1, y = 2 }', the fields in the order they are written
+    StructLiteral {
+        name: Path<'a>,
+        fields: Vec<(&'a str, Expression<'a>)>,
+    },
+    // 'point.x'
+    Field {
+        object: Expression<'a>,
+        name: &'a str,
+    },
+    // 'match state { State::Running => 1, _ => 0 }', the first arm that matches gives the value
+    Match {
+        value: Expression<'a>,
+        arms: Vec<Arm<'a, Expression<'a>>>,
+    },
+    // '[1, 2, 3]', the elements in the order they are written
+    ArrayLiteral(Vec<Expression<'a>>),
 }
 
 impl<'a> RawExpression<'a> {
@@ -52,23 +140,36 @@ impl<'a> RawExpression<'a> {
         use TokenKind::*;
         matches!(kind,
             IntegerLiteral | FloatLiteral | CharLiteral | StringLiteral |
-            LeftParen | RightParen | True | False | Identifier | Minus | Not
+            LeftParen | RightParen | LeftBracket | True | False | Identifier | Minus | Not | Match | Error
         )
     }
 
+    // C-like precedence, every binary operator is left associative
     pub fn get_binding_power(kind: TokenKind) -> (u8, u8) {
         match kind {
-            // Low precedence
-            TokenKind::Plus | TokenKind::Minus => (1, 2),
-            // Higher
-            TokenKind::Multiplication | TokenKind::Division | TokenKind::Modulus => (3, 4),
-            // Highest (Function calls and Array access)
-            TokenKind::LeftParen | TokenKind::LeftBracket => (5, 6),
+            // Lowest
+            TokenKind::Or => (1, 2),
+            TokenKind::And => (3, 4),
+            TokenKind::BitwiseOr => (5, 6),
+            TokenKind::BitwiseXor => (7, 8),
+            TokenKind::BitwiseAnd => (9, 10),
+            TokenKind::Equal | TokenKind::NotEqual => (11, 12),
+            TokenKind::LessThan | TokenKind::LessThanOrEqual |
+            TokenKind::GreaterThan | TokenKind::GreaterThanOrEqual => (13, 14),
+            TokenKind::BitwiseLShift | TokenKind::BitwiseRShift => (15, 16),
+            TokenKind::Plus | TokenKind::Minus => (17, 18),
+            TokenKind::Multiplication | TokenKind::Division | TokenKind::Modulus => (19, 20),
+            // Highest (Function calls, Array and Field access), unary operators sit right below
+            TokenKind::LeftParen | TokenKind::LeftBracket | TokenKind::Dot => (23, 24),
             _ => (0, 0),
         }
     }
+
+    // Binding power of the operand of '-' and '!', tighter than any binary operator
+    pub const UNARY_BINDING_POWER: u8 = 21;
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[derive(Debug, Clone)]
 pub enum RawStatement<'a> {
     VariableDeclaration {
@@ -78,7 +179,7 @@ pub enum RawStatement<'a> {
         value: Option<Expression<'a>>,
     },
     VariableAssignment {
-        name: &'a str,
+        target: Place<'a>,
         operator: TokenKind,
         value: Expression<'a>,
     },
@@ -91,6 +192,13 @@ pub enum RawStatement<'a> {
         condition: Expression<'a>,
         body: Body<'a>,
     },
+    For {
+        // Inferred from the iterable when it's left out
+        type_: Option<TokenKind>,
+        name: &'a str,
+        iterable: Iterable<'a>,
+        body: Body<'a>,
+    },
     LoopControl(&'a str),
     Function {
       name: &'a str,
@@ -100,22 +208,84 @@ pub enum RawStatement<'a> {
     },
     Return(Option<Expression<'a>>),
     FunctionCall {
-        name: &'a str,
+        name: Path<'a>,
         arguments: Vec<Expression<'a>>,
     },
+    Namespace {
+        name: &'a str,
+        body: Body<'a>,
+    },
+    Import(Import<'a>),
+    Struct {
+        name: &'a str,
+        fields: Vec<Field<'a>>,
+    },
+    Enum {
+        name: &'a str,
+        variants: Vec<Variant<'a>>,
+    },
+    // Runs the body of the first arm that matches the value
+    Match {
+        value: Expression<'a>,
+        arms: Vec<Arm<'a, Body<'a>>>,
+    },
+}
+
+// What an 'import' loads
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone)]
+pub enum Import<'a> {
+    // A path to a file, as written with its quotes
+    File(&'a str),
+    // 'lib::math' names the file 'lib/math.rc'
+    Module(Path<'a>),
 }
 
+// What an assignment writes to, like 'lines[i].from.x' in 'lines[i].from.x = 1'
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone)]
+pub struct Place<'a> {
+    pub name: &'a str,
+    // Empty when the variable itself is assigned
+    pub accessors: Vec<Accessor<'a>>,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone)]
+pub enum Accessor<'a> {
+    // '.x'
+    Field(&'a str),
+    // '[i]'
+    Index(Expression<'a>),
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[derive(Debug, Clone)]
 pub enum ElseBranch<'a> {
     ElseIf(Statement<'a>),
     Else(Body<'a>),
 }
 
+// What a 'for' loop goes through
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone)]
+pub enum Iterable<'a> {
+    // From 'start' up to 'end', excluded
+    Range {
+        start: Expression<'a>,
+        end: Expression<'a>,
+    },
+    Array(Expression<'a>),
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[derive(Debug, Clone)]
 pub struct Type<'a> {
     pub kind: TokenKind,
     pub is_array: bool,
     pub array_length: Option<Expression<'a>>,
+    // The struct the type names, 'kind' is Identifier then
+    pub name: Option<Path<'a>>,
 }
 
 impl<'a> Type<'a> {    
@@ -127,17 +297,109 @@ impl<'a> Type<'a> {
             Float32 | Float64 | Character | String | Boolean | Const
         )
     }
+
+    // Where only a type can be written a name is a struct type too
+    pub fn is_or_struct(kind: TokenKind) -> bool {
+        Type::is(kind) || kind == TokenKind::Identifier
+    }
+
+    // The size of an array type when it's written as an integer literal
+    pub fn literal_length(&self) -> Option<usize> {
+        self.array_length.as_ref().and_then(|length| match &length.node {
+            RawExpression::Literal { kind: TokenKind::IntegerLiteral, value } => value.parse().ok(),
+            _ => None,
+        })
+    }
 }
 
-#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Clone)]
 pub struct Parameter<'a> {
     pub name: &'a str,
     pub type_: Type<'a>,
+    pub span: StatementSpan,
+}
+
+// Like Spanned, the span is left out so equal trees print the same
+impl<'a> std::fmt::Debug for Parameter<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Parameter").field("name", &self.name).field("type_", &self.type_).finish()
+    }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Clone)]
+pub struct Field<'a> {
+    pub name: &'a str,
+    pub type_: Type<'a>,
+    pub span: StatementSpan,
+}
+
+impl<'a> std::fmt::Debug for Field<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Field").field("name", &self.name).field("type_", &self.type_).finish()
+    }
+}
+
+// 'Failed(str, i32)', the types of the values the variant holds, empty when it holds none
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Clone)]
+pub struct Variant<'a> {
+    pub name: &'a str,
+    pub payload: Vec<Type<'a>>,
+    pub span: StatementSpan,
+}
+
+impl<'a> std::fmt::Debug for Variant<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Variant").field("name", &self.name).field("payload", &self.payload).finish()
+    }
+}
+
+// What a match arm compares the value with
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[derive(Debug, Clone)]
+pub enum Pattern<'a> {
+    // '_', matches every value
+    Wildcard,
+    // A literal, numbers may be negative like '-1'
+    Literal(Expression<'a>),
+    // 'State::Failed(message, _)' binds the values of the variant to the names, '_' skips one.
+    // Without bindings the variant matches whatever values it holds
+    Variant {
+        path: Path<'a>,
+        bindings: Vec<&'a str>,
+    },
+}
+
+// 'pattern => value', the value is a body in a match statement and an expression in a match expression
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Clone)]
+pub struct Arm<'a, T> {
+    pub pattern: Pattern<'a>,
+    pub value: T,
+    // The pattern only
+    pub span: StatementSpan,
+}
+
+impl<'a, T: std::fmt::Debug> std::fmt::Debug for Arm<'a, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Arm").field("pattern", &self.pattern).field("value", &self.value).finish()
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Clone)]
 pub struct Body<'a> {
     pub statements: Vec<Statement<'a>>,
+    // From the '{' to the '}'
+    pub span: StatementSpan,
+}
+
+impl<'a> std::fmt::Debug for Body<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Body").field("statements", &self.statements).finish()
+    }
 }
 
 pub type Expression<'a> = Box<Spanned<RawExpression<'a>>>;
@@ -169,10 +431,10 @@ pub struct ParserError {
 }
 
 impl ParserError {
-    pub fn to_diagnostic<'a>(&'a self, filename: &'a str) -> Diagnostic<'a> {
+    pub fn to_diagnostic<'a>(&'a self, sources: &'a SourceMap) -> Diagnostic<'a> {
         Diagnostic { 
             kind: DiagnosticKind::Error(self.code), 
-            info: DiagnosticInfo { filename, line: self.line, column: self.column }, 
+            info: DiagnosticInfo::from_span(sources, self.span.file, self.span.start, self.span.end), 
             hint: Some(self.get_hint(self.code)) 
         }
     }
@@ -214,6 +476,40 @@ impl ParserError {
             ErrorCode::EP032 => "check your syntax; an expression cannot start with this token",
             ErrorCode::EP033 => "close the array index access with ']'",
             ErrorCode::EP034 => "only functions can be called; ensure the identifier is a valid function name",
+            ErrorCode::EP035 => "remove the extra tokens, the input must be a single expression",
+            ErrorCode::EP036 => "statements start with a type, an identifier, or a keyword like 'fn', 'if' or 'while'",
+            ErrorCode::EP037 => "name the loop variable, e.g., for i in 0..10",
+            ErrorCode::EP038 => "add 'in' followed by a range or an array",
+            ErrorCode::EP039 => "loop over a range like 0..10 or over an array",
+            ErrorCode::EP040 => "ranges need both ends, e.g., 0..10",
+            ErrorCode::EP041 => "start the 'for' loop body with an opening brace '{'",
+            ErrorCode::EP042 => "give the namespace a name, e.g., namespace math { }",
+            ErrorCode::EP043 => "start the namespace body with an opening brace '{'",
+            ErrorCode::EP044 => "write the name of a function, a constant or a namespace after '::'",
+            ErrorCode::EP045 => "qualified names can only be called in a statement, namespace constants can't be assigned",
+            ErrorCode::EP046 => "write the path of the file between quotes, like \"lib/math.rc\", or the module name, like 'lib::math'",
+            ErrorCode::EP047 => "terminate the import with a ';'",
+            ErrorCode::EP048 => "give the struct a name, e.g., struct Point { }",
+            ErrorCode::EP049 => "start the struct fields with an opening brace '{'",
+            ErrorCode::EP050 => "declare each field with a type, a name and a ';', e.g., i32 x;",
+            ErrorCode::EP051 => "write the name of the field after '.', e.g., point.x",
+            ErrorCode::EP052 => "set each field with its name and a value, e.g., Point { x = 1, y = 2 }",
+            ErrorCode::EP053 => "separate the fields with a comma or close the literal with '}'",
+            ErrorCode::EP054 => "give the enum a name, e.g., enum State { }",
+            ErrorCode::EP055 => "start the enum variants with an opening brace '{'",
+            ErrorCode::EP056 => "write the name of each variant, e.g., enum State { Running, Stopped }",
+            ErrorCode::EP057 => "separate the variants with a comma or close the enum with '}'",
+            ErrorCode::EP058 => "write the types of the values the variant holds, e.g., Failed(str, i32)",
+            ErrorCode::EP059 => "separate the value types with a comma or close them with ')'",
+            ErrorCode::EP060 => "write the value to match after 'match'",
+            ErrorCode::EP061 => "start the match arms with an opening brace '{'",
+            ErrorCode::EP062 => "arms start with a variant like State::Running, a literal or '_'",
+            ErrorCode::EP063 => "separate the pattern from what the arm does with '=>'",
+            ErrorCode::EP064 => "bind each value of the variant to a name, or skip it with '_'",
+            ErrorCode::EP065 => "separate the bound names with a comma or close them with ')'",
+            ErrorCode::EP066 => "write a body like { ... } after '=>', or a value in a match expression",
+            ErrorCode::EP067 => "separate the arms with a comma or close the match with '}'",
+            ErrorCode::EP068 => "separate the elements with a comma or close the array with ']'",
             _ => "",
         }
     }
@@ -231,42 +527,70 @@ pub struct Parser<'a> {
     // recursive nature of the parse_expression function
     expression_end: usize,
 
-    line: usize,
-    column: usize,
+    file: FileId,
+    // The last consumed token, the one errors point at
+    last: TokenSpan<'a>,
 
     // context related stuff
     inside_if: bool,
     inside_elif: bool,
     inside_while: bool,
     inside_function: bool,
+    // Set while parsing the condition of 'if', 'while' and the iterable of 'for',
+    // where a '{' after a name opens the body instead of a struct literal
+    no_struct_literal: bool,
+
+    // Error recovery, only used by 'parse_recovering'
+    recovering: bool,
+    errors: Vec<ParserError>,
+    // Set after an error until a statement parses again, an error at the
+    // same position is caused by the previous one and isn't reported
+    panicking: bool,
+    body_depth: usize,
+    // Opening braces skipped while synchronizing, their '}' isn't an error
+    skipped_braces: usize,
+    // The last consumed token was rejected by the tokenizer
+    after_error_token: bool,
 }
 
 impl<'a> Parser<'a> {
     pub fn new(tokens: Vec<Token<'a>>) -> Self {        
+        // All the tokens come from the same source
+        let file: FileId = tokens.first().map_or(FileId::default(), |token| token.span.file);
+
         Self {
             tokens: tokens.into_iter().peekable(),
             peeked: Token{ 
                 kind: TokenKind::Eof, 
-                span: TokenSpan { start: 0, end: 0, literal: "", line: 0, column: 0 } 
+                span: TokenSpan { start: 0, end: 0, literal: "", line: 0, column: 0, file },
+                trivia: "",
             },
             statement_start: 0,
             statement_end: 0,
             expression_end: 0,
 
-            line: 1,
-            column: 1,
+            file,
+            last: TokenSpan { start: 0, end: 0, literal: "", line: 1, column: 1, file },
 
             inside_if: false,
             inside_elif: false,
             inside_while: false,
             inside_function: false,
+            no_struct_literal: false,
+
+            recovering: false,
+            errors: Vec::new(),
+            panicking: false,
+            body_depth: 0,
+            skipped_braces: 0,
+            after_error_token: false,
         }
     }
 
     pub fn parse(&mut self) -> Result<Vec<Statement<'a>>, ParserError> {
         let mut statements: Vec<Statement<'a>> = Vec::new();
 
-        while let Some(_) = self.peek() {
+        while self.peek().is_some() {
             if self.peeked.kind == TokenKind::Eof {
                 break;
             }
@@ -277,7 +601,123 @@ impl<'a> Parser<'a> {
         Ok(statements)
     }
 
+    // Unlike 'parse' it doesn't stop at the first error: it skips to the next
+    // statement and keeps going, returning every statement that parsed correctly
+    pub fn parse_recovering(&mut self) -> (Vec<Statement<'a>>, Vec<ParserError>) {
+        self.recovering = true;
+        let mut statements: Vec<Statement<'a>> = Vec::new();
+
+        while self.peek().is_some() {
+            if self.peeked.kind == TokenKind::Eof {
+                break;
+            }
+
+            if self.peeked.kind == TokenKind::RightBrace && self.skipped_braces > 0 {
+                self.skipped_braces -= 1;
+                self.next();
+                continue;
+            }
+
+            if let Some(statement) = self.get_statement_recovering() {
+                statements.push(statement);
+            }
+        }
+
+        (statements, std::mem::take(&mut self.errors))
+    }
+
+    fn get_statement_recovering(&mut self) -> Option<Statement<'a>> {
+        // The tokenizer already reported this one
+        if self.peeked.kind == TokenKind::Error {
+            self.next();
+            return None;
+        }
+
+        let start: usize = self.peeked.span.start;
+
+        match self.get_statement() {
+            Ok(statement) => {
+                self.panicking = false;
+                Some(statement)
+            },
+            Err(error) => {
+                self.report(error);
+
+                // Makes sure at least one token is skipped, so the same error can't repeat forever
+                if self.peek().is_some_and(|token| token.span.start == start && token.kind != TokenKind::Eof) {
+                    self.next();
+                }
+                self.synchronize();
+                None
+            }
+        }
+    }
+
+    fn report(&mut self, error: ParserError) {
+        // A run of tokens on one line that can't start a statement is reported once
+        let cascade: bool = self.panicking && self.errors.last().is_some_and(|last| {
+            (last.line, last.column) == (error.line, error.column) ||
+            (last.code, last.line) == (ErrorCode::EP036, error.line) && error.code == ErrorCode::EP036
+        });
+
+        // Errors next to a token the tokenizer rejected are caused by it
+        let lexical: bool = self.after_error_token || self.peeked.kind == TokenKind::Error;
+
+        if !cascade && !lexical {
+            self.errors.push(error);
+        }
+        self.panicking = true;
+    }
+
+    // Skips tokens until a point where a new statement can start
+    fn synchronize(&mut self) {
+        while let Some(token) = self.peek() {
+            match token.kind {
+                TokenKind::Eof => return,
+                TokenKind::Semicolon => {
+                    self.next();
+                    return;
+                },
+                TokenKind::LeftBrace => {
+                    self.skipped_braces += 1;
+                    self.next();
+                },
+                TokenKind::RightBrace if self.skipped_braces > 0 => {
+                    self.skipped_braces -= 1;
+                    self.next();
+                },
+                // The enclosing body ends here, a '}' outside of any body is just skipped
+                TokenKind::RightBrace if self.body_depth > 0 => return,
+                TokenKind::Function | TokenKind::If | TokenKind::While | TokenKind::For | TokenKind::Return |
+                TokenKind::Break | TokenKind::Continue | TokenKind::Const | TokenKind::Namespace |
+                TokenKind::Import | TokenKind::Struct | TokenKind::Enum | TokenKind::Match => return,
+                kind if Type::is(kind) => return,
+                _ => { self.next(); },
+            }
+        }
+    }
+
+    // Parses an input made of exactly one expression, like the values typed in the REPL
+    pub fn parse_standalone_expression(&mut self) -> Result<Expression<'a>, ParserError> {
+        self.statement_start = self.peek().map_or(0, |token| token.span.start);
+        let expression: Expression<'a> = self.parse_expression(0)?;
+
+        match self.peek() {
+            Some(token) if token.kind != TokenKind::Eof => Err(self.error(ErrorCode::EP035)),
+            _ => Ok(expression),
+        }
+    }
+
     fn get_statement(&mut self) -> Result<Statement<'a>, ParserError> {
+        let start: usize = self.peeked.span.start;
+        let mut statement: Statement<'a> = self.parse_statement()?;
+
+        // Statements with a body parse other statements, which move 'statement_start'
+        statement.span.start = start;
+        Ok(statement)
+    }
+
+    fn parse_statement(&mut self) -> Result<Statement<'a>, ParserError> {
         self.statement_start = self.peeked.span.start;
 
         if self.is_variable() {
@@ -296,6 +736,10 @@ impl<'a> Parser<'a> {
             self.parse_while_statement()
         }
 
+        else if self.match_peek(TokenKind::For) {
+            self.parse_for_statement()
+        }
+
         else if self.is_loop_control() {
             self.parse_loop_control()
         }
@@ -308,13 +752,34 @@ impl<'a> Parser<'a> {
             self.parse_return_statement()
         }
 
+        else if self.match_peek(TokenKind::Namespace) {
+            self.parse_namespace()
+        }
+
+        else if self.match_peek(TokenKind::Import) {
+            self.parse_import()
+        }
+
+        else if self.match_peek(TokenKind::Struct) {
+            self.parse_struct()
+        }
+
+        else if self.match_peek(TokenKind::Enum) {
+            self.parse_enum()
+        }
+
+        else if self.match_peek(TokenKind::Match) {
+            self.parse_match_statement()
+        }
+
         else {
             if self.match_peek(TokenKind::ElseIf) ||
                self.match_peek(TokenKind::Else) {
                 return Err(self.error(ErrorCode::EP000));
             }
 
-            panic!("Not implemented {}", self.peeked.kind); 
+            // Points at the token that can't start a statement, not at the end of the last one
+            Err(self.error_at(ErrorCode::EP036, self.peeked.span))
         }
     }
 
@@ -334,12 +799,24 @@ impl<'a> Parser<'a> {
     }
 
     fn is_loop_control(&mut self) -> bool {
-        return self.match_peek(TokenKind::Break) ||
-               self.match_peek(TokenKind::Continue);
+        self.match_peek(TokenKind::Break) ||
+        self.match_peek(TokenKind::Continue)
     }
 
     fn parse_type(&mut self) -> Result<Type<'a>, ParserError> {
-        let kind: TokenKind  = self.next().kind; // Consumes the kind like 'i32'
+        let token: Token<'a> = self.next(); // Consumes the kind like 'i32'
+
+        // A struct type is written by its name, like 'Point' or 'geo::Point'
+        let name: Option<Path<'a>> = match token.kind {
+            TokenKind::Identifier => Some(self.parse_path(token.span.literal)?),
+            _ => None,
+        };
+
+        self.parse_array_type(token.kind, name)
+    }
+
+    // Consumes the array size that may follow the kind of a type
+    fn parse_array_type(&mut self, kind: TokenKind, name: Option<Path<'a>>) -> Result<Type<'a>, ParserError> {
         let mut array_length: Option<Expression<'a>> = None; // Stores the array size
         let mut is_array: bool = false; // Check if the type is an array type
 
@@ -359,7 +836,8 @@ impl<'a> Parser<'a> {
         Ok(Type { 
             kind, 
             is_array, 
-            array_length 
+            array_length,
+            name,
         })
     }
 
@@ -372,6 +850,11 @@ impl<'a> Parser<'a> {
         }
 
         let type_: Type = self.parse_type()?;
+        self.parse_declaration(is_const, type_)
+    }
+
+    // Parses what follows the type of a declaration: the name and the initial value
+    fn parse_declaration(&mut self, is_const: bool, type_: Type<'a>) -> Result<Statement<'a>, ParserError> {
         let name: &str = self.expect_next(TokenKind::Identifier, 
             ErrorCode::EP003)?.span.literal;
         let mut value: Option<Expression<'a>> = None;
@@ -418,28 +901,29 @@ impl<'a> Parser<'a> {
         // and to provide better errors
         self.inside_if = true; 
 
-        let condition: Expression<'a>;
-        let body: Body<'a>;
+        // Read once, so an 'if' nested in the elif body collects its own branches
+        // and an error in the elif can't leave the flag set
+        let inside_elif: bool = std::mem::replace(&mut self.inside_elif, false);
+
         let mut elses: Vec<ElseBranch> = Vec::new();
 
         self.next(); // Consumes the 'if' keyword or 'elif' keyword
 
         // Consumes the condition
         self.expect_peek(RawExpression::is, ErrorCode::EP007)?;
-        condition = self.parse_expression(0)?;
+        let condition: Expression<'a> = self.parse_condition()?;
         
         // Consumes the body
         self.expect_peek(TokenKind::LeftBrace, ErrorCode::EP008)?;
-        body = self.parse_body()?;
+        let body: Body<'a> = self.parse_body()?;
 
         // If we are not inside an elif statement we can start parsing all the
         // other 'elif' and 'else' statements
-        if !self.inside_elif {
-            while let Some(_) = self.peek() {
+        if !inside_elif {
+            while self.peek().is_some() {
                 if self.peeked.kind == TokenKind::ElseIf {
                     self.inside_elif = true;
                     elses.push(ElseBranch::ElseIf(self.parse_if_statement()?));
-                    self.inside_elif = false;
                 }
                 else if self.peeked.kind == TokenKind::Else {
                     self.next(); // Parses the 'else' keyword
@@ -460,12 +944,25 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_body(&mut self) -> Result<Body<'a>, ParserError> {
+        let start: usize = self.peeked.span.start;
+
+        self.body_depth += 1;
+        let statements: Result<Vec<Statement<'a>>, ParserError> = self.parse_body_statements();
+        self.body_depth -= 1;
+
+        Ok(Body {
+            statements: statements?,
+            span: StatementSpan { start, end: self.statement_end, file: self.file },
+        })
+    }
+
+    fn parse_body_statements(&mut self) -> Result<Vec<Statement<'a>>, ParserError> {
         self.next(); // Consumes '{'
 
         let mut statements: Vec<Statement<'a>> = Vec::new();
         
         // Consumes all the various statements that are inside the body
-        while let Some(_) = self.peek() {
+        while self.peek().is_some() {
             // This here means an incomplete body, because it's missing the '}'
             if self.peeked.kind == TokenKind::Eof {
                 return Err(self.error(ErrorCode::EP010));
@@ -477,28 +974,31 @@ impl<'a> Parser<'a> {
                 break;
             }
 
-            statements.push(self.get_statement()?);
+            // When recovering, a broken statement doesn't break the whole body
+            if self.recovering {
+                if let Some(statement) = self.get_statement_recovering() {
+                    statements.push(statement);
+                }
+            }
+            else {
+                statements.push(self.get_statement()?);
+            }
         }
 
-        Ok(Body {
-            statements,
-        })
+        Ok(statements)
     }
 
     fn parse_while_statement(&mut self) -> Result<Statement<'a>, ParserError> {
         self.inside_while = true;
         self.next(); // Consumes the 'while' keyword
 
-        let condition: Expression<'a>;
-        let body: Body<'a>;
-
         // Consumes the condtion
         self.expect_peek(RawExpression::is, ErrorCode::EP011)?;
-        condition = self.parse_expression(0)?;
+        let condition: Expression<'a> = self.parse_condition()?;
         
         // Consumes the body
         self.expect_peek(TokenKind::LeftBrace, ErrorCode::EP012)?;
-        body = self.parse_body()?;
+        let body: Body<'a> = self.parse_body()?;
 
         self.inside_while = false;
 
@@ -510,17 +1010,100 @@ impl<'a> Parser<'a> {
         ))
     }
 
+    fn parse_for_statement(&mut self) -> Result<Statement<'a>, ParserError> {
+        self.next(); // Consumes the 'for' keyword
+
+        // The type of the loop variable is optional
+        let mut type_: Option<TokenKind> = None;
+        if self.match_peek(|kind: TokenKind| Type::is(kind) && kind != TokenKind::Const) {
+            type_ = Some(self.next().kind);
+        }
+
+        let name: &str = self.expect_next(TokenKind::Identifier, ErrorCode::EP037)?.span.literal;
+        self.expect_next(TokenKind::In, ErrorCode::EP038)?;
+
+        // Consumes the array or the start of the range
+        self.expect_peek(RawExpression::is, ErrorCode::EP039)?;
+        let start: Expression<'a> = self.parse_condition()?;
+
+        let iterable: Iterable<'a> = if self.match_peek(TokenKind::Range) {
+            self.next(); // Consumes the '..'
+
+            self.expect_peek(RawExpression::is, ErrorCode::EP040)?;
+            Iterable::Range { start, end: self.parse_condition()? }
+        }
+        else {
+            Iterable::Array(start)
+        };
+
+        // Consumes the body
+        self.expect_peek(TokenKind::LeftBrace, ErrorCode::EP041)?;
+        let body: Body<'a> = self.parse_body()?;
+
+        Ok(self.statement(
+            RawStatement::For {
+                type_,
+                name,
+                iterable,
+                body
+            }
+        ))
+    }
+
     fn parse_identifier(&mut self) -> Result<Statement<'a>, ParserError> {
         let identifier: Token<'a> = self.next();
+        let path: Path<'a> = self.parse_path(identifier.span.literal)?;
+
+        // A name followed by another one is the struct type of a declaration, like 'Point p;' or 'geo::Point[2] p;'
+        if self.match_peek(TokenKind::Identifier) || (path.is_qualified() && self.match_peek(TokenKind::LeftBracket)) {
+            let type_: Type<'a> = self.parse_array_type(TokenKind::Identifier, Some(path))?;
+            if !self.match_peek(TokenKind::Identifier) {
+                return Err(self.error(ErrorCode::EP013));
+            }
+            return self.parse_declaration(false, type_);
+        }
+
+        // Only functions can be reached through a namespace from a statement
+        if path.is_qualified() && !self.match_peek(TokenKind::LeftParen) {
+            return Err(self.error(ErrorCode::EP045));
+        }
+
+        // Consumes what follows the name in an assignment like 'lines[i].from.x = 1'
+        let mut accessors: Vec<Accessor<'a>> = Vec::new();
+        loop {
+            if self.match_peek(TokenKind::Dot) {
+                self.next(); // Consumes the '.'
+                accessors.push(Accessor::Field(self.expect_next(TokenKind::Identifier, ErrorCode::EP051)?.span.literal));
+            }
+            else if self.match_peek(TokenKind::LeftBracket) {
+                self.next(); // Consumes the '['
+                let index: Expression<'a> = self.parse_nested_expression()?;
+                self.expect_next(TokenKind::RightBracket, ErrorCode::EP033)?;
+
+                // Right after the name it can be the size of an array of structs, like 'Point[2] ps;'
+                if accessors.is_empty() && self.match_peek(TokenKind::Identifier) {
+                    let type_: Type<'a> = Type { kind: TokenKind::Identifier, is_array: true, array_length: Some(index), name: Some(path) };
+                    return self.parse_declaration(false, type_);
+                }
+                accessors.push(Accessor::Index(index));
+            }
+            else {
+                break;
+            }
+        }
 
         // If the next token is an assigment operator (=, +=, -=, etc.)
         // Then it's a variable assignment
         if self.match_peek(Parser::is_assignment()) {
-            self.parse_variable_assignment(identifier)
+            self.parse_variable_assignment(Place { name: identifier.span.literal, accessors })
+        }
+        // Fields and elements can only be assigned
+        else if !accessors.is_empty() {
+            Err(self.error(ErrorCode::EP013))
         }
         // If'the next token is a '(' then it's a function call
         else if self.match_peek(TokenKind::LeftParen){
-            self.parse_function_call(identifier)
+            self.parse_function_call(path)
         }
         // The user wrote something that has no sense after the identifier
         else {
@@ -528,7 +1111,21 @@ impl<'a> Parser<'a> {
         }
     }
 
-    fn parse_function_call(&mut self, name: Token<'a>) -> Result<Statement<'a>, ParserError> {
+    // Consumes the '::' separated names that follow the first one, if any
+    fn parse_path(&mut self, first: &'a str) -> Result<Path<'a>, ParserError> {
+        let mut path: Path<'a> = Path::from(first);
+
+        while self.match_peek(TokenKind::PathSeparator) {
+            self.next(); // Consumes the '::'
+
+            let name: &'a str = self.expect_next(TokenKind::Identifier, ErrorCode::EP044)?.span.literal;
+            path.namespaces.push(std::mem::replace(&mut path.name, name));
+        }
+
+        Ok(path)
+    }
+
+    fn parse_function_call(&mut self, name: Path<'a>) -> Result<Statement<'a>, ParserError> {
         // Function call are a bit more complex because they can be either
         // expression or statements, this function here parse the statement version
         // which is usually points to a function with no return value
@@ -542,7 +1139,7 @@ impl<'a> Parser<'a> {
         self.expect_next(TokenKind::Semicolon, ErrorCode::EP017)?;
 
         Ok(self.statement(RawStatement::FunctionCall { 
-            name: name.span.literal, 
+            name, 
             arguments 
         }))
     }
@@ -551,7 +1148,7 @@ impl<'a> Parser<'a> {
         let mut arguments: Vec<Expression> = Vec::new();
         
         // Loop to get all the arguments
-        while let Some(_) = self.peek() {
+        while self.peek().is_some() {
             // Here means incomplete arguments, because it abruptly ends
             if self.peeked.kind == TokenKind::Eof {
                 return Err(self.error(ErrorCode::EP014));
@@ -566,7 +1163,7 @@ impl<'a> Parser<'a> {
             // We check for an expression because argument are just either
             // variables, function calls, or plain math expressions
             self.expect_peek(RawExpression::is, ErrorCode::EP015)?;
-            arguments.push(self.parse_expression(0)?);
+            arguments.push(self.parse_nested_expression()?);
 
             // Now after we got the expression we expect a comma or a right paren
             // The check for the right paren is at the start so no point in repeating code
@@ -584,7 +1181,7 @@ impl<'a> Parser<'a> {
         Ok(arguments)
     }
 
-    fn parse_variable_assignment(&mut self, name: Token<'a>) -> Result<Statement<'a>, ParserError> {
+    fn parse_variable_assignment(&mut self, target: Place<'a>) -> Result<Statement<'a>, ParserError> {
         let operator: TokenKind = self.next().kind; // Consumes the '=', '+=' and similar
         
         // Consumes the expression to assing to the variable
@@ -596,7 +1193,7 @@ impl<'a> Parser<'a> {
         
         Ok(self.statement(
             RawStatement::VariableAssignment {
-            name: name.span.literal, 
+            target,
             operator, 
             value 
         }))
@@ -682,11 +1279,304 @@ impl<'a> Parser<'a> {
         ))
     }
 
+    fn parse_namespace(&mut self) -> Result<Statement<'a>, ParserError> {
+        self.next(); // Consumes the 'namespace' keyword
+
+        // Consumes the name
+        let name: &'a str = self.expect_next(TokenKind::Identifier, ErrorCode::EP042)?.span.literal;
+
+        // Consumes the body, what it may contain is checked by the SemanticAnalyzer
+        self.expect_peek(TokenKind::LeftBrace, ErrorCode::EP043)?;
+        let body: Body<'a> = self.parse_body()?;
+
+        Ok(self.statement(RawStatement::Namespace { name, body }))
+    }
+
+    fn parse_import(&mut self) -> Result<Statement<'a>, ParserError> {
+        self.next(); // Consumes the 'import' keyword
+
+        // Consumes either the file path or the module name, the ModuleLoader resolves them
+        let import: Import<'a> = if self.match_peek(TokenKind::StringLiteral) {
+            Import::File(self.next().span.literal)
+        }
+        else if self.match_peek(TokenKind::Identifier) {
+            let first: &'a str = self.next().span.literal;
+            Import::Module(self.parse_path(first)?)
+        }
+        else {
+            return Err(self.error(ErrorCode::EP046));
+        };
+
+        // Consumes the ';'
+        self.expect_next(TokenKind::Semicolon, ErrorCode::EP047)?;
+
+        Ok(self.statement(RawStatement::Import(import)))
+    }
+
+    fn parse_struct(&mut self) -> Result<Statement<'a>, ParserError> {
+        self.next(); // Consumes the 'struct' keyword
+
+        // Consumes the name
+        let name: &'a str = self.expect_next(TokenKind::Identifier, ErrorCode::EP048)?.span.literal;
+        self.expect_next(TokenKind::LeftBrace, ErrorCode::EP049)?;
+
+        // A broken field skips the rest of the struct, so its fields aren't read as statements
+        let fields: Result<Vec<Field<'a>>, ParserError> = self.parse_fields();
+        if fields.is_err() {
+            self.skip_declaration();
+        }
+
+        Ok(self.statement(RawStatement::Struct { name, fields: fields? }))
+    }
+
+    // When recovering, skips what's left of a struct or an enum up to its '}'
+    fn skip_declaration(&mut self) {
+        if !self.recovering {
+            return;
+        }
+
+        while self.peek().is_some_and(|token| token.kind != TokenKind::Eof) {
+            if self.next().kind == TokenKind::RightBrace {
+                break;
+            }
+        }
+    }
+
+    fn parse_fields(&mut self) -> Result<Vec<Field<'a>>, ParserError> {
+        let mut fields: Vec<Field<'a>> = Vec::new();
+
+        // Loops to find all the fields
+        while self.peek().is_some() {
+            // This means the fields never end, because it's missing the '}'
+            if self.peeked.kind == TokenKind::Eof {
+                return Err(self.error(ErrorCode::EP010));
+            }
+
+            // Found the '}' we can quit
+            if self.peeked.kind == TokenKind::RightBrace {
+                self.next();
+                break;
+            }
+
+            // Every field is declared like a variable without a value, 'i32 x;'
+            self.expect_peek(|kind: TokenKind| Type::is_or_struct(kind) && kind != TokenKind::Const, ErrorCode::EP050)?;
+            let start: usize = self.peeked.span.start;
+            let type_: Type<'a> = self.parse_type()?;
+
+            let name: TokenSpan<'a> = self.expect_next(TokenKind::Identifier, ErrorCode::EP050)?.span;
+            self.expect_next(TokenKind::Semicolon, ErrorCode::EP050)?;
+
+            fields.push(Field {
+                name: name.literal,
+                type_,
+                span: StatementSpan { start, end: name.end, file: self.file },
+            });
+        }
+
+        Ok(fields)
+    }
+
+    fn parse_enum(&mut self) -> Result<Statement<'a>, ParserError> {
+        self.next(); // Consumes the 'enum' keyword
+
+        // Consumes the name
+        let name: &'a str = self.expect_next(TokenKind::Identifier, ErrorCode::EP054)?.span.literal;
+        self.expect_next(TokenKind::LeftBrace, ErrorCode::EP055)?;
+
+        // Like a broken field, a broken variant skips the rest of the enum
+        let variants: Result<Vec<Variant<'a>>, ParserError> = self.parse_variants();
+        if variants.is_err() {
+            self.skip_declaration();
+        }
+
+        Ok(self.statement(RawStatement::Enum { name, variants: variants? }))
+    }
+
+    fn parse_variants(&mut self) -> Result<Vec<Variant<'a>>, ParserError> {
+        let mut variants: Vec<Variant<'a>> = Vec::new();
+
+        while !self.match_peek(TokenKind::RightBrace) {
+            // This means the variants never end, because it's missing the '}'
+            if self.match_peek(TokenKind::Eof) {
+                return Err(self.error(ErrorCode::EP010));
+            }
+
+            let name: TokenSpan<'a> = self.expect_next(TokenKind::Identifier, ErrorCode::EP056)?.span;
+            let mut end: usize = name.end;
+
+            // Consumes the types of the values the variant holds, like '(str, i32)'
+            let mut payload: Vec<Type<'a>> = Vec::new();
+            if self.match_peek(TokenKind::LeftParen) {
+                self.next();
+
+                while !self.match_peek(TokenKind::RightParen) {
+                    self.expect_peek(|kind: TokenKind| Type::is_or_struct(kind) && kind != TokenKind::Const, ErrorCode::EP058)?;
+                    payload.push(self.parse_type()?);
+
+                    if self.match_peek(TokenKind::Comma) {
+                        self.next();
+                    }
+                    else if !self.match_peek(TokenKind::RightParen) {
+                        return Err(self.error(ErrorCode::EP059));
+                    }
+                }
+                end = self.next().span.end; // Consumes the ')'
+            }
+
+            variants.push(Variant {
+                name: name.literal,
+                payload,
+                span: StatementSpan { start: name.start, end, file: self.file },
+            });
+
+            // A comma may follow the last variant too
+            if self.match_peek(TokenKind::Comma) {
+                self.next();
+            }
+            else if !self.match_peek(TokenKind::RightBrace) {
+                return Err(self.error(ErrorCode::EP057));
+            }
+        }
+        self.next(); // Consumes the '}'
+
+        Ok(variants)
+    }
+
+    fn parse_match_statement(&mut self) -> Result<Statement<'a>, ParserError> {
+        self.next(); // Consumes the 'match' keyword
+        let value: Expression<'a> = self.parse_match_value()?;
+
+        // Every arm runs a body, no comma is needed between them
+        let mut arms: Vec<Arm<'a, Body<'a>>> = Vec::new();
+        while !self.match_peek(TokenKind::RightBrace) {
+            if self.match_peek(TokenKind::Eof) {
+                return Err(self.error(ErrorCode::EP010));
+            }
+
+            let (pattern, span) = self.parse_pattern()?;
+            self.expect_peek(TokenKind::LeftBrace, ErrorCode::EP066)?;
+            arms.push(Arm { pattern, value: self.parse_body()?, span });
+        }
+        self.next(); // Consumes the '}'
+
+        Ok(self.statement(RawStatement::Match { value, arms }))
+    }
+
+    // Parses what follows the 'match' keyword of an expression
+    fn parse_match_expression(&mut self, expression_start: usize) -> Result<Expression<'a>, ParserError> {
+        let value: Expression<'a> = self.parse_match_value()?;
+
+        let mut arms: Vec<Arm<'a, Expression<'a>>> = Vec::new();
+        while !self.match_peek(TokenKind::RightBrace) {
+            if self.match_peek(TokenKind::Eof) {
+                return Err(self.error(ErrorCode::EP010));
+            }
+
+            let (pattern, span) = self.parse_pattern()?;
+            self.expect_peek(RawExpression::is, ErrorCode::EP066)?;
+            arms.push(Arm { pattern, value: self.parse_nested_expression()?, span });
+
+            // A comma may follow the last arm too
+            if self.match_peek(TokenKind::Comma) {
+                self.next();
+            }
+            else if !self.match_peek(TokenKind::RightBrace) {
+                return Err(self.error(ErrorCode::EP067));
+            }
+        }
+        self.next(); // Consumes the '}'
+
+        Ok(self.expression(expression_start, RawExpression::Match { value, arms }))
+    }
+
+    // Consumes the matched value and the '{' that opens the arms
+    fn parse_match_value(&mut self) -> Result<Expression<'a>, ParserError> {
+        self.expect_peek(RawExpression::is, ErrorCode::EP060)?;
+        let value: Expression<'a> = self.parse_condition()?;
+
+        self.expect_next(TokenKind::LeftBrace, ErrorCode::EP061)?;
+        Ok(value)
+    }
+
+    // Consumes the pattern of an arm and the '=>' after it
+    fn parse_pattern(&mut self) -> Result<(Pattern<'a>, StatementSpan), ParserError> {
+        self.peek(); // Refreshes 'peeked'
+        let start: usize = self.peeked.span.start;
+
+        let pattern: Pattern<'a> = match self.peeked.kind {
+            TokenKind::Identifier if self.peeked.span.literal == "_" => {
+                self.next();
+                Pattern::Wildcard
+            },
+
+            TokenKind::Identifier => {
+                let first: &'a str = self.next().span.literal;
+                let path: Path<'a> = self.parse_path(first)?;
+
+                // Consumes the names the values are bound to, like '(message, _)'
+                let mut bindings: Vec<&'a str> = Vec::new();
+                if self.match_peek(TokenKind::LeftParen) {
+                    self.next();
+
+                    while !self.match_peek(TokenKind::RightParen) {
+                        bindings.push(self.expect_next(TokenKind::Identifier, ErrorCode::EP064)?.span.literal);
+
+                        if self.match_peek(TokenKind::Comma) {
+                            self.next();
+                        }
+                        else if !self.match_peek(TokenKind::RightParen) {
+                            return Err(self.error(ErrorCode::EP065));
+                        }
+                    }
+                    self.next(); // Consumes the ')'
+                }
+
+                Pattern::Variant { path, bindings }
+            },
+
+            TokenKind::IntegerLiteral | TokenKind::FloatLiteral | TokenKind::CharLiteral |
+            TokenKind::StringLiteral | TokenKind::True | TokenKind::False | TokenKind::Minus => {
+                Pattern::Literal(self.parse_literal_pattern()?)
+            },
+
+            _ => return Err(self.error(ErrorCode::EP062)),
+        };
+
+        let span: StatementSpan = StatementSpan { start, end: self.expression_end, file: self.file };
+        self.expect_next(TokenKind::FatArrow, ErrorCode::EP063)?;
+        Ok((pattern, span))
+    }
+
+    // Only numbers can be negative, '-1' is kept as the '-' applied to the literal
+    fn parse_literal_pattern(&mut self) -> Result<Expression<'a>, ParserError> {
+        let start: usize = self.peeked.span.start;
+        let negative: bool = self.match_peek(TokenKind::Minus);
+        if negative {
+            self.next(); // Consumes the '-'
+        }
+
+        let literal: Token<'a> = self.expect_next(|kind: TokenKind| match kind {
+            TokenKind::IntegerLiteral | TokenKind::FloatLiteral => true,
+            TokenKind::CharLiteral | TokenKind::StringLiteral | TokenKind::True | TokenKind::False => !negative,
+            _ => false,
+        }, ErrorCode::EP062)?;
+
+        let value: Expression<'a> = self.expression(literal.span.start, RawExpression::Literal {
+            kind: literal.kind,
+            value: literal.span.literal,
+        });
+
+        if negative {
+            return Ok(self.expression(start, RawExpression::Unary { operator: TokenKind::Minus, operand: value }));
+        }
+        Ok(value)
+    }
+
     fn parse_parameters(&mut self) -> Result<Vec<Parameter<'a>>, ParserError> {
         let mut parameters: Vec<Parameter> = Vec::new();
         
         // Loops to find all the parameters
-        while let Some(_) = self.peek() {
+        while self.peek().is_some() {
             // This means incomplete parameters, because it's missing the ')'
             if self.peeked.kind == TokenKind::Eof {
                 return Err(self.error(ErrorCode::EP027));
@@ -699,16 +1589,18 @@ impl<'a> Parser<'a> {
             }
             
             // Consumes the parameter type
-            self.expect_peek(Type::is, ErrorCode::EP028)?;
+            self.expect_peek(Type::is_or_struct, ErrorCode::EP028)?;
+            let start: usize = self.peeked.span.start;
             let type_: Type<'a> = self.parse_type()?;
 
             // Consumes the name
-            let name: &'a str = self.expect_next(TokenKind::Identifier, 
-                        ErrorCode::EP029)?.span.literal;
+            let name: TokenSpan<'a> = self.expect_next(TokenKind::Identifier, 
+                        ErrorCode::EP029)?.span;
 
             parameters.push(Parameter {
-                name, 
-                type_ 
+                name: name.literal, 
+                type_,
+                span: StatementSpan { start, end: name.end, file: self.file },
             });
 
             // Same thing as for arguments, if the next token is a comma
@@ -716,7 +1608,7 @@ impl<'a> Parser<'a> {
             // it's an error
             if self.match_peek(TokenKind::Comma){
                 self.next();
-                if !self.match_peek(Type::is) {
+                if !self.match_peek(Type::is_or_struct) {
                     return Err(self.error(ErrorCode::EP030));
                 }
             }            
@@ -726,8 +1618,18 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_expression(&mut self, min_bp: u8) -> Result<Expression<'a>, ParserError> {
+        self.peek(); // Refreshes 'peeked', it can be stale after an operator
         let expression_start: usize = self.peeked.span.start;
-        
+
+        // The token is left in place, so error recovery can restart from it
+        if !matches!(self.peeked.kind,
+            TokenKind::IntegerLiteral | TokenKind::FloatLiteral | TokenKind::CharLiteral |
+            TokenKind::StringLiteral | TokenKind::True | TokenKind::False |
+            TokenKind::Identifier | TokenKind::Minus | TokenKind::Not | TokenKind::LeftParen |
+            TokenKind::LeftBracket | TokenKind::Match | TokenKind::Error) {
+            return Err(self.error(ErrorCode::EP032));
+        }
+
         let token: Token<'a> = self.next(); // Consume the first token
 
         // This is the first part of the expression 
@@ -735,7 +1637,10 @@ impl<'a> Parser<'a> {
         let mut left: Expression<'a> = match token.kind {
             TokenKind::IntegerLiteral | TokenKind::FloatLiteral | 
             TokenKind::CharLiteral    | TokenKind::StringLiteral |
-            TokenKind::True | TokenKind::False => {
+            TokenKind::True | TokenKind::False |
+            // A rejected lexeme stands in for the value it was meant to be, so it
+            // doesn't cause more errors after the tokenizer one
+            TokenKind::Error => {
                 self.expression(expression_start, 
                     RawExpression::Literal { 
                         kind: token.kind, 
@@ -745,14 +1650,21 @@ impl<'a> Parser<'a> {
             },
 
             TokenKind::Identifier => {
-                self.expression(expression_start, 
-                    RawExpression::Variable(token.span.literal)
-                )
+                let path: Path<'a> = self.parse_path(token.span.literal)?;
+
+                if self.match_peek(TokenKind::LeftBrace) && !self.no_struct_literal {
+                    self.parse_struct_literal(path, expression_start)?
+                }
+                else {
+                    self.expression(expression_start, 
+                        RawExpression::Variable(path)
+                    )
+                }
             },
             
             TokenKind::Minus | TokenKind::Not => {
                 // Unary Op: recursive call with high binding power
-                let operand: Expression = self.parse_expression(4)?; 
+                let operand: Expression = self.parse_expression(RawExpression::UNARY_BINDING_POWER)?;
                 self.expression(expression_start, 
                         RawExpression::Unary { 
                         operator: token.kind, 
@@ -761,13 +1673,17 @@ impl<'a> Parser<'a> {
                 )
             },
             
+            TokenKind::Match => self.parse_match_expression(expression_start)?,
+
             TokenKind::LeftParen => {
                 // Grouping: reset BP to 0 to parse inside the parens
-                let expression: Expression<'a> = self.parse_expression(0)?;
+                let expression: Expression<'a> = self.parse_nested_expression()?;
                 self.expect_next(TokenKind::RightParen, ErrorCode::EP031)?;
                 expression
             }
 
+            TokenKind::LeftBracket => self.parse_array_literal(expression_start)?,
+
             _ => return Err(self.error(ErrorCode::EP032)),
         };
 
@@ -788,10 +1704,15 @@ impl<'a> Parser<'a> {
                 self.next();
 
             match operator.kind {
-                // Standard Math
+                // Arithmetic, bitwise, comparison and logical operators
                 TokenKind::Plus | TokenKind::Minus | 
-                TokenKind::Multiplication | TokenKind::Division |
-                TokenKind::Modulus => {
+                TokenKind::Multiplication | TokenKind::Division | TokenKind::Modulus |
+                TokenKind::BitwiseAnd | TokenKind::BitwiseOr | TokenKind::BitwiseXor |
+                TokenKind::BitwiseLShift | TokenKind::BitwiseRShift |
+                TokenKind::Equal | TokenKind::NotEqual |
+                TokenKind::LessThan | TokenKind::LessThanOrEqual |
+                TokenKind::GreaterThan | TokenKind::GreaterThanOrEqual |
+                TokenKind::And | TokenKind::Or => {
                     let right: Expression<'a> = self.parse_expression(r_bp)?;
                     left = self.expression(expression_start, 
                             RawExpression::Binary {
@@ -804,7 +1725,7 @@ impl<'a> Parser<'a> {
 
                 // Array Access: name[index]
                 TokenKind::LeftBracket => {
-                    let index: Expression<'a> = self.parse_expression(0)?; // Inner expr
+                    let index: Expression<'a> = self.parse_nested_expression()?; // Inner expr
                     self.expect_next(TokenKind::RightBracket, ErrorCode::EP033)?;
                     left = self.expression(expression_start, RawExpression::ArrayAccess {
                         array: left,
@@ -812,11 +1733,20 @@ impl<'a> Parser<'a> {
                     });
                 }
 
+                // Field Access: object.name
+                TokenKind::Dot => {
+                    let name: &'a str = self.expect_next(TokenKind::Identifier, ErrorCode::EP051)?.span.literal;
+                    left = self.expression(expression_start, RawExpression::Field {
+                        object: left,
+                        name,
+                    });
+                }
+
                 // Function Call: name(arg1, arg2)
                 TokenKind::LeftParen => {
                     // Here 'left' is the function name (Expression::Variable)
-                    let name: &str = match left.node {
-                        RawExpression::Variable(n) => n,
+                    let name: Path<'a> = match left.node {
+                        RawExpression::Variable(path) => path,
 
                         // The error here means that it's trying to call a non-function
                         _ => return Err(self.error(ErrorCode::EP034)),
@@ -838,12 +1768,74 @@ impl<'a> Parser<'a> {
         Ok(left)
     }
 
+    // Parses the fields of a literal, after the name of the struct
+    fn parse_struct_literal(&mut self, name: Path<'a>, expression_start: usize) -> Result<Expression<'a>, ParserError> {
+        self.next(); // Consumes the '{'
+
+        let mut fields: Vec<(&'a str, Expression<'a>)> = Vec::new();
+        while !self.match_peek(TokenKind::RightBrace) {
+            let field: &'a str = self.expect_next(TokenKind::Identifier, ErrorCode::EP052)?.span.literal;
+            self.expect_next(TokenKind::Assignment, ErrorCode::EP052)?;
+
+            self.expect_peek(RawExpression::is, ErrorCode::EP052)?;
+            fields.push((field, self.parse_nested_expression()?));
+
+            // A comma may follow the last field too
+            if self.match_peek(TokenKind::Comma) {
+                self.next();
+            }
+            else if !self.match_peek(TokenKind::RightBrace) {
+                return Err(self.error(ErrorCode::EP053));
+            }
+        }
+        self.next(); // Consumes the '}'
+
+        Ok(self.expression(expression_start, RawExpression::StructLiteral { name, fields }))
+    }
+
+    // Parses the elements of a literal, after the '['
+    fn parse_array_literal(&mut self, expression_start: usize) -> Result<Expression<'a>, ParserError> {
+        let mut elements: Vec<Expression<'a>> = Vec::new();
+        while !self.match_peek(TokenKind::RightBracket) {
+            elements.push(self.parse_nested_expression()?);
+
+            // A comma may follow the last element too
+            if self.match_peek(TokenKind::Comma) {
+                self.next();
+            }
+            else if !self.match_peek(TokenKind::RightBracket) {
+                return Err(self.error(ErrorCode::EP068));
+            }
+        }
+        self.next(); // Consumes the ']'
+
+        Ok(self.expression(expression_start, RawExpression::ArrayLiteral(elements)))
+    }
+
+    // The condition of 'if' and 'while' and the iterable of 'for' are followed by a body,
+    // so a name followed by '{' can't start a struct literal there
+    fn parse_condition(&mut self) -> Result<Expression<'a>, ParserError> {
+        let outer: bool = std::mem::replace(&mut self.no_struct_literal, true);
+        let expression: Result<Expression<'a>, ParserError> = self.parse_expression(0);
+        self.no_struct_literal = outer;
+        expression
+    }
+
+    // Inside brackets struct literals can be written again, like 'if (p == Point { x = 1 }) {}'
+    fn parse_nested_expression(&mut self) -> Result<Expression<'a>, ParserError> {
+        let outer: bool = std::mem::replace(&mut self.no_struct_literal, false);
+        let expression: Result<Expression<'a>, ParserError> = self.parse_expression(0);
+        self.no_struct_literal = outer;
+        expression
+    }
+
     fn statement(&mut self, node: RawStatement<'a>) -> Statement<'a> {
         Box::new(Spanned { 
             node, 
             span: StatementSpan { 
                 start: self.statement_start, 
-                end: self.statement_end 
+                end: self.statement_end,
+                file: self.file,
             } 
         })
     }
@@ -853,20 +1845,26 @@ impl<'a> Parser<'a> {
             node, 
             span: StatementSpan { 
                 start: expression_start, 
-                end: self.expression_end 
+                end: self.expression_end,
+                file: self.file,
             } 
         })
     }
 
     fn error(&self, code: ErrorCode) -> ParserError {
+        self.error_at(code, self.last)
+    }
+
+    fn error_at(&self, code: ErrorCode, token: TokenSpan<'a>) -> ParserError {
         ParserError { 
             code, 
             span: StatementSpan { 
-                start: self.statement_start, 
-                end: self.statement_end 
+                start: token.start, 
+                end: token.end,
+                file: self.file,
             },
-            line: self.line,
-            column: self.column
+            line: token.line,
+            column: token.column
         }
     }
 
@@ -876,8 +1874,8 @@ impl<'a> Parser<'a> {
             Some(token) => {
                 self.statement_end = token.span.end;
                 self.expression_end = token.span.end;
-                self.line = token.span.line;
-                self.column = token.span.column;
+                self.last = token.span;
+                self.after_error_token = token.kind == TokenKind::Error;
                 token
             }
             None => {
@@ -897,28 +1895,25 @@ impl<'a> Parser<'a> {
     }
 
     pub fn match_peek<M: TokenMatcher>(&mut self, matcher: M) -> bool {
-        if let Some(token) = self.peek() {
-            if matcher.matches(token.kind) {
-                return true;
-            }
+        if let Some(token) = self.peek() 
+            && matcher.matches(token.kind) {
+            return true;
         }
-        return false;
+        false
     }
 
     fn expect_next<M: TokenMatcher>(&mut self, matcher: M, error: ErrorCode) -> Result<Token<'a>, ParserError> {
-        if let Some(token) = self.peek() {
-            if matcher.matches(token.kind) {
-                return Ok(self.next());
-            }
+        if let Some(token) = self.peek() 
+            && matcher.matches(token.kind) {
+            return Ok(self.next());
         }
         Err(self.error(error))
     }
 
     fn expect_peek<M: TokenMatcher>(&mut self, matcher: M, error: ErrorCode) -> Result<Token<'a>, ParserError> {
-        if let Some(token) = self.peek() {
-            if matcher.matches(token.kind) {
-                return Ok(token);
-            }
+        if let Some(token) = self.peek() 
+            && matcher.matches(token.kind) {
+            return Ok(token);
         }
         Err(self.error(error))
     }
@@ -0,0 +1,115 @@
+use crate::engine::{Engine, EngineError};
+use crate::error::ErrorCode;
+use crate::value::Value;
+
+// What the REPL did with a line of input
+#[derive(Debug)]
+pub enum Response {
+    // The input has an unclosed '{' body, more lines are needed
+    Incomplete,
+    // Statements ran, or the expression had no value
+    Executed,
+    Value(Value),
+    Error(EngineError),
+}
+
+// Feeds input line by line into an Engine, the state survives between entries
+pub struct Repl {
+    engine: Engine,
+    buffer: String,
+    // Source of the last complete entry, diagnostics point inside it
+    last_input: String,
+    history: Vec<String>,
+}
+
+impl Default for Repl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Repl {
+    pub fn new() -> Repl {
+        Repl::with_engine(Engine::new())
+    }
+
+    pub fn with_engine(engine: Engine) -> Repl {
+        Repl {
+            engine,
+            buffer: String::new(),
+            last_input: String::new(),
+            history: Vec::new(),
+        }
+    }
+
+    pub fn engine(&mut self) -> &mut Engine {
+        &mut self.engine
+    }
+
+    pub fn is_continuing(&self) -> bool {
+        !self.buffer.is_empty()
+    }
+
+    pub fn last_input(&self) -> &str {
+        &self.last_input
+    }
+
+    pub fn history(&self) -> &[String] {
+        &self.history
+    }
+
+    // Entries from an earlier session, they are only listed, never run again automatically
+    pub fn load_history(&mut self, entries: impl IntoIterator<Item = String>) {
+        self.history.extend(entries);
+    }
+
+    // Drops the lines of an entry that wasn't completed yet
+    pub fn cancel(&mut self) {
+        self.buffer.clear();
+    }
+
+    pub fn feed(&mut self, line: &str) -> Response {
+        if !self.buffer.is_empty() {
+            self.buffer.push('\n');
+        }
+        self.buffer.push_str(line.trim_end_matches(['\r', '\n']));
+
+        if self.buffer.trim().is_empty() {
+            self.buffer.clear();
+            return Response::Executed;
+        }
+
+        self.evaluate(false)
+    }
+
+    // Called when the input ends, an incomplete entry is reported instead of waiting for more lines
+    pub fn finish(&mut self) -> Option<Response> {
+        if self.buffer.is_empty() {
+            return None;
+        }
+        Some(self.evaluate(true))
+    }
+
+    fn evaluate(&mut self, force: bool) -> Response {
+        // A single expression prints its value, anything else runs as statements
+        let response: Response = match self.engine.eval_expression_named::<Value>("<repl>", &self.buffer) {
+            Ok(Value::Void) => Response::Executed,
+            Ok(value) => Response::Value(value),
+            Err(EngineError::Syntax(_)) => match self.engine.compile_named("<repl>", &self.buffer) {
+                Err(EngineError::Syntax(error)) if error.code == ErrorCode::EP010 && !force => {
+                    return Response::Incomplete;
+                },
+                Err(error) => Response::Error(error),
+                Ok(script) => match self.engine.run(&script) {
+                    Ok(()) => Response::Executed,
+                    Err(error) => Response::Error(error),
+                },
+            },
+            Err(error) => Response::Error(error),
+        };
+
+        self.last_input = std::mem::take(&mut self.buffer);
+        self.history.push(self.last_input.clone());
+        response
+    }
+}
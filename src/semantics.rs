@@ -1,108 +1,665 @@
 use std::collections::HashMap;
-use crate::parser::{Parameter, Parser, ParserError, RawStatement, Statement};
+use crate::parser::{Accessor, Arm, Body, ElseBranch, Expression, Iterable, Parameter, Path, Pattern, RawExpression, RawStatement, Statement, StatementSpan, Type};
+use crate::tokenizer::TokenKind;
+use crate::typechecker::ValueType;
+use crate::error::*;
+use crate::source::SourceMap;
 
 pub enum Symbol<'a> {
     Statement(&'a Statement<'a>),
-    Parameter(&'a Parameter<'a>)
+    Parameter(&'a Parameter<'a>),
+    // Functions implemented by the host, like the builtins
+    Native,
+    // Variables declared outside of the analyzed code, by the host or an earlier script.
+    // Holds the element type and whether it's an array
+    Global(TokenKind, bool),
+    // Variables with the type the TypeChecker inferred or resolved, like the one of
+    // a 'for' loop or a struct declared in another namespace. Only the TypeChecker declares them
+    Inferred(ValueType),
+    Namespace,
+    // Structs and enums declared by an earlier script, their fields and values aren't known
+    Type,
+    // A variant of an enum, declared inside it like 'State::Running'
+    Variant,
+    // A name a match arm binds a value of a variant to
+    Binding,
+}
+
+impl<'a> Symbol<'a> {
+    pub fn is_function(&self) -> bool {
+        match self {
+            Symbol::Statement(statement) => matches!(statement.node, RawStatement::Function { .. }),
+            Symbol::Parameter(_) | Symbol::Global(..) | Symbol::Inferred(_) | Symbol::Namespace |
+            Symbol::Type | Symbol::Variant | Symbol::Binding => false,
+            Symbol::Native => true,
+        }
+    }
+
+    pub fn is_type(&self) -> bool {
+        match self {
+            Symbol::Statement(statement) => matches!(statement.node, RawStatement::Struct { .. } | RawStatement::Enum { .. }),
+            Symbol::Type => true,
+            _ => false,
+        }
+    }
 }
 
 pub struct Scope<'a> {
+    // Everything declared at the top level, by its qualified name like 'math::sqrt'
+    globals: HashMap<String, Symbol<'a>>,
+    // One map for every body the declarations are in, empty at the top level
     symbols: Vec<HashMap<&'a str, Symbol<'a>>>,
-    scope: usize
+    // Namespaces the top level declarations are in, outermost first
+    namespaces: Vec<&'a str>,
+}
+
+impl<'a> Default for Scope<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<'a> Scope<'a> {
     pub fn new() -> Scope<'a> {
         Scope {
-            symbols: vec![HashMap::new()],
-            scope: 0
+            globals: HashMap::new(),
+            symbols: Vec::new(),
+            namespaces: Vec::new(),
         }
     }
 
     pub fn enter(&mut self) {
         self.symbols.push(HashMap::new());
-        self.scope += 1;
     }
 
     pub fn exit(&mut self) {
         self.symbols.pop();
-        self.scope -= 1;
+    }
+
+    pub fn enter_namespace(&mut self, name: &'a str) {
+        self.namespaces.push(name);
+    }
+
+    pub fn exit_namespace(&mut self) {
+        self.namespaces.pop();
     }
 
     pub fn declare(&mut self, name: &'a str, symbol: Symbol<'a>) {
-        self.symbols.last_mut().unwrap().insert(name, symbol);
+        match self.symbols.last_mut() {
+            Some(symbols) => { symbols.insert(name, symbol); },
+            None => {
+                let name: String = Path::from(name).qualified(&self.namespaces).into_owned();
+
+                // The namespaces of a name declared by the host, like 'math::sqrt', exist too
+                for (end, _) in name.match_indices("::") {
+                    self.globals.entry(name[..end].to_string()).or_insert(Symbol::Namespace);
+                }
+
+                self.globals.insert(name, symbol);
+            },
+        }
     }
 
-    pub fn is_declared(&mut self, name: &'a str) -> bool {
-        for symbols in self.symbols.iter().rev() {
-            if symbols.contains_key(name) {
-                return true;
+    // Variants are declared inside their enum, like the members of a namespace
+    pub fn declare_variants(&mut self, name: &'a str, variants: impl IntoIterator<Item = &'a str>) {
+        self.enter_namespace(name);
+        for variant in variants {
+            if self.declared(variant).is_none() {
+                self.declare(variant, Symbol::Variant);
             }
         }
-        return false;
+        self.exit_namespace();
     }
 
+    pub fn is_declared(&mut self, name: &'a str) -> bool {
+        self.get(name).is_some()
+    }
+
+    pub fn depth(&self) -> usize {
+        self.symbols.len()
+    }
+
+    pub fn namespaces(&self) -> &[&'a str] {
+        &self.namespaces
+    }
+
+    // What 'name' is already bound to where a new declaration would go
+    pub fn declared(&self, name: &'a str) -> Option<&Symbol<'a>> {
+        match self.symbols.last() {
+            Some(symbols) => symbols.get(name),
+            None => self.globals.get(Path::from(name).qualified(&self.namespaces).as_ref()),
+        }
+    }
+
+    // Names provided from outside can be declared again, the new declaration replaces them
     pub fn is_redeclared(&mut self, name: &'a str) -> bool {
-        self.symbols.last().unwrap().contains_key(name)
+        matches!(self.declared(name), Some(Symbol::Statement(_) | Symbol::Parameter(_) | Symbol::Namespace | Symbol::Binding))
     }
 
-    pub fn get(&mut self, name: &'a str) -> Option<&Symbol<'a>> {
-        for symbols in self.symbols.iter().rev() {
-            if let Some(symbol) = symbols.get(name) {
-                return Some(&symbol);
+    pub fn get(&self, name: &'a str) -> Option<&Symbol<'a>> {
+        self.resolve(&Path::from(name))
+    }
+
+    // Plain names are looked up in the bodies first, then every name is looked up
+    // in the current namespace and in the ones around it, up to the top level
+    pub fn resolve(&self, path: &Path<'a>) -> Option<&Symbol<'a>> {
+        if !path.is_qualified() {
+            for symbols in self.symbols.iter().rev() {
+                if let Some(symbol) = symbols.get(path.name) {
+                    return Some(symbol);
+                }
             }
         }
-        None
+
+        path.candidates(&self.namespaces).find_map(|name| self.globals.get(name.as_ref()))
+    }
+}
+
+#[derive(Debug)]
+pub struct SemanticError {
+    pub code: ErrorCode,
+    pub span: StatementSpan,
+}
+
+impl SemanticError {
+    pub fn to_diagnostic<'a>(&'a self, sources: &'a SourceMap) -> Diagnostic<'a> {
+        Diagnostic {
+            kind: DiagnosticKind::Error(self.code),
+            info: DiagnosticInfo::from_span(sources, self.span.file, self.span.start, self.span.end),
+            hint: Some(self.get_hint(self.code))
+        }
+    }
+
+    fn get_hint(&self, code: ErrorCode) -> &str {
+        match code {
+            ErrorCode::ES001 => "declare the variable before using it, or check the spelling",
+            ErrorCode::ES002 => "define the function with 'fn', or check the spelling",
+            ErrorCode::ES003 => "rename one of the two declarations",
+            ErrorCode::ES004 => "only names declared with 'fn' can be called",
+            ErrorCode::ES005 => "functions must be called with '()'",
+            ErrorCode::ES006 => "move the function out of the enclosing body",
+            ErrorCode::ES007 => "'break' and 'continue' can only be used inside a 'while' or 'for' body",
+            ErrorCode::ES008 => "'return' can only be used inside a function body",
+            ErrorCode::ES009 => "move the namespace out of the enclosing body",
+            ErrorCode::ES010 => "namespaces hold 'fn', 'const', 'struct' and 'enum' declarations and other namespaces, move the rest out",
+            ErrorCode::ES011 => "declare the namespace before using it, or check the spelling",
+            ErrorCode::ES012 => "use one of the names inside the namespace, like 'name::member'",
+            ErrorCode::ES013 => "move the import out of the enclosing body, to the top level of the file",
+            ErrorCode::ES014 => "move the struct out of the enclosing body",
+            ErrorCode::ES015 => "declare the type with 'struct' or 'enum', or check the spelling",
+            ErrorCode::ES016 => "only names declared with 'struct' or 'enum' can be used as types",
+            ErrorCode::ES017 => "rename one of the two fields",
+            ErrorCode::ES018 => "build a struct with a literal, like 'Name { field = value }', or pick a variant of an enum, like 'Name::Variant'",
+            ErrorCode::ES019 => "move the enum out of the enclosing body",
+            ErrorCode::ES020 => "rename one of the two variants",
+            ErrorCode::ES021 => "use one of the variants declared in the enum, or check the spelling",
+            ErrorCode::ES022 => "arms match a variant like 'State::Running', a literal or '_'",
+            ErrorCode::ES023 => "pick a variant of the enum, like 'Name::Variant', instead of writing a literal",
+            ErrorCode::ES024 => "constants can't change once declared, remove 'const' from the declaration to assign it",
+            ErrorCode::ES025 => "give the constant its value where it's declared, like 'const i32 limit = 10;'",
+            _ => "",
+        }
     }
 }
 
 pub struct SemanticAnalyzer<'a> {
-    ast: Vec<Statement<'a>>
+    ast: &'a [Statement<'a>],
+    scope: Scope<'a>,
+    errors: Vec<SemanticError>,
+
+    inside_function: bool,
+    loop_depth: usize,
 }
 
 impl<'a> SemanticAnalyzer<'a> {
-    pub fn new(ast: Vec<Statement<'a>>) -> SemanticAnalyzer<'a> {
+    pub fn new(ast: &'a [Statement<'a>]) -> SemanticAnalyzer<'a> {
         SemanticAnalyzer {
-            ast
+            ast,
+            scope: Scope::new(),
+            errors: Vec::new(),
+
+            inside_function: false,
+            loop_depth: 0,
         }
     }
 
-    pub fn analyze_statement(statement: &Statement) -> Result<(), ParserError> {
+    pub fn declare_native(&mut self, name: &'a str) {
+        self.scope.declare(name, Symbol::Native);
+    }
+
+    pub fn declare_global(&mut self, name: &'a str, kind: TokenKind, is_array: bool) {
+        self.scope.declare(name, Symbol::Global(kind, is_array));
+    }
+
+    pub fn declare_type(&mut self, name: &'a str) {
+        self.scope.declare(name, Symbol::Type);
+    }
+
+    pub fn declare_enum(&mut self, name: &'a str, variants: impl IntoIterator<Item = &'a str>) {
+        self.scope.declare(name, Symbol::Type);
+        self.scope.declare_variants(name, variants);
+    }
+
+    pub fn analyze(&mut self) -> Result<(), Vec<SemanticError>> {
+        self.analyze_statements(self.ast);
+
+        if self.errors.is_empty() {
+            Ok(())
+        }
+        else {
+            Err(std::mem::take(&mut self.errors))
+        }
+    }
+
+    // Analyzes an expression that isn't part of the AST, like the ones typed in the REPL
+    pub fn analyze_standalone(&mut self, expression: &'a Expression<'a>) -> Result<(), Vec<SemanticError>> {
+        self.analyze_expression(expression);
+
+        if self.errors.is_empty() {
+            Ok(())
+        }
+        else {
+            Err(std::mem::take(&mut self.errors))
+        }
+    }
+
+    fn analyze_statements(&mut self, statements: &'a [Statement<'a>]) {
+        self.hoist(statements);
+
+        for statement in statements {
+            self.analyze_statement(statement);
+        }
+    }
+
+    // Functions, structs and enums are declared before anything else in the block, so they
+    // can be used before their definition and can refer to themselves.
+    // The ones inside namespaces are declared with them
+    fn hoist(&mut self, statements: &'a [Statement<'a>]) {
+        for statement in statements {
+            match &statement.node {
+                RawStatement::Function { name, .. } | RawStatement::Struct { name, .. } => {
+                    self.declare(name, Symbol::Statement(statement), statement.span);
+                },
+
+                RawStatement::Enum { name, variants } => {
+                    self.declare(name, Symbol::Statement(statement), statement.span);
+                    if self.scope.depth() == 0 {
+                        self.scope.declare_variants(name, variants.iter().map(|variant| variant.name));
+                    }
+                },
+
+                RawStatement::Namespace { name, body } if self.scope.depth() == 0 => {
+                    // The same namespace can be opened again to add more to it
+                    if !matches!(self.scope.declared(name), Some(Symbol::Namespace)) {
+                        self.declare(name, Symbol::Namespace, statement.span);
+                    }
+
+                    self.scope.enter_namespace(name);
+                    self.hoist(&body.statements);
+                    self.scope.exit_namespace();
+                },
+
+                _ => {},
+            }
+        }
+    }
+
+    fn analyze_body(&mut self, body: &'a Body<'a>) {
+        self.scope.enter();
+        self.analyze_statements(&body.statements);
+        self.scope.exit();
+    }
+
+    fn analyze_statement(&mut self, statement: &'a Statement<'a>) {
         match &statement.node {
             RawStatement::VariableDeclaration { is_const, type_, name, value } => {
+                self.analyze_type(type_, statement.span);
+                if *is_const && value.is_none() {
+                    self.error(ErrorCode::ES025, statement.span);
+                }
 
+                // The value is checked first, so 'i32 x = x;' is reported
+                if let Some(value) = value {
+                    self.analyze_expression(value);
+                }
+
+                self.declare(name, Symbol::Statement(statement), statement.span);
             },
 
-            RawStatement::VariableAssignment { name, operator, value } => {
+            RawStatement::VariableAssignment { target, value, .. } => {
+                let span: StatementSpan = StatementSpan {
+                    start: statement.span.start,
+                    end: statement.span.start + target.name.len(),
+                    file: statement.span.file,
+                };
+                self.resolve_variable(&Path::from(target.name), span);
+
+                // Elements and fields of a constant can't change either
+                if let Some(Symbol::Statement(declaration)) = self.scope.resolve(&Path::from(target.name))
+                    && matches!(declaration.node, RawStatement::VariableDeclaration { is_const: true, .. }) {
+                    self.error(ErrorCode::ES024, span);
+                }
 
+                for accessor in &target.accessors {
+                    if let Accessor::Index(index) = accessor {
+                        self.analyze_expression(index);
+                    }
+                }
+                self.analyze_expression(value);
             },
 
             RawStatement::If { condition, body, elses } => {
+                self.analyze_expression(condition);
+                self.analyze_body(body);
 
+                for branch in elses {
+                    match branch {
+                        ElseBranch::ElseIf(statement) => self.analyze_statement(statement),
+                        ElseBranch::Else(body) => self.analyze_body(body),
+                    }
+                }
             },
 
             RawStatement::While { condition, body } => {
+                self.analyze_expression(condition);
+
+                self.loop_depth += 1;
+                self.analyze_body(body);
+                self.loop_depth -= 1;
+            },
+
+            RawStatement::For { name, iterable, body, .. } => {
+                match iterable {
+                    Iterable::Range { start, end } => {
+                        self.analyze_expression(start);
+                        self.analyze_expression(end);
+                    },
+                    Iterable::Array(array) => self.analyze_expression(array),
+                }
 
+                // Like parameters, the variable lives in the same scope as the body
+                self.scope.enter();
+                self.declare(name, Symbol::Statement(statement), statement.span);
+
+                self.loop_depth += 1;
+                self.analyze_statements(&body.statements);
+                self.loop_depth -= 1;
+                self.scope.exit();
             },
-            
-            RawStatement::LoopControl(keyword) => {
 
+            RawStatement::LoopControl(_) => {
+                if self.loop_depth == 0 {
+                    self.error(ErrorCode::ES007, statement.span);
+                }
             },
 
-            RawStatement::Function { name, parameters, type_, body } => {
+            RawStatement::Function { parameters, body, .. } => {
+                // Functions only see globals and their own locals, so they
+                // can't be nested inside another body
+                if self.scope.depth() > 0 {
+                    self.error(ErrorCode::ES006, statement.span);
+                }
+
+                // The function name itself was declared by analyze_statements.
+                // Parameters live in the same scope as the function body,
+                // so a local with the same name as a parameter is a redeclaration
+                self.scope.enter();
+                self.inside_function = true;
+
+                for parameter in parameters {
+                    self.analyze_type(&parameter.type_, parameter.span);
+                    self.declare(parameter.name, Symbol::Parameter(parameter), statement.span);
+                }
 
+                self.analyze_statements(&body.statements);
+                self.inside_function = false;
+                self.scope.exit();
             },
 
             RawStatement::Return(value) => {
+                if !self.inside_function {
+                    self.error(ErrorCode::ES008, statement.span);
+                }
 
+                if let Some(value) = value {
+                    self.analyze_expression(value);
+                }
             },
 
             RawStatement::FunctionCall { name, arguments } => {
+                self.resolve_function(name, statement.span);
 
-            }
+                for argument in arguments {
+                    self.analyze_expression(argument);
+                }
+            },
+
+            RawStatement::Namespace { name, body } => {
+                // Namespaces only hold top level declarations
+                if self.scope.depth() > 0 {
+                    self.error(ErrorCode::ES009, statement.span);
+                    return;
+                }
+
+                // Its functions were declared by 'hoist'
+                self.scope.enter_namespace(name);
+                for statement in &body.statements {
+                    match &statement.node {
+                        RawStatement::Function { .. } | RawStatement::Namespace { .. } |
+                        RawStatement::Struct { .. } | RawStatement::Enum { .. } |
+                        RawStatement::VariableDeclaration { is_const: true, .. } => self.analyze_statement(statement),
+                        _ => self.error(ErrorCode::ES010, statement.span),
+                    }
+                }
+                self.scope.exit_namespace();
+            },
+
+            // The ModuleLoader only loads the imports at the top level
+            RawStatement::Import(_) => {
+                if self.scope.depth() > 0 {
+                    self.error(ErrorCode::ES013, statement.span);
+                }
+            },
+
+            // The struct itself was declared by 'hoist'
+            RawStatement::Struct { fields, .. } => {
+                // Like functions they are only declared at the top level
+                if self.scope.depth() > 0 {
+                    self.error(ErrorCode::ES014, statement.span);
+                }
+
+                for (index, field) in fields.iter().enumerate() {
+                    if fields[..index].iter().any(|other| other.name == field.name) {
+                        self.error(ErrorCode::ES017, field.span);
+                    }
+                    self.analyze_type(&field.type_, field.span);
+                }
+            },
+
+            // The enum and its variants were declared by 'hoist'
+            RawStatement::Enum { variants, .. } => {
+                if self.scope.depth() > 0 {
+                    self.error(ErrorCode::ES019, statement.span);
+                }
 
+                for (index, variant) in variants.iter().enumerate() {
+                    if variants[..index].iter().any(|other| other.name == variant.name) {
+                        self.error(ErrorCode::ES020, variant.span);
+                    }
+                    for type_ in &variant.payload {
+                        self.analyze_type(type_, variant.span);
+                    }
+                }
+            },
+
+            RawStatement::Match { value, arms } => {
+                self.analyze_expression(value);
+
+                // Like the variable of a 'for' loop, the bindings live in the same scope as the body
+                for arm in arms {
+                    self.scope.enter();
+                    self.analyze_pattern(arm);
+                    self.analyze_statements(&arm.value.statements);
+                    self.scope.exit();
+                }
+            },
         }
+    }
+
+    fn analyze_expression(&mut self, expression: &'a Expression<'a>) {
+        match &expression.node {
+            RawExpression::Variable(name) => {
+                self.resolve_variable(name, expression.span);
+            },
+
+            RawExpression::Literal { .. } => {},
+
+            RawExpression::Binary { left, right, .. } => {
+                self.analyze_expression(left);
+                self.analyze_expression(right);
+            },
+
+            RawExpression::Unary { operand, .. } => {
+                self.analyze_expression(operand);
+            },
+
+            RawExpression::FunctionCall { name, arguments } => {
+                self.resolve_function(name, expression.span);
+
+                for argument in arguments {
+                    self.analyze_expression(argument);
+                }
+            },
+
+            RawExpression::ArrayAccess { array, index } => {
+                self.analyze_expression(array);
+                self.analyze_expression(index);
+            },
+
+            RawExpression::StructLiteral { name, fields } => {
+                self.resolve_type(name, expression.span);
+                if let Some(Symbol::Statement(statement)) = self.scope.resolve(name)
+                    && matches!(statement.node, RawStatement::Enum { .. }) {
+                    self.error(ErrorCode::ES023, expression.span);
+                }
+
+                for (_, value) in fields {
+                    self.analyze_expression(value);
+                }
+            },
+
+            RawExpression::Field { object, .. } => {
+                self.analyze_expression(object);
+            },
+
+            RawExpression::Match { value, arms } => {
+                self.analyze_expression(value);
+
+                for arm in arms {
+                    self.scope.enter();
+                    self.analyze_pattern(arm);
+                    self.analyze_expression(&arm.value);
+                    self.scope.exit();
+                }
+            },
+
+            RawExpression::ArrayLiteral(elements) => {
+                for element in elements {
+                    self.analyze_expression(element);
+                }
+            },
+        }
+    }
+
+    // The variant of a pattern must exist, its bindings are declared in the scope of the arm
+    fn analyze_pattern<T>(&mut self, arm: &'a Arm<'a, T>) {
+        match &arm.pattern {
+            Pattern::Wildcard => {},
+            Pattern::Literal(literal) => self.analyze_expression(literal),
+            Pattern::Variant { path, bindings } => {
+                match self.scope.resolve(path) {
+                    Some(Symbol::Variant) => {},
+                    Some(_) => self.error(ErrorCode::ES022, arm.span),
+                    None if self.is_missing_variant(path) => self.error(ErrorCode::ES021, arm.span),
+                    None if self.is_missing_namespace(path) => self.error(ErrorCode::ES011, arm.span),
+                    None => self.error(ErrorCode::ES022, arm.span),
+                }
+
+                for binding in bindings.iter().filter(|binding| **binding != "_") {
+                    self.declare(binding, Symbol::Binding, arm.span);
+                }
+            },
+        }
+    }
+
+    // The struct a type names must exist, the array size is an expression like any other
+    fn analyze_type(&mut self, type_: &'a Type<'a>, span: StatementSpan) {
+        if let Some(name) = &type_.name {
+            self.resolve_type(name, span);
+        }
+
+        if let Some(length) = &type_.array_length {
+            self.analyze_expression(length);
+        }
+    }
+
+    fn declare(&mut self, name: &'a str, symbol: Symbol<'a>, span: StatementSpan) {
+        if self.scope.is_redeclared(name) {
+            self.error(ErrorCode::ES003, span);
+            return;
+        }
+
+        self.scope.declare(name, symbol);
+    }
+
+    fn resolve_variable(&mut self, path: &Path<'a>, span: StatementSpan) {
+        match self.scope.resolve(path) {
+            Some(symbol) if symbol.is_function() => self.error(ErrorCode::ES005, span),
+            Some(Symbol::Namespace) => self.error(ErrorCode::ES012, span),
+            Some(symbol) if symbol.is_type() => self.error(ErrorCode::ES018, span),
+            Some(_) => {},
+            None if self.is_missing_variant(path) => self.error(ErrorCode::ES021, span),
+            None if self.is_missing_namespace(path) => self.error(ErrorCode::ES011, span),
+            None => self.error(ErrorCode::ES001, span),
+        }
+    }
+
+    fn resolve_type(&mut self, path: &Path<'a>, span: StatementSpan) {
+        match self.scope.resolve(path) {
+            Some(symbol) if !symbol.is_type() => self.error(ErrorCode::ES016, span),
+            Some(_) => {},
+            None if self.is_missing_namespace(path) => self.error(ErrorCode::ES011, span),
+            None => self.error(ErrorCode::ES015, span),
+        }
+    }
+
+    // Variants holding values are built like a call, 'State::Stopped(3)'
+    fn resolve_function(&mut self, path: &Path<'a>, span: StatementSpan) {
+        match self.scope.resolve(path) {
+            Some(Symbol::Variant) => {},
+            Some(symbol) if !symbol.is_function() => self.error(ErrorCode::ES004, span),
+            Some(_) => {},
+            None if self.is_missing_variant(path) => self.error(ErrorCode::ES021, span),
+            None if self.is_missing_namespace(path) => self.error(ErrorCode::ES011, span),
+            None => self.error(ErrorCode::ES002, span),
+        }
+    }
+
+    // The name itself may be missing, or already the namespace it's looked up in
+    fn is_missing_namespace(&self, path: &Path<'a>) -> bool {
+        path.parent().is_some_and(|parent| !matches!(self.scope.resolve(&parent), Some(Symbol::Namespace)))
+    }
+
+    // The name is looked up in an enum that doesn't declare it
+    fn is_missing_variant(&self, path: &Path<'a>) -> bool {
+        path.parent().is_some_and(|parent| self.scope.resolve(&parent).is_some_and(|symbol| match symbol {
+            Symbol::Statement(statement) => matches!(statement.node, RawStatement::Enum { .. }),
+            Symbol::Type => true,
+            _ => false,
+        }))
+    }
 
-        Ok(())
+    fn error(&mut self, code: ErrorCode, span: StatementSpan) {
+        self.errors.push(SemanticError { code, span });
     }
 }
@@ -0,0 +1,78 @@
+use std::rc::Rc;
+
+// Identifies a source registered in a SourceMap, every span carries one.
+// Code tokenized without a map gets the default id, which is also the first one a map gives
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Default, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct FileId(pub u32);
+
+pub struct SourceFile {
+    pub name: String,
+    pub source: Rc<str>,
+    // Byte offset where each line starts, the first one is always 0
+    line_starts: Vec<usize>,
+}
+
+impl SourceFile {
+    fn new(name: String, source: Rc<str>) -> SourceFile {
+        let line_starts: Vec<usize> = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+
+        SourceFile { name, source, line_starts }
+    }
+
+    // Line and column of a byte offset, both start from 1 and the column counts chars
+    pub fn location(&self, offset: usize) -> (usize, usize) {
+        let offset: usize = offset.min(self.source.len());
+        let line: usize = self.line_starts.partition_point(|&start| start <= offset);
+        let before: &str = self.source.get(self.line_starts[line - 1]..offset).unwrap_or("");
+
+        (line, before.chars().count() + 1)
+    }
+
+    // Text of a line without its line terminator
+    pub fn line(&self, line: usize) -> Option<&str> {
+        let start: usize = *self.line_starts.get(line.checked_sub(1)?)?;
+        let end: usize = self.line_starts.get(line).map_or(self.source.len(), |&next| next - 1);
+
+        Some(self.source[start..end].trim_end_matches('\r'))
+    }
+}
+
+// Owns the text of every source the compiler has seen, diagnostics read their snippets from here
+#[derive(Default)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+}
+
+impl SourceMap {
+    pub fn new() -> SourceMap {
+        SourceMap { files: Vec::new() }
+    }
+
+    pub fn add(&mut self, name: impl Into<String>, source: impl Into<Rc<str>>) -> FileId {
+        self.files.push(SourceFile::new(name.into(), source.into()));
+        FileId(self.files.len() as u32 - 1)
+    }
+
+    pub fn get(&self, file: FileId) -> Option<&SourceFile> {
+        self.files.get(file.0 as usize)
+    }
+
+    pub fn name(&self, file: FileId) -> Option<&str> {
+        self.get(file).map(|file| file.name.as_str())
+    }
+
+    pub fn source(&self, file: FileId) -> Option<&str> {
+        self.get(file).map(|file| &*file.source)
+    }
+
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
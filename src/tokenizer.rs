@@ -1,14 +1,22 @@
 use core::fmt;
-use std::{char, collections::HashMap, iter::Peekable, str::Chars};
+use std::{char, collections::HashMap, iter::Peekable, str::CharIndices};
+
+use unicode_xid::UnicodeXID;
 
 use crate::error::*;
+use crate::source::{FileId, SourceMap};
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum TokenKind {
     Eof,
+    // Covers a lexeme the tokenizer rejected, only produced by 'tokenize_recovering'
+    Error,
     Semicolon,
     Comma,
     Dot,
+    Range,
+    PathSeparator,
     IntegerLiteral,
     FloatLiteral,
     CharLiteral,
@@ -39,6 +47,7 @@ pub enum TokenKind {
     GreaterThanOrEqual,
     LessThanOrEqual,
     Assignment,
+    FatArrow,
     AddAssignment,
     SubtractAssignment,
     MultiplyAssignment,
@@ -69,20 +78,49 @@ pub enum TokenKind {
     ElseIf,
     Else,
     While,
+    For,
+    In,
     Break,
     Continue,
     Function,
     Return,
+    Namespace,
+    Import,
+    Struct,
+    Enum,
+    Match,
     True,
     False,
 }
 
+impl TokenKind {
+    // The binary operator behind a compound assignment, '+=' gives '+'
+    pub fn compound_operator(&self) -> TokenKind {
+        match self {
+            Self::AddAssignment => Self::Plus,
+            Self::SubtractAssignment => Self::Minus,
+            Self::MultiplyAssignment => Self::Multiplication,
+            Self::DivideAssignment => Self::Division,
+            Self::ModulusAssignment => Self::Modulus,
+            Self::BitwiseAndAssignment => Self::BitwiseAnd,
+            Self::BitwiseOrAssignment => Self::BitwiseOr,
+            Self::BitwiseXorAssignment => Self::BitwiseXor,
+            Self::BitwiseLShiftAssignment => Self::BitwiseLShift,
+            Self::BitwiseRShiftAssignment => Self::BitwiseRShift,
+            other => *other,
+        }
+    }
+}
+
 impl fmt::Display for TokenKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Eof => write!(f, "EOF"),
+            Self::Error => write!(f, "error"),
             Self::Comma => write!(f, ","),
             Self::Dot => write!(f, "."),
+            Self::Range => write!(f, ".."),
+            Self::PathSeparator => write!(f, "::"),
             Self::Semicolon => write!(f, ";"),
             Self::IntegerLiteral => write!(f, "IntegerLiteral"),
             Self::FloatLiteral => write!(f, "FloatLiteral"),
@@ -102,18 +140,19 @@ impl fmt::Display for TokenKind {
             Self::BitwiseAnd => write!(f, "&"),
             Self::BitwiseOr => write!(f, "|"),
             Self::BitwiseXor => write!(f, "^"),
-            Self::BitwiseLShift => write!(f, ">>"),
-            Self::BitwiseRShift => write!(f, "<<"),
+            Self::BitwiseLShift => write!(f, "<<"),
+            Self::BitwiseRShift => write!(f, ">>"),
             Self::And => write!(f, "&&"),
             Self::Or => write!(f, "||"),
             Self::Not => write!(f, "!"),
             Self::Equal => write!(f, "=="),
             Self::NotEqual => write!(f, "!="),
             Self::GreaterThan => write!(f, ">"),
-            Self::LessThan => write!(f, "<LessThan>"),
+            Self::LessThan => write!(f, "<"),
             Self::GreaterThanOrEqual => write!(f, ">="),
             Self::LessThanOrEqual => write!(f, "<="),
             Self::Assignment => write!(f, "="),
+            Self::FatArrow => write!(f, "=>"),
             Self::AddAssignment => write!(f, "+="),
             Self::SubtractAssignment => write!(f, "-="),
             Self::MultiplyAssignment => write!(f, "*="),
@@ -122,8 +161,8 @@ impl fmt::Display for TokenKind {
             Self::BitwiseAndAssignment => write!(f, "&="),
             Self::BitwiseOrAssignment => write!(f, "|="),
             Self::BitwiseXorAssignment => write!(f, "^="),
-            Self::BitwiseLShiftAssignment => write!(f, ">>="),
-            Self::BitwiseRShiftAssignment => write!(f, "<<="),
+            Self::BitwiseLShiftAssignment => write!(f, "<<="),
+            Self::BitwiseRShiftAssignment => write!(f, ">>="),
             Self::Identifier => write!(f, "Identifier"),
             Self::Const => write!(f, "const"),
             Self::SignedInt8 => write!(f, "i8"),
@@ -144,28 +183,38 @@ impl fmt::Display for TokenKind {
             Self::ElseIf => write!(f, "elif"),
             Self::Else => write!(f, "else"),
             Self::While => write!(f, "while"),
+            Self::For => write!(f, "for"),
+            Self::In => write!(f, "in"),
             Self::Break => write!(f, "break"),
             Self::Continue => write!(f, "continue"),
             Self::Function => write!(f, "fn"),
             Self::Return => write!(f, "return"),
+            Self::Namespace => write!(f, "namespace"),
+            Self::Import => write!(f, "import"),
+            Self::Struct => write!(f, "struct"),
+            Self::Enum => write!(f, "enum"),
+            Self::Match => write!(f, "match"),
             Self::True => write!(f, "true"),
             Self::False => write!(f, "false"),
         }
     }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub struct TokenSpan<'a> {
     pub start: usize,
     pub end: usize,
     pub literal: &'a str,
+    // Where the token starts, columns count chars from 1
     pub line: usize,
     pub column: usize,
+    pub file: FileId,
 }
 
 impl<'a> TokenSpan<'a> {
-    pub fn new(start: usize, end: usize, literal: &'a str, line: usize, column: usize) -> Self {
-        Self { start, end, literal, line, column }
+    pub fn new(start: usize, end: usize, literal: &'a str, line: usize, column: usize, file: FileId) -> Self {
+        Self { start, end, literal, line, column, file }
     }
 }
 
@@ -184,14 +233,10 @@ pub struct TokenizerError<'a> {
 }
 
 impl<'a> TokenizerError<'a>  {
-    pub fn to_diagnostic(&'a self, filename: &'a str) -> Diagnostic<'a> {
+    pub fn to_diagnostic(&'a self, sources: &'a SourceMap) -> Diagnostic<'a> {
         Diagnostic {
             kind: DiagnosticKind::Error(self.code),
-            info: DiagnosticInfo { 
-                filename, 
-                line: self.line, 
-                column: self.column 
-            },
+            info: DiagnosticInfo::from_span(sources, self.span.file, self.span.start, self.span.end),
             hint: Some(self.get_hint(self.code))
         }
     }
@@ -215,10 +260,13 @@ impl<'a> TokenizerError<'a>  {
     }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub struct Token<'a>  {
     pub kind: TokenKind,
     pub span: TokenSpan<'a> ,
+    // Whitespace and '#' comments between the previous token and this one, as written
+    pub trivia: &'a str,
 }
 
 impl<'a> fmt::Display for Token<'a> {
@@ -229,7 +277,8 @@ impl<'a> fmt::Display for Token<'a> {
 
 pub struct Tokenizer<'a> {
     source: &'a str,
-    input: Peekable<Chars<'a>>,
+    // Spans are byte offsets into 'source', so every char keeps its index
+    input: Peekable<CharIndices<'a>>,
     keywords: HashMap<&'a str, TokenKind>,
 
     character: char,
@@ -237,21 +286,38 @@ pub struct Tokenizer<'a> {
     end: usize,
     is_parsing_string_literal: bool,
 
+    // Position of the next char
     line: usize,
     column: usize,
+    // Position of the char at 'start'
+    start_line: usize,
+    start_column: usize,
+    file: FileId,
 }
 
 impl<'a> Tokenizer<'a> {
     pub fn new(input: &'a str) -> Self {
+        Self::with_file(input, FileId::default())
+    }
+
+    // Tokenizes a source registered in a SourceMap, its id ends up in every span
+    pub fn with_file(input: &'a str, file: FileId) -> Self {
         let mut keywords: HashMap<&str, TokenKind> = HashMap::new();
         keywords.insert("if", TokenKind::If);
         keywords.insert("elif", TokenKind::ElseIf);
         keywords.insert("else", TokenKind::Else);
         keywords.insert("while", TokenKind::While);
+        keywords.insert("for", TokenKind::For);
+        keywords.insert("in", TokenKind::In);
         keywords.insert("break", TokenKind::Break);
         keywords.insert("continue", TokenKind::Continue);
         keywords.insert("fn", TokenKind::Function);
         keywords.insert("return", TokenKind::Return);
+        keywords.insert("namespace", TokenKind::Namespace);
+        keywords.insert("import", TokenKind::Import);
+        keywords.insert("struct", TokenKind::Struct);
+        keywords.insert("enum", TokenKind::Enum);
+        keywords.insert("match", TokenKind::Match);
         keywords.insert("true", TokenKind::True);
         keywords.insert("false", TokenKind::False);
         keywords.insert("const", TokenKind::Const);
@@ -272,7 +338,7 @@ impl<'a> Tokenizer<'a> {
 
         Self { 
             source: input,
-            input: input.chars().peekable(), 
+            input: input.char_indices().peekable(), 
             keywords,
 
             character: ' ', 
@@ -282,36 +348,99 @@ impl<'a> Tokenizer<'a> {
 
             line: 1,
             column: 1,
+            start_line: 1,
+            start_column: 1,
+            file,
         }
     }
 
     pub fn tokenize(&mut self) -> Result<Vec<Token<'a>>, TokenizerError<'a>> {
         let mut tokens: Vec<Token<'a>> = Vec::new();
 
-        while let Some(_) = self.next() {
+        while self.next().is_some() {
             if self.character.is_whitespace() {
-                self.start = self.end;
+                self.begin();
                 continue;
             }
             if let Some(token) = self.get_token()? {
-                tokens.push(token);
-                self.start = self.end;
+                self.push(&mut tokens, token);
             }
+            self.begin();
         }
 
-        tokens.push(Token {
-            kind: TokenKind::Eof,
-            span: TokenSpan::new(self.end, self.end, "", self.line, self.column),
-        });
+        let eof: Token<'a> = Token { kind: TokenKind::Eof, span: self.span(self.end), trivia: "" };
+        self.push(&mut tokens, eof);
 
         Ok(tokens)
     }
 
+    // Unlike 'tokenize' it doesn't stop at the first error: the bad lexeme becomes
+    // a TokenKind::Error token and lexing continues after it
+    pub fn tokenize_recovering(&mut self) -> (Vec<Token<'a>>, Vec<TokenizerError<'a>>) {
+        let mut tokens: Vec<Token<'a>> = Vec::new();
+        let mut errors: Vec<TokenizerError<'a>> = Vec::new();
+
+        while self.next().is_some() {
+            if self.character.is_whitespace() {
+                self.begin();
+                continue;
+            }
+
+            match self.get_token() {
+                Ok(Some(token)) => self.push(&mut tokens, token),
+                Ok(None) => {},
+                Err(error) => {
+                    self.skip_bad_lexeme(error.code);
+                    let token: Token<'a> = Token { kind: TokenKind::Error, span: self.span(self.start), trivia: "" };
+                    self.push(&mut tokens, token);
+                    errors.push(error);
+                },
+            }
+            self.begin();
+        }
+
+        let eof: Token<'a> = Token { kind: TokenKind::Eof, span: self.span(self.end), trivia: "" };
+        self.push(&mut tokens, eof);
+
+        (tokens, errors)
+    }
+
+    // Attaches the whitespace and comments since the previous token
+    fn push(&self, tokens: &mut Vec<Token<'a>>, mut token: Token<'a>) {
+        let previous_end: usize = tokens.last().map_or(0, |previous| previous.span.end);
+        token.trivia = &self.source[previous_end..token.span.start];
+        tokens.push(token);
+    }
+
+    // Escape errors are found in the middle of a literal, the rest of it is skipped
+    fn skip_bad_lexeme(&mut self, code: ErrorCode) {
+        let delimiter: char = if self.is_parsing_string_literal { '\"' } else { '\'' };
+        self.is_parsing_string_literal = false;
+
+        if !matches!(code, ErrorCode::ET009 | ErrorCode::ET010 | ErrorCode::ET011) {
+            return;
+        }
+
+        while let Some(next) = self.peek() {
+            if next == '\n' {
+                break;
+            }
+
+            self.next();
+            if next == delimiter {
+                break;
+            }
+            if next == '\\' && self.peek().is_some_and(|escaped| escaped != '\n') {
+                self.next();
+            }
+        }
+    }
+
     fn get_token(&mut self) -> Result<Option<Token<'a>>, TokenizerError<'a>> {
         if self.character.is_ascii_digit() {
             self.parse_number()
         } 
-        else if self.character.is_ascii_alphanumeric() || self.character == '_' {
+        else if self.character.is_xid_start() || self.character == '_' {
             self.parse_text()
         }
         else {
@@ -327,6 +456,10 @@ impl<'a> Tokenizer<'a> {
                 break;
             }
             if next == '.' {
+                // '0..10' is a range, the number ends before it
+                if self.peek_second() == Some('.') {
+                    break;
+                }
                 dots += 1;
             }
             self.next().unwrap();
@@ -338,7 +471,7 @@ impl<'a> Tokenizer<'a> {
 
         let mut invalid_found = false;
         while let Some(next) = self.peek() {
-            if next.is_ascii_alphanumeric() || next == '_' {
+            if next.is_xid_continue() {
                 self.next().unwrap();
                 invalid_found = true;
             }
@@ -353,20 +486,21 @@ impl<'a> Tokenizer<'a> {
 
         let literal: &str = &self.source[self.start..self.end];
         if dots == 0 {
-            return self.token(TokenKind::IntegerLiteral);
+            self.token(TokenKind::IntegerLiteral)
         }
         else {
             if literal.ends_with('.') {
                 return self.error(ErrorCode::ET003);
             }
 
-            return self.token(TokenKind::FloatLiteral);
+            self.token(TokenKind::FloatLiteral)
         }
     }
 
+    // Identifiers follow Unicode XID, like Rust ones
     fn parse_text(&mut self) -> Result<Option<Token<'a>>, TokenizerError<'a>> {
         while let Some(next) = self.peek() {
-            if next.is_ascii_alphanumeric() || next == '_' {
+            if next.is_xid_continue() {
                 self.next().unwrap();
             }
             else {
@@ -377,10 +511,10 @@ impl<'a> Tokenizer<'a> {
         let literal: &str = &self.source[self.start..self.end];
 
         if let Some(&keyword) = self.keywords.get(literal) {
-            return self.token(keyword);
+            self.token(keyword)
         }
         else {
-            return self.token(TokenKind::Identifier);
+            self.token(TokenKind::Identifier)
         }
     }
     
@@ -468,6 +602,9 @@ impl<'a> Tokenizer<'a> {
                 if self.match_next('=') {
                     self.token(TokenKind::Equal)
                 }
+                else if self.match_next('>') {
+                    self.token(TokenKind::FatArrow)
+                }
                 else {
                     self.token(TokenKind::Assignment)
                 }
@@ -523,8 +660,24 @@ impl<'a> Tokenizer<'a> {
             '{' => self.token(TokenKind::LeftBrace),
             '}' => self.token(TokenKind::RightBrace),
             ',' => self.token(TokenKind::Comma),
-            '.' => self.token(TokenKind::Dot),
+            '.' => {
+                if self.match_next('.') {
+                    self.token(TokenKind::Range)
+                }
+                else {
+                    self.token(TokenKind::Dot)
+                }
+            },
             ';' => self.token(TokenKind::Semicolon),
+            // A single ':' has no meaning on its own
+            ':' => {
+                if self.match_next(':') {
+                    self.token(TokenKind::PathSeparator)
+                }
+                else {
+                    self.error(ErrorCode::ET004)
+                }
+            },
 
             '\'' => self.parse_char(),
             '\"' => self.parse_string(),
@@ -539,6 +692,11 @@ impl<'a> Tokenizer<'a> {
         let mut is_escaping_single_quote: bool = false;
 
         while let Some(next) = self.peek() {
+            // Literals can't span lines, an unterminated one ends with the line
+            if next == '\n' {
+                break;
+            }
+
             if next == '\''{
                 self.next().unwrap();
 
@@ -580,7 +738,7 @@ impl<'a> Tokenizer<'a> {
         }
 
         self.next().unwrap();
-        return Ok(false)
+        Ok(false)
     }
 
     fn parse_escape_sequence(&mut self) -> Result<bool, TokenizerError<'a>> {
@@ -626,7 +784,7 @@ impl<'a> Tokenizer<'a> {
                 break;
             }
 
-            if !self.is_ascii_octal(next).is_some() {
+            if self.is_ascii_octal(next).is_none() {
                 is_invalid = true;
             }
 
@@ -667,6 +825,10 @@ impl<'a> Tokenizer<'a> {
         self.is_parsing_string_literal = true;
 
         while let Some(next) = self.peek() {
+            if next == '\n' {
+                break;
+            }
+
             if next == '\"' {
                 self.next().unwrap();
                 break;
@@ -700,9 +862,17 @@ impl<'a> Tokenizer<'a> {
         Ok(None)
     }
 
+    // The next token starts after the consumed chars
+    fn begin(&mut self) {
+        self.start = self.end;
+        self.start_line = self.line;
+        self.start_column = self.column;
+    }
+
     fn next(&mut self) -> Option<char> {
         match self.input.next() {
-            Some(c) => {
+            Some((index, c)) => {
+                // Columns count chars, offsets count bytes
                 if c == '\n' {
                     self.line += 1;
                     self.column = 1;
@@ -712,7 +882,7 @@ impl<'a> Tokenizer<'a> {
                 }
 
                 self.character = c;
-                self.end += 1;
+                self.end = index + c.len_utf8();
                 Some(c)
             }
             None => None
@@ -720,35 +890,42 @@ impl<'a> Tokenizer<'a> {
     }
 
     fn peek(&mut self) -> Option<char> {
-        match self.input.peek() {
-            Some(c) => Some(*c),
-            None => None
-        }
+        self.input.peek().map(|&(_, c)| c)
+    }
+
+    // The char after the one returned by 'peek'
+    fn peek_second(&self) -> Option<char> {
+        self.input.clone().nth(1).map(|(_, c)| c)
     }
 
     fn token(&self, kind: TokenKind) -> Result<Option<Token<'a>>, TokenizerError<'a>> {
         Ok(Some(Token {
             kind,
-            span: TokenSpan::new(self.start, self.end, &self.source[self.start..self.end], self.line, self.column),
+            span: self.span(self.start),
+            trivia: "",
         }))
     }
 
+    fn span(&self, start: usize) -> TokenSpan<'a> {
+        TokenSpan::new(start, self.end, &self.source[start..self.end], self.start_line, self.start_column, self.file)
+    }
+
     fn error<T>(&self, code: ErrorCode) -> Result<T, TokenizerError<'a>> {
         Err(TokenizerError {
             code,
-            span: TokenSpan::new(self.start, self.end, &self.source[self.start..self.end], self.line, self.column),
-            line: self.line,
-            column: self.column,
+            span: self.span(self.start),
+            line: self.start_line,
+            column: self.start_column,
         })
     }
 
     fn match_next(&mut self, expected: char) -> bool {
         if self.peek() == Some(expected) {
             self.next();
-            return true
+            true
         } 
         else {
-            return false;
+            false
         }
     }
 }
\ No newline at end of file
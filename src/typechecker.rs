@@ -0,0 +1,1054 @@
+use core::fmt;
+use std::collections::{HashMap, HashSet};
+
+use crate::parser::{Accessor, Arm, Body, ElseBranch, Expression, Field, Iterable, Path, Pattern, RawExpression, RawStatement, Statement, StatementSpan, Type, Variant};
+use crate::semantics::{Scope, Symbol};
+use crate::tokenizer::TokenKind;
+use crate::error::*;
+use crate::source::SourceMap;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ValueType {
+    Primitive(TokenKind),
+    // The type of the elements and the length, when it's known
+    Array(Box<ValueType>, Option<usize>),
+    // A struct by its qualified name, like 'geo::Point'
+    Struct(String),
+    Enum(String),
+    // Literals without a suffix adapt to the type they are used with
+    IntegerLiteral,
+    FloatLiteral,
+    Void,
+    // Produced after an error was already reported, so it matches anything
+    // and doesn't cause a cascade of errors
+    Unknown,
+}
+
+impl ValueType {
+    // 'element' is what the type names, the type is an array of it when it has a size
+    pub fn from_type(type_: &Type, element: ValueType) -> ValueType {
+        if type_.is_array {
+            ValueType::Array(Box::new(element), type_.literal_length())
+        }
+        else {
+            element
+        }
+    }
+
+    // The type of a value declared outside of the checked code, where only the kind is known.
+    // Structs and enums are known by name only, so they match anything
+    pub fn from_kind(kind: TokenKind, is_array: bool) -> ValueType {
+        match (kind, is_array) {
+            (TokenKind::Identifier, _) => ValueType::Unknown,
+            (kind, true) => ValueType::Array(Box::new(ValueType::Primitive(kind)), None),
+            (kind, false) => ValueType::from_return_type(kind),
+        }
+    }
+
+    pub fn from_return_type(kind: TokenKind) -> ValueType {
+        match kind {
+            TokenKind::Null => ValueType::Void,
+            kind => ValueType::Primitive(kind),
+        }
+    }
+
+    pub fn is_integer(&self) -> bool {
+        use TokenKind::*;
+        match self {
+            ValueType::Primitive(kind) => matches!(kind,
+                SignedInt8 | SignedInt16 | SignedInt32 | SignedInt64 |
+                UnsignedInt8 | UnsignedInt16 | UnsignedInt32 | UnsignedInt64
+            ),
+            ValueType::IntegerLiteral | ValueType::Unknown => true,
+            _ => false,
+        }
+    }
+
+    pub fn is_float(&self) -> bool {
+        match self {
+            ValueType::Primitive(kind) => matches!(kind, TokenKind::Float32 | TokenKind::Float64),
+            ValueType::FloatLiteral | ValueType::Unknown => true,
+            _ => false,
+        }
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        self.is_integer() || self.is_float()
+    }
+
+    pub fn is_boolean(&self) -> bool {
+        matches!(self, ValueType::Primitive(TokenKind::Boolean) | ValueType::Unknown)
+    }
+
+    // Checks if a value of type 'value' can be stored where 'self' is expected
+    pub fn accepts(&self, value: &ValueType) -> bool {
+        match (self, value) {
+            (ValueType::Unknown, _) | (_, ValueType::Unknown) => true,
+            (target, ValueType::IntegerLiteral) => target.is_numeric(),
+            (target, ValueType::FloatLiteral) => target.is_float(),
+            (ValueType::Array(element, length), ValueType::Array(other_element, other_length)) => {
+                // Like single values, literal elements adapt and elements of an unknown type match anything.
+                // A size that isn't a literal is only known when the program runs, it can't fill a literal one
+                let elements: bool = element.accepts(other_element);
+                elements && (length.is_none() || length == other_length)
+            },
+            (target, value) => target == value,
+        }
+    }
+
+    // The common type of two operands, literals take the type of the other side
+    fn unify(&self, other: &ValueType) -> Option<ValueType> {
+        match (self, other) {
+            (ValueType::Unknown, _) | (_, ValueType::Unknown) => Some(ValueType::Unknown),
+            (ValueType::IntegerLiteral, ValueType::FloatLiteral) |
+            (ValueType::FloatLiteral, ValueType::IntegerLiteral) => Some(ValueType::FloatLiteral),
+            (left, right) if left.accepts(right) => Some(left.clone()),
+            (left, right) if right.accepts(left) => Some(right.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ValueType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Primitive(kind) => write!(f, "{}", kind),
+            Self::Array(element, Some(length)) => write!(f, "{}[{}]", element, length),
+            Self::Array(element, None) => write!(f, "{}[]", element),
+            Self::Struct(name) | Self::Enum(name) => write!(f, "{}", name),
+            Self::IntegerLiteral => write!(f, "integer"),
+            Self::FloatLiteral => write!(f, "float"),
+            Self::Void => write!(f, "void"),
+            Self::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct TypeError {
+    pub code: ErrorCode,
+    pub span: StatementSpan,
+    pub hint: String,
+}
+
+impl TypeError {
+    pub fn to_diagnostic<'a>(&'a self, sources: &'a SourceMap) -> Diagnostic<'a> {
+        Diagnostic {
+            kind: DiagnosticKind::Error(self.code),
+            info: DiagnosticInfo::from_span(sources, self.span.file, self.span.start, self.span.end),
+            hint: Some(&self.hint)
+        }
+    }
+}
+
+// The numeric kind each arithmetic expression computes in, by the span of the expression.
+// Values don't know how wide they are, the backends wrap every result to this kind
+pub type Kinds = HashMap<StatementSpan, TokenKind>;
+
+// The types of the parameters of a function and of its result
+pub type Signature = (Vec<ValueType>, ValueType);
+
+pub struct TypeChecker<'a> {
+    ast: &'a [Statement<'a>],
+    scope: Scope<'a>,
+    errors: Vec<TypeError>,
+
+    // Return type of the function being checked, None at the top level
+    return_type: Option<ValueType>,
+    // By qualified name, with the namespaces the types of their fields and parameters are written in
+    structs: HashMap<String, (&'a [Field<'a>], Vec<&'a str>)>,
+    enums: HashMap<String, (&'a [Variant<'a>], Vec<&'a str>)>,
+    functions: HashMap<String, Vec<&'a str>>,
+    // Types of the parameters and of the result of the natives declared with them
+    signatures: HashMap<String, Signature>,
+    kinds: Kinds,
+}
+
+impl<'a> TypeChecker<'a> {
+    pub fn new(ast: &'a [Statement<'a>]) -> TypeChecker<'a> {
+        TypeChecker {
+            ast,
+            scope: Scope::new(),
+            errors: Vec::new(),
+            return_type: None,
+            structs: HashMap::new(),
+            enums: HashMap::new(),
+            functions: HashMap::new(),
+            signatures: HashMap::new(),
+            kinds: Kinds::new(),
+        }
+    }
+
+    pub fn declare_native(&mut self, name: &'a str) {
+        self.scope.declare(name, Symbol::Native);
+    }
+
+    // A native whose calls are checked like the ones of a function
+    pub fn declare_signature(&mut self, name: &'a str, signature: Signature) {
+        self.scope.declare(name, Symbol::Native);
+        self.signatures.insert(name.to_string(), signature);
+    }
+
+    pub fn declare_global(&mut self, name: &'a str, kind: TokenKind, is_array: bool) {
+        self.scope.declare(name, Symbol::Global(kind, is_array));
+    }
+
+    pub fn declare_type(&mut self, name: &'a str) {
+        self.scope.declare(name, Symbol::Type);
+    }
+
+    pub fn declare_enum(&mut self, name: &'a str, variants: impl IntoIterator<Item = &'a str>) {
+        self.scope.declare(name, Symbol::Type);
+        self.scope.declare_variants(name, variants);
+    }
+
+    pub fn check(&mut self) -> Result<(), Vec<TypeError>> {
+        self.check_statements(self.ast);
+
+        if self.errors.is_empty() {
+            Ok(())
+        }
+        else {
+            Err(std::mem::take(&mut self.errors))
+        }
+    }
+
+    // Checks an expression that isn't part of the AST, like the ones typed in the REPL
+    pub fn check_standalone(&mut self, expression: &'a Expression<'a>) -> Result<ValueType, Vec<TypeError>> {
+        let type_: ValueType = self.type_of(expression);
+
+        if self.errors.is_empty() {
+            Ok(type_)
+        }
+        else {
+            Err(std::mem::take(&mut self.errors))
+        }
+    }
+
+    // The kinds of the arithmetic expressions checked so far, for the backends
+    pub fn into_kinds(self) -> Kinds {
+        self.kinds
+    }
+
+    fn check_statements(&mut self, statements: &'a [Statement<'a>]) {
+        self.hoist(statements);
+
+        for statement in statements {
+            self.check_statement(statement);
+        }
+    }
+
+    // Same hoisting rule as the SemanticAnalyzer
+    fn hoist(&mut self, statements: &'a [Statement<'a>]) {
+        for statement in statements {
+            match &statement.node {
+                RawStatement::Function { name, .. } => {
+                    self.functions.insert(self.qualified(name), self.scope.namespaces().to_vec());
+                    self.scope.declare(name, Symbol::Statement(statement));
+                },
+
+                RawStatement::Struct { name, fields } => {
+                    self.structs.insert(self.qualified(name), (fields, self.scope.namespaces().to_vec()));
+                    self.scope.declare(name, Symbol::Statement(statement));
+                },
+
+                RawStatement::Enum { name, variants } => {
+                    self.enums.insert(self.qualified(name), (variants, self.scope.namespaces().to_vec()));
+                    self.scope.declare(name, Symbol::Statement(statement));
+                    self.scope.declare_variants(name, variants.iter().map(|variant| variant.name));
+                },
+
+                RawStatement::Namespace { name, body } if self.scope.depth() == 0 => {
+                    self.scope.enter_namespace(name);
+                    self.hoist(&body.statements);
+                    self.scope.exit_namespace();
+                },
+
+                _ => {},
+            }
+        }
+    }
+
+    fn check_body(&mut self, body: &'a Body<'a>) {
+        self.scope.enter();
+        self.check_statements(&body.statements);
+        self.scope.exit();
+    }
+
+    fn check_statement(&mut self, statement: &'a Statement<'a>) {
+        match &statement.node {
+            RawStatement::VariableDeclaration { type_, name, value, .. } => {
+                self.check_array_length(type_);
+                let expected: ValueType = self.resolve_type(type_, self.scope.namespaces());
+
+                if let Some(value) = value {
+                    let found: ValueType = self.type_of(value);
+                    self.expect(ErrorCode::EC001, &expected, &found, value.span);
+                    self.check_literal(&expected, value);
+                }
+
+                // The struct is resolved here, the name can be used from another namespace
+                self.scope.declare(name, Symbol::Inferred(expected));
+            },
+
+            RawStatement::VariableAssignment { target, operator, value } => {
+                let mut expected: ValueType = self.lookup(&Path::from(target.name));
+                for accessor in &target.accessors {
+                    expected = match accessor {
+                        Accessor::Field(name) => self.field_type(&expected, name, statement.span),
+                        Accessor::Index(index) => self.index_type(expected, index, statement.span),
+                    };
+                }
+                let found: ValueType = self.type_of(value);
+                self.check_literal(&expected, value);
+
+                if *operator == TokenKind::Assignment {
+                    self.expect(ErrorCode::EC002, &expected, &found, value.span);
+                }
+                else {
+                    let operator: TokenKind = operator.compound_operator();
+                    let result: ValueType = self.binary_type(&expected, operator, &found, statement.span);
+                    self.expect(ErrorCode::EC002, &expected, &result, value.span);
+                }
+            },
+
+            RawStatement::If { condition, body, elses } => {
+                self.check_condition(condition);
+                self.check_body(body);
+
+                for branch in elses {
+                    match branch {
+                        ElseBranch::ElseIf(statement) => self.check_statement(statement),
+                        ElseBranch::Else(body) => self.check_body(body),
+                    }
+                }
+            },
+
+            RawStatement::While { condition, body } => {
+                self.check_condition(condition);
+                self.check_body(body);
+            },
+
+            RawStatement::For { type_, name, iterable, body } => {
+                let (element, span): (ValueType, StatementSpan) = self.element_type(iterable);
+
+                let variable: ValueType = match type_ {
+                    Some(kind) => {
+                        let declared: ValueType = ValueType::Primitive(*kind);
+                        self.expect(ErrorCode::EC014, &declared, &element, span);
+                        if let Iterable::Range { start, end } = iterable {
+                            self.check_literal(&declared, start);
+                            self.check_literal(&declared, end);
+                        }
+                        declared
+                    },
+                    None => element,
+                };
+
+                self.scope.enter();
+                self.scope.declare(name, Symbol::Inferred(variable));
+                self.check_statements(&body.statements);
+                self.scope.exit();
+            },
+
+            RawStatement::LoopControl(_) | RawStatement::Import(_) => {},
+
+            RawStatement::Function { parameters, type_, body, .. } => {
+                self.scope.enter();
+                for parameter in parameters {
+                    self.check_array_length(&parameter.type_);
+
+                    // The size is only known when the function is declared, not on each call
+                    if parameter.type_.is_array && parameter.type_.literal_length().is_none() {
+                        self.error(ErrorCode::EC021, parameter.span,
+                            format!("the size of '{}' must be an integer literal, like {}[4]", parameter.name, parameter.type_.kind));
+                    }
+                    let type_: ValueType = self.resolve_type(&parameter.type_, self.scope.namespaces());
+                    self.scope.declare(parameter.name, Symbol::Inferred(type_));
+                }
+
+                let outer: Option<ValueType> = self.return_type.replace(ValueType::from_return_type(*type_));
+                self.check_statements(&body.statements);
+                self.return_type = outer;
+
+                self.scope.exit();
+            },
+
+            RawStatement::Return(value) => {
+                let found: ValueType = match value {
+                    Some(value) => self.type_of(value),
+                    None => ValueType::Void,
+                };
+
+                if let Some(expected) = self.return_type.clone() {
+                    let matches: bool = match (&expected, &found) {
+                        (ValueType::Void, ValueType::Void) => true,
+                        (ValueType::Void, _) | (_, ValueType::Void) => false,
+                        (expected, found) => expected.accepts(found),
+                    };
+
+                    if !matches {
+                        self.error(ErrorCode::EC008, statement.span,
+                            format!("expected '{}', found '{}'", expected, found));
+                    }
+                    if let Some(value) = value {
+                        self.check_literal(&expected, value);
+                    }
+                }
+            },
+
+            RawStatement::FunctionCall { name, arguments } => {
+                self.call_type(name, arguments, statement.span);
+            },
+
+            RawStatement::Namespace { name, body } => {
+                self.scope.enter_namespace(name);
+                for statement in &body.statements {
+                    self.check_statement(statement);
+                }
+                self.scope.exit_namespace();
+            },
+
+            RawStatement::Struct { name, fields } => {
+                for field in fields {
+                    self.check_array_length(&field.type_);
+
+                    // Every value of the struct has the same size
+                    if field.type_.is_array && field.type_.literal_length().is_none() {
+                        self.error(ErrorCode::EC021, field.span,
+                            format!("the size of '{}' must be an integer literal, like {}[4]", field.name, field.type_.kind));
+                    }
+                }
+
+                self.check_recursion(name, statement.span);
+            },
+
+            RawStatement::Enum { name, variants } => {
+                for variant in variants {
+                    for type_ in &variant.payload {
+                        self.check_array_length(type_);
+
+                        // Like fields, every value of the variant has the same size
+                        if type_.is_array && type_.literal_length().is_none() {
+                            self.error(ErrorCode::EC021, variant.span,
+                                format!("the size of the values of '{}' must be an integer literal, like {}[4]", variant.name, type_.kind));
+                        }
+                    }
+                }
+
+                self.check_recursion(name, statement.span);
+            },
+
+            RawStatement::Match { value, arms } => {
+                let matched: ValueType = self.type_of(value);
+                let mut coverage: Coverage = Coverage::default();
+
+                // Like the variable of a 'for' loop, the bindings live in the same scope as the body
+                for arm in arms {
+                    self.scope.enter();
+                    self.check_pattern(&matched, arm, &mut coverage);
+                    self.check_statements(&arm.value.statements);
+                    self.scope.exit();
+                }
+
+                self.check_exhaustive(&matched, &coverage, statement.span);
+            },
+        }
+    }
+
+    fn check_recursion(&mut self, name: &'a str, span: StatementSpan) {
+        let name: String = self.qualified(name);
+        if self.contains(&name, &name, &mut HashSet::new()) {
+            self.error(ErrorCode::EC020, span,
+                format!("a value of '{}' would have to hold another '{}' forever", name, name));
+        }
+    }
+
+    // Computes the type of an expression, reporting every mismatch found inside it
+    pub fn type_of(&mut self, expression: &'a Expression<'a>) -> ValueType {
+        match &expression.node {
+            RawExpression::Variable(name) if matches!(self.scope.resolve(name), Some(Symbol::Variant)) => {
+                self.variant_type(name, &[], expression.span)
+            },
+            RawExpression::Variable(name) => self.lookup(name),
+
+            RawExpression::Literal { kind, .. } => match kind {
+                TokenKind::IntegerLiteral => ValueType::IntegerLiteral,
+                TokenKind::FloatLiteral => ValueType::FloatLiteral,
+                TokenKind::CharLiteral => ValueType::Primitive(TokenKind::Character),
+                TokenKind::StringLiteral => ValueType::Primitive(TokenKind::String),
+                TokenKind::True | TokenKind::False => ValueType::Primitive(TokenKind::Boolean),
+                _ => ValueType::Unknown,
+            },
+
+            RawExpression::Binary { left, operator, right } => {
+                let left_type: ValueType = self.type_of(left);
+                let right_type: ValueType = self.type_of(right);
+                let type_: ValueType = self.binary_type(&left_type, *operator, &right_type, expression.span);
+                self.record_kind(&type_, expression.span);
+
+                // A literal operand takes the type of the other one, except the amount of a shift
+                if !matches!(operator, TokenKind::BitwiseLShift | TokenKind::BitwiseRShift)
+                    && let Some(common) = left_type.unify(&right_type) {
+                    self.check_literal(&common, left);
+                    self.check_literal(&common, right);
+                }
+                type_
+            },
+
+            RawExpression::Unary { operator, operand } => {
+                let operand: ValueType = self.type_of(operand);
+                let valid: bool = match operator {
+                    TokenKind::Minus => operand.is_numeric(),
+                    TokenKind::Not => operand.is_boolean(),
+                    _ => false,
+                };
+
+                if valid {
+                    self.record_kind(&operand, expression.span);
+                    operand
+                }
+                else {
+                    self.error(ErrorCode::EC007, expression.span,
+                        format!("'{}' cannot be applied to '{}'", operator, operand));
+                    ValueType::Unknown
+                }
+            },
+
+            RawExpression::FunctionCall { name, arguments } => {
+                let type_: ValueType = self.call_type(name, arguments, expression.span);
+                if type_ == ValueType::Void {
+                    self.error(ErrorCode::EC012, expression.span,
+                        format!("'{}' doesn't return a value", name));
+                    return ValueType::Unknown;
+                }
+                type_
+            },
+
+            RawExpression::ArrayAccess { array, index } => {
+                let array: ValueType = self.type_of(array);
+                self.index_type(array, index, expression.span)
+            },
+
+            RawExpression::StructLiteral { name, fields } => self.literal_type(name, fields, expression.span),
+
+            RawExpression::Field { object, name } => {
+                let object: ValueType = self.type_of(object);
+                self.field_type(&object, name, expression.span)
+            },
+
+            RawExpression::Match { value, arms } => {
+                let matched: ValueType = self.type_of(value);
+                let mut coverage: Coverage = Coverage::default();
+
+                // Every arm must give a value of the same type
+                let mut result: Option<ValueType> = None;
+                for arm in arms {
+                    self.scope.enter();
+                    self.check_pattern(&matched, arm, &mut coverage);
+                    let found: ValueType = self.type_of(&arm.value);
+                    self.scope.exit();
+
+                    result = Some(match result {
+                        None => found,
+                        Some(type_) => type_.unify(&found).unwrap_or_else(|| {
+                            self.error(ErrorCode::EC027, arm.value.span,
+                                format!("the arms above give '{}', this one gives '{}'", type_, found));
+                            type_
+                        }),
+                    });
+                }
+
+                self.check_exhaustive(&matched, &coverage, expression.span);
+                result.unwrap_or(ValueType::Unknown)
+            },
+
+            RawExpression::ArrayLiteral(elements) => {
+                // Every element must be of the same type, an empty array holds anything
+                let mut result: Option<ValueType> = None;
+                for element in elements {
+                    let found: ValueType = self.type_of(element);
+
+                    result = Some(match result {
+                        None => found,
+                        Some(type_) => type_.unify(&found).unwrap_or_else(|| {
+                            self.error(ErrorCode::EC028, element.span,
+                                format!("the elements before give '{}', this one is '{}'", type_, found));
+                            type_
+                        }),
+                    });
+                }
+
+                ValueType::Array(Box::new(result.unwrap_or(ValueType::Unknown)), Some(elements.len()))
+            },
+        }
+    }
+
+    fn record_kind(&mut self, type_: &ValueType, span: StatementSpan) {
+        if let ValueType::Primitive(kind) = type_
+            && type_.is_numeric() {
+            self.kinds.insert(span, *kind);
+        }
+    }
+
+    // The type of an element of 'array', read or assigned through an integer index
+    fn index_type(&mut self, array: ValueType, index: &'a Expression<'a>, span: StatementSpan) -> ValueType {
+        let index_type: ValueType = self.type_of(index);
+
+        if !index_type.is_integer() {
+            self.error(ErrorCode::EC010, index.span,
+                format!("expected an integer, found '{}'", index_type));
+        }
+
+        match array {
+            ValueType::Array(element, _) => *element,
+            ValueType::Unknown => ValueType::Unknown,
+            other => {
+                self.error(ErrorCode::EC009, span,
+                    format!("'{}' is not an array", other));
+                ValueType::Unknown
+            }
+        }
+    }
+
+    // A variant written alone like 'State::Running', or with the values it holds like 'State::Stopped(3)'
+    fn variant_type(&mut self, path: &Path<'a>, arguments: &'a [Expression<'a>], span: StatementSpan) -> ValueType {
+        let found: Vec<(ValueType, StatementSpan)> = arguments.iter()
+            .map(|argument| (self.type_of(argument), argument.span))
+            .collect();
+
+        // Enums declared by an earlier script can't be checked
+        let Some((name, variant, namespaces)) = self.variant(path) else {
+            return ValueType::Unknown;
+        };
+
+        if variant.payload.len() != found.len() {
+            self.error(ErrorCode::EC022, span,
+                format!("'{}' holds {} value(s) but {} were given", path, variant.payload.len(), found.len()));
+        }
+        else {
+            for ((type_, (value, value_span)), argument) in variant.payload.iter().zip(found).zip(arguments) {
+                let expected: ValueType = self.resolve_type(type_, &namespaces);
+                self.expect(ErrorCode::EC023, &expected, &value, value_span);
+                self.check_literal(&expected, argument);
+            }
+        }
+
+        ValueType::Enum(name)
+    }
+
+    // Checks the pattern of an arm against the type of the matched value and declares the names it binds
+    fn check_pattern<T>(&mut self, matched: &ValueType, arm: &'a Arm<'a, T>, coverage: &mut Coverage<'a>) {
+        let reachable: bool = self.unmatched(matched, coverage).is_some();
+        if !reachable {
+            self.error(ErrorCode::EC026, arm.span, "every value is already matched by the arms above".to_string());
+        }
+
+        match &arm.pattern {
+            Pattern::Wildcard => coverage.wildcard = true,
+
+            Pattern::Literal(literal) => {
+                let found: ValueType = self.type_of(literal);
+                if !matched.accepts(&found) {
+                    self.error(ErrorCode::EC024, arm.span,
+                        format!("the matched value is '{}', the pattern is '{}'", matched, found));
+                }
+                else if !coverage.literals.insert(literal_key(literal)) && reachable {
+                    self.error(ErrorCode::EC026, arm.span, "the same value is already matched by an arm above".to_string());
+                }
+                else {
+                    // A value the matched type can't hold never matches
+                    self.check_literal(matched, literal);
+                }
+            },
+
+            Pattern::Variant { path, bindings } => {
+                let mut values: Vec<ValueType> = Vec::new();
+
+                if let Some((name, variant, namespaces)) = self.variant(path) {
+                    if !matches!(matched, ValueType::Enum(enum_) if *enum_ == name) && *matched != ValueType::Unknown {
+                        self.error(ErrorCode::EC024, arm.span,
+                            format!("the matched value is '{}', the pattern is a variant of '{}'", matched, name));
+                    }
+                    else if !coverage.variants.insert(variant.name) && reachable {
+                        self.error(ErrorCode::EC026, arm.span, format!("'{}' is already matched by an arm above", path));
+                    }
+
+                    // Without bindings the values aren't looked at
+                    if !bindings.is_empty() && bindings.len() != variant.payload.len() {
+                        self.error(ErrorCode::EC022, arm.span,
+                            format!("'{}' holds {} value(s) but {} name(s) were given", path, variant.payload.len(), bindings.len()));
+                    }
+                    values = variant.payload.iter().map(|type_| self.resolve_type(type_, &namespaces)).collect();
+                }
+
+                for (index, binding) in bindings.iter().enumerate() {
+                    if *binding != "_" {
+                        let type_: ValueType = values.get(index).cloned().unwrap_or(ValueType::Unknown);
+                        self.scope.declare(binding, Symbol::Inferred(type_));
+                    }
+                }
+            },
+        }
+    }
+
+    fn check_exhaustive(&mut self, matched: &ValueType, coverage: &Coverage, span: StatementSpan) {
+        // The values of an unknown type can't be listed
+        if *matched == ValueType::Unknown {
+            return;
+        }
+
+        if let Some(hint) = self.unmatched(matched, coverage) {
+            self.error(ErrorCode::EC025, span, hint);
+        }
+    }
+
+    // Describes the values the arms so far don't match, None once every value is matched
+    fn unmatched(&self, matched: &ValueType, coverage: &Coverage) -> Option<String> {
+        if coverage.wildcard {
+            return None;
+        }
+
+        match matched {
+            ValueType::Enum(name) => {
+                let (variants, _) = self.enums.get(name)?;
+                let missing: Vec<String> = variants.iter()
+                    .filter(|variant| !coverage.variants.contains(variant.name))
+                    .map(|variant| format!("'{}::{}'", name, variant.name))
+                    .collect();
+
+                (!missing.is_empty()).then(|| format!("add an arm for {}, or a '_' arm", missing.join(", ")))
+            },
+            ValueType::Primitive(TokenKind::Boolean) if coverage.literals.contains("true") && coverage.literals.contains("false") => None,
+            _ => Some("add a '_' arm for the values the other arms don't match".to_string()),
+        }
+    }
+
+    fn literal_type(&mut self, name: &Path<'a>, values: &'a [(&'a str, Expression<'a>)], span: StatementSpan) -> ValueType {
+        let found: Vec<(&str, ValueType, StatementSpan)> = values.iter()
+            .map(|(field, value)| (*field, self.type_of(value), value.span))
+            .collect();
+
+        // Structs declared by an earlier script can't be checked
+        let Some(qualified) = self.find_type(name, self.scope.namespaces()) else {
+            return ValueType::Unknown;
+        };
+        let Some((fields, namespaces)) = self.structs.get(&qualified).cloned() else {
+            return ValueType::Unknown;
+        };
+
+        for (index, (field, value, value_span)) in found.iter().enumerate() {
+            if found[..index].iter().any(|(other, ..)| other == field) {
+                self.error(ErrorCode::EC018, *value_span, format!("'{}' is already set", field));
+                continue;
+            }
+
+            match fields.iter().find(|declared| declared.name == *field) {
+                Some(declared) => {
+                    let expected: ValueType = self.resolve_type(&declared.type_, &namespaces);
+                    self.expect(ErrorCode::EC019, &expected, value, *value_span);
+                    self.check_literal(&expected, &values[index].1);
+                },
+                None => self.error(ErrorCode::EC015, *value_span, format!("'{}' has no field '{}'", qualified, field)),
+            }
+        }
+
+        let missing: Vec<String> = fields.iter()
+            .filter(|declared| !found.iter().any(|(field, ..)| *field == declared.name))
+            .map(|declared| format!("'{}'", declared.name))
+            .collect();
+        if !missing.is_empty() {
+            self.error(ErrorCode::EC017, span, format!("set {} too, every field needs a value", missing.join(", ")));
+        }
+
+        ValueType::Struct(qualified)
+    }
+
+    // The type of the field 'name' of a value of type 'object'
+    fn field_type(&mut self, object: &ValueType, name: &str, span: StatementSpan) -> ValueType {
+        let struct_ = match object {
+            ValueType::Struct(struct_) => struct_,
+            ValueType::Unknown => return ValueType::Unknown,
+            other => {
+                self.error(ErrorCode::EC016, span, format!("'{}' is not a struct, it has no field '{}'", other, name));
+                return ValueType::Unknown;
+            },
+        };
+
+        let Some((fields, namespaces)) = self.structs.get(struct_) else {
+            return ValueType::Unknown;
+        };
+        match fields.iter().find(|field| field.name == name) {
+            Some(field) => self.resolve_type(&field.type_, namespaces),
+            None => {
+                self.error(ErrorCode::EC015, span, format!("'{}' has no field '{}'", struct_, name));
+                ValueType::Unknown
+            },
+        }
+    }
+
+    fn binary_type(&mut self, left: &ValueType, operator: TokenKind, right: &ValueType, span: StatementSpan) -> ValueType {
+        use TokenKind::*;
+
+        let common: Option<ValueType> = left.unify(right);
+        let result: Option<ValueType> = match operator {
+            Plus => common.filter(|type_| type_.is_numeric() || *type_ == ValueType::Primitive(String)),
+            Minus | Multiplication | Division | Modulus => common.filter(ValueType::is_numeric),
+
+            BitwiseAnd | BitwiseOr | BitwiseXor => common.filter(ValueType::is_integer),
+            BitwiseLShift | BitwiseRShift => {
+                if left.is_integer() && right.is_integer() { Some(left.clone()) } else { None }
+            },
+
+            Equal | NotEqual => common.filter(|type_| !matches!(type_, ValueType::Array(..) | ValueType::Void))
+                .map(|_| ValueType::Primitive(Boolean)),
+            LessThan | GreaterThan | LessThanOrEqual | GreaterThanOrEqual => common
+                .filter(|type_| type_.is_numeric() || *type_ == ValueType::Primitive(Character))
+                .map(|_| ValueType::Primitive(Boolean)),
+
+            And | Or => {
+                if left.is_boolean() && right.is_boolean() { Some(ValueType::Primitive(Boolean)) } else { None }
+            },
+
+            _ => None,
+        };
+
+        match result {
+            Some(type_) => type_,
+            None => {
+                self.error(ErrorCode::EC006, span,
+                    format!("'{}' cannot be applied to '{}' and '{}'", operator, left, right));
+                ValueType::Unknown
+            }
+        }
+    }
+
+    fn call_type(&mut self, name: &Path<'a>, arguments: &'a [Expression<'a>], span: StatementSpan) -> ValueType {
+        if matches!(self.scope.resolve(name), Some(Symbol::Variant)) {
+            return self.variant_type(name, arguments, span);
+        }
+
+        let found: Vec<(ValueType, StatementSpan)> = arguments.iter()
+            .map(|argument| (self.type_of(argument), argument.span))
+            .collect();
+
+        let (parameters, returns): Signature = match self.scope.resolve(name) {
+            Some(Symbol::Statement(statement)) => match &statement.node {
+                RawStatement::Function { parameters, type_, .. } => {
+                    // The types of the parameters are written in the namespace of the function
+                    let namespaces: Vec<&'a str> = name.candidates(self.scope.namespaces())
+                        .find_map(|candidate| self.functions.get(candidate.as_ref()))
+                        .cloned()
+                        .unwrap_or_default();
+                    let parameters: Vec<ValueType> = parameters.iter()
+                        .map(|parameter| self.resolve_type(&parameter.type_, &namespaces))
+                        .collect();
+                    (parameters, ValueType::from_return_type(*type_))
+                },
+                _ => return ValueType::Unknown,
+            },
+            // Natives declared without their types take any value
+            Some(Symbol::Native) => match name.candidates(self.scope.namespaces()).find_map(|candidate| self.signatures.get(candidate.as_ref())) {
+                Some(signature) => signature.clone(),
+                None => return ValueType::Unknown,
+            },
+            _ => return ValueType::Unknown,
+        };
+
+        if parameters.len() != found.len() {
+            let hint: String = format!("'{}' takes {} argument(s) but {} were given", name, parameters.len(), found.len());
+            self.error(ErrorCode::EC005, span, hint);
+        }
+        else {
+            for ((expected, (argument, argument_span)), expression) in parameters.iter().zip(found).zip(arguments) {
+                self.expect(ErrorCode::EC004, expected, &argument, argument_span);
+                self.check_literal(expected, expression);
+            }
+        }
+
+        returns
+    }
+
+    fn check_condition(&mut self, condition: &'a Expression<'a>) {
+        let found: ValueType = self.type_of(condition);
+        if !found.is_boolean() {
+            self.error(ErrorCode::EC003, condition.span,
+                format!("expected 'bool', found '{}'", found));
+        }
+    }
+
+    // The type of the values a 'for' loop goes through and the span of what produces them
+    fn element_type(&mut self, iterable: &'a Iterable<'a>) -> (ValueType, StatementSpan) {
+        match iterable {
+            Iterable::Range { start: start_expression, end: end_expression } => {
+                let span: StatementSpan = StatementSpan { start: start_expression.span.start, end: end_expression.span.end, file: start_expression.span.file };
+                let (start, end) = (self.type_of(start_expression), self.type_of(end_expression));
+
+                match start.unify(&end) {
+                    Some(type_) if type_.is_integer() => {
+                    self.check_literal(&type_, start_expression);
+                    self.check_literal(&type_, end_expression);
+                    (type_, span)
+                },
+                    _ => {
+                        self.error(ErrorCode::EC013, span,
+                            format!("the range goes from '{}' to '{}', both ends must be integers of the same type", start, end));
+                        (ValueType::Unknown, span)
+                    },
+                }
+            },
+
+            Iterable::Array(array) => match self.type_of(array) {
+                ValueType::Array(element, _) => (*element, array.span),
+                ValueType::Unknown => (ValueType::Unknown, array.span),
+                other => {
+                    self.error(ErrorCode::EC013, array.span,
+                        format!("'{}' is not an array, use a range like 0..10 to count", other));
+                    (ValueType::Unknown, array.span)
+                },
+            },
+        }
+    }
+
+    fn check_array_length(&mut self, type_: &'a Type<'a>) {
+        if let Some(length) = &type_.array_length {
+            let found: ValueType = self.type_of(length);
+            if !found.is_integer() {
+                self.error(ErrorCode::EC011, length.span,
+                    format!("expected an integer, found '{}'", found));
+            }
+        }
+    }
+
+    // Variables and parameters are declared with their resolved type
+    fn lookup(&mut self, path: &Path<'a>) -> ValueType {
+        match self.scope.resolve(path) {
+            Some(Symbol::Global(kind, is_array)) => ValueType::from_kind(*kind, *is_array),
+            Some(Symbol::Inferred(type_)) => type_.clone(),
+            // Unresolved names are reported by the SemanticAnalyzer
+            Some(Symbol::Statement(_) | Symbol::Parameter(_) | Symbol::Native | Symbol::Namespace |
+                 Symbol::Type | Symbol::Variant | Symbol::Binding) | None => ValueType::Unknown,
+        }
+    }
+
+    // The type a declaration names, structs and enums are looked up from 'namespaces'
+    fn resolve_type(&self, type_: &Type<'a>, namespaces: &[&'a str]) -> ValueType {
+        let element: ValueType = match type_.name.as_ref().map(|name| self.find_type(name, namespaces)) {
+            Some(Some(name)) if self.enums.contains_key(&name) => ValueType::Enum(name),
+            Some(Some(name)) => ValueType::Struct(name),
+            Some(None) => ValueType::Unknown,
+            None => ValueType::Primitive(type_.kind),
+        };
+        ValueType::from_type(type_, element)
+    }
+
+    // The qualified name of the struct or the enum a path refers to, when it's declared in the checked code
+    fn find_type(&self, path: &Path<'a>, namespaces: &[&'a str]) -> Option<String> {
+        path.candidates(namespaces)
+            .find(|candidate| self.structs.contains_key(candidate.as_ref()) || self.enums.contains_key(candidate.as_ref()))
+            .map(|candidate| candidate.into_owned())
+    }
+
+    // The enum and the declaration of the variant a path refers to, with the namespaces its values are written in
+    fn variant(&self, path: &Path<'a>) -> Option<(String, &'a Variant<'a>, Vec<&'a str>)> {
+        let name: String = self.find_type(&path.parent()?, self.scope.namespaces())?;
+        let (variants, namespaces) = self.enums.get(&name)?;
+        let variants: &'a [Variant<'a>] = variants;
+
+        let variant: &'a Variant<'a> = variants.iter().find(|variant| variant.name == path.name)?;
+        Some((name, variant, namespaces.clone()))
+    }
+
+    // Whether a value of 'name' holds a 'target', through its fields or the values of its variants
+    fn contains(&self, name: &str, target: &str, visited: &mut HashSet<String>) -> bool {
+        let (types, namespaces): (Vec<&'a Type<'a>>, &[&'a str]) = match (self.structs.get(name), self.enums.get(name)) {
+            (Some((fields, namespaces)), _) => (fields.iter().map(|field| &field.type_).collect(), namespaces),
+            (None, Some((variants, namespaces))) => (variants.iter().flat_map(|variant| &variant.payload).collect(), namespaces),
+            (None, None) => return false,
+        };
+
+        types.into_iter().any(|type_| match type_.name.as_ref().and_then(|path| self.find_type(path, namespaces)) {
+            Some(inner) if inner == target => true,
+            Some(inner) => visited.insert(inner.clone()) && self.contains(&inner, target, visited),
+            None => false,
+        })
+    }
+
+    // The name a function or a struct declared here is stored with
+    fn qualified(&self, name: &'a str) -> String {
+        Path::from(name).qualified(self.scope.namespaces()).into_owned()
+    }
+
+    // Integer literals take the type they are used as, they must fit in it
+    fn check_literal(&mut self, expected: &ValueType, value: &'a Expression<'a>) {
+        match (expected, &value.node) {
+            (ValueType::Array(element, _), RawExpression::ArrayLiteral(elements)) => {
+                for element_value in elements {
+                    self.check_literal(element, element_value);
+                }
+            },
+            (ValueType::Primitive(kind), _) => {
+                let (Some((min, max)), Some(literal)) = (integer_range(*kind), integer_literal(value)) else {
+                    return;
+                };
+                if literal < min || literal > max {
+                    self.error(ErrorCode::EC029, value.span,
+                        format!("'{}' holds values from {} to {}, {} doesn't fit", kind, min, max, literal_key(value)));
+                }
+            },
+            _ => {},
+        }
+    }
+
+    fn expect(&mut self, code: ErrorCode, expected: &ValueType, found: &ValueType, span: StatementSpan) {
+        if !expected.accepts(found) {
+            self.error(code, span, format!("expected '{}', found '{}'", expected, found));
+        }
+    }
+
+    fn error(&mut self, code: ErrorCode, span: StatementSpan, hint: String) {
+        self.errors.push(TypeError { code, span, hint });
+    }
+}
+
+// What the arms of a match checked so far match
+#[derive(Default)]
+struct Coverage<'a> {
+    wildcard: bool,
+    variants: HashSet<&'a str>,
+    // Literals as written, a negative number with its '-'
+    literals: HashSet<String>,
+}
+
+// The smallest and the largest value of an integer kind
+fn integer_range(kind: TokenKind) -> Option<(i128, i128)> {
+    use TokenKind::*;
+    match kind {
+        SignedInt8 => Some((i8::MIN as i128, i8::MAX as i128)),
+        SignedInt16 => Some((i16::MIN as i128, i16::MAX as i128)),
+        SignedInt32 => Some((i32::MIN as i128, i32::MAX as i128)),
+        SignedInt64 => Some((i64::MIN as i128, i64::MAX as i128)),
+        UnsignedInt8 => Some((0, u8::MAX as i128)),
+        UnsignedInt16 => Some((0, u16::MAX as i128)),
+        UnsignedInt32 => Some((0, u32::MAX as i128)),
+        UnsignedInt64 => Some((0, u64::MAX as i128)),
+        _ => None,
+    }
+}
+
+// The value of an integer literal, negated ones included. Literals too large for any type give i128::MAX
+fn integer_literal(expression: &Expression) -> Option<i128> {
+    match &expression.node {
+        RawExpression::Literal { kind: TokenKind::IntegerLiteral, value } => Some(value.parse().unwrap_or(i128::MAX)),
+        RawExpression::Unary { operator: TokenKind::Minus, operand } => integer_literal(operand).map(|value| -value),
+        _ => None,
+    }
+}
+
+fn literal_key(literal: &Expression) -> String {
+    match &literal.node {
+        RawExpression::Literal { value, .. } => value.to_string(),
+        RawExpression::Unary { operand, .. } => format!("-{}", literal_key(operand)),
+        _ => String::new(),
+    }
+}
@@ -0,0 +1,469 @@
+use core::fmt;
+use std::cmp::Ordering;
+use std::rc::Rc;
+
+use crate::tokenizer::TokenKind;
+
+pub type NativeFunction = Rc<dyn Fn(&[Value]) -> Result<Value, String>>;
+
+// The fields of a struct in declaration order, shared by all of its values. Every value
+// stored in a field is cast to its kind, fields holding a struct have the kind Identifier
+#[derive(Debug, PartialEq)]
+pub struct StructType {
+    pub name: Rc<str>,
+    pub fields: Vec<(Rc<str>, TokenKind)>,
+}
+
+impl StructType {
+    pub fn field(&self, name: &str) -> Option<usize> {
+        self.fields.iter().position(|(field, _)| &**field == name)
+    }
+}
+
+// The variants of an enum in declaration order with the kinds of the values they hold,
+// values are cast like the fields of a struct
+#[derive(Debug, PartialEq)]
+pub struct EnumType {
+    pub name: Rc<str>,
+    pub variants: Vec<(Rc<str>, Vec<TokenKind>)>,
+}
+
+impl EnumType {
+    pub fn variant(&self, name: &str) -> Option<usize> {
+        self.variants.iter().position(|(variant, _)| &**variant == name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    // Values of kind f32, kept apart from f64 so they print at their own precision
+    Float32(f32),
+    Bool(bool),
+    Char(char),
+    Str(Rc<str>),
+    Array(Vec<Value>),
+    // The values of the fields, in the order of the type
+    Struct(Rc<StructType>, Vec<Value>),
+    // The index of the variant and the values it holds
+    Enum(Rc<EnumType>, usize, Vec<Value>),
+    Void,
+}
+
+impl Value {
+    // The value a variable holds when it's declared without an initializer
+    pub fn default_for(kind: TokenKind, array_length: Option<usize>) -> Value {
+        let value: Value = match kind {
+            TokenKind::UnsignedInt8 | TokenKind::UnsignedInt16 |
+            TokenKind::UnsignedInt32 | TokenKind::UnsignedInt64 => Value::UInt(0),
+            TokenKind::Float32 => Value::Float32(0.0),
+            TokenKind::Float64 => Value::Float(0.0),
+            TokenKind::Boolean => Value::Bool(false),
+            TokenKind::Character => Value::Char('\0'),
+            TokenKind::String => Value::Str(Rc::from("")),
+            _ => Value::Int(0),
+        };
+
+        match array_length {
+            Some(length) => Value::Array(vec![value; length]),
+            None => value,
+        }
+    }
+
+    pub fn from_literal(kind: TokenKind, literal: &str) -> Option<Value> {
+        match kind {
+            TokenKind::IntegerLiteral => literal.parse::<i64>().map(Value::Int).ok()
+                .or_else(|| literal.parse::<u64>().map(Value::UInt).ok()),
+            TokenKind::FloatLiteral => literal.parse::<f64>().map(Value::Float).ok(),
+            TokenKind::True => Some(Value::Bool(true)),
+            TokenKind::False => Some(Value::Bool(false)),
+            TokenKind::CharLiteral => {
+                let inner: &str = literal.strip_prefix('\'')?.strip_suffix('\'')?;
+                let unescaped: String = unescape(inner);
+                let mut chars = unescaped.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(character), None) => Some(Value::Char(character)),
+                    _ => None,
+                }
+            },
+            TokenKind::StringLiteral => {
+                let inner: &str = literal.strip_prefix('"')?.strip_suffix('"')?;
+                Some(Value::Str(Rc::from(unescape(inner))))
+            },
+            _ => None,
+        }
+    }
+
+    // Converts the value to the representation of a declared type,
+    // integers wrap around to the width of the type
+    pub fn cast(self, kind: TokenKind) -> Value {
+        use TokenKind::*;
+
+        match self {
+            Value::Array(values) => Value::Array(values.into_iter().map(|value| value.cast(kind)).collect()),
+            Value::Int(_) | Value::UInt(_) | Value::Float(_) | Value::Float32(_) => match kind {
+                SignedInt8 => Value::Int(self.as_i64() as i8 as i64),
+                SignedInt16 => Value::Int(self.as_i64() as i16 as i64),
+                SignedInt32 => Value::Int(self.as_i64() as i32 as i64),
+                SignedInt64 => Value::Int(self.as_i64()),
+                UnsignedInt8 => Value::UInt(self.as_i64() as u8 as u64),
+                UnsignedInt16 => Value::UInt(self.as_i64() as u16 as u64),
+                UnsignedInt32 => Value::UInt(self.as_i64() as u32 as u64),
+                UnsignedInt64 => Value::UInt(self.as_u64()),
+                Float32 => Value::Float32(self.as_f64() as f32),
+                Float64 => Value::Float(self.as_f64()),
+                _ => self,
+            },
+            other => other,
+        }
+    }
+
+    // Reads a field, None when the value isn't a struct with that field
+    pub fn get_field(&self, name: &str) -> Option<&Value> {
+        match self {
+            Value::Struct(type_, values) => values.get(type_.field(name)?),
+            _ => None,
+        }
+    }
+
+    // Stores a value in a field, cast to the kind of the field
+    pub fn set_field(&mut self, name: &str, value: Value) -> Option<()> {
+        match self {
+            Value::Struct(type_, values) => {
+                let index: usize = type_.field(name)?;
+                values[index] = value.cast(type_.fields[index].1);
+                Some(())
+            },
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> i64 {
+        match self {
+            Value::Int(value) => *value,
+            Value::UInt(value) => *value as i64,
+            Value::Float(value) => *value as i64,
+            Value::Float32(value) => *value as i64,
+            Value::Char(value) => *value as i64,
+            Value::Bool(value) => *value as i64,
+            _ => 0,
+        }
+    }
+
+    pub fn as_u64(&self) -> u64 {
+        match self {
+            Value::UInt(value) => *value,
+            Value::Float(value) => *value as u64,
+            Value::Float32(value) => *value as u64,
+            other => other.as_i64() as u64,
+        }
+    }
+
+    pub fn as_f64(&self) -> f64 {
+        match self {
+            Value::Int(value) => *value as f64,
+            Value::UInt(value) => *value as f64,
+            Value::Float(value) => *value,
+            Value::Float32(value) => *value as f64,
+            other => other.as_i64() as f64,
+        }
+    }
+
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            Value::Bool(value) => *value,
+            Value::Void => false,
+            other => other.as_i64() != 0,
+        }
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Int(_) => "signed integer",
+            Value::UInt(_) => "unsigned integer",
+            Value::Float(_) | Value::Float32(_) => "float",
+            Value::Bool(_) => "bool",
+            Value::Char(_) => "char",
+            Value::Str(_) => "str",
+            Value::Array(_) => "array",
+            Value::Struct(..) => "struct",
+            Value::Enum(..) => "enum",
+            Value::Void => "void",
+        }
+    }
+
+    // Steps a 'for' loop going through 'self', an array or the end of a range. Returns the
+    // value at 'position' and the position after it, or None once the loop is over
+    pub fn next_element(&self, position: &Value) -> Result<Option<(Value, Value)>, String> {
+        let unsupported = || format!("cannot count from {} to {}", position.type_name(), self.type_name());
+
+        match self {
+            Value::Array(values) => {
+                let index: i64 = position.as_i64();
+                Ok(values.get(index as usize).map(|value| (value.clone(), Value::Int(index + 1))))
+            },
+            end => match position.binary(TokenKind::LessThan, end) {
+                Some(Ok(Value::Bool(true))) => {
+                    let next: Value = position.binary(TokenKind::Plus, &Value::Int(1))
+                        .and_then(Result::ok)
+                        .ok_or_else(unsupported)?;
+                    Ok(Some((position.clone(), next)))
+                },
+                Some(_) => Ok(None),
+                None => Err(unsupported()),
+            },
+        }
+    }
+
+    // Applies a binary operator, None means the operands don't support it.
+    // '&&' and '||' are not handled here because they short-circuit
+    pub fn binary(&self, operator: TokenKind, right: &Value) -> Option<Result<Value, ArithmeticError>> {
+        use TokenKind::*;
+
+        match operator {
+            Equal => return Some(Ok(Value::Bool(self.compare(right)? == Ordering::Equal))),
+            NotEqual => return Some(Ok(Value::Bool(self.compare(right)? != Ordering::Equal))),
+            LessThan => return Some(Ok(Value::Bool(self.compare(right)? == Ordering::Less))),
+            GreaterThan => return Some(Ok(Value::Bool(self.compare(right)? == Ordering::Greater))),
+            LessThanOrEqual => return Some(Ok(Value::Bool(self.compare(right)? != Ordering::Greater))),
+            GreaterThanOrEqual => return Some(Ok(Value::Bool(self.compare(right)? != Ordering::Less))),
+            _ => {}
+        }
+
+        let result: Value = match (self, right) {
+            (Value::Str(left), Value::Str(right)) if operator == Plus => {
+                Value::Str(Rc::from(format!("{}{}", left, right)))
+            },
+
+            (Value::Float(_) | Value::Float32(_), Value::Int(_) | Value::UInt(_) | Value::Float(_) | Value::Float32(_)) |
+            (Value::Int(_) | Value::UInt(_), Value::Float(_) | Value::Float32(_)) => {
+                // An f32 operand only widens when the other one is an f64
+                let wide: bool = matches!(self, Value::Float(_)) || matches!(right, Value::Float(_));
+                let (left, right) = (self.as_f64(), right.as_f64());
+                let result: f64 = match operator {
+                    Plus => left + right,
+                    Minus => left - right,
+                    Multiplication => left * right,
+                    Division => left / right,
+                    Modulus => left % right,
+                    _ => return None,
+                };
+                if wide { Value::Float(result) } else { Value::Float32(result as f32) }
+            },
+
+            (Value::UInt(_), Value::Int(_) | Value::UInt(_)) |
+            (Value::Int(_), Value::UInt(_)) => {
+                let (left, right) = (self.as_u64(), right.as_u64());
+                Value::UInt(match operator {
+                    Plus => left.wrapping_add(right),
+                    Minus => left.wrapping_sub(right),
+                    Multiplication => left.wrapping_mul(right),
+                    Division | Modulus if right == 0 => return Some(Err(ArithmeticError::DivisionByZero)),
+                    Division => left / right,
+                    Modulus => left % right,
+                    BitwiseAnd => left & right,
+                    BitwiseOr => left | right,
+                    BitwiseXor => left ^ right,
+                    BitwiseLShift => left.wrapping_shl(right as u32),
+                    BitwiseRShift => left.wrapping_shr(right as u32),
+                    _ => return None,
+                })
+            },
+
+            (Value::Int(left), Value::Int(right)) => {
+                let (left, right) = (*left, *right);
+                Value::Int(match operator {
+                    Plus => left.wrapping_add(right),
+                    Minus => left.wrapping_sub(right),
+                    Multiplication => left.wrapping_mul(right),
+                    Division | Modulus if right == 0 => return Some(Err(ArithmeticError::DivisionByZero)),
+                    Division => left.wrapping_div(right),
+                    Modulus => left.wrapping_rem(right),
+                    BitwiseAnd => left & right,
+                    BitwiseOr => left | right,
+                    BitwiseXor => left ^ right,
+                    BitwiseLShift => left.wrapping_shl(right as u32),
+                    BitwiseRShift => left.wrapping_shr(right as u32),
+                    _ => return None,
+                })
+            },
+
+            _ => return None,
+        };
+
+        Some(Ok(result))
+    }
+
+    pub fn unary(&self, operator: TokenKind) -> Option<Value> {
+        match (operator, self) {
+            (TokenKind::Minus, Value::Int(value)) => Some(Value::Int(value.wrapping_neg())),
+            (TokenKind::Minus, Value::UInt(value)) => Some(Value::UInt(value.wrapping_neg())),
+            (TokenKind::Minus, Value::Float(value)) => Some(Value::Float(-value)),
+            (TokenKind::Minus, Value::Float32(value)) => Some(Value::Float32(-value)),
+            (TokenKind::Not, Value::Bool(value)) => Some(Value::Bool(!value)),
+            _ => None,
+        }
+    }
+
+    fn compare(&self, other: &Value) -> Option<Ordering> {
+        match (self, other) {
+            (Value::Int(left), Value::Int(right)) => Some(left.cmp(right)),
+            (Value::UInt(left), Value::UInt(right)) => Some(left.cmp(right)),
+            (Value::Int(left), Value::UInt(right)) => Some((*left as i128).cmp(&(*right as i128))),
+            (Value::UInt(left), Value::Int(right)) => Some((*left as i128).cmp(&(*right as i128))),
+            (Value::Float(_) | Value::Float32(_), Value::Int(_) | Value::UInt(_) | Value::Float(_) | Value::Float32(_)) |
+            (Value::Int(_) | Value::UInt(_), Value::Float(_) | Value::Float32(_)) => self.as_f64().partial_cmp(&other.as_f64()),
+            (Value::Bool(left), Value::Bool(right)) => Some(left.cmp(right)),
+            (Value::Char(left), Value::Char(right)) => Some(left.cmp(right)),
+            (Value::Str(left), Value::Str(right)) => Some(left.cmp(right)),
+            (Value::Array(left), Value::Array(right)) => {
+                if left.len() != right.len() {
+                    return Some(left.len().cmp(&right.len()));
+                }
+                Self::compare_elements(left, right)
+            },
+            // Scripts can only check structs for equality
+            (Value::Struct(left_type, left), Value::Struct(right_type, right)) if left_type == right_type => {
+                Self::compare_elements(left, right)
+            },
+            // and enums, the variants of the same type hold the same number of values
+            (Value::Enum(left_type, left_index, left), Value::Enum(right_type, right_index, right)) if left_type == right_type => {
+                match left_index.cmp(right_index) {
+                    Ordering::Equal => Self::compare_elements(left, right),
+                    ordering => Some(ordering),
+                }
+            },
+            _ => None,
+        }
+    }
+
+    // Compares two lists of the same length, the first pair that differs decides
+    fn compare_elements(left: &[Value], right: &[Value]) -> Option<Ordering> {
+        for (left, right) in left.iter().zip(right) {
+            match left.compare(right)? {
+                Ordering::Equal => continue,
+                ordering => return Some(ordering),
+            }
+        }
+        Some(Ordering::Equal)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ArithmeticError {
+    DivisionByZero,
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Int(value) => write!(f, "{}", value),
+            Self::UInt(value) => write!(f, "{}", value),
+            Self::Float(value) => write!(f, "{}", value),
+            Self::Float32(value) => write!(f, "{}", value),
+            Self::Bool(value) => write!(f, "{}", value),
+            Self::Char(value) => write!(f, "{}", value),
+            Self::Str(value) => write!(f, "{}", value),
+            Self::Array(values) => {
+                write!(f, "[")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, "]")
+            },
+            // Like the literal that builds it, 'Point { x = 1, y = 2 }'
+            Self::Struct(type_, values) => {
+                write!(f, "{} {{", type_.name)?;
+                for (i, ((name, _), value)) in type_.fields.iter().zip(values).enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, " {} = {}", name, value)?;
+                }
+                if !values.is_empty() {
+                    write!(f, " ")?;
+                }
+                write!(f, "}}")
+            },
+            // Like the expression that builds it, 'State::Failed(oops, 2)'
+            Self::Enum(type_, index, values) => {
+                write!(f, "{}::{}", type_.name, type_.variants[*index].0)?;
+                if !values.is_empty() {
+                    write!(f, "(")?;
+                    for (i, value) in values.iter().enumerate() {
+                        if i > 0 {
+                            write!(f, ", ")?;
+                        }
+                        write!(f, "{}", value)?;
+                    }
+                    write!(f, ")")?;
+                }
+                Ok(())
+            },
+            Self::Void => write!(f, "void"),
+        }
+    }
+}
+
+// Resolves the escape sequences the tokenizer accepted inside char and string literals
+pub fn unescape(literal: &str) -> String {
+    let mut result: String = String::with_capacity(literal.len());
+    let mut chars = literal.chars().peekable();
+
+    while let Some(character) = chars.next() {
+        if character != '\\' {
+            result.push(character);
+            continue;
+        }
+
+        let Some(escape) = chars.next() else {
+            result.push('\\');
+            break;
+        };
+
+        match escape {
+            'a' => result.push('\x07'),
+            'b' => result.push('\x08'),
+            'f' => result.push('\x0c'),
+            'n' => result.push('\n'),
+            'r' => result.push('\r'),
+            't' => result.push('\t'),
+            'v' => result.push('\x0b'),
+            '0' => result.push('\0'),
+            '1'..='7' => {
+                let mut code: u32 = escape.to_digit(8).unwrap();
+                for _ in 0..2 {
+                    match chars.peek().and_then(|next| next.to_digit(8)) {
+                        Some(digit) => {
+                            code = code * 8 + digit;
+                            chars.next();
+                        },
+                        None => break,
+                    }
+                }
+                result.push(char::from_u32(code).unwrap_or('\u{FFFD}'));
+            },
+            'x' => {
+                let mut code: u32 = 0;
+                for _ in 0..2 {
+                    match chars.peek().and_then(|next| next.to_digit(16)) {
+                        Some(digit) => {
+                            code = code * 16 + digit;
+                            chars.next();
+                        },
+                        None => break,
+                    }
+                }
+                result.push(char::from_u32(code).unwrap_or('\u{FFFD}'));
+            },
+            other => result.push(other),
+        }
+    }
+
+    result
+}
@@ -0,0 +1,604 @@
+use crate::parser::{Accessor, Arm, Body, ElseBranch, Expression, Field, Iterable, Parameter, Pattern, Place, RawExpression, RawStatement, Statement, Type, Variant};
+
+// Walks a syntax tree by reference. Every method visits the children of its node by
+// default, a pass overrides the nodes it cares about and calls the matching 'walk_'
+// function when it wants to keep going into the children
+pub trait Visitor<'a> {
+    fn visit_statement(&mut self, statement: &'a Statement<'a>) {
+        walk_statement(self, statement);
+    }
+
+    fn visit_expression(&mut self, expression: &'a Expression<'a>) {
+        walk_expression(self, expression);
+    }
+
+    fn visit_body(&mut self, body: &'a Body<'a>) {
+        walk_body(self, body);
+    }
+
+    fn visit_else_branch(&mut self, branch: &'a ElseBranch<'a>) {
+        walk_else_branch(self, branch);
+    }
+
+    fn visit_iterable(&mut self, iterable: &'a Iterable<'a>) {
+        walk_iterable(self, iterable);
+    }
+
+    fn visit_type(&mut self, type_: &'a Type<'a>) {
+        walk_type(self, type_);
+    }
+
+    fn visit_parameter(&mut self, parameter: &'a Parameter<'a>) {
+        walk_parameter(self, parameter);
+    }
+
+    fn visit_pattern(&mut self, pattern: &'a Pattern<'a>) {
+        walk_pattern(self, pattern);
+    }
+
+    fn visit_place(&mut self, place: &'a Place<'a>) {
+        walk_place(self, place);
+    }
+}
+
+pub fn walk_statements<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, statements: &'a [Statement<'a>]) {
+    for statement in statements {
+        visitor.visit_statement(statement);
+    }
+}
+
+pub fn walk_statement<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, statement: &'a Statement<'a>) {
+    match &statement.node {
+        RawStatement::VariableDeclaration { type_, value, .. } => {
+            visitor.visit_type(type_);
+            if let Some(value) = value {
+                visitor.visit_expression(value);
+            }
+        },
+        RawStatement::VariableAssignment { target, value, .. } => {
+            visitor.visit_place(target);
+            visitor.visit_expression(value);
+        },
+        RawStatement::If { condition, body, elses } => {
+            visitor.visit_expression(condition);
+            visitor.visit_body(body);
+            for branch in elses {
+                visitor.visit_else_branch(branch);
+            }
+        },
+        RawStatement::While { condition, body } => {
+            visitor.visit_expression(condition);
+            visitor.visit_body(body);
+        },
+        RawStatement::For { iterable, body, .. } => {
+            visitor.visit_iterable(iterable);
+            visitor.visit_body(body);
+        },
+        RawStatement::LoopControl(_) | RawStatement::Import(_) => {},
+        RawStatement::Function { parameters, body, .. } => {
+            for parameter in parameters {
+                visitor.visit_parameter(parameter);
+            }
+            visitor.visit_body(body);
+        },
+        RawStatement::Return(value) => {
+            if let Some(value) = value {
+                visitor.visit_expression(value);
+            }
+        },
+        RawStatement::FunctionCall { arguments, .. } => {
+            for argument in arguments {
+                visitor.visit_expression(argument);
+            }
+        },
+        RawStatement::Namespace { body, .. } => visitor.visit_body(body),
+        RawStatement::Struct { fields, .. } => {
+            for field in fields {
+                visitor.visit_type(&field.type_);
+            }
+        },
+        RawStatement::Enum { variants, .. } => {
+            for type_ in variants.iter().flat_map(|variant| &variant.payload) {
+                visitor.visit_type(type_);
+            }
+        },
+        RawStatement::Match { value, arms } => {
+            visitor.visit_expression(value);
+            for arm in arms {
+                visitor.visit_pattern(&arm.pattern);
+                visitor.visit_body(&arm.value);
+            }
+        },
+    }
+}
+
+pub fn walk_expression<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, expression: &'a Expression<'a>) {
+    match &expression.node {
+        RawExpression::Variable(_) | RawExpression::Literal { .. } => {},
+        RawExpression::Binary { left, right, .. } => {
+            visitor.visit_expression(left);
+            visitor.visit_expression(right);
+        },
+        RawExpression::Unary { operand, .. } => visitor.visit_expression(operand),
+        RawExpression::FunctionCall { arguments, .. } => {
+            for argument in arguments {
+                visitor.visit_expression(argument);
+            }
+        },
+        RawExpression::ArrayAccess { array, index } => {
+            visitor.visit_expression(array);
+            visitor.visit_expression(index);
+        },
+        RawExpression::StructLiteral { fields, .. } => {
+            for (_, value) in fields {
+                visitor.visit_expression(value);
+            }
+        },
+        RawExpression::Field { object, .. } => visitor.visit_expression(object),
+        RawExpression::Match { value, arms } => {
+            visitor.visit_expression(value);
+            for arm in arms {
+                visitor.visit_pattern(&arm.pattern);
+                visitor.visit_expression(&arm.value);
+            }
+        },
+        RawExpression::ArrayLiteral(elements) => {
+            for element in elements {
+                visitor.visit_expression(element);
+            }
+        },
+    }
+}
+
+pub fn walk_body<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, body: &'a Body<'a>) {
+    walk_statements(visitor, &body.statements);
+}
+
+// An 'elif' is visited as the 'if' statement it's stored as
+pub fn walk_else_branch<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, branch: &'a ElseBranch<'a>) {
+    match branch {
+        ElseBranch::ElseIf(statement) => visitor.visit_statement(statement),
+        ElseBranch::Else(body) => visitor.visit_body(body),
+    }
+}
+
+pub fn walk_iterable<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, iterable: &'a Iterable<'a>) {
+    match iterable {
+        Iterable::Range { start, end } => {
+            visitor.visit_expression(start);
+            visitor.visit_expression(end);
+        },
+        Iterable::Array(array) => visitor.visit_expression(array),
+    }
+}
+
+pub fn walk_type<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, type_: &'a Type<'a>) {
+    if let Some(length) = &type_.array_length {
+        visitor.visit_expression(length);
+    }
+}
+
+pub fn walk_parameter<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, parameter: &'a Parameter<'a>) {
+    visitor.visit_type(&parameter.type_);
+}
+
+// Only literal patterns hold an expression
+pub fn walk_pattern<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, pattern: &'a Pattern<'a>) {
+    if let Pattern::Literal(literal) = pattern {
+        visitor.visit_expression(literal);
+    }
+}
+
+// Only indexes hold an expression
+pub fn walk_place<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, place: &'a Place<'a>) {
+    for accessor in &place.accessors {
+        if let Accessor::Index(index) = accessor {
+            visitor.visit_expression(index);
+        }
+    }
+}
+
+// Like Visitor, but the nodes can be changed in place
+pub trait VisitorMut<'a> {
+    fn visit_statement_mut(&mut self, statement: &mut Statement<'a>) {
+        walk_statement_mut(self, statement);
+    }
+
+    fn visit_expression_mut(&mut self, expression: &mut Expression<'a>) {
+        walk_expression_mut(self, expression);
+    }
+
+    fn visit_body_mut(&mut self, body: &mut Body<'a>) {
+        walk_body_mut(self, body);
+    }
+
+    fn visit_else_branch_mut(&mut self, branch: &mut ElseBranch<'a>) {
+        walk_else_branch_mut(self, branch);
+    }
+
+    fn visit_iterable_mut(&mut self, iterable: &mut Iterable<'a>) {
+        walk_iterable_mut(self, iterable);
+    }
+
+    fn visit_type_mut(&mut self, type_: &mut Type<'a>) {
+        walk_type_mut(self, type_);
+    }
+
+    fn visit_parameter_mut(&mut self, parameter: &mut Parameter<'a>) {
+        walk_parameter_mut(self, parameter);
+    }
+
+    fn visit_pattern_mut(&mut self, pattern: &mut Pattern<'a>) {
+        walk_pattern_mut(self, pattern);
+    }
+
+    fn visit_place_mut(&mut self, place: &mut Place<'a>) {
+        walk_place_mut(self, place);
+    }
+}
+
+pub fn walk_statements_mut<'a, V: VisitorMut<'a> + ?Sized>(visitor: &mut V, statements: &mut [Statement<'a>]) {
+    for statement in statements {
+        visitor.visit_statement_mut(statement);
+    }
+}
+
+pub fn walk_statement_mut<'a, V: VisitorMut<'a> + ?Sized>(visitor: &mut V, statement: &mut Statement<'a>) {
+    match &mut statement.node {
+        RawStatement::VariableDeclaration { type_, value, .. } => {
+            visitor.visit_type_mut(type_);
+            if let Some(value) = value {
+                visitor.visit_expression_mut(value);
+            }
+        },
+        RawStatement::VariableAssignment { target, value, .. } => {
+            visitor.visit_place_mut(target);
+            visitor.visit_expression_mut(value);
+        },
+        RawStatement::If { condition, body, elses } => {
+            visitor.visit_expression_mut(condition);
+            visitor.visit_body_mut(body);
+            for branch in elses {
+                visitor.visit_else_branch_mut(branch);
+            }
+        },
+        RawStatement::While { condition, body } => {
+            visitor.visit_expression_mut(condition);
+            visitor.visit_body_mut(body);
+        },
+        RawStatement::For { iterable, body, .. } => {
+            visitor.visit_iterable_mut(iterable);
+            visitor.visit_body_mut(body);
+        },
+        RawStatement::LoopControl(_) | RawStatement::Import(_) => {},
+        RawStatement::Function { parameters, body, .. } => {
+            for parameter in parameters {
+                visitor.visit_parameter_mut(parameter);
+            }
+            visitor.visit_body_mut(body);
+        },
+        RawStatement::Return(value) => {
+            if let Some(value) = value {
+                visitor.visit_expression_mut(value);
+            }
+        },
+        RawStatement::FunctionCall { arguments, .. } => {
+            for argument in arguments {
+                visitor.visit_expression_mut(argument);
+            }
+        },
+        RawStatement::Namespace { body, .. } => visitor.visit_body_mut(body),
+        RawStatement::Struct { fields, .. } => {
+            for field in fields {
+                visitor.visit_type_mut(&mut field.type_);
+            }
+        },
+        RawStatement::Enum { variants, .. } => {
+            for type_ in variants.iter_mut().flat_map(|variant| &mut variant.payload) {
+                visitor.visit_type_mut(type_);
+            }
+        },
+        RawStatement::Match { value, arms } => {
+            visitor.visit_expression_mut(value);
+            for arm in arms {
+                visitor.visit_pattern_mut(&mut arm.pattern);
+                visitor.visit_body_mut(&mut arm.value);
+            }
+        },
+    }
+}
+
+pub fn walk_expression_mut<'a, V: VisitorMut<'a> + ?Sized>(visitor: &mut V, expression: &mut Expression<'a>) {
+    match &mut expression.node {
+        RawExpression::Variable(_) | RawExpression::Literal { .. } => {},
+        RawExpression::Binary { left, right, .. } => {
+            visitor.visit_expression_mut(left);
+            visitor.visit_expression_mut(right);
+        },
+        RawExpression::Unary { operand, .. } => visitor.visit_expression_mut(operand),
+        RawExpression::FunctionCall { arguments, .. } => {
+            for argument in arguments {
+                visitor.visit_expression_mut(argument);
+            }
+        },
+        RawExpression::ArrayAccess { array, index } => {
+            visitor.visit_expression_mut(array);
+            visitor.visit_expression_mut(index);
+        },
+        RawExpression::StructLiteral { fields, .. } => {
+            for (_, value) in fields {
+                visitor.visit_expression_mut(value);
+            }
+        },
+        RawExpression::Field { object, .. } => visitor.visit_expression_mut(object),
+        RawExpression::Match { value, arms } => {
+            visitor.visit_expression_mut(value);
+            for arm in arms {
+                visitor.visit_pattern_mut(&mut arm.pattern);
+                visitor.visit_expression_mut(&mut arm.value);
+            }
+        },
+        RawExpression::ArrayLiteral(elements) => {
+            for element in elements {
+                visitor.visit_expression_mut(element);
+            }
+        },
+    }
+}
+
+pub fn walk_body_mut<'a, V: VisitorMut<'a> + ?Sized>(visitor: &mut V, body: &mut Body<'a>) {
+    walk_statements_mut(visitor, &mut body.statements);
+}
+
+pub fn walk_else_branch_mut<'a, V: VisitorMut<'a> + ?Sized>(visitor: &mut V, branch: &mut ElseBranch<'a>) {
+    match branch {
+        ElseBranch::ElseIf(statement) => visitor.visit_statement_mut(statement),
+        ElseBranch::Else(body) => visitor.visit_body_mut(body),
+    }
+}
+
+pub fn walk_iterable_mut<'a, V: VisitorMut<'a> + ?Sized>(visitor: &mut V, iterable: &mut Iterable<'a>) {
+    match iterable {
+        Iterable::Range { start, end } => {
+            visitor.visit_expression_mut(start);
+            visitor.visit_expression_mut(end);
+        },
+        Iterable::Array(array) => visitor.visit_expression_mut(array),
+    }
+}
+
+pub fn walk_type_mut<'a, V: VisitorMut<'a> + ?Sized>(visitor: &mut V, type_: &mut Type<'a>) {
+    if let Some(length) = &mut type_.array_length {
+        visitor.visit_expression_mut(length);
+    }
+}
+
+pub fn walk_parameter_mut<'a, V: VisitorMut<'a> + ?Sized>(visitor: &mut V, parameter: &mut Parameter<'a>) {
+    visitor.visit_type_mut(&mut parameter.type_);
+}
+
+pub fn walk_pattern_mut<'a, V: VisitorMut<'a> + ?Sized>(visitor: &mut V, pattern: &mut Pattern<'a>) {
+    if let Pattern::Literal(literal) = pattern {
+        visitor.visit_expression_mut(literal);
+    }
+}
+
+pub fn walk_place_mut<'a, V: VisitorMut<'a> + ?Sized>(visitor: &mut V, place: &mut Place<'a>) {
+    for accessor in &mut place.accessors {
+        if let Accessor::Index(index) = accessor {
+            visitor.visit_expression_mut(index);
+        }
+    }
+}
+
+// Takes a syntax tree by value and builds a new one. By default every node is
+// rebuilt from its folded children, keeping its span
+pub trait Folder<'a> {
+    fn fold_statement(&mut self, statement: Statement<'a>) -> Statement<'a> {
+        rebuild_statement(self, statement)
+    }
+
+    fn fold_expression(&mut self, expression: Expression<'a>) -> Expression<'a> {
+        rebuild_expression(self, expression)
+    }
+
+    fn fold_body(&mut self, body: Body<'a>) -> Body<'a> {
+        rebuild_body(self, body)
+    }
+
+    fn fold_else_branch(&mut self, branch: ElseBranch<'a>) -> ElseBranch<'a> {
+        rebuild_else_branch(self, branch)
+    }
+
+    fn fold_iterable(&mut self, iterable: Iterable<'a>) -> Iterable<'a> {
+        rebuild_iterable(self, iterable)
+    }
+
+    fn fold_type(&mut self, type_: Type<'a>) -> Type<'a> {
+        rebuild_type(self, type_)
+    }
+
+    fn fold_parameter(&mut self, parameter: Parameter<'a>) -> Parameter<'a> {
+        rebuild_parameter(self, parameter)
+    }
+
+    fn fold_pattern(&mut self, pattern: Pattern<'a>) -> Pattern<'a> {
+        rebuild_pattern(self, pattern)
+    }
+
+    fn fold_place(&mut self, place: Place<'a>) -> Place<'a> {
+        rebuild_place(self, place)
+    }
+}
+
+pub fn rebuild_statements<'a, F: Folder<'a> + ?Sized>(folder: &mut F, statements: Vec<Statement<'a>>) -> Vec<Statement<'a>> {
+    statements.into_iter().map(|statement| folder.fold_statement(statement)).collect()
+}
+
+fn rebuild_expressions<'a, F: Folder<'a> + ?Sized>(folder: &mut F, expressions: Vec<Expression<'a>>) -> Vec<Expression<'a>> {
+    expressions.into_iter().map(|expression| folder.fold_expression(expression)).collect()
+}
+
+pub fn rebuild_statement<'a, F: Folder<'a> + ?Sized>(folder: &mut F, mut statement: Statement<'a>) -> Statement<'a> {
+    statement.node = match statement.node {
+        RawStatement::VariableDeclaration { is_const, type_, name, value } => RawStatement::VariableDeclaration {
+            is_const,
+            type_: folder.fold_type(type_),
+            name,
+            value: value.map(|value| folder.fold_expression(value)),
+        },
+        RawStatement::VariableAssignment { target, operator, value } => RawStatement::VariableAssignment {
+            target: folder.fold_place(target),
+            operator,
+            value: folder.fold_expression(value),
+        },
+        RawStatement::If { condition, body, elses } => RawStatement::If {
+            condition: folder.fold_expression(condition),
+            body: folder.fold_body(body),
+            elses: elses.into_iter().map(|branch| folder.fold_else_branch(branch)).collect(),
+        },
+        RawStatement::While { condition, body } => RawStatement::While {
+            condition: folder.fold_expression(condition),
+            body: folder.fold_body(body),
+        },
+        RawStatement::For { type_, name, iterable, body } => RawStatement::For {
+            type_,
+            name,
+            iterable: folder.fold_iterable(iterable),
+            body: folder.fold_body(body),
+        },
+        RawStatement::LoopControl(keyword) => RawStatement::LoopControl(keyword),
+        RawStatement::Import(import) => RawStatement::Import(import),
+        RawStatement::Function { name, parameters, type_, body } => RawStatement::Function {
+            name,
+            parameters: parameters.into_iter().map(|parameter| folder.fold_parameter(parameter)).collect(),
+            type_,
+            body: folder.fold_body(body),
+        },
+        RawStatement::Return(value) => RawStatement::Return(value.map(|value| folder.fold_expression(value))),
+        RawStatement::FunctionCall { name, arguments } => RawStatement::FunctionCall {
+            name,
+            arguments: rebuild_expressions(folder, arguments),
+        },
+        RawStatement::Namespace { name, body } => RawStatement::Namespace {
+            name,
+            body: folder.fold_body(body),
+        },
+        RawStatement::Struct { name, fields } => RawStatement::Struct {
+            name,
+            fields: fields.into_iter().map(|field| Field { type_: folder.fold_type(field.type_), ..field }).collect(),
+        },
+        RawStatement::Enum { name, variants } => RawStatement::Enum {
+            name,
+            variants: variants.into_iter().map(|variant| Variant {
+                payload: variant.payload.into_iter().map(|type_| folder.fold_type(type_)).collect(),
+                ..variant
+            }).collect(),
+        },
+        RawStatement::Match { value, arms } => RawStatement::Match {
+            value: folder.fold_expression(value),
+            arms: arms.into_iter().map(|arm| Arm {
+                pattern: folder.fold_pattern(arm.pattern),
+                value: folder.fold_body(arm.value),
+                span: arm.span,
+            }).collect(),
+        },
+    };
+    statement
+}
+
+pub fn rebuild_expression<'a, F: Folder<'a> + ?Sized>(folder: &mut F, mut expression: Expression<'a>) -> Expression<'a> {
+    expression.node = match expression.node {
+        RawExpression::Binary { left, operator, right } => RawExpression::Binary {
+            left: folder.fold_expression(left),
+            operator,
+            right: folder.fold_expression(right),
+        },
+        RawExpression::Unary { operator, operand } => RawExpression::Unary {
+            operator,
+            operand: folder.fold_expression(operand),
+        },
+        RawExpression::FunctionCall { name, arguments } => RawExpression::FunctionCall {
+            name,
+            arguments: rebuild_expressions(folder, arguments),
+        },
+        RawExpression::ArrayAccess { array, index } => RawExpression::ArrayAccess {
+            array: folder.fold_expression(array),
+            index: folder.fold_expression(index),
+        },
+        RawExpression::StructLiteral { name, fields } => RawExpression::StructLiteral {
+            name,
+            fields: fields.into_iter().map(|(field, value)| (field, folder.fold_expression(value))).collect(),
+        },
+        RawExpression::Field { object, name } => RawExpression::Field {
+            object: folder.fold_expression(object),
+            name,
+        },
+        RawExpression::Match { value, arms } => RawExpression::Match {
+            value: folder.fold_expression(value),
+            arms: arms.into_iter().map(|arm| Arm {
+                pattern: folder.fold_pattern(arm.pattern),
+                value: folder.fold_expression(arm.value),
+                span: arm.span,
+            }).collect(),
+        },
+        RawExpression::ArrayLiteral(elements) => RawExpression::ArrayLiteral(rebuild_expressions(folder, elements)),
+        leaf @ (RawExpression::Variable(_) | RawExpression::Literal { .. }) => leaf,
+    };
+    expression
+}
+
+pub fn rebuild_body<'a, F: Folder<'a> + ?Sized>(folder: &mut F, body: Body<'a>) -> Body<'a> {
+    Body {
+        statements: rebuild_statements(folder, body.statements),
+        span: body.span,
+    }
+}
+
+pub fn rebuild_else_branch<'a, F: Folder<'a> + ?Sized>(folder: &mut F, branch: ElseBranch<'a>) -> ElseBranch<'a> {
+    match branch {
+        ElseBranch::ElseIf(statement) => ElseBranch::ElseIf(folder.fold_statement(statement)),
+        ElseBranch::Else(body) => ElseBranch::Else(folder.fold_body(body)),
+    }
+}
+
+pub fn rebuild_iterable<'a, F: Folder<'a> + ?Sized>(folder: &mut F, iterable: Iterable<'a>) -> Iterable<'a> {
+    match iterable {
+        Iterable::Range { start, end } => Iterable::Range {
+            start: folder.fold_expression(start),
+            end: folder.fold_expression(end),
+        },
+        Iterable::Array(array) => Iterable::Array(folder.fold_expression(array)),
+    }
+}
+
+pub fn rebuild_type<'a, F: Folder<'a> + ?Sized>(folder: &mut F, type_: Type<'a>) -> Type<'a> {
+    Type {
+        array_length: type_.array_length.map(|length| folder.fold_expression(length)),
+        ..type_
+    }
+}
+
+pub fn rebuild_parameter<'a, F: Folder<'a> + ?Sized>(folder: &mut F, parameter: Parameter<'a>) -> Parameter<'a> {
+    Parameter {
+        type_: folder.fold_type(parameter.type_),
+        ..parameter
+    }
+}
+
+pub fn rebuild_pattern<'a, F: Folder<'a> + ?Sized>(folder: &mut F, pattern: Pattern<'a>) -> Pattern<'a> {
+    match pattern {
+        Pattern::Literal(literal) => Pattern::Literal(folder.fold_expression(literal)),
+        other => other,
+    }
+}
+
+pub fn rebuild_place<'a, F: Folder<'a> + ?Sized>(folder: &mut F, place: Place<'a>) -> Place<'a> {
+    Place {
+        accessors: place.accessors.into_iter().map(|accessor| match accessor {
+            Accessor::Index(index) => Accessor::Index(folder.fold_expression(index)),
+            field => field,
+        }).collect(),
+        ..place
+    }
+}
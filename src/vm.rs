@@ -0,0 +1,319 @@
+use std::rc::Rc;
+
+use crate::bytecode::{Callable, Definition, Function, Instruction, Program};
+use crate::interpreter::{array_length, check_length, element, no_field, set_element, MAX_CALL_DEPTH, NO_ARM, RuntimeError};
+use crate::parser::StatementSpan;
+use crate::value::{ArithmeticError, EnumType, Value};
+use crate::tokenizer::TokenKind;
+use crate::error::*;
+
+struct Frame {
+    function: Rc<Function>,
+    ip: usize,
+    // Position of the first local (or parameter) of the frame on the stack
+    base: usize,
+    // The top level of a script, it doesn't count as a call
+    script: bool,
+}
+
+// Executes the chunks produced by the Compiler, behaving exactly like the Interpreter
+#[derive(Default)]
+pub struct VM {
+    globals: Vec<Value>,
+    stack: Vec<Value>,
+    frames: Vec<Frame>,
+    depth: usize,
+}
+
+impl VM {
+    pub fn new() -> VM {
+        VM::default()
+    }
+
+    pub fn global(&self, slot: usize) -> Option<&Value> {
+        self.globals.get(slot)
+    }
+
+    pub fn set_global(&mut self, slot: usize, value: Value) {
+        if slot >= self.globals.len() {
+            self.globals.resize(slot + 1, Value::Void);
+        }
+        self.globals[slot] = value;
+    }
+
+    // Returns the value of the script, only functions compiled from a standalone expression have one
+    pub fn run(&mut self, program: &Program, script: Rc<Function>) -> Result<Value, RuntimeError> {
+        self.sync_globals(program);
+        self.frames.push(Frame { function: script, ip: 0, base: self.stack.len(), script: true });
+        self.execute(program)
+    }
+
+    // Calls the function stored in 'slot' from outside of any script
+    pub fn call(&mut self, program: &Program, slot: usize, arguments: Vec<Value>) -> Result<Value, RuntimeError> {
+        self.sync_globals(program);
+        let span: StatementSpan = StatementSpan::default();
+
+        match program.functions.get(slot) {
+            Some(Some(Callable::Native(native))) => native(&arguments).map_err(|message| error(ErrorCode::ER007, span, message)),
+            Some(Some(Callable::Script(function))) => {
+                let base: usize = self.stack.len();
+                self.stack.extend(arguments);
+                self.enter(function.clone(), base, span)?;
+                self.execute(program)
+            },
+            _ => Err(error(ErrorCode::ER008, span, format!("function slot {} is not defined", slot))),
+        }
+    }
+
+    fn sync_globals(&mut self, program: &Program) {
+        if self.globals.len() < program.globals.len() {
+            self.globals.resize(program.globals.len(), Value::Void);
+        }
+    }
+
+    // Runs until the frame on top of the stack returns, on error the VM is left ready for the next call
+    fn execute(&mut self, program: &Program) -> Result<Value, RuntimeError> {
+        let entry: usize = self.frames.len() - 1;
+        let base: usize = self.frames[entry].base;
+
+        let result: Result<Value, RuntimeError> = self.dispatch(program, entry);
+        if result.is_err() {
+            self.frames.truncate(entry);
+            self.stack.truncate(base);
+            self.depth = self.frames.iter().filter(|frame| !frame.script).count();
+        }
+        result
+    }
+
+    fn dispatch(&mut self, program: &Program, entry: usize) -> Result<Value, RuntimeError> {
+        let mut function: Rc<Function> = self.frames[entry].function.clone();
+        let mut ip: usize = self.frames[entry].ip;
+        let mut base: usize = self.frames[entry].base;
+
+        loop {
+            let instruction: Instruction = function.chunk.code[ip];
+            let span: StatementSpan = function.chunk.spans[ip];
+            ip += 1;
+
+            match instruction {
+                Instruction::Constant(index) => self.stack.push(function.chunk.constants[index as usize].clone()),
+                Instruction::Pop => { self.pop(); },
+                Instruction::PopN(count) => {
+                    let length: usize = self.stack.len() - count as usize;
+                    self.stack.truncate(length);
+                },
+                Instruction::Dup => self.stack.push(self.stack.last().expect("the compiler keeps the stack balanced").clone()),
+                Instruction::Dup2 => self.stack.extend_from_within(self.stack.len() - 2..),
+
+                Instruction::GetGlobal(slot) => self.stack.push(self.globals[slot as usize].clone()),
+                Instruction::SetGlobal(slot) => self.globals[slot as usize] = self.pop(),
+                Instruction::GetLocal(slot) => self.stack.push(self.stack[base + slot as usize].clone()),
+                Instruction::SetLocal(slot) => self.stack[base + slot as usize] = self.pop(),
+
+                Instruction::Cast(kind) => {
+                    let value: Value = self.pop().cast(kind);
+                    self.stack.push(value);
+                },
+                Instruction::DefaultArray(kind) => {
+                    let length: usize = array_length(&self.pop(), span)?;
+                    self.stack.push(Value::default_for(kind, Some(length)));
+                },
+                Instruction::Fill => {
+                    let value: Value = self.pop();
+                    let length: usize = array_length(&self.pop(), span)?;
+                    self.stack.push(Value::Array(vec![value; length]));
+                },
+                Instruction::Array(count) => {
+                    let values: Vec<Value> = self.stack.split_off(self.stack.len() - count as usize);
+                    self.stack.push(Value::Array(values));
+                },
+                Instruction::CheckLength => {
+                    let array: Value = self.pop();
+                    let length: usize = array_length(&self.pop(), span)?;
+                    check_length(&array, length, span)?;
+                    self.stack.push(array);
+                },
+
+                Instruction::Binary(operator) => {
+                    let right: Value = self.pop();
+                    let left: Value = self.pop();
+                    let value: Value = match left.binary(operator, &right) {
+                        Some(Ok(value)) => value,
+                        Some(Err(ArithmeticError::DivisionByZero)) => return Err(error(ErrorCode::ER001, span,
+                            "the right side of '/' or '%' evaluated to zero".to_string())),
+                        None => return Err(error(ErrorCode::ER009, span,
+                            format!("'{}' cannot be applied to {} and {}", operator, left.type_name(), right.type_name()))),
+                    };
+                    self.stack.push(value);
+                },
+                Instruction::Unary(operator) => {
+                    let operand: Value = self.pop();
+                    let value: Value = operand.unary(operator).ok_or_else(|| error(ErrorCode::ER009, span,
+                        format!("'{}' cannot be applied to {}", operator, operand.type_name())))?;
+                    self.stack.push(value);
+                },
+                Instruction::Index => {
+                    let index: Value = self.pop();
+                    let array: Value = self.pop();
+                    self.stack.push(element(&array, &index, span)?);
+                },
+                Instruction::SetIndex => {
+                    let value: Value = self.pop();
+                    let index: Value = self.pop();
+                    let array: Value = self.pop();
+                    self.stack.push(set_element(array, &index, value, span)?);
+                },
+
+                Instruction::Struct(slot) => {
+                    let Definition::Struct(type_) = &program.types[slot as usize].definition else {
+                        unreachable!("the compiler only builds structs from struct slots");
+                    };
+                    let values: Vec<Value> = self.stack.split_off(self.stack.len() - type_.fields.len());
+                    let values: Vec<Value> = values.into_iter().zip(&type_.fields).map(|(value, (_, kind))| value.cast(*kind)).collect();
+                    self.stack.push(Value::Struct(type_.clone(), values));
+                },
+                Instruction::GetField(name) => {
+                    let name: String = function.chunk.constants[name as usize].to_string();
+                    let object: Value = self.pop();
+                    let value: Value = object.get_field(&name).cloned()
+                        .ok_or_else(|| error(ErrorCode::ER009, span, no_field(&object, &name)))?;
+                    self.stack.push(value);
+                },
+                Instruction::SetField(name) => {
+                    let name: String = function.chunk.constants[name as usize].to_string();
+                    let value: Value = self.pop();
+                    let mut object: Value = self.pop();
+                    object.set_field(&name, value)
+                        .ok_or_else(|| error(ErrorCode::ER009, span, no_field(&object, &name)))?;
+                    self.stack.push(object);
+                },
+
+                Instruction::Variant(slot, index) => {
+                    let type_: &Rc<EnumType> = enum_type(program, slot);
+                    let kinds: &[TokenKind] = &type_.variants[index as usize].1;
+                    let values: Vec<Value> = self.stack.split_off(self.stack.len() - kinds.len());
+                    let values: Vec<Value> = values.into_iter().zip(kinds).map(|(value, kind)| value.cast(*kind)).collect();
+                    self.stack.push(Value::Enum(type_.clone(), index as usize, values));
+                },
+                Instruction::IsVariant(slot, index) => {
+                    let is_variant: bool = matches!(self.pop(),
+                        Value::Enum(type_, variant, _) if variant == index as usize && type_ == *enum_type(program, slot));
+                    self.stack.push(Value::Bool(is_variant));
+                },
+                Instruction::Payload(index) => {
+                    let Value::Enum(_, _, mut values) = self.pop() else {
+                        unreachable!("the compiler only reads the values of a matched variant");
+                    };
+                    self.stack.push(values.swap_remove(index as usize));
+                },
+
+                Instruction::Jump(target) => ip = target as usize,
+                Instruction::JumpIfFalse(target) => {
+                    if !self.pop().is_truthy() {
+                        ip = target as usize;
+                    }
+                },
+                Instruction::Next(slot, target) => {
+                    let position: usize = base + slot as usize;
+                    let next: Option<(Value, Value)> = self.stack[position + 1].next_element(&self.stack[position])
+                        .map_err(|hint| error(ErrorCode::ER009, span, hint))?;
+
+                    match next {
+                        Some((value, next)) => {
+                            self.stack[position] = next;
+                            self.stack.push(value);
+                        },
+                        None => ip = target as usize,
+                    }
+                },
+
+                Instruction::Call(slot, count) => {
+                    let arguments: usize = self.stack.len() - count as usize;
+
+                    match &program.functions[slot as usize] {
+                        Some(Callable::Native(native)) => {
+                            let value: Value = native(&self.stack[arguments..])
+                                .map_err(|message| error(ErrorCode::ER007, span, message))?;
+                            self.stack.truncate(arguments);
+                            self.stack.push(value);
+                        },
+                        Some(Callable::Script(callee)) => {
+                            self.frames.last_mut().unwrap().ip = ip;
+                            self.enter(callee.clone(), arguments, span)?;
+
+                            function = callee.clone();
+                            ip = 0;
+                            base = arguments;
+                        },
+                        None => return Err(error(ErrorCode::ER008, span,
+                            format!("'{}' is not defined", program.function_name(slot as usize)))),
+                    }
+                },
+
+                Instruction::Return | Instruction::ReturnVoid => {
+                    let value: Value = if instruction == Instruction::Return {
+                        self.pop().cast(function.return_kind)
+                    }
+                    else {
+                        Value::Void
+                    };
+
+                    self.stack.truncate(base);
+                    if !self.frames.pop().unwrap().script {
+                        self.depth -= 1;
+                    }
+                    if self.frames.len() == entry {
+                        return Ok(value);
+                    }
+                    self.stack.push(value);
+
+                    let frame: &Frame = self.frames.last().unwrap();
+                    function = frame.function.clone();
+                    ip = frame.ip;
+                    base = frame.base;
+                },
+
+                Instruction::Abort(code) => return Err(error(code, span, abort_hint(code, &function))),
+            }
+        }
+    }
+
+    fn enter(&mut self, function: Rc<Function>, base: usize, span: StatementSpan) -> Result<(), RuntimeError> {
+        if self.depth >= MAX_CALL_DEPTH {
+            return Err(error(ErrorCode::ER010, span, format!("calls nested deeper than {}", MAX_CALL_DEPTH)));
+        }
+
+        for (value, (kind, _)) in self.stack[base..].iter_mut().zip(&function.parameters) {
+            *value = std::mem::replace(value, Value::Void).cast(*kind);
+        }
+
+        self.frames.push(Frame { function, ip: 0, base, script: false });
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Value {
+        self.stack.pop().expect("the compiler keeps the stack balanced")
+    }
+}
+
+fn enum_type(program: &Program, slot: u16) -> &Rc<EnumType> {
+    match &program.types[slot as usize].definition {
+        Definition::Enum(type_) => type_,
+        Definition::Struct(_) => unreachable!("the compiler only builds variants from enum slots"),
+    }
+}
+
+fn abort_hint(code: ErrorCode, function: &Function) -> String {
+    match code {
+        ErrorCode::ER003 => "'break' and 'continue' can only be used inside a 'while' or 'for' body".to_string(),
+        ErrorCode::ER004 => "'return' can only be used inside a function body".to_string(),
+        ErrorCode::ER005 => format!("'{}' must return a value of type '{}'", function.name, function.return_kind),
+        ErrorCode::ER011 => NO_ARM.to_string(),
+        _ => format!("aborted with {:?}", code),
+    }
+}
+
+fn error(code: ErrorCode, span: StatementSpan, hint: String) -> RuntimeError {
+    RuntimeError { code, span, hint }
+}
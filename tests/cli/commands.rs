@@ -0,0 +1,183 @@
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::process::{Command, Output, Stdio};
+
+    // Runs the binary with the source on standard input
+    fn rcompiler(arguments: &[&str], source: &str) -> Output {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_rcompiler"))
+            .args(arguments)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        child.stdin.take().unwrap().write_all(source.as_bytes()).unwrap();
+        child.wait_with_output().unwrap()
+    }
+
+    // Same as 'rcompiler', but the reader closes standard output before anything is written
+    fn rcompiler_closed(arguments: &[&str], source: &str) -> Output {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_rcompiler"))
+            .args(arguments)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        drop(child.stdout.take());
+        child.stdin.take().unwrap().write_all(source.as_bytes()).unwrap();
+        child.wait_with_output().unwrap()
+    }
+
+    fn stdout(output: &Output) -> String {
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    }
+
+    #[test]
+    fn test_run_reads_stdin() {
+        let output = rcompiler(&["run", "-"], "println(1 + 2);");
+        assert_eq!(output.status.code(), Some(0));
+        assert_eq!(stdout(&output), "3\n");
+    }
+
+    #[test]
+    fn test_f32_prints_at_its_own_precision() {
+        let output = rcompiler(&["run", "-"], "f32 x = 0.1;\nprintln(x);");
+        assert_eq!(stdout(&output), "0.1\n");
+    }
+
+    #[test]
+    fn test_caret_is_under_the_column() {
+        let source = format!("{}i32 ab = nope;", "\n".repeat(11));
+        let output = stdout(&rcompiler(&["check", "-"], &source));
+
+        // Without the colors, the caret has to line up with the code above it
+        let mut text = String::new();
+        let mut escape = false;
+        for character in output.chars() {
+            match character {
+                '\x1b' => escape = true,
+                'm' if escape => escape = false,
+                character if !escape => text.push(character),
+                _ => {},
+            }
+        }
+        let lines: Vec<&str> = text.lines().collect();
+        let code = lines.iter().position(|line| line.ends_with("i32 ab = nope;")).unwrap();
+        assert_eq!(lines[code + 1].find('^'), lines[code].find("nope"));
+    }
+
+    #[test]
+    fn test_exit_codes() {
+        assert_eq!(rcompiler(&["check", "-"], "i32 x = 1; println(x);").status.code(), Some(0));
+        assert_eq!(rcompiler(&["check", "-"], "i32 x = ;").status.code(), Some(1));
+        assert_eq!(rcompiler(&["check", "-"], "println(y);").status.code(), Some(1));
+        assert_eq!(rcompiler(&["run", "-"], "println(1 / 0);").status.code(), Some(1));
+        assert_eq!(rcompiler(&["run", "missing/file.rc"], "").status.code(), Some(2));
+        assert_eq!(rcompiler(&["explain", "EP999"], "").status.code(), Some(2));
+        assert_eq!(rcompiler(&[], "").status.code(), Some(2));
+    }
+
+    #[test]
+    fn test_check_does_not_run() {
+        let output = rcompiler(&["check", "-"], "println(1);");
+        assert_eq!(output.status.code(), Some(0));
+        assert_eq!(stdout(&output), "");
+    }
+
+    #[test]
+    fn test_warnings() {
+        let output = rcompiler(&["check", "-"], "i32 x = 1;");
+        assert_eq!(output.status.code(), Some(0));
+        assert!(stdout(&output).contains("WL001"));
+
+        let output = rcompiler(&["--quiet", "check", "-"], "i32 x = 1;");
+        assert_eq!(stdout(&output), "");
+
+        // Denied lints are errors, so they are shown even with --quiet
+        let output = rcompiler(&["--quiet", "-D", "warnings", "check", "-"], "i32 x = 1;");
+        assert_eq!(output.status.code(), Some(1));
+        assert!(stdout(&output).contains("WL001"));
+    }
+
+    #[test]
+    fn test_tokens_and_ast() {
+        let output = rcompiler(&["tokens", "-"], "i32 x;");
+        assert_eq!(stdout(&output).lines().count(), 4);
+
+        let output = rcompiler(&["ast", "-"], "i32 x = 1;");
+        assert_eq!(output.status.code(), Some(0));
+        assert!(stdout(&output).contains("VariableDeclaration"));
+    }
+
+    #[test]
+    fn test_closed_stdout() {
+        let output = rcompiler_closed(&["ast", "-"], "i32 x = 1;");
+        assert_eq!(output.status.code(), Some(0));
+        assert!(output.stderr.is_empty());
+
+        let output = rcompiler_closed(&["tokens", "-"], "i32 x;");
+        assert_eq!(output.status.code(), Some(0));
+        assert!(output.stderr.is_empty());
+
+        let output = rcompiler_closed(&["check", "-"], "println(y);");
+        assert_eq!(output.status.code(), Some(1));
+        assert!(output.stderr.is_empty());
+    }
+
+    #[test]
+    fn test_diagnostics_name_stdin() {
+        let output = rcompiler(&["--error-format=json", "check", "-"], "println(y);");
+        assert!(String::from_utf8_lossy(&output.stderr).contains("\"file\":\"<stdin>\""));
+    }
+
+    #[test]
+    fn test_fmt() {
+        let output = rcompiler(&["fmt", "-"], "i32 x=1;");
+        assert_eq!(output.status.code(), Some(0));
+        assert_eq!(stdout(&output), "i32 x = 1;\n");
+
+        assert_eq!(rcompiler(&["fmt", "--check", "-"], "i32 x=1;").status.code(), Some(1));
+        assert_eq!(rcompiler(&["fmt", "--check", "-"], "i32 x = 1;\n").status.code(), Some(0));
+        assert_eq!(rcompiler(&["fmt", "--check", "-"], "i32 x = ;").status.code(), Some(1));
+    }
+
+    #[test]
+    fn test_import_path() {
+        let directory = std::env::temp_dir().join(format!("rcompiler-cli-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(directory.join("math.rc"), "namespace math { fn square(i32 x) i32 { return x * x; } }").unwrap();
+        let directory = directory.to_str().unwrap();
+
+        let output = rcompiler(&["run", "-I", directory, "-"], "import math;\nprintln(math::square(3));");
+        assert_eq!(output.status.code(), Some(0));
+        assert_eq!(stdout(&output), "9\n");
+
+        let output = rcompiler(&["check", "-"], "import math;");
+        assert_eq!(output.status.code(), Some(1));
+        assert!(stdout(&output).contains("EM001"));
+    }
+
+    #[test]
+    #[cfg(not(feature = "serde"))]
+    fn test_json_output_needs_the_feature() {
+        let output = rcompiler(&["ast", "--format", "json", "-"], "i32 x;");
+        assert_eq!(output.status.code(), Some(2));
+        assert!(String::from_utf8_lossy(&output.stderr).contains("'serde' feature"));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_json_output() {
+        let output = rcompiler(&["ast", "--format", "json", "-"], "i32 x;");
+        let json: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+        assert_eq!(json[0]["node"]["VariableDeclaration"]["name"], "x");
+
+        let output = rcompiler(&["tokens", "--format", "json", "-"], "i32 x;");
+        let json: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+        assert_eq!(json.as_array().unwrap().len(), 4);
+    }
+}
@@ -0,0 +1 @@
+mod commands;
\ No newline at end of file
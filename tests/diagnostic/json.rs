@@ -0,0 +1,126 @@
+#[cfg(test)]
+mod tests {
+    use rcompiler::error::*;
+    use rcompiler::parser::*;
+    use rcompiler::semantics::*;
+    use rcompiler::source::*;
+    use rcompiler::tokenizer::*;
+
+    use serde_json::Value;
+
+    fn parse_json(diagnostic: &Diagnostic) -> Value {
+        let json = diagnostic.to_json();
+        assert!(!json.contains('\n'), "one diagnostic per line");
+        serde_json::from_str(&json).unwrap()
+    }
+
+    #[test]
+    fn test_schema_fields() {
+        let mut sources = SourceMap::new();
+        let source = "i32 x = 1;\ni32 y = 2 @ 3;";
+        let file = sources.add("main.rc", source);
+
+        let error = Tokenizer::with_file(source, file).tokenize().unwrap_err();
+        let json = parse_json(&error.to_diagnostic(&sources));
+
+        assert_eq!(json["code"], "ET004");
+        assert_eq!(json["severity"], "error");
+        assert_eq!(json["message"], "Unexpected or unrecognized character in source");
+        assert_eq!(json["hint"], "this character is not supported in this position");
+        assert_eq!(json["file"], "main.rc");
+        assert_eq!(json["line"], 2);
+        assert_eq!(json["column"], 11);
+        assert_eq!(json["span"]["start"], 21);
+        assert_eq!(json["span"]["end"], 22);
+        assert_eq!(&source[21..22], "@");
+    }
+
+    #[test]
+    fn test_span_of_later_phases() {
+        let mut sources = SourceMap::new();
+        let source = "i32 a = 1;\ni32 b = missing + a;";
+        let file = sources.add("main.rc", source);
+
+        let tokens = Tokenizer::with_file(source, file).tokenize().unwrap();
+        let statements = Parser::new(tokens).parse().unwrap();
+        let errors = SemanticAnalyzer::new(&statements).analyze().unwrap_err();
+        let json = parse_json(&errors[0].to_diagnostic(&sources));
+
+        assert_eq!(json["code"], "ES001");
+        let (start, end) = (json["span"]["start"].as_u64().unwrap() as usize, json["span"]["end"].as_u64().unwrap() as usize);
+        assert_eq!(&source[start..end], "missing");
+    }
+
+    #[test]
+    fn test_span_of_parser_errors() {
+        let mut sources = SourceMap::new();
+        let source = "i32 a = 1;\ni32 b = a + ;";
+        let file = sources.add("main.rc", source);
+
+        // The span is the token the error points at, not the whole statement
+        let tokens = Tokenizer::with_file(source, file).tokenize().unwrap();
+        let error = Parser::new(tokens).parse().unwrap_err();
+        let json = parse_json(&error.to_diagnostic(&sources));
+
+        assert_eq!(json["code"], "EP032");
+        let (start, end) = (json["span"]["start"].as_u64().unwrap() as usize, json["span"]["end"].as_u64().unwrap() as usize);
+        assert_eq!(&source[start..end], "+");
+
+        let source = "i32 a = 1;\n) i32 b = 2;";
+        let file = sources.add("other.rc", source);
+        let tokens = Tokenizer::with_file(source, file).tokenize().unwrap();
+        let error = Parser::new(tokens).parse().unwrap_err();
+        let json = parse_json(&error.to_diagnostic(&sources));
+
+        assert_eq!(json["code"], "EP036");
+        let (start, end) = (json["span"]["start"].as_u64().unwrap() as usize, json["span"]["end"].as_u64().unwrap() as usize);
+        assert_eq!(&source[start..end], ")");
+    }
+
+    #[test]
+    fn test_columns_match_the_span() {
+        let mut sources = SourceMap::new();
+
+        // Every phase reports the column the span starts at
+        let source = "i32 abcdef = 12345";
+        let file = sources.add("main.rc", source);
+        let tokens = Tokenizer::with_file(source, file).tokenize().unwrap();
+        let error = Parser::new(tokens).parse().unwrap_err();
+        let json = parse_json(&error.to_diagnostic(&sources));
+        assert_eq!(json["code"], "EP005");
+        assert_eq!((json["line"].clone(), json["column"].clone()), (1.into(), 14.into()));
+        assert_eq!((json["span"]["start"].clone(), json["span"]["end"].clone()), (13.into(), 18.into()));
+
+        let source = "i32 x = @;";
+        let file = sources.add("other.rc", source);
+        let error = Tokenizer::with_file(source, file).tokenize().unwrap_err();
+        let json = parse_json(&error.to_diagnostic(&sources));
+        assert_eq!((json["column"].clone(), json["span"]["start"].clone()), (9.into(), 8.into()));
+    }
+
+    #[test]
+    fn test_strings_are_escaped() {
+        let mut sources = SourceMap::new();
+        let file = sources.add("dir\\\"quoted\"\n.rc", "'\\q'");
+
+        let error = Tokenizer::with_file("'\\q'", file).tokenize().unwrap_err();
+        let json = parse_json(&error.to_diagnostic(&sources));
+
+        assert_eq!(json["file"], "dir\\\"quoted\"\n.rc");
+        assert!(json["hint"].as_str().unwrap().contains("\\n, \\r"));
+    }
+
+    #[test]
+    fn test_missing_hint_is_null() {
+        let sources = SourceMap::new();
+        let diagnostic = Diagnostic {
+            kind: DiagnosticKind::Error(ErrorCode::EB001),
+            info: DiagnosticInfo::from_span(&sources, FileId::default(), 0, 0),
+            hint: None,
+        };
+        let json = parse_json(&diagnostic);
+
+        assert!(json["hint"].is_null());
+        assert_eq!(json["file"], "<unknown>");
+    }
+}
@@ -0,0 +1 @@
+mod json;
\ No newline at end of file
@@ -0,0 +1,232 @@
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use rcompiler::engine::*;
+    use rcompiler::value::*;
+    use rcompiler::error::*;
+
+    #[test]
+    fn test_globals_round_trip() {
+        let mut engine = Engine::new();
+        engine.eval("i32 x = 40; str name = \"rshell\";").unwrap();
+
+        assert_eq!(engine.get_global::<i32>("x").unwrap(), 40);
+        assert_eq!(engine.get_global::<String>("name").unwrap(), "rshell");
+
+        engine.set_global("x", 41).unwrap();
+        engine.eval("x += 1;").unwrap();
+        assert_eq!(engine.get_global::<i64>("x").unwrap(), 42);
+    }
+
+    #[test]
+    fn test_host_globals_are_typed() {
+        let mut engine = Engine::new();
+        engine.eval("u8 limit = 1;").unwrap();
+        engine.set_global("limit", 200).unwrap();
+        assert_eq!(engine.get_global::<u8>("limit").unwrap(), 200);
+
+        engine.set_global("ratio", 0.5f32).unwrap();
+        engine.eval("f32 half = ratio;").unwrap();
+        assert_eq!(engine.get_global::<f32>("half").unwrap(), 0.5);
+
+        engine.set_global("flags", vec![true, false]).unwrap();
+        engine.eval("bool first = flags[0];").unwrap();
+        assert!(engine.get_global::<bool>("first").unwrap());
+
+        let error = engine.compile("limit = true;").err().unwrap();
+        let EngineError::Compile(errors) = error else { panic!("expected a compile error") };
+        assert_eq!(errors[0].code, ErrorCode::EC002);
+    }
+
+    #[test]
+    fn test_host_globals_keep_their_type() {
+        let mut engine = Engine::new();
+        engine.eval("i32 x = 1; u8 limit = 1; bool[2] flags; struct P { i32 x; } P p;").unwrap();
+
+        assert_eq!(engine.set_global("x", "hello"), Err(EngineError::Conversion { expected: "i32", found: "str" }));
+        assert_eq!(engine.set_global("limit", 300), Err(EngineError::Conversion { expected: "u8", found: "signed integer" }));
+        assert_eq!(engine.set_global("limit", -1), Err(EngineError::Conversion { expected: "u8", found: "signed integer" }));
+        assert_eq!(engine.set_global("flags", true), Err(EngineError::Conversion { expected: "array", found: "bool" }));
+        assert_eq!(engine.set_global("flags", vec![1, 2]), Err(EngineError::Conversion { expected: "bool", found: "signed integer" }));
+        assert_eq!(engine.set_global("p", 1.5), Err(EngineError::Conversion { expected: "struct or enum", found: "float" }));
+        assert_eq!(engine.get_global::<i32>("x").unwrap(), 1);
+        assert_eq!(engine.get_global::<u8>("limit").unwrap(), 1);
+
+        engine.set_global("x", Value::UInt(7)).unwrap();
+        assert_eq!(engine.get_global::<i32>("x").unwrap(), 7);
+    }
+
+    #[test]
+    fn test_host_globals_keep_their_struct() {
+        let mut engine = Engine::new();
+        engine.eval("struct P { i32 x; } enum E { A } P p; P other = P { x = 2 }; E e = E::A;").unwrap();
+        let (other, e) = (engine.get_global::<Value>("other").unwrap(), engine.get_global::<Value>("e").unwrap());
+
+        assert_eq!(engine.set_global("p", e.clone()), Err(EngineError::Conversion { expected: "struct or enum", found: "enum" }));
+        engine.set_global("p", other.clone()).unwrap();
+        engine.eval("i32 x = p.x;").unwrap();
+        assert_eq!(engine.get_global::<i32>("x").unwrap(), 2);
+
+        // A global created by the host gets the struct of its value
+        engine.set_global("q", other).unwrap();
+        assert_eq!(engine.set_global("q", e), Err(EngineError::Conversion { expected: "struct or enum", found: "enum" }));
+    }
+
+    #[test]
+    fn test_call_script_function_by_name() {
+        let mut engine = Engine::new();
+        engine.eval("fn add(i32 a, i32 b) i32 { return a + b; }").unwrap();
+
+        assert_eq!(engine.call_fn::<i32>("add", (2, 3)).unwrap(), 5);
+        assert_eq!(engine.call_fn::<i64>("add", vec![Value::Int(1), Value::Int(1)]).unwrap(), 2);
+
+        assert!(matches!(engine.call_fn::<i32>("add", (1,)), Err(EngineError::ArgumentCount { expected: 2, found: 1, .. })));
+        assert!(matches!(engine.call_fn::<i32>("missing", ()), Err(EngineError::UndefinedFunction(_))));
+        assert!(matches!(engine.call_fn::<bool>("add", (1, 2)), Err(EngineError::Conversion { .. })));
+    }
+
+    #[test]
+    fn test_call_fn_checks_the_arguments() {
+        let mut engine = Engine::new();
+        engine.eval("fn f(i32 a, u8 b) u8 { return b; } fn sum(i32[2] a) i32 { return a[0] + a[1]; }").unwrap();
+
+        assert_eq!(engine.call_fn::<u8>("f", ("one", 2)), Err(EngineError::Conversion { expected: "i32", found: "str" }));
+        assert_eq!(engine.call_fn::<u8>("f", (1, 300i32)), Err(EngineError::Conversion { expected: "u8", found: "signed integer" }));
+        assert_eq!(engine.call_fn::<i32>("sum", (3,)), Err(EngineError::Conversion { expected: "array", found: "signed integer" }));
+        assert_eq!(engine.call_fn::<i32>("sum", (vec![1.5, 2.0],)), Err(EngineError::Conversion { expected: "i32", found: "float" }));
+
+        assert_eq!(engine.call_fn::<u8>("f", (1, 255)).unwrap(), 255);
+        assert_eq!(engine.call_fn::<i32>("sum", (vec![1, 2],)).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_functions_persist_between_scripts() {
+        let mut engine = Engine::new();
+        engine.eval("fn double(i64 x) i64 { return x * 2; }").unwrap();
+        engine.eval("i64 y = double(21);").unwrap();
+        assert_eq!(engine.get_global::<i64>("y").unwrap(), 42);
+    }
+
+    #[test]
+    fn test_structs_persist_between_scripts() {
+        let mut engine = Engine::new();
+        engine.eval("struct Point { i32 x; i32 y; } Point p = Point { x = 1, y = 2 };").unwrap();
+        engine.eval("p.x += 10; Point q = Point { y = p.x, x = 0 }; Point[2] ps;").unwrap();
+        assert_eq!(engine.get_global::<Value>("q").unwrap().to_string(), "Point { x = 0, y = 11 }");
+        assert_eq!(engine.eval_expression::<i32>("ps[1].y + p.y").unwrap(), 2);
+
+        // The fields of a struct declared by an earlier script are checked when the script is compiled
+        let EngineError::Compile(errors) = engine.compile("Point r = Point { x = 1 };").err().unwrap() else {
+            panic!("expected a compile error");
+        };
+        assert_eq!(errors[0].code, ErrorCode::EC017);
+    }
+
+    #[test]
+    fn test_enums_persist_between_scripts() {
+        let mut engine = Engine::new();
+        engine.eval("enum State { Idle, Failed(str, i32) } State s = State::Failed(\"disk\", 2);").unwrap();
+        engine.eval("i32 code = match s { State::Failed(_, code) => code, _ => 0 }; State t;").unwrap();
+        assert_eq!(engine.get_global::<i32>("code").unwrap(), 2);
+        assert_eq!(engine.get_global::<Value>("t").unwrap().to_string(), "State::Idle");
+
+        // The values of a variant declared by an earlier script are counted when the script is compiled
+        let EngineError::Compile(errors) = engine.compile("State u = State::Failed(\"disk\");").err().unwrap() else {
+            panic!("expected a compile error");
+        };
+        assert_eq!(errors[0].code, ErrorCode::EC022);
+    }
+
+    #[test]
+    fn test_register_typed_native() {
+        let mut engine = Engine::new();
+        engine.register_fn("square", |x: i64| x * x);
+        engine.register_fn("greet", |name: String| format!("hi {}", name));
+
+        engine.eval("i64 x = square(7); str s = greet(\"bob\");").unwrap();
+        assert_eq!(engine.get_global::<i64>("x").unwrap(), 49);
+        assert_eq!(engine.get_global::<String>("s").unwrap(), "hi bob");
+
+        // Calls are checked against the Rust types of the closure when the script is compiled
+        let EngineError::Compile(errors) = engine.compile("i64 y = square(true);").err().unwrap() else {
+            panic!("expected a compile error");
+        };
+        assert_eq!(errors[0].code, ErrorCode::EC004);
+
+        let EngineError::Compile(errors) = engine.compile("bool b = square(3);").err().unwrap() else {
+            panic!("expected a compile error");
+        };
+        assert_eq!(errors[0].code, ErrorCode::EC001);
+
+        let EngineError::Compile(errors) = engine.compile("str s = greet();").err().unwrap() else {
+            panic!("expected a compile error");
+        };
+        assert_eq!(errors[0].code, ErrorCode::EC005);
+    }
+
+    #[test]
+    fn test_functions_of_earlier_scripts_are_type_checked() {
+        let mut engine = Engine::new();
+        engine.eval("fn half(i32 x) i32 { return x / 2; }").unwrap();
+
+        assert_eq!(engine.eval_expression::<i32>("half(8)").unwrap(), 4);
+        let EngineError::Compile(errors) = engine.compile("bool b = half(8);").err().unwrap() else {
+            panic!("expected a compile error");
+        };
+        assert_eq!(errors[0].code, ErrorCode::EC001);
+    }
+
+    #[test]
+    fn test_register_raw_native() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let log = calls.clone();
+
+        let mut engine = Engine::new();
+        engine.register_native("log", move |arguments| {
+            log.borrow_mut().push(arguments.len());
+            Ok(Value::Void)
+        });
+
+        engine.eval("log(1, 2, 3); log();").unwrap();
+        assert_eq!(*calls.borrow(), [3, 0]);
+    }
+
+    #[test]
+    fn test_scripts_can_run_again() {
+        let mut engine = Engine::new();
+        engine.set_global("count", 0i32).unwrap();
+
+        let script = engine.compile("count += 1;").unwrap();
+        for _ in 0..3 {
+            engine.run(&script).unwrap();
+        }
+        assert_eq!(engine.get_global::<i32>("count").unwrap(), 3);
+    }
+
+    #[test]
+    fn test_scripts_only_run_on_their_engine() {
+        let mut engine = Engine::new();
+        engine.set_global("count", 0i32).unwrap();
+        let script = engine.compile("count += 1;").unwrap();
+
+        let mut other = Engine::new();
+        assert_eq!(other.run(&script), Err(EngineError::ForeignScript));
+    }
+
+    #[test]
+    fn test_error_positions() {
+        let mut engine = Engine::new();
+
+        let EngineError::Compile(errors) = engine.compile("i32 x = 1;\ni32 y = z;").err().unwrap() else {
+            panic!("expected a compile error");
+        };
+        assert_eq!((errors[0].code, errors[0].line), (ErrorCode::ES001, 2));
+
+        let EngineError::Runtime(error) = engine.eval("i32 a = 0;\ni32 b = 1 / a;").err().unwrap() else {
+            panic!("expected a runtime error");
+        };
+        assert_eq!((error.code, error.line), (ErrorCode::ER001, 2));
+    }
+}
@@ -0,0 +1 @@
+mod api;
\ No newline at end of file
@@ -0,0 +1,112 @@
+#[cfg(test)]
+mod tests {
+    use rcompiler::engine::*;
+    use rcompiler::error::*;
+    use rcompiler::explain::*;
+    use rcompiler::lints::*;
+    use rcompiler::parser::*;
+    use rcompiler::tokenizer::*;
+    use rcompiler::value::Value;
+    use std::path::PathBuf;
+    use std::sync::OnceLock;
+
+    // Codes that checked programs can't report, their examples only illustrate the mistake
+    const UNREACHABLE: [&str; 6] = ["ER003", "ER004", "ER008", "ER009", "EB001", "EB002"];
+
+    // Codes whose examples are made of more files, which a single source can't show
+    const MULTI_FILE: [&str; 2] = ["EM002", "EM003"];
+
+    // The module the import examples use, written once for every test
+    fn modules() -> &'static PathBuf {
+        static MODULES: OnceLock<PathBuf> = OnceLock::new();
+        MODULES.get_or_init(|| {
+            let directory = std::env::temp_dir().join(format!("rcompiler-explain-{}", std::process::id()));
+            std::fs::create_dir_all(&directory).unwrap();
+            std::fs::write(directory.join("math.rc"), "namespace math {\n    fn square(i32 x) i32 {\n        return x * x;\n    }\n}").unwrap();
+            directory
+        })
+    }
+
+    fn error_codes(error: EngineError) -> Vec<String> {
+        let errors: Vec<ScriptError> = match error {
+            EngineError::Syntax(error) | EngineError::Runtime(error) => vec![error],
+            EngineError::Compile(errors) => errors,
+            other => panic!("unexpected error {:?}", other),
+        };
+        errors.iter().map(|error| DiagnosticKind::Error(error.code).code_to_string()).collect()
+    }
+
+    // The examples print, their output would end up in the test output
+    fn engine() -> Engine {
+        let mut engine = Engine::new();
+        engine.register_native("print", |_| Ok(Value::Void));
+        engine.register_native("println", |_| Ok(Value::Void));
+        engine.add_search_path(modules());
+        engine
+    }
+
+    // Codes reported by a program, the ones of the errors or else the ones of the warnings
+    fn reported(code: &str, source: &str) -> Vec<String> {
+        // Only standalone expressions can have leftover tokens
+        if code == "EP035" {
+            return match engine().eval_expression::<Value>(source) {
+                Ok(_) => Vec::new(),
+                Err(error) => error_codes(error),
+            };
+        }
+
+        if let Err(error) = engine().eval(source) {
+            return error_codes(error);
+        }
+
+        let tokens = Tokenizer::new(source).tokenize().unwrap();
+        let statements = Parser::new(tokens).parse().unwrap();
+        Linter::new(&statements, &LintConfig::new()).lint().iter()
+            .map(|warning| DiagnosticKind::Warning(warning.code).code_to_string())
+            .collect()
+    }
+
+    fn kinds() -> impl Iterator<Item = DiagnosticKind> {
+        ErrorCode::ALL.into_iter().map(DiagnosticKind::Error)
+            .chain(WarningCode::ALL.into_iter().map(DiagnosticKind::Warning))
+    }
+
+    #[test]
+    fn test_every_entry_is_complete() {
+        for kind in kinds() {
+            let code = kind.code_to_string();
+            let explanation = explanation(kind);
+
+            assert!(explanation.text.contains("\n\n"), "{} needs more than one paragraph", code);
+            assert!(!explanation.wrong.is_empty() && !explanation.right.is_empty());
+            assert_eq!(explain(&code).map(|(found, _)| found), Some(kind));
+        }
+    }
+
+    #[test]
+    fn test_lookup_ignores_case() {
+        assert_eq!(explain("ep013").unwrap().0, DiagnosticKind::Error(ErrorCode::EP013));
+        assert_eq!(explain("wl004").unwrap().0, DiagnosticKind::Warning(WarningCode::WL004));
+        assert!(explain("EP999").is_none());
+        assert!(explain("").is_none());
+    }
+
+    #[test]
+    fn test_examples() {
+        for kind in kinds() {
+            let (code, explanation) = (kind.code_to_string(), explanation(kind));
+            if UNREACHABLE.contains(&code.as_str()) || MULTI_FILE.contains(&code.as_str()) {
+                continue;
+            }
+
+            let wrong = reported(&code, explanation.wrong);
+            assert!(wrong.contains(&code),
+                "the wrong example of {} reports {:?}", code, wrong);
+
+            // Other warnings are fine, the right example only has to fix this code
+            let right = reported(&code, explanation.right);
+            assert!(!right.iter().any(|reported| *reported == code || reported.starts_with('E')),
+                "the right example of {} reports {:?}", code, right);
+        }
+    }
+}
@@ -0,0 +1 @@
+mod index;
\ No newline at end of file
@@ -0,0 +1,176 @@
+#[cfg(test)]
+mod tests {
+    use rcompiler::formatter::*;
+    use rcompiler::parser::*;
+    use rcompiler::tokenizer::*;
+
+    fn format(source: &str) -> String {
+        let tokens = Tokenizer::new(source).tokenize().unwrap();
+        let statements = Parser::new(tokens.clone()).parse().unwrap();
+        Formatter::new(&tokens).format(&statements)
+    }
+
+    // The Debug output of the statements leaves the spans out
+    fn ast(source: &str) -> String {
+        let tokens = Tokenizer::new(source).tokenize().unwrap();
+        format!("{:?}", Parser::new(tokens).parse().unwrap())
+    }
+
+    fn assert_round_trip(source: &str) {
+        let formatted = format(source);
+        assert_eq!(ast(&formatted), ast(source), "formatting changed the meaning of:\n{}", formatted);
+        assert_eq!(format(&formatted), formatted, "formatting isn't stable for:\n{}", formatted);
+    }
+
+    #[test]
+    fn test_spacing_and_indentation() {
+        let source = "i32  x=1+2*3;fn f(i32 a,i32[3] b)i32{if a>0{return a;}return len(b);}";
+        let expected = "\
+i32 x = 1 + 2 * 3;
+fn f(i32 a, i32[3] b) i32 {
+    if a > 0 {
+        return a;
+    }
+    return len(b);
+}
+";
+        assert_eq!(format(source), expected);
+    }
+
+    #[test]
+    fn test_else_branches_and_empty_bodies() {
+        let source = "if a { } elif b { c(); } else { while d {} }";
+        let expected = "\
+if a {}
+elif b {
+    c();
+}
+else {
+    while d {}
+}
+";
+        assert_eq!(format(source), expected);
+    }
+
+    #[test]
+    fn test_for_loops() {
+        assert_eq!(format("for  u8 i  in 0 .. n+1{println(i);}"), "for u8 i in 0..n + 1 {\n    println(i);\n}\n");
+        assert_eq!(format("for x in a {}"), "for x in a {}\n");
+    }
+
+    #[test]
+    fn test_namespaces() {
+        let source = "namespace math{const i32 two=2;namespace inner{}fn sq(i32 x)i32{return x*math :: two;}}";
+        let expected = "\
+namespace math {
+    const i32 two = 2;
+    namespace inner {}
+    fn sq(i32 x) i32 {
+        return x * math::two;
+    }
+}
+";
+        assert_eq!(format(source), expected);
+    }
+
+    #[test]
+    fn test_parentheses_are_kept_only_when_needed() {
+        assert_eq!(format("x = ((a + b)) * c;"), "x = (a + b) * c;\n");
+        assert_eq!(format("x = a - (b - c);"), "x = a - (b - c);\n");
+        assert_eq!(format("x = (a - b) - c;"), "x = a - b - c;\n");
+        assert_eq!(format("x = -(a + b) + (-c);"), "x = -(a + b) + -c;\n");
+        assert_eq!(format("x = (a || b) && !(c == d);"), "x = (a || b) && !(c == d);\n");
+        assert_eq!(format("x = (1 << 2) + 3;"), "x = (1 << 2) + 3;\n");
+    }
+
+    #[test]
+    fn test_comments_are_kept() {
+        let source = "\
+# header
+i32 x = 1;   # trailing
+fn f() {
+  # inside
+  x = 2;
+  # last
+}
+# end";
+        let expected = "\
+# header
+i32 x = 1; # trailing
+fn f() {
+    # inside
+    x = 2;
+    # last
+}
+# end
+";
+        assert_eq!(format(source), expected);
+    }
+
+    #[test]
+    fn test_comment_only_bodies_stay_open() {
+        assert_eq!(format("while true {\n# todo\n}"), "while true {\n    # todo\n}\n");
+    }
+
+    #[test]
+    fn test_structs() {
+        let source = "struct Point{i32 x;# across\n\n\ni32 y;}Point p=Point{x=1,y=2};p.x+=p.y;";
+        let expected = "\
+struct Point {
+    i32 x; # across
+
+    i32 y;
+}
+Point p = Point { x = 1, y = 2 };
+p.x += p.y;
+";
+        assert_eq!(format(source), expected);
+        assert_eq!(format("struct Empty {} Empty e = Empty {};"), "struct Empty {}\nEmpty e = Empty {};\n");
+        assert_eq!(format("Point[2] ps=[Point{x=1},p,];ps[ 0 ].x*=2;"), "Point[2] ps = [Point { x = 1 }, p];\nps[0].x *= 2;\n");
+    }
+
+    #[test]
+    fn test_enums_and_match() {
+        let source = "enum State{Idle,# waiting\nFailed(str,i32)}match s{State::Failed(m,_)=>{println(m);}_=>{}}i32 x=match s{State::Idle=>-1,_=>0,};";
+        let expected = "\
+enum State {
+    Idle, # waiting
+    Failed(str, i32),
+}
+match s {
+    State::Failed(m, _) => {
+        println(m);
+    }
+    _ => {}
+}
+i32 x = match s { State::Idle => -1, _ => 0 };
+";
+        assert_eq!(format(source), expected);
+        assert_eq!(format("enum Empty {}"), "enum Empty {}\n");
+    }
+
+    #[test]
+    fn test_blank_lines_are_collapsed() {
+        let source = "i32 x = 1;\n\n\n\ni32 y = 2;\nfn f() {\n\n    x = 3;\n\n}";
+        assert_eq!(format(source), "i32 x = 1;\n\ni32 y = 2;\nfn f() {\n    x = 3;\n}\n");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        assert_round_trip("const i32 limit = 10; str s = \"a # not a comment\"; char c = '#';");
+        assert_round_trip("i32 x = 1 | 2 ^ 3 & 4 == 5 < 6 << 7 + 8 * -9 % len(\"ab\");");
+        assert_round_trip("bool b = !(true || false) && (1 + 2) * 3 >= 4;");
+        assert_round_trip("i32[4] a; i32 i = a[(1 + 2) * 0]; i += a[0] - (a[1] - a[2]);");
+        assert_round_trip("fn f(i32 n) i32 { while n > 0 { n -= 1; if n == 2 { break; } elif n == 3 { continue; } } return n; }");
+        assert_round_trip("for u8 i in 0..len(\"ab\") { i32[2] a; for x in a { println(i, x); } }");
+        assert_round_trip("namespace a { namespace b { fn f() { a::b::f(); } } const i32 x = b::f() + a::x; }");
+        assert_round_trip("import \"lib/math.rc\"; import lib::strings; import util;");
+        assert_round_trip("struct P { geo::Q q; i32[2] a; } P p; p.q.x += (P { q = Q { x = 1 }, a = a, }).q.x; fn f(P p) {}");
+        assert_round_trip("if p == (P { x = 1 }) { } while (P { x = 1 }).x > 0 { } for i in 0..(P { x = 1 }).x { }");
+        assert_round_trip("enum E { A, B(geo::P[2]) } match (P { x = 1 }) { E::B(p) => { } 1 => { } -2.5 => { } _ => { } }");
+        assert_round_trip("i32 x = (match s { _ => 1 }) + match s { E::A => 1 + 2, _ => -(3) } * 2;");
+        assert_round_trip("i32[3] a = [1, -2, f(3)]; a[i + 1] -= [a[0], 4][1]; l[0].ids[1] = 2; i32[0] e = [];");
+        assert_round_trip("# only a comment");
+        assert_round_trip("");
+    }
+}
@@ -0,0 +1 @@
+mod format;
\ No newline at end of file
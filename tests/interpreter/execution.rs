@@ -0,0 +1,168 @@
+#[cfg(test)]
+mod tests {
+    use rcompiler::parser::*;
+    use rcompiler::tokenizer::*;
+    use rcompiler::interpreter::*;
+    use rcompiler::typechecker::*;
+    use rcompiler::value::*;
+    use rcompiler::error::*;
+
+    // Runs a program and returns the value of a global afterwards
+    fn run_and_get(source: &str, name: &str) -> Value {
+        let tokens = Tokenizer::new(source).tokenize().unwrap();
+        let statements = Parser::new(tokens).parse().unwrap();
+
+        let mut interpreter = Interpreter::new();
+        interpreter.run(&statements).unwrap();
+        interpreter.global(name).cloned().unwrap()
+    }
+
+    // Same as 'run_and_get', with the kinds the type checker finds for the arithmetic
+    fn run_checked(source: &str, name: &str) -> Value {
+        let tokens = Tokenizer::new(source).tokenize().unwrap();
+        let statements = Parser::new(tokens).parse().unwrap();
+
+        let mut checker = TypeChecker::new(&statements);
+        checker.check().unwrap();
+
+        let mut interpreter = Interpreter::new().with_kinds(checker.into_kinds());
+        interpreter.run(&statements).unwrap();
+        interpreter.global(name).cloned().unwrap()
+    }
+
+    fn run_error(source: &str) -> ErrorCode {
+        let tokens = Tokenizer::new(source).tokenize().unwrap();
+        let statements = Parser::new(tokens).parse().unwrap();
+
+        Interpreter::new().run(&statements).unwrap_err().code
+    }
+
+    #[test]
+    fn test_declaration_and_arithmetic() {
+        assert_eq!(run_and_get("i32 x = 1 + 2 * 3;", "x"), Value::Int(7));
+        assert_eq!(run_and_get("f64 x = 7.0 / 2;", "x"), Value::Float(3.5));
+    }
+
+    #[test]
+    fn test_default_values() {
+        assert_eq!(run_and_get("u16[3] a;", "a"), Value::Array(vec![Value::UInt(0); 3]));
+        assert_eq!(run_and_get("bool b;", "b"), Value::Bool(false));
+    }
+
+    #[test]
+    fn test_integer_width_wraps_inside_expressions() {
+        assert_eq!(run_checked("u8 a = 200; u8 b = 100; u8 c = (a + b) / 2;", "c"), Value::UInt(22));
+        assert_eq!(run_checked("i32 x = 2147483647; bool b = x + 1 > x;", "b"), Value::Bool(false));
+        assert_eq!(run_checked("u32 x = 5; u32 y = -x; bool b = -x == y;", "b"), Value::Bool(true));
+        assert_eq!(run_checked("u32 x = 5; u32 y = -x;", "y"), Value::UInt(4294967291));
+        assert_eq!(run_checked("i32 m = -2147483647 - 1; bool b = -m == m;", "b"), Value::Bool(true));
+        assert_eq!(run_checked("u8 a = 1; bool b = a << 9 == 0;", "b"), Value::Bool(true));
+    }
+
+    #[test]
+    fn test_integer_width_wraps() {
+        assert_eq!(run_and_get("u8 x = 255; x += 1;", "x"), Value::UInt(0));
+        assert_eq!(run_and_get("i8 x = 127; x = x + 1;", "x"), Value::Int(-128));
+    }
+
+    #[test]
+    fn test_literals() {
+        assert_eq!(run_and_get("char c = '\\n';", "c"), Value::Char('\n'));
+        assert_eq!(run_and_get("char c = '\\x41';", "c"), Value::Char('A'));
+        assert_eq!(run_and_get("str s = \"a\\tb\";", "s"), Value::Str("a\tb".into()));
+    }
+
+    #[test]
+    fn test_if_elif_else_chain() {
+        let source = "i32 x; bool a = false; bool b = true; if a { x = 1; } elif b { x = 2; } else { x = 3; }";
+        assert_eq!(run_and_get(source, "x"), Value::Int(2));
+
+        let source = "i32 x; if false { x = 1; } elif false { x = 2; } else { x = 3; }";
+        assert_eq!(run_and_get(source, "x"), Value::Int(3));
+    }
+
+    #[test]
+    fn test_while_with_break() {
+        let source = "i32 x = 0; while true { x += 1; break; x += 100; }";
+        assert_eq!(run_and_get(source, "x"), Value::Int(1));
+    }
+
+    #[test]
+    fn test_while_with_continue() {
+        let source = "
+            i32 x = 0;
+            bool first = true;
+            bool go = true;
+            while go {
+                x += 1;
+                if first {
+                    first = false;
+                    continue;
+                }
+                go = false;
+            }
+        ";
+        assert_eq!(run_and_get(source, "x"), Value::Int(2));
+    }
+
+    #[test]
+    fn test_body_scopes_are_dropped() {
+        let source = "i32 x = 1; if true { i32 x = 5; x = 6; }";
+        assert_eq!(run_and_get(source, "x"), Value::Int(1));
+    }
+
+    #[test]
+    fn test_functions() {
+        let source = "
+            i32 total = 0;
+            fn add(i32 a, i32 b) i32 { return a + b; }
+            fn store(i32 value) { total = value; return; }
+            store(add(2, 3) * 2);
+        ";
+        assert_eq!(run_and_get(source, "total"), Value::Int(10));
+    }
+
+    #[test]
+    fn test_recursion() {
+        let source = "
+            fn fact(u64 n) u64 {
+                if zero(n) { return 1; }
+                return n * fact(n - 1);
+            }
+            fn zero(u64 n) bool { if n { return false; } return true; }
+            u64 x = fact(10);
+        ";
+        assert_eq!(run_and_get(source, "x"), Value::UInt(3628800));
+    }
+
+    #[test]
+    fn test_return_value_is_cast() {
+        let source = "fn f() u8 { return 300; } u64 x = f();";
+        assert_eq!(run_and_get(source, "x"), Value::UInt(44));
+    }
+
+    #[test]
+    fn test_builtin_len() {
+        assert_eq!(run_and_get("i32[4] a; u64 n = len(a);", "n"), Value::UInt(4));
+    }
+
+    #[test]
+    fn test_division_by_zero() {
+        assert_eq!(run_error("i32 z = 0; i32 x = 1 / z;"), ErrorCode::ER001);
+    }
+
+    #[test]
+    fn test_index_out_of_bounds() {
+        assert_eq!(run_error("i32[2] a; i32 x = a[2];"), ErrorCode::ER002);
+    }
+
+    #[test]
+    fn test_missing_return_value() {
+        assert_eq!(run_error("fn f() i32 { } i32 x = f();"), ErrorCode::ER005);
+    }
+
+    #[test]
+    fn test_unbounded_recursion() {
+        assert_eq!(run_error("fn f() { f(); } f();"), ErrorCode::ER010);
+    }
+}
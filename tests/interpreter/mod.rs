@@ -0,0 +1 @@
+mod execution;
\ No newline at end of file
@@ -0,0 +1 @@
+mod warnings;
\ No newline at end of file
@@ -0,0 +1,131 @@
+#[cfg(test)]
+mod tests {
+    use rcompiler::error::*;
+    use rcompiler::lints::*;
+    use rcompiler::parser::*;
+    use rcompiler::source::*;
+    use rcompiler::tokenizer::*;
+
+    fn lint_with(source: &str, config: &LintConfig) -> Vec<(WarningCode, String)> {
+        let tokens = Tokenizer::new(source).tokenize().unwrap();
+        let statements = Parser::new(tokens).parse().unwrap();
+
+        Linter::new(&statements, config).lint().iter()
+            .map(|warning| (warning.code, source[warning.span.start..warning.span.end].to_string()))
+            .collect()
+    }
+
+    fn lint(source: &str) -> Vec<WarningCode> {
+        lint_with(source, &LintConfig::new()).into_iter().map(|(code, _)| code).collect()
+    }
+
+    #[test]
+    fn test_unused_variable() {
+        assert_eq!(lint("i32 x = 1;"), vec![WarningCode::WL001]);
+        assert_eq!(lint("i32 x = 1; println(x);"), vec![]);
+        assert_eq!(lint("i32 _x = 1;"), vec![]);
+    }
+
+    #[test]
+    fn test_unused_loop_variable() {
+        assert_eq!(lint("for i in 0..3 {}"), vec![WarningCode::WL001]);
+        assert_eq!(lint("for i in 0..3 { println(i); }"), vec![]);
+        assert_eq!(lint("for _ in 0..3 {}"), vec![]);
+    }
+
+    #[test]
+    fn test_unused_match_binding() {
+        let source = "enum E { A(i32, i32) } E e = E::A(1, 2); ";
+        assert_eq!(lint(&format!("{}match e {{ E::A(x, y) => {{ println(x); }} }}", source)), vec![WarningCode::WL001]);
+        assert_eq!(lint(&format!("{}println(match e {{ E::A(x, _) => x }});", source)), vec![]);
+        assert_eq!(lint("i32 i = 0; println(i); for i in 0..3 { println(i); }"), vec![WarningCode::WL004]);
+    }
+
+    #[test]
+    fn test_namespace_members_are_not_reported() {
+        assert_eq!(lint("namespace a { const i32 x = 1; fn f(i32 y) {} namespace b { } }"), vec![WarningCode::WL002]);
+        assert_eq!(lint("fn f() {} namespace a { fn g() { f(); } }"), vec![]);
+        assert_eq!(lint("i32 x = 1; namespace a { fn g() i32 { return x; } }"), vec![]);
+        assert_eq!(lint("namespace a { fn g() { i32 x = 1; } }"), vec![WarningCode::WL001]);
+    }
+
+    #[test]
+    fn test_assignment_is_not_a_use() {
+        assert_eq!(lint("i32 x = 1; x = 2;"), vec![WarningCode::WL001]);
+        assert_eq!(lint("i32 x = 1; x = x + 1;"), vec![]);
+        assert_eq!(lint("i32 i = 0; i32[2] a = [i, 1]; a[i] = 2;"), vec![WarningCode::WL001]);
+    }
+
+    #[test]
+    fn test_unused_parameter() {
+        let warnings = lint_with("fn f(i32 a, i32 b) i32 { return a; } println(f(1, 2));", &LintConfig::new());
+        assert_eq!(warnings, vec![(WarningCode::WL002, "i32 b".to_string())]);
+    }
+
+    #[test]
+    fn test_unused_function() {
+        let warnings = lint_with("fn never() { never(); }\nfn _helper() {}", &LintConfig::new());
+        assert_eq!(warnings, vec![(WarningCode::WL003, "fn never() { never(); }".to_string())]);
+        assert_eq!(lint("fn used() {} used();"), vec![]);
+    }
+
+    #[test]
+    fn test_shadowing() {
+        assert_eq!(lint("i32 x = 1; if x > 0 { i32 x = 2; println(x); }"), vec![WarningCode::WL004]);
+        assert_eq!(lint("if true == false {} i32 x = 1; println(x);"), vec![WarningCode::WL006]);
+    }
+
+    #[test]
+    fn test_unreachable_code() {
+        let source = "fn f() i32 { return 1; println(2); println(3); } println(f());";
+        assert_eq!(lint(source), vec![WarningCode::WL005]);
+
+        let source = "while true { break; println(1); }";
+        assert_eq!(lint(source), vec![WarningCode::WL005]);
+
+        let source = "for i in 0..3 { continue; println(i); }";
+        assert_eq!(lint(source), vec![WarningCode::WL005]);
+    }
+
+    #[test]
+    fn test_constant_condition() {
+        assert_eq!(lint("if 1 < 2 {}"), vec![WarningCode::WL006]);
+        assert_eq!(lint("while !false {}"), vec![WarningCode::WL006]);
+        assert_eq!(lint("while true { break; }"), vec![]);
+        assert_eq!(lint("i32 x = 1; if x < 2 {}"), vec![]);
+    }
+
+    #[test]
+    fn test_levels() {
+        let source = "i32 x = 1; if true {}";
+
+        let mut config = LintConfig::new();
+        config.set(WarningCode::WL001, LintLevel::Allow);
+        assert_eq!(lint_with(source, &config).len(), 1);
+
+        config.set_all(LintLevel::Allow);
+        assert!(lint_with(source, &config).is_empty());
+
+        config.set(WarningCode::WL006, LintLevel::Deny);
+        let tokens = Tokenizer::new(source).tokenize().unwrap();
+        let statements = Parser::new(tokens).parse().unwrap();
+        let warnings = Linter::new(&statements, &config).lint();
+
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].is_denied());
+
+        let sources = SourceMap::new();
+        let diagnostic = warnings[0].to_diagnostic(&sources);
+        assert_eq!(diagnostic.kind, DiagnosticKind::Denied(WarningCode::WL006));
+    }
+
+    #[test]
+    fn test_names() {
+        for code in WarningCode::ALL {
+            assert_eq!(WarningCode::from_name(code.name()), Some(code));
+            assert_eq!(WarningCode::from_name(&format!("{:?}", code)), Some(code));
+        }
+        assert_eq!(WarningCode::from_name("unused-variables"), Some(WarningCode::WL001));
+        assert_eq!(WarningCode::from_name("nope"), None);
+    }
+}
@@ -0,0 +1 @@
+mod modules;
\ No newline at end of file
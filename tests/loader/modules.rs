@@ -0,0 +1,131 @@
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    use rcompiler::engine::*;
+    use rcompiler::error::*;
+    use rcompiler::loader::*;
+    use rcompiler::source::*;
+
+    // Writes the files in a directory of their own, so the tests can run in parallel
+    fn directory(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let directory: PathBuf = std::env::temp_dir().join(format!("rcompiler-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+
+        for (path, contents) in files {
+            let path: PathBuf = directory.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+        }
+        directory
+    }
+
+    // Loads 'main.rc' of the directory, returns the names of the modules in order
+    fn load(loader: &mut ModuleLoader, directory: &Path) -> Result<Vec<String>, Vec<ErrorCode>> {
+        let path: PathBuf = directory.join("main.rc");
+        let source: String = fs::read_to_string(&path).unwrap();
+        let mut sources: SourceMap = SourceMap::new();
+
+        match loader.load(&path.to_string_lossy(), &source, &mut sources) {
+            Ok(modules) => Ok(modules.iter().map(|module| {
+                let name: &str = sources.name(module.file).unwrap();
+                Path::new(name).strip_prefix(directory).unwrap().to_string_lossy().replace('\\', "/")
+            }).collect()),
+            Err(errors) => Err(errors.iter().map(|error| error.code).collect()),
+        }
+    }
+
+    #[test]
+    fn test_modules_come_before_their_importers() {
+        let directory: PathBuf = directory("order", &[
+            ("main.rc", "import lib::math;\nimport \"shared/consts.rc\";"),
+            ("lib/math.rc", "import \"../shared/consts.rc\";"),
+            ("shared/consts.rc", ""),
+        ]);
+
+        // 'consts' is imported twice, but only loaded once
+        let modules = load(&mut ModuleLoader::new(), &directory).unwrap();
+        assert_eq!(modules, vec!["lib/../shared/consts.rc", "lib/math.rc", "main.rc"]);
+    }
+
+    #[test]
+    fn test_search_paths() {
+        let directory: PathBuf = directory("search", &[
+            ("main.rc", "import util;"),
+            ("vendor/util.rc", ""),
+        ]);
+        assert_eq!(load(&mut ModuleLoader::new(), &directory), Err(vec![ErrorCode::EM001]));
+
+        let mut loader: ModuleLoader = ModuleLoader::new();
+        loader.add_search_path(directory.join("vendor"));
+        assert!(load(&mut loader, &directory).is_ok());
+    }
+
+    #[test]
+    fn test_import_cycle() {
+        let directory: PathBuf = directory("cycle", &[
+            ("main.rc", "import a;"),
+            ("a.rc", "import b;"),
+            ("b.rc", "\nimport a;"),
+        ]);
+
+        let mut sources: SourceMap = SourceMap::new();
+        let path: PathBuf = directory.join("main.rc");
+        let errors = ModuleLoader::new().load(&path.to_string_lossy(), "import a;", &mut sources).err().unwrap();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!((errors[0].code, errors[0].line), (ErrorCode::EM002, 2));
+        assert!(sources.name(errors[0].file).unwrap().ends_with("b.rc"));
+
+        // The cycle goes from the first module in it back to the same one
+        let cycle: Vec<&str> = errors[0].hint.rsplit(": ").next().unwrap().split(" -> ").collect();
+        assert_eq!(cycle.len(), 3);
+        assert!(cycle[0].ends_with("a.rc") && cycle[1].ends_with("b.rc") && cycle[2].ends_with("a.rc"));
+    }
+
+    #[test]
+    fn test_errors_of_imported_modules() {
+        let directory: PathBuf = directory("errors", &[
+            ("main.rc", "import lib;\nimport missing;\ni32 x = ;"),
+            ("lib.rc", "i32 y = ;\nu8 z = ;"),
+        ]);
+
+        // Every syntax error of every module is reported, a module's own come before the ones of its imports
+        let errors = load(&mut ModuleLoader::new(), &directory).err().unwrap();
+        assert_eq!(errors, vec![ErrorCode::EP004, ErrorCode::EP004, ErrorCode::EP004, ErrorCode::EM001]);
+    }
+
+    #[test]
+    fn test_modules_are_loaded_once() {
+        let directory: PathBuf = directory("once", &[
+            ("main.rc", "import lib;"),
+            ("lib.rc", ""),
+        ]);
+
+        let mut loader: ModuleLoader = ModuleLoader::new();
+        assert_eq!(load(&mut loader, &directory).unwrap(), vec!["lib.rc", "main.rc"]);
+        assert_eq!(load(&mut loader, &directory).unwrap(), vec!["main.rc"]);
+    }
+
+    #[test]
+    fn test_engine_imports() {
+        let directory: PathBuf = directory("engine", &[
+            ("math.rc", "namespace math { fn square(i64 x) i64 { return x * x; } }"),
+        ]);
+
+        let mut engine = Engine::new();
+        engine.add_search_path(&directory);
+
+        // A failed script doesn't keep the module, the next one loads it again
+        assert!(engine.eval("import math;\ni32 x = true;").is_err());
+        engine.eval("import math; i64 a = math::square(3);").unwrap();
+        engine.eval("import math; i64 b = math::square(4);").unwrap();
+        assert_eq!(engine.get_global::<i64>("b").unwrap(), 16);
+
+        let EngineError::Compile(errors) = engine.compile("import missing;").err().unwrap() else {
+            panic!("expected a compile error");
+        };
+        assert_eq!(errors[0].code, ErrorCode::EM001);
+    }
+}
@@ -0,0 +1,66 @@
+#[cfg(test)]
+mod tests {
+    use rcompiler::interpreter::*;
+    use rcompiler::owned::*;
+    use rcompiler::parser::*;
+    use rcompiler::tokenizer::*;
+    use rcompiler::value::*;
+
+    fn parse_owned(source: &str) -> Vec<OwnedStatement> {
+        let tokens = Tokenizer::new(source).tokenize().unwrap();
+        to_owned(&Parser::new(tokens).parse().unwrap())
+    }
+
+    #[test]
+    fn test_round_trip_keeps_tree() {
+        let source: &str = "
+            const i32[2] a;
+            fn f(i32 x, str[3] y) i32 { return -x * g(y[0], 'c'); }
+            if !b { c += 1; } elif d { while true { break; } } else { println(\"e\"); }
+            import \"lib.rc\"; import lib::m;
+            namespace m { const i32 k = 1; fn h() i32 { return m::k + n::p::h(); } }
+            struct Line { geo::Point from; i32[2] ids; } Line l; l.from.x = Point { x = 1 }.x;
+            enum State { Idle, Failed(str, geo::Point[2]) } match s { State::Failed(m, _) => { } -1 => { } _ => { } }
+            i32 v = match s { State::Idle => 1, _ => 0 };
+            i32[2] w = [1, v]; w[v - 1] += l.ids[0]; ls[0].ids[1] = 2;
+        ";
+        let tokens = Tokenizer::new(source).tokenize().unwrap();
+        let statements = Parser::new(tokens).parse().unwrap();
+
+        let owned: Vec<OwnedStatement> = to_owned(&statements);
+        assert_eq!(format!("{:?}", borrow(&owned)), format!("{:?}", statements));
+    }
+
+    #[test]
+    fn test_spans_are_kept() {
+        let owned: Vec<OwnedStatement> = parse_owned("i32 x = 1;\nx = 2;");
+        let spans: Vec<(usize, usize)> = owned.iter().map(|statement| (statement.span.start, statement.span.end)).collect();
+        assert_eq!(spans, vec![(0, 10), (11, 17)]);
+    }
+
+    #[test]
+    fn test_tree_outlives_source() {
+        let source: String = String::from("i32 total = 0; i32 i = 0; while i < 4 { i += 1; total += i; }");
+        let owned: Vec<OwnedStatement> = parse_owned(&source);
+        drop(source);
+
+        let statements = borrow(&owned);
+        let mut interpreter = Interpreter::new();
+        interpreter.run(&statements).unwrap();
+        assert_eq!(interpreter.global("total").cloned(), Some(Value::Int(10)));
+    }
+
+    #[test]
+    fn test_tree_can_be_sent_to_another_thread() {
+        let owned: Vec<OwnedStatement> = parse_owned("fn square(i64 x) i64 { return x * x; }");
+
+        let names = std::thread::spawn(move || {
+            owned.iter().filter_map(|statement| match &statement.node {
+                OwnedRawStatement::Function { name, .. } => Some(name.to_string()),
+                _ => None,
+            }).collect::<Vec<String>>()
+        }).join().unwrap();
+
+        assert_eq!(names, vec!["square".to_string()]);
+    }
+}
@@ -0,0 +1 @@
+mod convert;
\ No newline at end of file
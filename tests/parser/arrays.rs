@@ -0,0 +1,81 @@
+#[cfg(test)]
+mod tests {
+    use rcompiler::parser::*;
+    use rcompiler::tokenizer::*;
+    use rcompiler::error::*;
+    use crate::parser::{error, parse};
+
+    #[test]
+    fn test_array_literal() {
+        let statements = parse("i32[3] a = [1, x + 1, f(2),]; i32[0] b = [];").unwrap();
+
+        let RawStatement::VariableDeclaration { value: Some(value), .. } = &statements[0].node else {
+            panic!("expected a declaration, found {:?}", statements[0]);
+        };
+        let RawExpression::ArrayLiteral(elements) = &value.node else {
+            panic!("expected an array literal, found {:?}", value);
+        };
+        assert_eq!(elements.len(), 3);
+        assert!(matches!(elements[1].node, RawExpression::Binary { .. }));
+        assert!(matches!(elements[2].node, RawExpression::FunctionCall { .. }));
+
+        let RawStatement::VariableDeclaration { value: Some(value), .. } = &statements[1].node else {
+            panic!("expected a declaration, found {:?}", statements[1]);
+        };
+        assert!(matches!(&value.node, RawExpression::ArrayLiteral(elements) if elements.is_empty()));
+    }
+
+    #[test]
+    fn test_array_literals_can_be_indexed() {
+        let statements = parse("i32 x = [1, 2][i];").unwrap();
+
+        let RawStatement::VariableDeclaration { value: Some(value), .. } = &statements[0].node else {
+            panic!("expected a declaration, found {:?}", statements[0]);
+        };
+        let RawExpression::ArrayAccess { array, .. } = &value.node else {
+            panic!("expected an array access, found {:?}", value);
+        };
+        assert!(matches!(array.node, RawExpression::ArrayLiteral(_)));
+    }
+
+    #[test]
+    fn test_element_assignment() {
+        let statements = parse("a[i + 1] = 5; lines[0].ids[1] *= 2;").unwrap();
+
+        let RawStatement::VariableAssignment { target, operator, .. } = &statements[0].node else {
+            panic!("expected an assignment, found {:?}", statements[0]);
+        };
+        assert_eq!(target.name, "a");
+        assert!(matches!(&target.accessors[..], [Accessor::Index(index)] if matches!(index.node, RawExpression::Binary { .. })));
+        assert_eq!(*operator, TokenKind::Assignment);
+
+        let RawStatement::VariableAssignment { target, operator, .. } = &statements[1].node else {
+            panic!("expected an assignment, found {:?}", statements[1]);
+        };
+        assert_eq!(target.name, "lines");
+        assert!(matches!(target.accessors[..], [Accessor::Index(_), Accessor::Field("ids"), Accessor::Index(_)]));
+        assert_eq!(*operator, TokenKind::MultiplyAssignment);
+    }
+
+    #[test]
+    fn test_index_after_a_struct_name_is_an_array_size() {
+        let statements = parse("Point[n + 1] ps;").unwrap();
+
+        let RawStatement::VariableDeclaration { type_, name: "ps", .. } = &statements[0].node else {
+            panic!("expected a declaration, found {:?}", statements[0]);
+        };
+        assert!(type_.is_array && type_.array_length.is_some());
+        assert_eq!(type_.name.as_ref().unwrap().to_string(), "Point");
+    }
+
+    #[test]
+    fn test_array_errors() {
+        assert_eq!(error("i32[3] a = [1, 2 3];"), ErrorCode::EP068);
+        assert_eq!(error("i32[3] a = [1, 2"), ErrorCode::EP068);
+        assert_eq!(error("a[0 = 1;"), ErrorCode::EP033);
+        assert_eq!(error("a[] = 1;"), ErrorCode::EP032);
+        assert_eq!(error("a[0];"), ErrorCode::EP013);
+        assert_eq!(error("a[0].x[1] p;"), ErrorCode::EP013);
+        assert_eq!(error("math::values[0] = 1;"), ErrorCode::EP013);
+    }
+}
@@ -0,0 +1,97 @@
+#[cfg(test)]
+mod tests {
+    use rcompiler::parser::*;
+    use rcompiler::tokenizer::*;
+    use rcompiler::error::*;
+    use crate::parser::{error, parse};
+
+    #[test]
+    fn test_enum_declaration() {
+        let statements = parse("enum State { Idle, Failed(str, geo::Point[2]), }").unwrap();
+
+        let RawStatement::Enum { name, variants } = &statements[0].node else {
+            panic!("expected an enum, found {:?}", statements[0]);
+        };
+        assert_eq!(*name, "State");
+        assert_eq!(variants.len(), 2);
+
+        assert_eq!(variants[0].name, "Idle");
+        assert!(variants[0].payload.is_empty());
+
+        assert_eq!(variants[1].name, "Failed");
+        assert_eq!(variants[1].payload[0].kind, TokenKind::String);
+        assert!(variants[1].payload[1].is_array);
+        assert_eq!(variants[1].payload[1].name.as_ref().unwrap().to_string(), "geo::Point");
+    }
+
+    #[test]
+    fn test_match_statement() {
+        let statements = parse("match s { State::Failed(message, _) => { } -1 => { } _ => { } }").unwrap();
+
+        let RawStatement::Match { value, arms } = &statements[0].node else {
+            panic!("expected a match, found {:?}", statements[0]);
+        };
+        assert!(matches!(value.node, RawExpression::Variable(_)));
+        assert_eq!(arms.len(), 3);
+
+        let Pattern::Variant { path, bindings } = &arms[0].pattern else {
+            panic!("expected a variant pattern, found {:?}", arms[0].pattern);
+        };
+        assert_eq!(path.to_string(), "State::Failed");
+        assert_eq!(*bindings, vec!["message", "_"]);
+
+        let Pattern::Literal(literal) = &arms[1].pattern else {
+            panic!("expected a literal pattern, found {:?}", arms[1].pattern);
+        };
+        assert!(matches!(literal.node, RawExpression::Unary { operator: TokenKind::Minus, .. }));
+        assert!(matches!(arms[2].pattern, Pattern::Wildcard));
+    }
+
+    #[test]
+    fn test_match_expression() {
+        let statements = parse("i32 x = 1 + match s { State::Idle => 0, _ => p.x, };").unwrap();
+
+        let RawStatement::VariableDeclaration { value: Some(value), .. } = &statements[0].node else {
+            panic!("expected a declaration, found {:?}", statements[0]);
+        };
+        let RawExpression::Binary { right, .. } = &value.node else {
+            panic!("expected a binary expression, found {:?}", value);
+        };
+        let RawExpression::Match { arms, .. } = &right.node else {
+            panic!("expected a match, found {:?}", right);
+        };
+        assert_eq!(arms.len(), 2);
+        assert!(matches!(arms[1].value.node, RawExpression::Field { .. }));
+    }
+
+    #[test]
+    fn test_match_value_is_not_a_struct_literal() {
+        let statements = parse("match p { _ => { } }").unwrap();
+        assert!(matches!(statements[0].node, RawStatement::Match { .. }));
+    }
+
+    #[test]
+    fn test_enum_errors() {
+        assert_eq!(error("enum { A }"), ErrorCode::EP054);
+        assert_eq!(error("enum E A, B"), ErrorCode::EP055);
+        assert_eq!(error("enum E { 1 }"), ErrorCode::EP056);
+        assert_eq!(error("enum E { A B }"), ErrorCode::EP057);
+        assert_eq!(error("enum E { A(1) }"), ErrorCode::EP058);
+        assert_eq!(error("enum E { A(i32 i32) }"), ErrorCode::EP059);
+        assert_eq!(error("enum E { A, "), ErrorCode::EP010);
+    }
+
+    #[test]
+    fn test_match_errors() {
+        assert_eq!(error("match { }"), ErrorCode::EP060);
+        assert_eq!(error("match x _ => { }"), ErrorCode::EP061);
+        assert_eq!(error("match x { + => { } }"), ErrorCode::EP062);
+        assert_eq!(error("match x { _ { } }"), ErrorCode::EP063);
+        assert_eq!(error("match x { E::A(1) => { } }"), ErrorCode::EP064);
+        assert_eq!(error("match x { E::A(a b) => { } }"), ErrorCode::EP065);
+        assert_eq!(error("match x { _ => x = 1; }"), ErrorCode::EP066);
+        assert_eq!(error("i32 y = match x { _ => }; "), ErrorCode::EP066);
+        assert_eq!(error("i32 y = match x { 1 => 2 _ => 3 };"), ErrorCode::EP067);
+        assert_eq!(error("match x { _ => { } "), ErrorCode::EP010);
+    }
+}
@@ -2,12 +2,14 @@
 mod tests {
     use rcompiler::parser::*;
     use rcompiler::tokenizer::*;
+    use rcompiler::source::FileId;
     // --- Helpers ---
 
     fn tok(kind: TokenKind, lit: &'static str) -> Token<'static> {
          Token {
             kind,
-            span: TokenSpan { start: 0, end: 0, literal: lit, line: 0, column: 0 },
+            span: TokenSpan { start: 0, end: 0, literal: lit, line: 0, column: 0, file: FileId::default() },
+            trivia: "",
         }
     }
 
@@ -527,4 +529,20 @@ mod tests {
 
         assert!(parse_test(tokens).is_err());
     }
+
+    #[test]
+    fn test_operand_spans_start_at_their_first_token() {
+        // The right operand of 'a + b' starts at 'b', not at the '+' before it
+        let tokens = Tokenizer::new("i32 x = a + b;").tokenize().unwrap();
+        let statements = Parser::new(tokens).parse().unwrap();
+
+        let RawStatement::VariableDeclaration { value: Some(value), .. } = &statements[0].node else {
+            panic!("expected a declaration, found {:?}", statements[0]);
+        };
+        let RawExpression::Binary { left, right, .. } = &value.node else {
+            panic!("expected a binary expression, found {:?}", value);
+        };
+        assert_eq!((left.span.start, left.span.end), (8, 9));
+        assert_eq!((right.span.start, right.span.end), (12, 13));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,28 @@
+#[cfg(test)]
+mod tests {
+    use rcompiler::parser::*;
+    use rcompiler::error::*;
+    use crate::parser::{error, parse};
+
+    #[test]
+    fn test_imports() {
+        let statements = parse("import \"lib/math.rc\"; import lib::math;").unwrap();
+
+        assert!(matches!(statements[0].node, RawStatement::Import(Import::File("\"lib/math.rc\""))));
+
+        let RawStatement::Import(Import::Module(path)) = &statements[1].node else {
+            panic!("expected a module import, found {:?}", statements[1]);
+        };
+        assert_eq!(path.namespaces, vec!["lib"]);
+        assert_eq!(path.name, "math");
+    }
+
+    #[test]
+    fn test_import_errors() {
+        assert_eq!(error("import;"), ErrorCode::EP046);
+        assert_eq!(error("import 5;"), ErrorCode::EP046);
+        assert_eq!(error("import lib::;"), ErrorCode::EP044);
+        assert_eq!(error("import lib"), ErrorCode::EP047);
+        assert_eq!(error("import \"a.rc\" import \"b.rc\";"), ErrorCode::EP047);
+    }
+}
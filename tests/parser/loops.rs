@@ -0,0 +1,52 @@
+#[cfg(test)]
+mod tests {
+    use rcompiler::parser::*;
+    use rcompiler::tokenizer::*;
+    use rcompiler::error::*;
+    use crate::parser::{error, parse};
+
+    #[test]
+    fn test_for_range() {
+        let statements = parse("for i32 i in 0..n + 1 { break; }").unwrap();
+
+        let RawStatement::For { type_, name, iterable, body } = &statements[0].node else {
+            panic!("expected a for loop, found {:?}", statements[0]);
+        };
+        assert_eq!(*type_, Some(TokenKind::SignedInt32));
+        assert_eq!(*name, "i");
+        assert_eq!(body.statements.len(), 1);
+
+        let Iterable::Range { start, end } = iterable else {
+            panic!("expected a range, found {:?}", iterable);
+        };
+        assert!(matches!(start.node, RawExpression::Literal { value: "0", .. }));
+        assert!(matches!(end.node, RawExpression::Binary { operator: TokenKind::Plus, .. }));
+    }
+
+    #[test]
+    fn test_for_array_without_type() {
+        let statements = parse("for item in items { println(item); }").unwrap();
+
+        let RawStatement::For { type_: None, name: "item", iterable: Iterable::Array(array), .. } = &statements[0].node else {
+            panic!("expected a loop over an array, found {:?}", statements[0]);
+        };
+        assert!(matches!(&array.node, RawExpression::Variable(path) if path.name == "items"));
+    }
+
+    #[test]
+    fn test_for_span_covers_the_body() {
+        let source: &str = "i32 x;\nfor i in 0..3 {\n    x += i;\n}";
+        let statements = parse(source).unwrap();
+        assert_eq!(&source[statements[1].span.start..statements[1].span.end], "for i in 0..3 {\n    x += i;\n}");
+    }
+
+    #[test]
+    fn test_for_errors() {
+        assert_eq!(error("for in 0..3 {}"), ErrorCode::EP037);
+        assert_eq!(error("for const i in 0..3 {}"), ErrorCode::EP037);
+        assert_eq!(error("for i 0..3 {}"), ErrorCode::EP038);
+        assert_eq!(error("for i in {}"), ErrorCode::EP039);
+        assert_eq!(error("for i in 0.. {}"), ErrorCode::EP040);
+        assert_eq!(error("for i in 0..3 break;"), ErrorCode::EP041);
+    }
+}
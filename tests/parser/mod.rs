@@ -1,2 +1,24 @@
 mod statements;
-mod expression;
\ No newline at end of file
+mod expression;
+mod recovery;
+mod precedence;
+mod loops;
+mod namespaces;
+mod imports;
+mod structs;
+mod enums;
+mod arrays;
+
+use rcompiler::error::ErrorCode;
+use rcompiler::parser::{Parser, ParserError, Statement};
+use rcompiler::tokenizer::Tokenizer;
+
+// Shared by the tests that parse whole sources
+pub fn parse(source: &str) -> Result<Vec<Statement<'_>>, ParserError> {
+    let tokens = Tokenizer::new(source).tokenize().unwrap();
+    Parser::new(tokens).parse()
+}
+
+pub fn error(source: &str) -> ErrorCode {
+    parse(source).unwrap_err().code
+}
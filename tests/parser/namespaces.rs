@@ -0,0 +1,73 @@
+#[cfg(test)]
+mod tests {
+    use rcompiler::parser::*;
+    use rcompiler::error::*;
+    use crate::parser::{error, parse};
+
+    #[test]
+    fn test_namespace() {
+        let statements = parse("namespace math { const i32 two = 2; fn sq(i32 x) i32 { return x * x; } }").unwrap();
+
+        let RawStatement::Namespace { name, body } = &statements[0].node else {
+            panic!("expected a namespace, found {:?}", statements[0]);
+        };
+        assert_eq!(*name, "math");
+        assert_eq!(body.statements.len(), 2);
+        assert!(matches!(body.statements[1].node, RawStatement::Function { name: "sq", .. }));
+    }
+
+    #[test]
+    fn test_qualified_names() {
+        let statements = parse("i32 x = a::b::c + a::f(1);").unwrap();
+
+        let RawStatement::VariableDeclaration { value: Some(value), .. } = &statements[0].node else {
+            panic!("expected a declaration, found {:?}", statements[0]);
+        };
+        let RawExpression::Binary { left, right, .. } = &value.node else {
+            panic!("expected a binary expression, found {:?}", value);
+        };
+
+        let RawExpression::Variable(path) = &left.node else {
+            panic!("expected a variable, found {:?}", left);
+        };
+        assert_eq!(path.namespaces, vec!["a", "b"]);
+        assert_eq!(path.name, "c");
+        assert_eq!(path.to_string(), "a::b::c");
+
+        let RawExpression::FunctionCall { name, arguments } = &right.node else {
+            panic!("expected a call, found {:?}", right);
+        };
+        assert_eq!(name.to_string(), "a::f");
+        assert_eq!(arguments.len(), 1);
+    }
+
+    #[test]
+    fn test_qualified_call_statement() {
+        let statements = parse("io::log(1);").unwrap();
+
+        let RawStatement::FunctionCall { name, .. } = &statements[0].node else {
+            panic!("expected a call, found {:?}", statements[0]);
+        };
+        assert_eq!(name.namespaces, vec!["io"]);
+        assert_eq!(name.name, "log");
+    }
+
+    #[test]
+    fn test_path_candidates() {
+        let path: Path = Path { namespaces: vec!["b"], name: "c" };
+        let candidates: Vec<String> = path.candidates(&["x", "y"]).map(|name| name.into_owned()).collect();
+
+        // From the innermost namespace out to the top level
+        assert_eq!(candidates, vec!["x::y::b::c", "x::b::c", "b::c"]);
+        assert_eq!(path.parent(), Some(Path::from("b")));
+    }
+
+    #[test]
+    fn test_namespace_errors() {
+        assert_eq!(error("namespace { }"), ErrorCode::EP042);
+        assert_eq!(error("namespace math fn one() {}"), ErrorCode::EP043);
+        assert_eq!(error("i32 x = math::;"), ErrorCode::EP044);
+        assert_eq!(error("math::1();"), ErrorCode::EP044);
+        assert_eq!(error("math::x = 1;"), ErrorCode::EP045);
+    }
+}
@@ -0,0 +1,109 @@
+#[cfg(test)]
+mod tests {
+    use rcompiler::parser::*;
+    use rcompiler::tokenizer::*;
+
+    // Writes the tree back with every binary and unary operation in parentheses
+    fn render(expression: &Expression) -> String {
+        match &expression.node {
+            RawExpression::Variable(name) => name.to_string(),
+            RawExpression::Literal { value, .. } => value.to_string(),
+            RawExpression::Binary { left, operator, right } => {
+                format!("({} {} {})", render(left), operator, render(right))
+            },
+            RawExpression::Unary { operator, operand } => format!("({}{})", operator, render(operand)),
+            RawExpression::FunctionCall { name, arguments } => {
+                let arguments: Vec<String> = arguments.iter().map(render).collect();
+                format!("{}({})", name, arguments.join(", "))
+            },
+            RawExpression::ArrayAccess { array, index } => format!("{}[{}]", render(array), render(index)),
+            RawExpression::StructLiteral { name, fields } => {
+                let fields: Vec<String> = fields.iter().map(|(field, value)| format!("{} = {}", field, render(value))).collect();
+                format!("{} {{ {} }}", name, fields.join(", "))
+            },
+            RawExpression::Field { object, name } => format!("{}.{}", render(object), name),
+            RawExpression::Match { value, arms } => {
+                let arms: Vec<String> = arms.iter().map(|arm| format!("{:?} => {}", arm.pattern, render(&arm.value))).collect();
+                format!("match {} {{ {} }}", render(value), arms.join(", "))
+            },
+            RawExpression::ArrayLiteral(elements) => {
+                let elements: Vec<String> = elements.iter().map(render).collect();
+                format!("[{}]", elements.join(", "))
+            },
+        }
+    }
+
+    fn parse(source: &str) -> String {
+        let tokens = Tokenizer::new(source).tokenize().unwrap();
+        let expression = Parser::new(tokens).parse_standalone_expression().unwrap();
+        render(&expression)
+    }
+
+    #[test]
+    fn test_comparisons_bind_looser_than_arithmetic() {
+        assert_eq!(parse("x > 0"), "(x > 0)");
+        assert_eq!(parse("a + 1 < b * 2"), "((a + 1) < (b * 2))");
+        assert_eq!(parse("a >= b - 1"), "(a >= (b - 1))");
+    }
+
+    #[test]
+    fn test_equality_binds_looser_than_relational() {
+        assert_eq!(parse("a < b == c > d"), "((a < b) == (c > d))");
+        assert_eq!(parse("a != b <= c"), "(a != (b <= c))");
+    }
+
+    #[test]
+    fn test_logical_operators() {
+        assert_eq!(parse("a || b && c"), "(a || (b && c))");
+        assert_eq!(parse("a && b || c && d"), "((a && b) || (c && d))");
+        assert_eq!(parse("x > 0 && x < 10"), "((x > 0) && (x < 10))");
+        assert_eq!(parse("!a || b"), "((!a) || b)");
+    }
+
+    #[test]
+    fn test_bitwise_operators() {
+        // Like C, '&' binds tighter than '^', which binds tighter than '|'
+        assert_eq!(parse("a | b ^ c & d"), "(a | (b ^ (c & d)))");
+        assert_eq!(parse("a & b == c"), "(a & (b == c))");
+        assert_eq!(parse("a | b && c"), "((a | b) && c)");
+    }
+
+    #[test]
+    fn test_shifts() {
+        assert_eq!(parse("1 << 2 + 3"), "(1 << (2 + 3))");
+        assert_eq!(parse("a >> 1 < b"), "((a >> 1) < b)");
+    }
+
+    #[test]
+    fn test_left_associativity() {
+        assert_eq!(parse("a - b - c"), "((a - b) - c)");
+        assert_eq!(parse("a << 1 << 2"), "((a << 1) << 2)");
+        assert_eq!(parse("a == b == c"), "((a == b) == c)");
+        assert_eq!(parse("a || b || c"), "((a || b) || c)");
+    }
+
+    #[test]
+    fn test_unary_and_postfix() {
+        assert_eq!(parse("-a * b"), "((-a) * b)");
+        assert_eq!(parse("-a[0] < f(1) + 2"), "((-a[0]) < (f(1) + 2))");
+        assert_eq!(parse("!(a == b)"), "(!(a == b))");
+    }
+
+    #[test]
+    fn test_field_access() {
+        assert_eq!(parse("-p.x * 2"), "((-p.x) * 2)");
+        assert_eq!(parse("a.b.c + ps[0].x"), "(a.b.c + ps[0].x)");
+        assert_eq!(parse("Point { x = 1 + 2, y = q.y }.x"), "Point { x = (1 + 2), y = q.y }.x");
+    }
+
+    #[test]
+    fn test_if_condition_with_comparison() {
+        let tokens = Tokenizer::new("if x > 0 { y = x; }").tokenize().unwrap();
+        let statements = Parser::new(tokens).parse().unwrap();
+
+        let RawStatement::If { condition, .. } = &statements[0].node else {
+            panic!("expected an if statement");
+        };
+        assert_eq!(render(condition), "(x > 0)");
+    }
+}
@@ -0,0 +1,100 @@
+#[cfg(test)]
+mod tests {
+    use rcompiler::parser::*;
+    use rcompiler::tokenizer::*;
+    use rcompiler::error::*;
+
+    fn parse_all(source: &str) -> (Vec<Statement<'_>>, Vec<ParserError>) {
+        let tokens = Tokenizer::new(source).tokenize().unwrap();
+        Parser::new(tokens).parse_recovering()
+    }
+
+    fn codes(errors: &[ParserError]) -> Vec<ErrorCode> {
+        errors.iter().map(|error| error.code).collect()
+    }
+
+    #[test]
+    fn test_reports_every_error() {
+        let source = "i32 x = ;\ni32 y = 5\nbool ok = true;\nz = ;";
+        let (statements, errors) = parse_all(source);
+
+        assert_eq!(codes(&errors), [ErrorCode::EP004, ErrorCode::EP005, ErrorCode::EP018]);
+        assert_eq!(statements.len(), 1);
+    }
+
+    #[test]
+    fn test_valid_source_has_no_errors() {
+        let (statements, errors) = parse_all("i32 x = 1; fn f() { x = 2; }");
+        assert!(errors.is_empty());
+        assert_eq!(statements.len(), 2);
+    }
+
+    #[test]
+    fn test_errors_inside_bodies_keep_the_rest() {
+        let source = "while true { x = ; i32 z = 2; }\nfn f() { return ; g(1,; }";
+        let (statements, errors) = parse_all(source);
+
+        assert_eq!(codes(&errors), [ErrorCode::EP018, ErrorCode::EP015]);
+        assert_eq!(statements.len(), 2);
+
+        let RawStatement::While { body, .. } = &statements[0].node else { panic!("expected a while") };
+        assert_eq!(body.statements.len(), 1);
+        let RawStatement::Function { body, .. } = &statements[1].node else { panic!("expected a function") };
+        assert_eq!(body.statements.len(), 1);
+    }
+
+    #[test]
+    fn test_resynchronizes_on_statement_keywords() {
+        let source = "i32 x = 1 + \nfn f() { }\nif true { }";
+        let (statements, errors) = parse_all(source);
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(statements.len(), 2);
+    }
+
+    #[test]
+    fn test_cascades_are_suppressed() {
+        // The '{' of the broken header is skipped, so its '}' isn't reported
+        let source = "fn f( {\n    return 1;\n}\ni32 x = 1;";
+        let (_, errors) = parse_all(source);
+        assert_eq!(codes(&errors), [ErrorCode::EP028]);
+
+        // A run of unexpected tokens is a single error
+        let (_, errors) = parse_all("1 + 2 * 3;\ni32 x = 1;");
+        assert_eq!(codes(&errors), [ErrorCode::EP036]);
+    }
+
+    #[test]
+    fn test_stray_tokens_on_new_lines() {
+        let (statements, errors) = parse_all("i32 x;\n}\nbool b;\n1 + 2;\nchar c;");
+        assert_eq!(codes(&errors), [ErrorCode::EP036, ErrorCode::EP036]);
+        assert_eq!((errors[0].line, errors[0].column), (2, 1));
+        assert_eq!(statements.len(), 3);
+    }
+
+    #[test]
+    fn test_unexpected_tokens_skip_their_statement() {
+        let (statements, errors) = parse_all("(a)[0] = 5;\ni32 x = 1;");
+        assert_eq!(codes(&errors), [ErrorCode::EP036]);
+        assert_eq!(statements.len(), 1);
+    }
+
+    #[test]
+    fn test_strict_parse_stops_at_first_error() {
+        let tokens = Tokenizer::new("i32 x = ;\ni32 y = 5").tokenize().unwrap();
+        assert_eq!(Parser::new(tokens).parse().unwrap_err().code, ErrorCode::EP004);
+
+        let tokens = Tokenizer::new("1 + 2;").tokenize().unwrap();
+        assert_eq!(Parser::new(tokens).parse().unwrap_err().code, ErrorCode::EP036);
+    }
+
+    #[test]
+    fn test_if_nested_in_elif_keeps_its_else() {
+        let source = "if a { } elif b { if c { } else { } } else { }";
+        let (statements, errors) = parse_all(source);
+        assert!(errors.is_empty());
+
+        let RawStatement::If { elses, .. } = &statements[0].node else { panic!("expected an if") };
+        assert_eq!(elses.len(), 2);
+    }
+}
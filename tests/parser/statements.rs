@@ -3,13 +3,15 @@
 mod tests {
     use rcompiler::parser::*;
     use rcompiler::tokenizer::*;
+    use rcompiler::source::FileId;
     use rcompiler::error::*;
 
     // Helper to generate tokens quickly
     fn tok(kind: TokenKind, lit: &'static str) -> Token<'static> {
         Token {
             kind,
-            span: TokenSpan { start: 0, end: 0, literal: lit, line: 0, column: 0 },
+            span: TokenSpan { start: 0, end: 0, literal: lit, line: 0, column: 0, file: FileId::default() },
+            trivia: "",
         }
     }
 
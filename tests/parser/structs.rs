@@ -0,0 +1,95 @@
+#[cfg(test)]
+mod tests {
+    use rcompiler::parser::*;
+    use rcompiler::tokenizer::*;
+    use rcompiler::error::*;
+    use crate::parser::{error, parse};
+
+    #[test]
+    fn test_struct_declaration() {
+        let statements = parse("struct Line { geo::Point from; i32[2] ids; }").unwrap();
+
+        let RawStatement::Struct { name, fields } = &statements[0].node else {
+            panic!("expected a struct, found {:?}", statements[0]);
+        };
+        assert_eq!(*name, "Line");
+        assert_eq!(fields.len(), 2);
+
+        assert_eq!(fields[0].name, "from");
+        assert_eq!(fields[0].type_.kind, TokenKind::Identifier);
+        assert_eq!(fields[0].type_.name.as_ref().unwrap().to_string(), "geo::Point");
+
+        assert_eq!(fields[1].name, "ids");
+        assert_eq!(fields[1].type_.kind, TokenKind::SignedInt32);
+        assert!(fields[1].type_.is_array && fields[1].type_.name.is_none());
+    }
+
+    #[test]
+    fn test_struct_typed_declarations() {
+        let statements = parse("Point p; geo::Point[3] ps; fn f(Point p) {}").unwrap();
+
+        let RawStatement::VariableDeclaration { type_, name: "p", value: None, .. } = &statements[0].node else {
+            panic!("expected a declaration, found {:?}", statements[0]);
+        };
+        assert_eq!(type_.name.as_ref().unwrap().to_string(), "Point");
+
+        let RawStatement::VariableDeclaration { type_, name: "ps", .. } = &statements[1].node else {
+            panic!("expected a declaration, found {:?}", statements[1]);
+        };
+        assert!(type_.is_array);
+        assert_eq!(type_.name.as_ref().unwrap().to_string(), "geo::Point");
+
+        let RawStatement::Function { parameters, .. } = &statements[2].node else {
+            panic!("expected a function, found {:?}", statements[2]);
+        };
+        assert_eq!(parameters[0].type_.name.as_ref().unwrap().to_string(), "Point");
+    }
+
+    #[test]
+    fn test_struct_literal() {
+        let statements = parse("Point p = Point { x = 1, y = -2, };").unwrap();
+
+        let RawStatement::VariableDeclaration { value: Some(value), .. } = &statements[0].node else {
+            panic!("expected a declaration, found {:?}", statements[0]);
+        };
+        let RawExpression::StructLiteral { name, fields } = &value.node else {
+            panic!("expected a struct literal, found {:?}", value);
+        };
+        assert_eq!(name.to_string(), "Point");
+        let names: Vec<&str> = fields.iter().map(|(name, _)| *name).collect();
+        assert_eq!(names, vec!["x", "y"]);
+    }
+
+    #[test]
+    fn test_field_assignment() {
+        let statements = parse("line.from.x += 1;").unwrap();
+
+        let RawStatement::VariableAssignment { target, operator, .. } = &statements[0].node else {
+            panic!("expected an assignment, found {:?}", statements[0]);
+        };
+        assert_eq!(target.name, "line");
+        assert!(matches!(target.accessors[..], [Accessor::Field("from"), Accessor::Field("x")]));
+        assert_eq!(*operator, TokenKind::AddAssignment);
+    }
+
+    #[test]
+    fn test_conditions_are_not_struct_literals() {
+        let statements = parse("if p == q { x = 1; } while (p == Point { x = 1 }) { }").unwrap();
+        assert!(matches!(statements[0].node, RawStatement::If { .. }));
+        assert!(matches!(statements[1].node, RawStatement::While { .. }));
+    }
+
+    #[test]
+    fn test_struct_errors() {
+        assert_eq!(error("struct { i32 x; }"), ErrorCode::EP048);
+        assert_eq!(error("struct Point i32 x;"), ErrorCode::EP049);
+        assert_eq!(error("struct Point { i32 x = 1; }"), ErrorCode::EP050);
+        assert_eq!(error("struct Point { const i32 x; }"), ErrorCode::EP050);
+        assert_eq!(error("struct Point { i32 x; "), ErrorCode::EP010);
+        assert_eq!(error("p. = 1;"), ErrorCode::EP051);
+        assert_eq!(error("i32 x = p.1;"), ErrorCode::EP051);
+        assert_eq!(error("Point p = Point { x 1 };"), ErrorCode::EP052);
+        assert_eq!(error("Point p = Point { x = 1 y = 2 };"), ErrorCode::EP053);
+        assert_eq!(error("p.x;"), ErrorCode::EP013);
+    }
+}
\ No newline at end of file
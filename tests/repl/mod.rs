@@ -0,0 +1 @@
+mod session;
\ No newline at end of file
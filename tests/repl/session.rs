@@ -0,0 +1,82 @@
+#[cfg(test)]
+mod tests {
+    use rcompiler::engine::*;
+    use rcompiler::repl::*;
+    use rcompiler::value::*;
+    use rcompiler::error::*;
+
+    fn value(response: Response) -> Value {
+        match response {
+            Response::Value(value) => value,
+            other => panic!("expected a value, found {:?}", other),
+        }
+    }
+
+    fn error_code(response: Response) -> ErrorCode {
+        match response {
+            Response::Error(EngineError::Syntax(error) | EngineError::Runtime(error)) => error.code,
+            Response::Error(EngineError::Compile(errors)) => errors[0].code,
+            other => panic!("expected an error, found {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_state_persists_between_entries() {
+        let mut repl = Repl::new();
+        assert!(matches!(repl.feed("i32 x = 40;"), Response::Executed));
+        assert_eq!(value(repl.feed("x + 2")), Value::Int(42));
+
+        assert!(matches!(repl.feed("fn twice(i32 v) i32 { return v * 2; }"), Response::Executed));
+        assert_eq!(value(repl.feed("twice(x)")), Value::Int(80));
+    }
+
+    #[test]
+    fn test_open_body_keeps_reading() {
+        let mut repl = Repl::new();
+        assert!(matches!(repl.feed("fn add(i32 a, i32 b) i32 {"), Response::Incomplete));
+        assert!(repl.is_continuing());
+        assert!(matches!(repl.feed("    return a + b;"), Response::Incomplete));
+        assert!(matches!(repl.feed("}"), Response::Executed));
+        assert!(!repl.is_continuing());
+
+        assert_eq!(value(repl.feed("add(1, 2)")), Value::Int(3));
+        assert_eq!(repl.history().len(), 2);
+    }
+
+    #[test]
+    fn test_unfinished_entry_at_the_end() {
+        let mut repl = Repl::new();
+        assert!(matches!(repl.feed("while true {"), Response::Incomplete));
+        assert_eq!(error_code(repl.finish().unwrap()), ErrorCode::EP010);
+        assert!(repl.finish().is_none());
+    }
+
+    #[test]
+    fn test_errors_dont_stop_the_session() {
+        let mut repl = Repl::new();
+        assert_eq!(error_code(repl.feed("i32 x = 1")), ErrorCode::EP005);
+        assert_eq!(error_code(repl.feed("y")), ErrorCode::ES001);
+        assert_eq!(error_code(repl.feed("1 / 0")), ErrorCode::ER001);
+        assert_eq!(repl.last_input(), "1 / 0");
+
+        assert!(matches!(repl.feed("i32 x = 1;"), Response::Executed));
+        assert_eq!(value(repl.feed("x")), Value::Int(1));
+    }
+
+    #[test]
+    fn test_variables_can_be_declared_again() {
+        let mut repl = Repl::new();
+        repl.feed("i32 x = 1;");
+        assert!(matches!(repl.feed("bool x = true;"), Response::Executed));
+        assert_eq!(value(repl.feed("x")), Value::Bool(true));
+    }
+
+    #[test]
+    fn test_void_expressions_print_nothing() {
+        let mut repl = Repl::new();
+        repl.engine().register_native("noop", |_| Ok(Value::Void));
+        assert!(matches!(repl.feed("noop()"), Response::Executed));
+        assert!(matches!(repl.feed(""), Response::Executed));
+        assert!(repl.history().len() == 1);
+    }
+}
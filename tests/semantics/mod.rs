@@ -0,0 +1,2 @@
+mod name_resolution;
+mod type_checking;
\ No newline at end of file
@@ -0,0 +1,252 @@
+#[cfg(test)]
+mod tests {
+    use rcompiler::parser::*;
+    use rcompiler::tokenizer::*;
+    use rcompiler::semantics::*;
+    use rcompiler::error::*;
+
+    // Helper to run the whole front-end on a source string
+    fn analyze(source: &str) -> Vec<ErrorCode> {
+        let tokens = Tokenizer::new(source).tokenize().unwrap();
+        let statements = Parser::new(tokens).parse().unwrap();
+
+        match SemanticAnalyzer::new(&statements).analyze() {
+            Ok(()) => Vec::new(),
+            Err(errors) => errors.iter().map(|error| error.code).collect(),
+        }
+    }
+
+    #[test]
+    fn test_declared_variable() {
+        assert!(analyze("i32 x = 5; x = x + 1;").is_empty());
+    }
+
+    #[test]
+    fn test_undeclared_variable() {
+        assert_eq!(analyze("i32 x = y;"), vec![ErrorCode::ES001]);
+    }
+
+    #[test]
+    fn test_assignment_to_undeclared_variable() {
+        assert_eq!(analyze("count = 1;"), vec![ErrorCode::ES001]);
+    }
+
+    #[test]
+    fn test_use_before_declaration() {
+        assert_eq!(analyze("x = 1; i32 x;"), vec![ErrorCode::ES001]);
+    }
+
+    #[test]
+    fn test_self_referencing_initializer() {
+        assert_eq!(analyze("i32 x = x;"), vec![ErrorCode::ES001]);
+    }
+
+    #[test]
+    fn test_redeclaration_same_scope() {
+        assert_eq!(analyze("i32 x; bool x;"), vec![ErrorCode::ES003]);
+    }
+
+    #[test]
+    fn test_shadowing_in_inner_scope() {
+        assert!(analyze("i32 x; while true { i32 x = 2; }").is_empty());
+    }
+
+    #[test]
+    fn test_body_scope_ends() {
+        assert_eq!(analyze("if true { i32 y; } y = 2;"), vec![ErrorCode::ES001]);
+    }
+
+    #[test]
+    fn test_parameters_are_declared() {
+        assert!(analyze("fn add(i32 a, i32 b) i32 { return a + b; }").is_empty());
+    }
+
+    #[test]
+    fn test_parameter_redeclared_in_body() {
+        assert_eq!(analyze("fn f(i32 a) { i32 a; }"), vec![ErrorCode::ES003]);
+    }
+
+    #[test]
+    fn test_duplicate_parameters() {
+        assert_eq!(analyze("fn f(i32 a, bool a) { }"), vec![ErrorCode::ES003]);
+    }
+
+    #[test]
+    fn test_call_before_definition() {
+        assert!(analyze("main(); fn main() { main(); }").is_empty());
+    }
+
+    #[test]
+    fn test_undeclared_function() {
+        assert_eq!(analyze("i32 x = foo(1);"), vec![ErrorCode::ES002]);
+    }
+
+    #[test]
+    fn test_calling_a_variable() {
+        assert_eq!(analyze("i32 x; x();"), vec![ErrorCode::ES004]);
+    }
+
+    #[test]
+    fn test_function_used_as_variable() {
+        assert_eq!(analyze("fn f() { } i32 x = f;"), vec![ErrorCode::ES005]);
+    }
+
+    #[test]
+    fn test_reports_every_error() {
+        assert_eq!(
+            analyze("a = 1; i32 b; i32 b; c();"),
+            vec![ErrorCode::ES001, ErrorCode::ES003, ErrorCode::ES002]
+        );
+    }
+
+    #[test]
+    fn test_nested_function() {
+        assert_eq!(analyze("fn outer() { fn inner() { } }"), vec![ErrorCode::ES006]);
+    }
+
+    #[test]
+    fn test_loop_control_outside_loop() {
+        assert_eq!(analyze("break;"), vec![ErrorCode::ES007]);
+        assert_eq!(analyze("fn f() { continue; }"), vec![ErrorCode::ES007]);
+        assert!(analyze("while true { if true { break; } }").is_empty());
+    }
+
+    #[test]
+    fn test_return_outside_function() {
+        assert_eq!(analyze("return;"), vec![ErrorCode::ES008]);
+    }
+
+    #[test]
+    fn test_native_functions() {
+        let tokens = Tokenizer::new("print(1);").tokenize().unwrap();
+        let statements = Parser::new(tokens).parse().unwrap();
+
+        let mut analyzer = SemanticAnalyzer::new(&statements);
+        analyzer.declare_native("print");
+        assert!(analyzer.analyze().is_ok());
+    }
+
+    #[test]
+    fn test_for_variable_is_scoped_to_the_body() {
+        assert!(analyze("for i in 0..3 { i32 x = i; }").is_empty());
+        assert_eq!(analyze("for i in 0..3 {} i32 x = i;"), vec![ErrorCode::ES001]);
+        assert_eq!(analyze("for i in 0..3 { i32 i = 0; }"), vec![ErrorCode::ES003]);
+        assert_eq!(analyze("for i in 0..n {}"), vec![ErrorCode::ES001]);
+    }
+
+    #[test]
+    fn test_loop_control_inside_for() {
+        assert!(analyze("i32[2] a; for x in a { if x > 0 { continue; } break; }").is_empty());
+    }
+
+    #[test]
+    fn test_qualified_names() {
+        let source: &str = "namespace math { const i32 two = 2; fn sq(i32 x) i32 { return x * x; } }
+            i32 x = math::sq(math::two);";
+        assert!(analyze(source).is_empty());
+        assert_eq!(analyze("namespace math { const i32 two = 2; } i32 x = two;"), vec![ErrorCode::ES001]);
+        assert_eq!(analyze("namespace math { } math::sq();"), vec![ErrorCode::ES002]);
+    }
+
+    #[test]
+    fn test_names_are_looked_up_from_the_innermost_namespace() {
+        let source: &str = "namespace a {
+            const i32 one = 1;
+            namespace b { const i32 two = 2; fn get() i32 { return one + b::two + a::b::two; } }
+            fn get() i32 { return b::get(); }
+        }
+        i32 x = a::get();";
+        assert!(analyze(source).is_empty());
+        assert_eq!(analyze("namespace a { namespace b { } } a::c::f();"), vec![ErrorCode::ES011]);
+        assert_eq!(analyze("namespace a { namespace b { } } b::f();"), vec![ErrorCode::ES011]);
+    }
+
+    #[test]
+    fn test_namespaces_can_be_reopened() {
+        assert!(analyze("namespace a { fn f() {} } namespace a { fn g() { f(); } } a::g();").is_empty());
+        assert_eq!(analyze("namespace a { fn f() {} } namespace a { fn f() {} }"), vec![ErrorCode::ES003]);
+        assert_eq!(analyze("fn a() {} namespace a { }"), vec![ErrorCode::ES003]);
+    }
+
+    #[test]
+    fn test_namespace_placement_and_members() {
+        assert_eq!(analyze("fn f() { namespace a { } }"), vec![ErrorCode::ES009]);
+        assert_eq!(analyze("if true { namespace a { } }"), vec![ErrorCode::ES009]);
+        assert_eq!(analyze("namespace a { i32 x = 1; }"), vec![ErrorCode::ES010]);
+        assert_eq!(analyze("namespace a { print(1); }"), vec![ErrorCode::ES010]);
+        assert!(analyze("namespace a { fn f() {} const i32 x = 1; namespace b { } }").is_empty());
+    }
+
+    #[test]
+    fn test_namespace_used_as_a_value() {
+        assert_eq!(analyze("namespace a { } i32 x = a;"), vec![ErrorCode::ES012]);
+        assert_eq!(analyze("namespace a { namespace b { } } i32 x = a::b;"), vec![ErrorCode::ES012]);
+    }
+
+    #[test]
+    fn test_imports_only_at_the_top_level() {
+        assert!(analyze("import lib;").is_empty());
+        assert_eq!(analyze("fn f() { import lib; }"), vec![ErrorCode::ES013]);
+        assert_eq!(analyze("while true { import \"lib.rc\"; }"), vec![ErrorCode::ES013]);
+        assert_eq!(analyze("namespace a { import lib; }"), vec![ErrorCode::ES010]);
+    }
+
+    #[test]
+    fn test_struct_types() {
+        assert!(analyze("Point p; struct Point { i32 x; } fn f(Point q) {}").is_empty());
+        assert!(analyze("namespace geo { struct Point { i32 x; } } geo::Point p = geo::Point { x = 1 };").is_empty());
+        assert_eq!(analyze("Pointt p;"), vec![ErrorCode::ES015]);
+        assert_eq!(analyze("i32 size = 1; size s;"), vec![ErrorCode::ES016]);
+        assert_eq!(analyze("geo::Point p;"), vec![ErrorCode::ES011]);
+        assert_eq!(analyze("struct Point { i32 x; Poin y; }"), vec![ErrorCode::ES015]);
+    }
+
+    #[test]
+    fn test_struct_declarations() {
+        assert_eq!(analyze("fn f() { struct Point { i32 x; } }"), vec![ErrorCode::ES014]);
+        assert_eq!(analyze("struct Point { i32 x; bool x; }"), vec![ErrorCode::ES017]);
+        assert_eq!(analyze("struct Point { i32 x; } i32 x = Point;"), vec![ErrorCode::ES018]);
+        assert_eq!(analyze("struct Point { i32 x; } Point p = Point { x = y };"), vec![ErrorCode::ES001]);
+    }
+    #[test]
+    fn test_enum_declarations() {
+        assert!(analyze("State s = State::Idle; enum State { Idle, Failed(str) } namespace a { enum E { A } }").is_empty());
+        assert_eq!(analyze("fn f() { enum E { A } }"), vec![ErrorCode::ES019]);
+        assert_eq!(analyze("enum E { A, B, A }"), vec![ErrorCode::ES020]);
+        assert_eq!(analyze("enum E { A(Pointt) }"), vec![ErrorCode::ES015]);
+        assert_eq!(analyze("enum E { A } E e = E::B;"), vec![ErrorCode::ES021]);
+        assert_eq!(analyze("enum E { A } i32 x = E;"), vec![ErrorCode::ES018]);
+        assert_eq!(analyze("enum E { A } E e = E { };"), vec![ErrorCode::ES023]);
+    }
+
+    #[test]
+    fn test_match_patterns() {
+        let source: &str = "enum E { A(i32, i32), B }";
+        assert!(analyze(&format!("{} E e; match e {{ E::A(x, _) => {{ i32 y = x; }} _ => {{ }} }}", source)).is_empty());
+        assert_eq!(analyze(&format!("{} E e; match e {{ E::A(x, y) => {{ }} _ => {{ i32 y = x; }} }}", source)), vec![ErrorCode::ES001]);
+        assert_eq!(analyze(&format!("{} E e; match e {{ E::C => {{ }} }}", source)), vec![ErrorCode::ES021]);
+        assert_eq!(analyze(&format!("{} E e; match e {{ F::A => {{ }} }}", source)), vec![ErrorCode::ES011]);
+        assert_eq!(analyze("fn f() {} i32 x; match x { f => { } _ => { } }"), vec![ErrorCode::ES022]);
+    }
+
+    #[test]
+    fn test_array_elements() {
+        assert!(analyze("i32 i; i32[2] a = [i, i + 1]; a[i] = a[0];").is_empty());
+        assert_eq!(analyze("i32[2] a = [1, x];"), vec![ErrorCode::ES001]);
+        assert_eq!(analyze("i32[2] a; a[i] = 1;"), vec![ErrorCode::ES001]);
+        assert_eq!(analyze("b[0] = 1;"), vec![ErrorCode::ES001]);
+    }
+    #[test]
+    fn test_constants_cant_be_assigned() {
+        assert_eq!(analyze("const i32 x = 1; x = 2;"), vec![ErrorCode::ES024]);
+        assert_eq!(analyze("const i32[2] a = [1, 2]; a[0] = 5;"), vec![ErrorCode::ES024]);
+        assert_eq!(analyze("struct P { i32 x; } const P p = P { x = 1 }; p.x += 1;"), vec![ErrorCode::ES024]);
+        assert!(analyze("const i32 x = 1; fn f() { i32 x = 2; x = 3; }").is_empty());
+    }
+
+    #[test]
+    fn test_constants_need_a_value() {
+        assert_eq!(analyze("const i32 x;"), vec![ErrorCode::ES025]);
+        assert_eq!(analyze("const bool[2] flags;"), vec![ErrorCode::ES025]);
+    }
+}
@@ -0,0 +1,308 @@
+#[cfg(test)]
+mod tests {
+    use rcompiler::parser::*;
+    use rcompiler::tokenizer::*;
+    use rcompiler::typechecker::*;
+    use rcompiler::error::*;
+
+    // Helper to run the whole front-end on a source string
+    fn check(source: &str) -> Vec<ErrorCode> {
+        let tokens = Tokenizer::new(source).tokenize().unwrap();
+        let statements = Parser::new(tokens).parse().unwrap();
+
+        match TypeChecker::new(&statements).check() {
+            Ok(()) => Vec::new(),
+            Err(errors) => errors.iter().map(|error| error.code).collect(),
+        }
+    }
+
+    #[test]
+    fn test_matching_declarations() {
+        assert!(check("i32 a = 5; f64 b = 1.5; bool c = true; char d = 'c'; str e = \"hi\";").is_empty());
+    }
+
+    #[test]
+    fn test_integer_literal_into_bool() {
+        assert_eq!(check("bool x = 5;"), vec![ErrorCode::EC001]);
+    }
+
+    #[test]
+    fn test_char_literal_into_str() {
+        assert_eq!(check("str s = 'c';"), vec![ErrorCode::EC001]);
+    }
+
+    #[test]
+    fn test_float_literal_into_integer() {
+        assert_eq!(check("i32 x = 1.5;"), vec![ErrorCode::EC001]);
+    }
+
+    #[test]
+    fn test_integer_literal_into_float() {
+        assert!(check("f32 x = 2;").is_empty());
+    }
+
+    #[test]
+    fn test_different_integer_widths() {
+        assert_eq!(check("i64 a = 1; i32 b = a;"), vec![ErrorCode::EC001]);
+    }
+
+    #[test]
+    fn test_assignment_mismatch() {
+        assert_eq!(check("i32 x; x = true;"), vec![ErrorCode::EC002]);
+    }
+
+    #[test]
+    fn test_compound_assignment() {
+        assert!(check("i32 x = 1; x += 2; x *= x;").is_empty());
+        assert_eq!(check("bool b; b += 1;"), vec![ErrorCode::EC006]);
+    }
+
+    #[test]
+    fn test_non_bool_conditions() {
+        assert_eq!(check("if 3 {}"), vec![ErrorCode::EC003]);
+        assert_eq!(check("while \"yes\" {}"), vec![ErrorCode::EC003]);
+        assert!(check("bool ok = true; if ok {} elif !ok {}").is_empty());
+    }
+
+    #[test]
+    fn test_argument_mismatch() {
+        let source = "fn f(u8[4] bytes) { } i32 x; f(x);";
+        assert_eq!(check(source), vec![ErrorCode::EC004]);
+    }
+
+    #[test]
+    fn test_array_argument() {
+        let source = "fn f(u8[4] bytes) { } u8[4] a; f(a);";
+        assert!(check(source).is_empty());
+    }
+
+    #[test]
+    fn test_array_length_mismatch() {
+        let source = "fn f(u8[4] bytes) { } u8[2] a; f(a);";
+        assert_eq!(check(source), vec![ErrorCode::EC004]);
+    }
+
+    #[test]
+    fn test_runtime_sized_array_into_a_literal_size() {
+        assert_eq!(check("fn f(i32[2] a) { } i32 n = 3; i32[n] b; f(b);"), vec![ErrorCode::EC004]);
+        assert_eq!(check("i32 n = 2; fn f(i32[n] a) { } i32[5] b; f(b);"), vec![ErrorCode::EC021]);
+        assert_eq!(check("i32 n = 2; i32[n] a; i32[2] b = a;"), vec![ErrorCode::EC001]);
+        assert!(check("i32 n = 2; i32[2] a; i32[n] b = a; i32[n] c = [1, 2];").is_empty());
+    }
+
+    #[test]
+    fn test_integer_literals_must_fit() {
+        assert_eq!(check("u8 x = 300;"), vec![ErrorCode::EC029]);
+        assert_eq!(check("i8 x = -200;"), vec![ErrorCode::EC029]);
+        assert_eq!(check("i32 x = 99999999999;"), vec![ErrorCode::EC029]);
+        assert_eq!(check("u32 x = -1;"), vec![ErrorCode::EC029]);
+        assert_eq!(check("for u8 i in 254..256 { }"), vec![ErrorCode::EC029]);
+        assert_eq!(check("u8 x; x += 256; bool b = x < 300;"), vec![ErrorCode::EC029, ErrorCode::EC029]);
+        assert_eq!(check("fn f(i16 a) { } f(40000);"), vec![ErrorCode::EC029]);
+        assert_eq!(check("u8[2] a = [1, 256];"), vec![ErrorCode::EC029]);
+        assert_eq!(check("struct P { u8 x; } P p = P { x = 256 };"), vec![ErrorCode::EC029]);
+        assert_eq!(check("fn f() u8 { return 256; }"), vec![ErrorCode::EC029]);
+        assert_eq!(check("u8 x = 255; match x { -1 => { } _ => { } }"), vec![ErrorCode::EC029]);
+        assert_eq!(check("i8 x = 1; match x { 300 => { } _ => { } }"), vec![ErrorCode::EC029]);
+
+        assert!(check("i8 a = -128; u8 b = 255; i64 c = -9223372036854775808; u64 d = 18446744073709551615;").is_empty());
+        assert!(check("u8 x = 1; u8 y = x << 9; f64 z = 99999999999;").is_empty());
+    }
+
+    #[test]
+    fn test_argument_count() {
+        assert_eq!(check("fn f(i32 a) { } f(1, 2);"), vec![ErrorCode::EC005]);
+    }
+
+    #[test]
+    fn test_mixed_operands() {
+        assert_eq!(check("i32 a; i64 b; i64 c = a + b;"), vec![ErrorCode::EC006]);
+        assert_eq!(check("bool a = true; bool b = a * 2;"), vec![ErrorCode::EC006]);
+    }
+
+    #[test]
+    fn test_string_concatenation() {
+        assert!(check("str a = \"a\"; str b = a + \"b\";").is_empty());
+    }
+
+    #[test]
+    fn test_unary_operators() {
+        assert!(check("i32 a = -5; bool b = !true;").is_empty());
+        assert_eq!(check("bool b = -true;"), vec![ErrorCode::EC007]);
+        assert_eq!(check("i32 a = !1;"), vec![ErrorCode::EC007]);
+    }
+
+    #[test]
+    fn test_return_types() {
+        assert!(check("fn f() i32 { return 1; } fn g() { return; }").is_empty());
+        assert_eq!(check("fn f() i32 { return true; }"), vec![ErrorCode::EC008]);
+        assert_eq!(check("fn f() i32 { return; }"), vec![ErrorCode::EC008]);
+        assert_eq!(check("fn f() { return 1; }"), vec![ErrorCode::EC008]);
+    }
+
+    #[test]
+    fn test_call_result_type() {
+        assert!(check("fn f() bool { return true; } bool b = f();").is_empty());
+        assert_eq!(check("fn f() bool { return true; } i32 x = f();"), vec![ErrorCode::EC001]);
+    }
+
+    #[test]
+    fn test_void_call_as_value() {
+        assert_eq!(check("fn f() { } i32 x = f();"), vec![ErrorCode::EC012]);
+    }
+
+    #[test]
+    fn test_array_access() {
+        assert!(check("u16[5] a; u16 item = a[0];").is_empty());
+        assert_eq!(check("i32 a; i32 b = a[0];"), vec![ErrorCode::EC009]);
+        assert_eq!(check("i32[2] a; i32 b = a[true];"), vec![ErrorCode::EC010]);
+    }
+
+    #[test]
+    fn test_array_size_type() {
+        assert_eq!(check("i32[true] a;"), vec![ErrorCode::EC011]);
+    }
+
+    #[test]
+    fn test_for_over_range_and_array() {
+        assert!(check("i64 n = 3; for i in 0..n { i64 x = i; }").is_empty());
+        assert!(check("for u8 i in 0..10 { u8 x = i; }").is_empty());
+        assert!(check("str[2] names; for name in names { str x = name; }").is_empty());
+    }
+
+    #[test]
+    fn test_for_infers_the_variable_type() {
+        assert_eq!(check("str[2] names; for name in names { i32 x = name; }"), vec![ErrorCode::EC001]);
+        assert_eq!(check("for i in 0..3 { bool b = i; }"), vec![ErrorCode::EC001]);
+    }
+
+    #[test]
+    fn test_for_needs_integer_range_or_array() {
+        assert_eq!(check("for i in 0..\"a\" {}"), vec![ErrorCode::EC013]);
+        assert_eq!(check("for i in 0.5..3 {}"), vec![ErrorCode::EC013]);
+        assert_eq!(check("i32 a = 1; u8 b = 2; for i in a..b {}"), vec![ErrorCode::EC013]);
+        assert_eq!(check("i32 n = 5; for i in n {}"), vec![ErrorCode::EC013]);
+    }
+
+    #[test]
+    fn test_for_variable_type_mismatch() {
+        assert_eq!(check("str[2] names; for i32 name in names {}"), vec![ErrorCode::EC014]);
+        assert_eq!(check("i64 n = 3; for i32 i in 0..n {}"), vec![ErrorCode::EC014]);
+    }
+
+    #[test]
+    fn test_qualified_names() {
+        let source: &str = "namespace math { const i64 big = 1; fn sq(i32 x) i32 { return x * x; } }";
+        assert!(check(&format!("{} i32 x = math::sq(2); i64 y = math::big;", source)).is_empty());
+        assert_eq!(check(&format!("{} bool x = math::sq(2);", source)), vec![ErrorCode::EC001]);
+        assert_eq!(check(&format!("{} i32 x = math::big;", source)), vec![ErrorCode::EC001]);
+        assert_eq!(check(&format!("{} i32 x = math::sq(true);", source)), vec![ErrorCode::EC004]);
+    }
+
+    #[test]
+    fn test_struct_literals() {
+        let source: &str = "struct Point { i32 x; bool y; }";
+        assert!(check(&format!("{} Point p = Point {{ y = true, x = 1 }};", source)).is_empty());
+        assert_eq!(check(&format!("{} Point p = Point {{ x = 1 }};", source)), vec![ErrorCode::EC017]);
+        assert_eq!(check(&format!("{} Point p = Point {{ x = 1, y = true, x = 2 }};", source)), vec![ErrorCode::EC018]);
+        assert_eq!(check(&format!("{} Point p = Point {{ x = 1, y = true, z = 2 }};", source)), vec![ErrorCode::EC015]);
+        assert_eq!(check(&format!("{} Point p = Point {{ x = true, y = true }};", source)), vec![ErrorCode::EC019]);
+        assert_eq!(check(&format!("{} i32 p = Point {{ x = 1, y = true }};", source)), vec![ErrorCode::EC001]);
+    }
+
+    #[test]
+    fn test_fields() {
+        let source: &str = "namespace geo { struct Point { i32 x; } struct Line { Point from; } } geo::Line line;";
+        assert!(check(&format!("{} i32 x = line.from.x; line.from.x += 1; line.from = geo::Point {{ x = 2 }};", source)).is_empty());
+        assert_eq!(check(&format!("{} bool x = line.from.x;", source)), vec![ErrorCode::EC001]);
+        assert_eq!(check(&format!("{} line.from.x = true;", source)), vec![ErrorCode::EC002]);
+        assert_eq!(check(&format!("{} i32 x = line.to.x;", source)), vec![ErrorCode::EC015]);
+        assert_eq!(check(&format!("{} i32 x = line.from.x.y;", source)), vec![ErrorCode::EC016]);
+        assert_eq!(check("i32[2] a; i32 x = a.length;"), vec![ErrorCode::EC016]);
+    }
+
+    #[test]
+    fn test_struct_parameters_and_comparisons() {
+        let source: &str = "struct Point { i32 x; } fn f(Point p) bool { return p == Point { x = 1 }; }";
+        assert!(check(&format!("{} Point p; bool b = f(p);", source)).is_empty());
+        assert_eq!(check(&format!("{} bool b = f(1);", source)), vec![ErrorCode::EC004]);
+    }
+
+    #[test]
+    fn test_struct_layout() {
+        assert_eq!(check("struct Node { Node next; }"), vec![ErrorCode::EC020]);
+        assert_eq!(check("struct A { B b; } struct B { A[2] a; }"), vec![ErrorCode::EC020, ErrorCode::EC020]);
+        assert!(check("struct A { B b; B c; } struct B { i32 x; }").is_empty());
+        assert_eq!(check("const i32 n = 2; struct A { i32[n] a; }"), vec![ErrorCode::EC021]);
+    }
+    #[test]
+    fn test_variants() {
+        let source: &str = "enum State { Idle, Failed(str, i32) }";
+        assert!(check(&format!("{} State s = State::Failed(\"disk\", 2); s = State::Idle; bool b = s == State::Idle;", source)).is_empty());
+        assert_eq!(check(&format!("{} State s = State::Failed(\"disk\");", source)), vec![ErrorCode::EC022]);
+        assert_eq!(check(&format!("{} State s = State::Idle(1);", source)), vec![ErrorCode::EC022]);
+        assert_eq!(check(&format!("{} State s = State::Failed(2, 2);", source)), vec![ErrorCode::EC023]);
+        assert_eq!(check(&format!("{} i32 s = State::Idle;", source)), vec![ErrorCode::EC001]);
+    }
+
+    #[test]
+    fn test_match_exhaustiveness() {
+        let source: &str = "enum State { Idle, Failed(str, i32) } State s;";
+        assert!(check(&format!("{} match s {{ State::Idle => {{ }} State::Failed(m, c) => {{ i32 x = c; }} }}", source)).is_empty());
+        assert!(check("bool b; match b { true => { } false => { } } i32 x; match x { 1 => { } _ => { } }").is_empty());
+        assert_eq!(check(&format!("{} match s {{ State::Idle => {{ }} }}", source)), vec![ErrorCode::EC025]);
+        assert_eq!(check("i32 x; match x { 1 => { } 2 => { } }"), vec![ErrorCode::EC025]);
+        assert_eq!(check("bool b; match b { true => { } }"), vec![ErrorCode::EC025]);
+    }
+
+    #[test]
+    fn test_match_arms() {
+        let source: &str = "enum State { Idle, Failed(str, i32) } State s;";
+        assert_eq!(check(&format!("{} match s {{ _ => {{ }} State::Idle => {{ }} }}", source)), vec![ErrorCode::EC026]);
+        assert_eq!(check(&format!("{} match s {{ State::Idle => {{ }} State::Idle => {{ }} _ => {{ }} }}", source)), vec![ErrorCode::EC026]);
+        assert_eq!(check("i32 x; match x { 1 => { } 1 => { } _ => { } }"), vec![ErrorCode::EC026]);
+        assert_eq!(check(&format!("{} match s {{ 1 => {{ }} _ => {{ }} }}", source)), vec![ErrorCode::EC024]);
+        assert_eq!(check("enum E { A } i32 x; match x { E::A => { } _ => { } }"), vec![ErrorCode::EC024]);
+        assert_eq!(check(&format!("{} match s {{ State::Failed(m) => {{ }} _ => {{ }} }}", source)), vec![ErrorCode::EC022]);
+        assert_eq!(check(&format!("{} match s {{ State::Failed(m, c) => {{ bool b = c; }} _ => {{ }} }}", source)), vec![ErrorCode::EC001]);
+    }
+
+    #[test]
+    fn test_match_expressions() {
+        let source: &str = "enum State { Idle, Failed(str, i32) } State s;";
+        assert!(check(&format!("{} i32 x = 1 + match s {{ State::Failed(_, c) => c, _ => 0 }};", source)).is_empty());
+        assert_eq!(check(&format!("{} i32 x = match s {{ State::Idle => 0, _ => \"one\" }};", source)), vec![ErrorCode::EC027]);
+        assert_eq!(check(&format!("{} str x = match s {{ _ => 0 }};", source)), vec![ErrorCode::EC001]);
+        assert_eq!(check(&format!("{} i32 x = match s {{ State::Idle => 0 }};", source)), vec![ErrorCode::EC025]);
+    }
+
+    #[test]
+    fn test_enum_layout() {
+        assert_eq!(check("enum List { Empty, Node(i32, List) }"), vec![ErrorCode::EC020]);
+        assert!(check("struct Point { i32 x; } enum Shape { Dot(Point), Line(Point[2]) }").is_empty());
+        assert_eq!(check("const i32 n = 2; enum E { A(i32[n]) }"), vec![ErrorCode::EC021]);
+    }
+
+    #[test]
+    fn test_array_literals() {
+        assert!(check("i32[3] a = [1, 2, 3]; f64[2] b = [1, 2.5]; i32[0] c = [];").is_empty());
+        assert!(check("struct Point { i32 x; } Point[2] ps = [Point { x = 1 }, Point { x = 2 }];").is_empty());
+        assert!(check("i32 x = [1, 2][0]; for v in [1, 2] { x += v; }").is_empty());
+        assert_eq!(check("i32[2] a = [1, \"two\"];"), vec![ErrorCode::EC028]);
+        assert_eq!(check("i32[3] a = [1, 2];"), vec![ErrorCode::EC001]);
+        assert_eq!(check("i32[2] a = [1.5, 2];"), vec![ErrorCode::EC001]);
+        assert_eq!(check("i32 x = [1, 2];"), vec![ErrorCode::EC001]);
+    }
+
+    #[test]
+    fn test_element_assignment() {
+        let source: &str = "struct Line { i32[2] ids; } Line[2] lines; i32[2] a;";
+        assert!(check(&format!("{} a[0] = 1; a[1] += a[0]; lines[1].ids[0] *= 2;", source)).is_empty());
+        assert_eq!(check(&format!("{} a[0] = true;", source)), vec![ErrorCode::EC002]);
+        assert_eq!(check(&format!("{} lines[0].ids[1] += \"x\";", source)), vec![ErrorCode::EC006]);
+        assert_eq!(check(&format!("{} a[true] = 1;", source)), vec![ErrorCode::EC010]);
+        assert_eq!(check(&format!("{} lines[0][1] = 1;", source)), vec![ErrorCode::EC009]);
+        assert_eq!(check(&format!("{} a[0].x = 1;", source)), vec![ErrorCode::EC016]);
+    }
+}
+
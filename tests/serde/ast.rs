@@ -0,0 +1,68 @@
+// Only built with 'cargo test --features serde'
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use rcompiler::owned::*;
+    use rcompiler::parser::*;
+    use rcompiler::source::*;
+    use rcompiler::tokenizer::*;
+
+    use serde_json::{json, Value};
+
+    fn statements(source: &str) -> Value {
+        let tokens = Tokenizer::new(source).tokenize().unwrap();
+        serde_json::to_value(Parser::new(tokens).parse().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_tokens() {
+        let mut sources = SourceMap::new();
+        sources.add("first.rc", "");
+        let file = sources.add("second.rc", "i32 x;");
+
+        let tokens = Tokenizer::with_file("i32 x;", file).tokenize().unwrap();
+        let json = serde_json::to_value(&tokens).unwrap();
+
+        assert_eq!(json[1], json!({
+            "kind": "Identifier",
+            "span": { "start": 4, "end": 5, "literal": "x", "line": 1, "column": 5, "file": 1 },
+            "trivia": " ",
+        }));
+        assert_eq!(json[3]["kind"], "Eof");
+    }
+
+    #[test]
+    fn test_spans_are_included() {
+        let json = statements("i32 x = 1 + y;");
+        let value = &json[0]["node"]["VariableDeclaration"]["value"];
+
+        assert_eq!(json[0]["span"], json!({ "start": 0, "end": 14, "file": 0 }));
+        assert_eq!(value["span"], json!({ "start": 8, "end": 13, "file": 0 }));
+        assert_eq!(value["node"]["Binary"]["operator"], "Plus");
+        assert_eq!(value["node"]["Binary"]["right"]["node"], json!({ "Variable": "y" }));
+    }
+
+    #[test]
+    fn test_statements() {
+        let json = statements("fn f(i32[2] a) i32 { while true { break; } return a[0]; } if f() { } else { }");
+
+        let function = &json[0]["node"]["Function"];
+        assert_eq!(function["name"], "f");
+        assert_eq!(function["type_"], "SignedInt32");
+        assert_eq!(function["parameters"][0]["type_"]["is_array"], true);
+        assert_eq!(function["parameters"][0]["span"], json!({ "start": 5, "end": 13, "file": 0 }));
+        assert_eq!(function["body"]["statements"][0]["node"]["While"]["body"]["statements"][0]["node"], json!({ "LoopControl": "break" }));
+        assert_eq!(function["body"]["span"]["start"], 19);
+
+        let branch = &json[1]["node"]["If"]["elses"][0];
+        assert!(branch["Else"]["statements"].as_array().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_owned_tree_serializes_the_same() {
+        let source: &str = "fn f(i32[2] a) { if a[0] > 1 { return; } else { println(\"x\"); } }";
+        let tokens = Tokenizer::new(source).tokenize().unwrap();
+        let owned = to_owned(&Parser::new(tokens).parse().unwrap());
+
+        assert_eq!(serde_json::to_value(&owned).unwrap(), statements(source));
+    }
+}
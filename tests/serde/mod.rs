@@ -0,0 +1 @@
+mod ast;
\ No newline at end of file
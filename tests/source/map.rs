@@ -0,0 +1,101 @@
+#[cfg(test)]
+mod tests {
+    use rcompiler::engine::*;
+    use rcompiler::error::*;
+    use rcompiler::parser::*;
+    use rcompiler::semantics::*;
+    use rcompiler::source::*;
+    use rcompiler::tokenizer::*;
+
+    #[test]
+    fn test_locations_and_lines() {
+        let mut sources = SourceMap::new();
+        let file = sources.add("main.rc", "i32 x = 1;\r\nstr s = \"ñ\"; y\n\nlast");
+        let source = sources.get(file).unwrap();
+
+        assert_eq!(source.location(0), (1, 1));
+        assert_eq!(source.location(4), (1, 5));
+        // Columns count chars, 'ñ' takes two bytes
+        assert_eq!(source.location(source.source.find('y').unwrap()), (2, 14));
+        assert_eq!(source.location(source.source.len()), (4, 5));
+
+        assert_eq!(source.line(1), Some("i32 x = 1;"));
+        assert_eq!(source.line(3), Some(""));
+        assert_eq!(source.line(4), Some("last"));
+        assert_eq!(source.line(5), None);
+        assert_eq!(source.line(0), None);
+    }
+
+    #[test]
+    fn test_files_get_their_own_ids() {
+        let mut sources = SourceMap::new();
+        let first = sources.add("a.rc", "a");
+        let second = sources.add("b.rc", "b");
+
+        assert_ne!(first, second);
+        assert_eq!(sources.name(second), Some("b.rc"));
+        assert_eq!(sources.source(first), Some("a"));
+        assert_eq!(sources.len(), 2);
+    }
+
+    #[test]
+    fn test_diagnostic_snippet_comes_from_memory() {
+        let mut sources = SourceMap::new();
+        let source = "i32 x = 1;\ni32 y = 2 @ 3;";
+        let file = sources.add("<memory>", source);
+
+        let error = Tokenizer::with_file(source, file).tokenize().unwrap_err();
+        let diagnostic = error.to_diagnostic(&sources);
+
+        assert_eq!(diagnostic.info.filename, "<memory>");
+        assert_eq!(diagnostic.info.line, 2);
+        assert_eq!(diagnostic.info.snippet, Some("i32 y = 2 @ 3;"));
+    }
+
+    #[test]
+    fn test_errors_point_to_their_file() {
+        let mut sources = SourceMap::new();
+        let first_source = "i32 a = 1;";
+        let second_source = "\ni32 b = missing;";
+        let first = sources.add("first.rc", first_source);
+        let second = sources.add("second.rc", second_source);
+
+        let first_tokens = Tokenizer::with_file(first_source, first).tokenize().unwrap();
+        let second_tokens = Tokenizer::with_file(second_source, second).tokenize().unwrap();
+        let mut statements = Parser::new(first_tokens).parse().unwrap();
+        statements.extend(Parser::new(second_tokens).parse().unwrap());
+
+        let errors = SemanticAnalyzer::new(&statements).analyze().unwrap_err();
+        let diagnostic = errors[0].to_diagnostic(&sources);
+
+        assert_eq!(diagnostic.kind, DiagnosticKind::Error(ErrorCode::ES001));
+        assert_eq!(diagnostic.info.filename, "second.rc");
+        assert_eq!((diagnostic.info.line, diagnostic.info.column), (2, 9));
+        assert_eq!(diagnostic.info.snippet, Some("i32 b = missing;"));
+    }
+
+    #[test]
+    fn test_unknown_file_has_no_snippet() {
+        let sources = SourceMap::new();
+        let info = DiagnosticInfo::from_span(&sources, FileId(3), 10, 12);
+
+        assert_eq!(info.filename, "<unknown>");
+        assert_eq!(info.snippet, None);
+    }
+
+    #[test]
+    fn test_engine_runtime_error_in_earlier_script() {
+        let mut engine = Engine::new();
+        engine.compile_named("lib.rc", "fn divide(i32 a, i32 b) i32 {\n    return a / b;\n}").unwrap();
+        let script = engine.compile_named("main.rc", "i32 x = 1;\ni32 y = divide(x, 0);").unwrap();
+
+        let EngineError::Runtime(error) = engine.run(&script).unwrap_err() else {
+            panic!("expected a runtime error");
+        };
+        let diagnostic = error.to_diagnostic(engine.sources());
+
+        assert_eq!(diagnostic.info.filename, "lib.rc");
+        assert_eq!(diagnostic.info.line, 2);
+        assert_eq!(diagnostic.info.snippet, Some("    return a / b;"));
+    }
+}
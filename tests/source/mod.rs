@@ -0,0 +1 @@
+mod map;
\ No newline at end of file
@@ -1,2 +1,20 @@
+// The original tests are kept as they were written
+#![allow(clippy::module_inception, clippy::clone_on_copy, clippy::useless_vec)]
+
 mod tokenizer;
-mod parser;
\ No newline at end of file
+mod parser;
+mod semantics;
+mod interpreter;
+mod vm;
+mod engine;
+mod repl;
+mod source;
+mod diagnostic;
+mod lints;
+mod explain;
+mod cli;
+mod formatter;
+mod serde;
+mod visitor;
+mod owned;
+mod loader;
\ No newline at end of file
@@ -53,6 +53,17 @@ mod tokenizer_error_tests {
         assert_eq!(err.span.literal, "@");
     }
 
+    #[test]
+    fn test_err_et004_single_colon() {
+        let mut tokenizer = Tokenizer::new("math:sq");
+        let result = tokenizer.tokenize();
+
+        assert!(result.is_err());
+        let err = result.unwrap_err();
+        assert_eq!(err.code, ErrorCode::ET004);
+        assert_eq!(err.span.literal, ":");
+    }
+
     #[test]
     fn test_err_et005_too_many_chars_in_literal() {
         let mut tokenizer = Tokenizer::new("'abc'");
@@ -308,4 +308,68 @@ mod keywords_and_operators {
             assert_eq!(token.kind, expected_kinds[i], "Token {} kind mismatch", i);
         }
     }
+
+    #[test]
+    fn test_for_loop_keywords() {
+        let mut tokenizer = Tokenizer::new("for i in items");
+        let tokens = tokenizer.tokenize().unwrap();
+
+        let expected_kinds = [
+            TokenKind::For, TokenKind::Identifier, TokenKind::In, TokenKind::Identifier, TokenKind::Eof
+        ];
+
+        assert_eq!(tokens.len(), expected_kinds.len());
+        for (i, token) in tokens.iter().enumerate() {
+            assert_eq!(token.kind, expected_kinds[i], "Token {} kind mismatch", i);
+        }
+    }
+
+    #[test]
+    fn test_range_after_integer() {
+        let mut tokenizer = Tokenizer::new("0..10");
+        let tokens = tokenizer.tokenize().unwrap();
+
+        // The integer stops before '..' instead of becoming a malformed float
+        assert_eq!(tokens.len(), 4);
+        assert_eq!(tokens[0].kind, TokenKind::IntegerLiteral);
+        assert_eq!(tokens[0].span.literal, "0");
+        assert_eq!(tokens[1].kind, TokenKind::Range);
+        assert_eq!(tokens[1].span.literal, "..");
+        assert_eq!(tokens[2].kind, TokenKind::IntegerLiteral);
+        assert_eq!(tokens[2].span.literal, "10");
+    }
+
+    #[test]
+    fn test_range_between_expressions() {
+        let mut tokenizer = Tokenizer::new("1.5..x . y");
+        let tokens = tokenizer.tokenize().unwrap();
+
+        let expected_kinds = [
+            TokenKind::FloatLiteral, TokenKind::Range, TokenKind::Identifier,
+            TokenKind::Dot, TokenKind::Identifier, TokenKind::Eof
+        ];
+
+        assert_eq!(tokens.len(), expected_kinds.len());
+        for (i, token) in tokens.iter().enumerate() {
+            assert_eq!(token.kind, expected_kinds[i], "Token {} kind mismatch", i);
+        }
+        assert_eq!(tokens[0].span.literal, "1.5");
+    }
+
+    #[test]
+    fn test_import_namespace_and_path_separator() {
+        let mut tokenizer = Tokenizer::new("import namespace math { } math::sq");
+        let tokens = tokenizer.tokenize().unwrap();
+
+        let expected_kinds = [
+            TokenKind::Import, TokenKind::Namespace, TokenKind::Identifier, TokenKind::LeftBrace, TokenKind::RightBrace,
+            TokenKind::Identifier, TokenKind::PathSeparator, TokenKind::Identifier, TokenKind::Eof
+        ];
+
+        assert_eq!(tokens.len(), expected_kinds.len());
+        for (i, token) in tokens.iter().enumerate() {
+            assert_eq!(token.kind, expected_kinds[i], "Token {} kind mismatch", i);
+        }
+        assert_eq!(tokens[6].span.literal, "::");
+    }
 }
\ No newline at end of file
@@ -2,4 +2,7 @@ pub mod chars_literals;
 pub mod keywods_and_operators;
 pub mod simple_expressions;
 pub mod string_literals;
-pub mod error;
\ No newline at end of file
+pub mod error;
+pub mod recovery;
+pub mod unicode;
+pub mod trivia;
\ No newline at end of file
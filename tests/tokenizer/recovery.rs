@@ -0,0 +1,70 @@
+use rcompiler::tokenizer::*;
+use rcompiler::parser::*;
+use rcompiler::error::ErrorCode;
+
+#[cfg(test)]
+mod tokenizer_recovery_tests {
+    use super::*;
+
+    fn kinds(tokens: &[Token]) -> Vec<TokenKind> {
+        tokens.iter().map(|token| token.kind).collect()
+    }
+
+    #[test]
+    fn test_collects_every_error() {
+        let source = "i32 a = 5 @ 3;\nchar c = '\\q';\nstr s = \"abc\ni32 b = 1.2.3;";
+        let (_, errors) = Tokenizer::new(source).tokenize_recovering();
+
+        let codes: Vec<ErrorCode> = errors.iter().map(|error| error.code).collect();
+        assert_eq!(codes, vec![ErrorCode::ET004, ErrorCode::ET009, ErrorCode::ET012, ErrorCode::ET001]);
+        assert_eq!(errors.iter().map(|error| error.line).collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_error_tokens_cover_the_bad_lexeme() {
+        let (tokens, errors) = Tokenizer::new("x = 'a\\zb' + 1;").tokenize_recovering();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(kinds(&tokens), vec![
+            TokenKind::Identifier, TokenKind::Assignment, TokenKind::Error,
+            TokenKind::Plus, TokenKind::IntegerLiteral, TokenKind::Semicolon, TokenKind::Eof
+        ]);
+        assert_eq!(tokens[2].span.literal, "'a\\zb'");
+    }
+
+    #[test]
+    fn test_unterminated_literal_ends_with_the_line() {
+        let (tokens, errors) = Tokenizer::new("'a\nx;").tokenize_recovering();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].code, ErrorCode::ET007);
+        assert_eq!(kinds(&tokens), vec![TokenKind::Error, TokenKind::Identifier, TokenKind::Semicolon, TokenKind::Eof]);
+    }
+
+    #[test]
+    fn test_valid_source_has_no_error_tokens() {
+        let source = "fn f(i32 x) i32 { return x * 2; } str s = \"\\x41\\n\";";
+        let (tokens, errors) = Tokenizer::new(source).tokenize_recovering();
+
+        assert!(errors.is_empty());
+        assert_eq!(tokens, Tokenizer::new(source).tokenize().unwrap());
+    }
+
+    #[test]
+    fn test_strict_api_stops_at_first_error() {
+        let err = Tokenizer::new("@ '\\q'").tokenize().unwrap_err();
+        assert_eq!(err.code, ErrorCode::ET004);
+    }
+
+    #[test]
+    fn test_parser_ignores_error_tokens() {
+        let (tokens, errors) = Tokenizer::new("i32 a = 5 @ 3;\ni32 c = $;\n@\ni32 b = 2;").tokenize_recovering();
+        assert_eq!(errors.len(), 3);
+
+        // The rejected lexemes don't cause parser errors of their own, one in
+        // place of a value still gives a statement
+        let (statements, errors) = Parser::new(tokens).parse_recovering();
+        assert!(errors.is_empty());
+        assert_eq!(statements.len(), 2);
+    }
+}
@@ -0,0 +1,36 @@
+use rcompiler::tokenizer::*;
+
+#[cfg(test)]
+mod tokenizer_trivia_tests {
+    use super::*;
+
+    #[test]
+    fn test_comments_are_trivia_of_the_next_token() {
+        let source = "# first\ni32 x; # second\n\nx = 1;";
+        let tokens = Tokenizer::new(source).tokenize().unwrap();
+
+        assert_eq!(tokens[0].trivia, "# first\n");
+        assert_eq!(tokens[1].trivia, " ");
+        assert_eq!(tokens[2].trivia, "");
+        assert_eq!(tokens[3].trivia, " # second\n\n");
+    }
+
+    #[test]
+    fn test_trivia_and_tokens_cover_the_source() {
+        let source = "  fn f() { # body\n    return; }\n# end\n";
+        let (tokens, errors) = Tokenizer::new(source).tokenize_recovering();
+        assert!(errors.is_empty());
+
+        let rebuilt: String = tokens.iter().map(|token| format!("{}{}", token.trivia, token.span.literal)).collect();
+        assert_eq!(rebuilt, source);
+        assert_eq!(tokens.last().unwrap().trivia, "\n# end\n");
+    }
+
+    #[test]
+    fn test_error_tokens_keep_their_trivia() {
+        let (tokens, _) = Tokenizer::new("i32 x = # note\n @;").tokenize_recovering();
+
+        let error = tokens.iter().find(|token| token.kind == TokenKind::Error).unwrap();
+        assert_eq!(error.trivia, " # note\n ");
+    }
+}
@@ -0,0 +1,87 @@
+use rcompiler::tokenizer::*;
+use rcompiler::error::ErrorCode;
+
+#[cfg(test)]
+mod tokenizer_unicode_tests {
+    use super::*;
+
+    #[test]
+    fn test_string_literal_with_multibyte_chars() {
+        let source = "str s = \"Ciao, 世界! ñandú\";";
+        let tokens = Tokenizer::new(source).tokenize().unwrap();
+
+        let literal = &tokens[3];
+        assert_eq!(literal.kind, TokenKind::StringLiteral);
+        assert_eq!(literal.span.literal, "\"Ciao, 世界! ñandú\"");
+        assert_eq!(&source[literal.span.start..literal.span.end], literal.span.literal);
+
+        // The ';' right after the literal starts at its byte offset
+        assert_eq!(tokens[4].kind, TokenKind::Semicolon);
+        assert_eq!(tokens[4].span.start, source.len() - 1);
+        assert_eq!(tokens[5].span.start, source.len());
+    }
+
+    #[test]
+    fn test_char_literal_with_multibyte_char() {
+        let tokens = Tokenizer::new("'é' '世'").tokenize().unwrap();
+
+        assert_eq!(tokens[0].kind, TokenKind::CharLiteral);
+        assert_eq!(tokens[0].span.literal, "'é'");
+        assert_eq!(tokens[1].span.literal, "'世'");
+        assert_eq!(tokens[1].span.start, 5);
+    }
+
+    #[test]
+    fn test_comment_with_multibyte_chars() {
+        let source = "# perché è così?\nx;";
+        let tokens = Tokenizer::new(source).tokenize().unwrap();
+
+        assert_eq!(tokens[0].kind, TokenKind::Identifier);
+        assert_eq!(tokens[0].span.literal, "x");
+        assert_eq!(tokens[0].span.start, source.find('x').unwrap());
+    }
+
+    #[test]
+    fn test_columns_count_chars() {
+        let tokens = Tokenizer::new("\"日本\" x").tokenize().unwrap();
+
+        // Columns are where the token starts, in chars
+        assert_eq!(tokens[0].span.column, 1);
+        assert_eq!(tokens[1].span.column, 6);
+    }
+
+    #[test]
+    fn test_unicode_identifiers() {
+        let tokens = Tokenizer::new("größe _név 変数 x2").tokenize().unwrap();
+        let literals: Vec<&str> = tokens.iter()
+            .filter(|token| token.kind == TokenKind::Identifier)
+            .map(|token| token.span.literal)
+            .collect();
+
+        assert_eq!(literals, vec!["größe", "_név", "変数", "x2"]);
+    }
+
+    #[test]
+    fn test_non_identifier_symbols_are_rejected() {
+        let err = Tokenizer::new("i32 🙂 = 1;").tokenize().unwrap_err();
+        assert_eq!(err.code, ErrorCode::ET004);
+        assert_eq!(err.span.literal, "🙂");
+        assert_eq!(err.column, 5);
+    }
+
+    #[test]
+    fn test_identifier_after_number_with_unicode() {
+        let err = Tokenizer::new("42é").tokenize().unwrap_err();
+        assert_eq!(err.code, ErrorCode::ET002);
+        assert_eq!(err.span.literal, "42é");
+    }
+
+    #[test]
+    fn test_recovering_after_multibyte_error() {
+        let (tokens, errors) = Tokenizer::new("'ñ\\q' ü").tokenize_recovering();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(tokens[0].span.literal, "'ñ\\q'");
+        assert_eq!(tokens[1].span.literal, "ü");
+    }
+}
@@ -0,0 +1 @@
+mod walk;
\ No newline at end of file
@@ -0,0 +1,157 @@
+#[cfg(test)]
+mod tests {
+    use rcompiler::parser::*;
+    use rcompiler::tokenizer::*;
+    use rcompiler::visitor::*;
+
+    fn parse(source: &str) -> Vec<Statement<'_>> {
+        let tokens = Tokenizer::new(source).tokenize().unwrap();
+        Parser::new(tokens).parse().unwrap()
+    }
+
+    // Collects every variable read, in the order the visitor reaches them
+    #[derive(Default)]
+    struct Variables<'a> {
+        names: Vec<&'a str>,
+    }
+
+    impl<'a> Visitor<'a> for Variables<'a> {
+        fn visit_expression(&mut self, expression: &'a Expression<'a>) {
+            if let RawExpression::Variable(path) = &expression.node {
+                self.names.push(path.name);
+            }
+            walk_expression(self, expression);
+        }
+    }
+
+    fn variables<'a>(statements: &'a [Statement<'a>]) -> Vec<&'a str> {
+        let mut visitor: Variables = Variables::default();
+        walk_statements(&mut visitor, statements);
+        visitor.names
+    }
+
+    #[test]
+    fn test_visitor_reaches_every_expression() {
+        let source: &str = "
+            i32[a] x = b + -c;
+            fn f(i32[d] p) i32 { return g(e)[h]; }
+            if i { j = k; } elif l { while m { } } else { println(n); }
+            o[p].q[r] = [s, t];
+        ";
+        let statements = parse(source);
+        assert_eq!(variables(&statements), vec!["a", "b", "c", "d", "e", "h", "i", "k", "l", "m", "n", "p", "r", "s", "t"]);
+    }
+
+    // Counts the functions without going into their bodies
+    struct Functions {
+        count: usize,
+    }
+
+    impl<'a> Visitor<'a> for Functions {
+        fn visit_statement(&mut self, statement: &'a Statement<'a>) {
+            if let RawStatement::Function { .. } = statement.node {
+                self.count += 1;
+                return;
+            }
+            walk_statement(self, statement);
+        }
+    }
+
+    #[test]
+    fn test_visitor_can_skip_children() {
+        let statements = parse("fn f() { fn g() {} } if true { fn h() {} }");
+        let mut visitor: Functions = Functions { count: 0 };
+        walk_statements(&mut visitor, &statements);
+        assert_eq!(visitor.count, 2);
+    }
+
+    struct Rename<'a> {
+        from: &'a str,
+        to: &'a str,
+    }
+
+    impl<'a> VisitorMut<'a> for Rename<'a> {
+        fn visit_statement_mut(&mut self, statement: &mut Statement<'a>) {
+            match &mut statement.node {
+                RawStatement::VariableDeclaration { name, .. } if *name == self.from => {
+                    *name = self.to;
+                },
+                _ => {},
+            }
+            walk_statement_mut(self, statement);
+        }
+
+        fn visit_place_mut(&mut self, place: &mut Place<'a>) {
+            if place.name == self.from {
+                place.name = self.to;
+            }
+            walk_place_mut(self, place);
+        }
+
+        fn visit_expression_mut(&mut self, expression: &mut Expression<'a>) {
+            if let RawExpression::Variable(path) = &mut expression.node && path.name == self.from {
+                path.name = self.to;
+            }
+            walk_expression_mut(self, expression);
+        }
+
+        fn visit_parameter_mut(&mut self, parameter: &mut Parameter<'a>) {
+            if parameter.name == self.from {
+                parameter.name = self.to;
+            }
+            walk_parameter_mut(self, parameter);
+        }
+    }
+
+    #[test]
+    fn test_visitor_mut_renames_in_place() {
+        let mut statements = parse("i32 x = 1; fn f(i32 x) { x += 1; } while x < 3 { if x { println(x); } else { x[x] = 0; } }");
+        walk_statements_mut(&mut Rename { from: "x", to: "y" }, &mut statements);
+
+        let expected = parse("i32 y = 1; fn f(i32 y) { y += 1; } while y < 3 { if y { println(y); } else { y[y] = 0; } }");
+        assert_eq!(format!("{:?}", statements), format!("{:?}", expected));
+    }
+
+    // Removes double negations, '!!x' becomes 'x'
+    struct DoubleNot;
+
+    impl<'a> Folder<'a> for DoubleNot {
+        fn fold_expression(&mut self, expression: Expression<'a>) -> Expression<'a> {
+            let expression: Expression<'a> = rebuild_expression(self, expression);
+
+            match expression.node {
+                RawExpression::Unary { operator: TokenKind::Not, operand } => match operand.node {
+                    RawExpression::Unary { operator: TokenKind::Not, operand } => operand,
+                    node => Box::new(Spanned {
+                        node: RawExpression::Unary {
+                            operator: TokenKind::Not,
+                            operand: Box::new(Spanned { node, span: operand.span }),
+                        },
+                        span: expression.span,
+                    }),
+                },
+                node => Box::new(Spanned { node, span: expression.span }),
+            }
+        }
+    }
+
+    #[test]
+    fn test_folder_rebuilds_tree() {
+        let statements = parse("bool a = !!true; if !!!a { println(!!(a && !!a)); }");
+        let folded = rebuild_statements(&mut DoubleNot, statements);
+
+        let expected = parse("bool a = true; if !a { println(a && a); }");
+        assert_eq!(format!("{:?}", folded), format!("{:?}", expected));
+    }
+
+    struct Identity;
+
+    impl<'a> Folder<'a> for Identity {}
+
+    #[test]
+    fn test_default_folder_keeps_tree() {
+        let source: &str = "const u8[2] a; fn f(i32 x) i32 { return x * 2; } if f(1) > 1 { println(a[0]); } elif false {} else { break; }";
+        let folded = rebuild_statements(&mut Identity, parse(source));
+        assert_eq!(format!("{:?}", folded), format!("{:?}", parse(source)));
+    }
+}
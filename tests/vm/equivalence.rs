@@ -0,0 +1,545 @@
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use rcompiler::bytecode::*;
+    use rcompiler::compiler::*;
+    use rcompiler::parser::*;
+    use rcompiler::tokenizer::*;
+    use rcompiler::interpreter::*;
+    use rcompiler::typechecker::*;
+    use rcompiler::value::*;
+    use rcompiler::vm::*;
+    use rcompiler::error::*;
+
+    // Runs a program on both backends and checks they agree on the value of a global
+    fn run_and_get(source: &str, name: &str) -> Value {
+        let tokens = Tokenizer::new(source).tokenize().unwrap();
+        let statements = Parser::new(tokens).parse().unwrap();
+
+        let mut interpreter = Interpreter::new();
+        interpreter.run(&statements).unwrap();
+        let expected = interpreter.global(name).cloned().unwrap();
+
+        let mut program = Program::new();
+        let script = Compiler::new(&mut program).compile(&statements).unwrap();
+        let mut vm = VM::new();
+        vm.run(&program, Rc::new(script)).unwrap();
+        let actual = vm.global(program.global(name).unwrap()).cloned().unwrap();
+
+        assert_eq!(actual, expected);
+        actual
+    }
+
+    // Same as 'run_and_get', both backends get the kinds the type checker finds for the arithmetic
+    fn run_checked(source: &str, name: &str) -> Value {
+        let tokens = Tokenizer::new(source).tokenize().unwrap();
+        let statements = Parser::new(tokens).parse().unwrap();
+
+        let mut checker = TypeChecker::new(&statements);
+        checker.check().unwrap();
+        let kinds = checker.into_kinds();
+
+        let mut interpreter = Interpreter::new().with_kinds(kinds.clone());
+        interpreter.run(&statements).unwrap();
+        let expected = interpreter.global(name).cloned().unwrap();
+
+        let mut program = Program::new();
+        let script = Compiler::new(&mut program).with_kinds(kinds).compile(&statements).unwrap();
+        let mut vm = VM::new();
+        vm.run(&program, Rc::new(script)).unwrap();
+        let actual = vm.global(program.global(name).unwrap()).cloned().unwrap();
+
+        assert_eq!(actual, expected);
+        actual
+    }
+
+    // Runs a program on both backends and checks they fail with the same code
+    fn run_error(source: &str) -> ErrorCode {
+        let tokens = Tokenizer::new(source).tokenize().unwrap();
+        let statements = Parser::new(tokens).parse().unwrap();
+
+        let expected = Interpreter::new().run(&statements).unwrap_err().code;
+
+        let mut program = Program::new();
+        let script = Compiler::new(&mut program).compile(&statements).unwrap();
+        let actual = VM::new().run(&program, Rc::new(script)).unwrap_err().code;
+
+        assert_eq!(actual, expected);
+        actual
+    }
+
+    #[test]
+    fn test_arithmetic_and_casts() {
+        assert_eq!(run_and_get("i32 x = 1 + 2 * 3;", "x"), Value::Int(7));
+        assert_eq!(run_and_get("u8 x = 255; x += 1;", "x"), Value::UInt(0));
+        assert_eq!(run_and_get("u16[3] a;", "a"), Value::Array(vec![Value::UInt(0); 3]));
+    }
+
+    #[test]
+    fn test_integer_width_wraps_inside_expressions() {
+        assert_eq!(run_checked("u8 a = 200; u8 b = 100; u8 c = (a + b) / 2;", "c"), Value::UInt(22));
+        assert_eq!(run_checked("u8 a = 200; bool b = a * 2 < a;", "b"), Value::Bool(true));
+        assert_eq!(run_checked("i32 x = 2147483647; bool b = x + 1 > x;", "b"), Value::Bool(false));
+        assert_eq!(run_checked("i32 x = 2147483647; i32 z = (x + 1) / 2;", "z"), Value::Int(-1073741824));
+        assert_eq!(run_checked("i32 m = -2147483647 - 1; bool b = -m == m;", "b"), Value::Bool(true));
+        assert_eq!(run_checked("u32 x = 5; u32 y = -x; bool b = -x == y;", "b"), Value::Bool(true));
+    }
+
+    #[test]
+    fn test_if_elif_else_chain() {
+        let source = "i32 x; bool a = false; bool b = true; if a { x = 1; } elif b { x = 2; } else { x = 3; }";
+        assert_eq!(run_and_get(source, "x"), Value::Int(2));
+
+        let source = "i32 x; if false { x = 1; } elif false { x = 2; } else { x = 3; }";
+        assert_eq!(run_and_get(source, "x"), Value::Int(3));
+
+        let source = "i32 x; if true { x = 1; } else { x = 3; }";
+        assert_eq!(run_and_get(source, "x"), Value::Int(1));
+    }
+
+    #[test]
+    fn test_loops_drop_their_locals() {
+        let source = "
+            i32 x = 0;
+            bool first = true;
+            bool go = true;
+            while go {
+                i32 step = 1;
+                x += step;
+                if first {
+                    i32 unused = 5;
+                    first = false;
+                    continue;
+                }
+                i64 other = 2;
+                go = false;
+            }
+            while true { i32 a = 1; i32 b = 2; x += a + b; break; }
+        ";
+        assert_eq!(run_and_get(source, "x"), Value::Int(5));
+    }
+
+    #[test]
+    fn test_body_scopes_are_dropped() {
+        let source = "i32 x = 1; if true { i32 x = 5; x = 6; }";
+        assert_eq!(run_and_get(source, "x"), Value::Int(1));
+    }
+
+    #[test]
+    fn test_functions_and_recursion() {
+        let source = "
+            fn fact(u64 n) u64 {
+                if zero(n) { return 1; }
+                return n * fact(n - 1);
+            }
+            fn zero(u64 n) bool { if n { return false; } return true; }
+            u64 x = fact(10);
+        ";
+        assert_eq!(run_and_get(source, "x"), Value::UInt(3628800));
+    }
+
+    #[test]
+    fn test_arguments_and_returns_are_cast() {
+        assert_eq!(run_and_get("fn f() u8 { return 300; } u64 x = f();", "x"), Value::UInt(44));
+        assert_eq!(run_and_get("fn f(u8 a) u64 { return a; } u64 x = f(257);", "x"), Value::UInt(1));
+    }
+
+    #[test]
+    fn test_builtins() {
+        assert_eq!(run_and_get("i32[4] a; u64 n = len(a);", "n"), Value::UInt(4));
+    }
+
+    #[test]
+    fn test_comparison_and_bitwise_operators() {
+        assert_eq!(run_and_get("i32 a = 6; i32 x = a & 3 | 8 ^ 1;", "x"), Value::Int(11));
+        assert_eq!(run_and_get("u32 x = 1 << 2 + 1;", "x"), Value::UInt(8));
+        assert_eq!(run_and_get("bool x = 1 + 1 >= 2 == 3 > 4;", "x"), Value::Bool(false));
+    }
+
+    #[test]
+    fn test_logical_operators() {
+        assert_eq!(run_and_get("bool x = true && false || true;", "x"), Value::Bool(true));
+        assert_eq!(run_and_get("bool x = false || true && false;", "x"), Value::Bool(false));
+        assert_eq!(run_and_get("i32 n = 5; bool x = n > 0 && n < 10;", "x"), Value::Bool(true));
+        assert_eq!(run_and_get("i32 n = 5; bool x = !(n == 5) || n != 5;", "x"), Value::Bool(false));
+    }
+
+    #[test]
+    fn test_short_circuit_skips_calls() {
+        let source = "
+            i32 calls = 0;
+            fn touch() bool { calls += 1; return true; }
+            bool a = false && touch();
+            bool b = true || touch();
+            bool c = true && touch();
+            bool d = false || touch();
+        ";
+        assert_eq!(run_and_get(source, "calls"), Value::Int(2));
+    }
+
+    #[test]
+    fn test_runtime_errors() {
+        assert_eq!(run_error("i32 z = 0; i32 x = 1 / z;"), ErrorCode::ER001);
+        assert_eq!(run_error("i32[2] a; i32 x = a[2];"), ErrorCode::ER002);
+        assert_eq!(run_error("fn f() i32 { } i32 x = f();"), ErrorCode::ER005);
+        assert_eq!(run_error("fn f() { f(); } f();"), ErrorCode::ER010);
+    }
+
+    #[test]
+    fn test_vm_can_call_functions_by_slot() {
+        let tokens = Tokenizer::new("fn add(i32 a, i32 b) i32 { return a + b; }").tokenize().unwrap();
+        let statements = Parser::new(tokens).parse().unwrap();
+
+        let mut program = Program::new();
+        let script = Compiler::new(&mut program).compile(&statements).unwrap();
+        let mut vm = VM::new();
+        vm.run(&program, Rc::new(script)).unwrap();
+
+        let slot = program.function("add").unwrap();
+        assert_eq!(vm.call(&program, slot, vec![Value::Int(2), Value::Int(3)]).unwrap(), Value::Int(5));
+    }
+
+    #[test]
+    fn test_for_over_range() {
+        let source = "i32 total; for i in 0..10 { if i == 2 { continue; } if i == 6 { break; } total += i; }";
+        assert_eq!(run_and_get(source, "total"), Value::Int(13));
+
+        assert_eq!(run_and_get("i32 count; for i in 5..2 { count += 1; }", "count"), Value::Int(0));
+        assert_eq!(run_and_get("u8 last; for u8 i in 250..255 { last = i; }", "last"), Value::UInt(254));
+    }
+
+    #[test]
+    fn test_for_over_array() {
+        let source = "str[3] words; words = words; str joined; for word in words { i32 unused = 1; joined = joined + word + \"-\"; }";
+        assert_eq!(run_and_get(source, "joined"), Value::Str("---".into()));
+
+        let source = "i32[3] a; i32 sum; for i in 0..3 { a = a; } for x in a { sum += x + 1; }";
+        assert_eq!(run_and_get(source, "sum"), Value::Int(3));
+    }
+
+    #[test]
+    fn test_nested_for_and_return() {
+        let source = "
+            fn find(i32 target) i32 {
+                for i in 0..10 {
+                    for j in 0..10 {
+                        i32 product = i * j;
+                        if product == target { return i * 100 + j; }
+                    }
+                }
+                return -1;
+            }
+            i32 found = find(42);
+            i32 missing = find(97);
+        ";
+        assert_eq!(run_and_get(source, "found"), Value::Int(607));
+        assert_eq!(run_and_get(source, "missing"), Value::Int(-1));
+    }
+
+    #[test]
+    fn test_for_variable_assignment_doesnt_change_the_iteration() {
+        let source = "i32 count; for i in 0..5 { i = 10; count += 1; }";
+        assert_eq!(run_and_get(source, "count"), Value::Int(5));
+    }
+
+    #[test]
+    fn test_qualified_calls_and_constants() {
+        let source = "
+            namespace math {
+                const i32 two = 2;
+                fn sq(i32 x) i32 { return x * x; }
+                namespace inner { fn twice(i32 x) i32 { return x * two; } }
+            }
+            i32 x = math::sq(math::two) + math::inner::twice(5);
+        ";
+        assert_eq!(run_and_get(source, "x"), Value::Int(14));
+        assert_eq!(run_and_get(source, "math::two"), Value::Int(2));
+    }
+
+    #[test]
+    fn test_names_inside_a_namespace_come_first() {
+        let source = "
+            i32 scale = 10;
+            fn get() i32 { return scale; }
+            namespace a {
+                const i32 scale = 100;
+                fn get() i32 { return scale; }
+                fn call() i32 { return get(); }
+            }
+            i32 inner = a::call();
+            i32 outer = get();
+        ";
+        assert_eq!(run_and_get(source, "inner"), Value::Int(100));
+        assert_eq!(run_and_get(source, "outer"), Value::Int(10));
+    }
+
+    #[test]
+    fn test_namespace_function_can_shadow_a_builtin() {
+        let source = "
+            namespace text { fn len(str s) i32 { return 7; } fn size(str s) i32 { return len(s); } }
+            i32 inner = text::size(\"ab\");
+            u64 outer = len(\"ab\");
+        ";
+        assert_eq!(run_and_get(source, "inner"), Value::Int(7));
+        assert_eq!(run_and_get(source, "outer"), Value::UInt(2));
+    }
+
+    #[test]
+    fn test_struct_literals_and_fields() {
+        let source = "
+            struct Point { i32 x; u8 y; }
+            Point p = Point { y = 300, x = 1 };
+            i32 x = p.x;
+            u8 y = p.y;
+        ";
+        assert_eq!(run_and_get(source, "x"), Value::Int(1));
+        assert_eq!(run_and_get(source, "y"), Value::UInt(44));
+
+        let source = "
+            struct Line { Point from; Point to; }
+            struct Point { i32 x; i32 y; }
+            Line line;
+            line.to.y = 5;
+            line.to.y *= 3;
+            line.from = Point { x = 1, y = 2 };
+            i32 y = line.to.y;
+            Point from = line.from;
+        ";
+        assert_eq!(run_and_get(source, "y"), Value::Int(15));
+        assert_eq!(run_and_get(source, "from").to_string(), "Point { x = 1, y = 2 }");
+    }
+
+    #[test]
+    fn test_struct_defaults() {
+        let source = "
+            namespace geo { struct Point { f64 x; bool[2] seen; } }
+            geo::Point[2] points;
+            geo::Point second = points[1];
+        ";
+        assert_eq!(run_and_get(source, "second").to_string(), "geo::Point { x = 0, seen = [false, false] }");
+    }
+
+    #[test]
+    fn test_structs_are_copied() {
+        let source = "
+            struct Counter { i32 count; }
+            fn bump(Counter c) i32 { c.count += 1; return c.count; }
+            Counter a = Counter { count = 1 };
+            Counter b = a;
+            b.count = 5;
+            i32 bumped = bump(a);
+            i32 count = a.count;
+            bool same = a == Counter { count = 1 };
+        ";
+        assert_eq!(run_and_get(source, "bumped"), Value::Int(2));
+        assert_eq!(run_and_get(source, "count"), Value::Int(1));
+        assert_eq!(run_and_get(source, "same"), Value::Bool(true));
+    }
+
+    #[test]
+    fn test_struct_literal_runs_in_declaration_order() {
+        let source = "
+            struct Pair { i32 first; i32 second; }
+            i32 calls = 0;
+            fn next() i32 { calls += 1; return calls; }
+            Pair pair = Pair { second = next(), first = next() };
+            i32 first = pair.first;
+        ";
+        assert_eq!(run_and_get(source, "first"), Value::Int(1));
+    }
+
+    #[test]
+    fn test_field_assignment_keeps_changes_made_by_the_value() {
+        let source = "
+            struct P { i32 x; i32 y; }
+            P p;
+            fn g() i32 { p.y = 7; return 1; }
+            p.x = g();
+            p.x += g();
+            i32 y = p.y;
+            i32 x = p.x;
+        ";
+        assert_eq!(run_and_get(source, "y"), Value::Int(7));
+        assert_eq!(run_and_get(source, "x"), Value::Int(2));
+    }
+    #[test]
+    fn test_variants_and_defaults() {
+        let source = "
+            enum State { Idle, Failed(str, u8) }
+            namespace geo { enum Shape { Dot(State[2]), Empty } }
+            State failed = State::Failed(\"disk\", 300);
+            geo::Shape shape;
+            bool same = failed == State::Failed(\"disk\", 44);
+        ";
+        assert_eq!(run_and_get(source, "failed").to_string(), "State::Failed(disk, 44)");
+        assert_eq!(run_and_get(source, "shape").to_string(), "geo::Shape::Dot([State::Idle, State::Idle])");
+        assert_eq!(run_and_get(source, "same"), Value::Bool(true));
+    }
+
+    #[test]
+    fn test_match_statements() {
+        let source = "
+            enum State { Idle, Failed(str, i32) }
+            fn code(State s) i32 {
+                i32 base = 100;
+                match s {
+                    State::Failed(_, code) => {
+                        i32 doubled = code * 2;
+                        return base + doubled;
+                    }
+                    _ => { }
+                }
+                return base;
+            }
+            i32 total = 0;
+            for i32 i in 0..5 {
+                match i {
+                    3 => { break; }
+                    -1 => { }
+                    _ => { total += code(State::Failed(\"x\", i)); }
+                }
+            }
+            i32 idle = code(State::Idle);
+        ";
+        assert_eq!(run_and_get(source, "total"), Value::Int(306));
+        assert_eq!(run_and_get(source, "idle"), Value::Int(100));
+    }
+
+    #[test]
+    fn test_match_expressions_inside_expressions() {
+        let source = "
+            enum Op { Add(i32), Neg }
+            fn apply(i32 x, Op op) i32 {
+                i32 before = x;
+                i32 result = before * 10 + match op { Op::Add(n) => before + n + match n { 0 => 0, _ => 1000 }, Op::Neg => -before };
+                i32 after = result;
+                return after;
+            }
+            i32 a = apply(2, Op::Add(3));
+            i32 b = apply(2, Op::Neg);
+            str word = match true { true => \"yes\", false => \"no\" };
+        ";
+        assert_eq!(run_and_get(source, "a"), Value::Int(1025));
+        assert_eq!(run_and_get(source, "b"), Value::Int(18));
+        assert_eq!(run_and_get(source, "word"), Value::Str(Rc::from("yes")));
+    }
+
+    #[test]
+    fn test_match_without_arm() {
+        assert_eq!(run_error("match len(\"ab\") { 1 => { } }"), ErrorCode::ER011);
+        assert_eq!(run_error("i32 x = match len(\"ab\") { 1 => 0 };"), ErrorCode::ER011);
+    }
+
+    #[test]
+    fn test_array_literals() {
+        assert_eq!(run_and_get("u8[3] a = [1, 256, 2 + 3];", "a"), Value::Array(vec![Value::UInt(1), Value::UInt(0), Value::UInt(5)]));
+        assert_eq!(run_and_get("i32 x = [4, 5, 6][1];", "x"), Value::Int(5));
+        assert_eq!(run_and_get("i32 sum; for v in [1, 2, 3] { sum += v; }", "sum"), Value::Int(6));
+
+        let source = "
+            struct Point { i32 x; i32 y; }
+            Point origin;
+            Point[2] points = [origin, Point { x = 1, y = 2 }];
+            i32 y = points[1].y;
+        ";
+        assert_eq!(run_and_get(source, "y"), Value::Int(2));
+    }
+
+    #[test]
+    fn test_element_assignment() {
+        let source = "
+            u8[3] a = [1, 2, 3];
+            a[0] = 300;
+            a[1] += 10;
+            a[2] *= a[1];
+        ";
+        assert_eq!(run_and_get(source, "a"), Value::Array(vec![Value::UInt(44), Value::UInt(12), Value::UInt(36)]));
+
+        let source = "
+            struct Line { Point from; i32[2] ids; }
+            struct Point { i32 x; i32 y; }
+            Line[2] lines;
+            lines[1].from.x = 7;
+            lines[1].from.x += 1;
+            lines[0].ids[1] = 3;
+            lines[0].ids[1] <<= 2;
+            i32 x = lines[1].from.x;
+            i32 id = lines[0].ids[1];
+        ";
+        assert_eq!(run_and_get(source, "x"), Value::Int(8));
+        assert_eq!(run_and_get(source, "id"), Value::Int(12));
+    }
+
+    #[test]
+    fn test_element_assignment_of_locals() {
+        let source = "
+            fn reversed(i32[3] values) i32[3] {
+                i32[3] result;
+                for i in 0..3 {
+                    i32 last = 2 - i;
+                    result[last] = values[i];
+                }
+                return result;
+            }
+            i32[3] a = reversed([1, 2, 3]);
+        ";
+        assert_eq!(run_and_get(source, "a"), Value::Array(vec![Value::Int(3), Value::Int(2), Value::Int(1)]));
+    }
+
+    #[test]
+    fn test_element_assignment_order() {
+        // The index is evaluated before the value, both before the element is written
+        let source = "
+            i32[4] a;
+            i32 calls = 0;
+            fn next() i32 { calls += 1; return calls; }
+            a[next()] = next() * 10;
+            a[next()] -= calls;
+        ";
+        assert_eq!(run_and_get(source, "a"), Value::Array(vec![Value::Int(0), Value::Int(20), Value::Int(0), Value::Int(-3)]));
+    }
+
+    #[test]
+    fn test_element_assignment_keeps_changes_made_by_the_value() {
+        let source = "
+            i32[2] a;
+            fn g() i32 { a[1] = 7; return 1; }
+            a[0] = g();
+            struct Line { i32[2] ids; }
+            Line[2] lines;
+            fn h() i32 { lines[1].ids[0] = 4; lines[0].ids[0] = 5; return 3; }
+            lines[0].ids[1] += h();
+        ";
+        assert_eq!(run_and_get(source, "a"), Value::Array(vec![Value::Int(1), Value::Int(7)]));
+        assert_eq!(run_and_get(source, "lines").to_string(), "[Line { ids = [5, 3] }, Line { ids = [4, 0] }]");
+    }
+
+    #[test]
+    fn test_array_sizes_are_checked() {
+        assert_eq!(run_and_get("i32 n = 2; i32[n + 1] a = [1, 2, 3];", "a"), Value::Array(vec![Value::Int(1), Value::Int(2), Value::Int(3)]));
+        assert_eq!(run_and_get("u8 n = 0; bool[n] a;", "a"), Value::Array(Vec::new()));
+        assert_eq!(run_error("i32 n = 2; i32[n] a = [1, 2, 3, 4];"), ErrorCode::ER012);
+        assert_eq!(run_error("i32 n = -1; i32[n] a;"), ErrorCode::ER012);
+        assert_eq!(run_error("i64 n = 100000000000; i32[n] a;"), ErrorCode::ER012);
+        assert_eq!(run_error("struct P { i32 x; } i64 n = -5; P[n] ps;"), ErrorCode::ER012);
+        assert_eq!(run_error("fn f(i32 n) { i32[n] a = [1]; } f(3);"), ErrorCode::ER012);
+    }
+
+    #[test]
+    fn test_f32_keeps_its_precision() {
+        assert_eq!(run_and_get("f32 x = 0.1;", "x"), Value::Float32(0.1));
+        assert_eq!(run_and_get("f32 x = 0.1;", "x").to_string(), "0.1");
+        assert_eq!(run_and_get("f32[2] a = [0.1, 0.2];", "a").to_string(), "[0.1, 0.2]");
+        assert_eq!(run_and_get("f32 x = 0.5; f32 y = -x * 3;", "y"), Value::Float32(-1.5));
+        assert_eq!(run_and_get("f32 x = 0.1; f64 y = x;", "y").to_string(), "0.10000000149011612");
+    }
+
+    #[test]
+    fn test_element_assignment_out_of_bounds() {
+        assert_eq!(run_error("i32[2] a; a[2] = 1;"), ErrorCode::ER002);
+        assert_eq!(run_error("i32[2] a; a[-1] += 1;"), ErrorCode::ER002);
+        assert_eq!(run_error("struct P { i32[1] ids; } P[1] ps; ps[0].ids[1] = 1;"), ErrorCode::ER002);
+        assert_eq!(run_error("i32[2] a; i32 x = [1, 2][a[0] + 2];"), ErrorCode::ER002);
+    }
+}
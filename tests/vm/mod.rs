@@ -0,0 +1 @@
+mod equivalence;
\ No newline at end of file